@@ -195,8 +195,11 @@ impl KinematicCharacterController {
         self.check_and_fix_penetrations();
 
         let mut translation_remaining = desired_translation;
+        let mut kinematic_friction_translation = Vector::zeros();
 
-        // Check if we are grounded at the initial position.
+        // Check if we are grounded at the initial position. This also adds the movement of the
+        // kinematic platforms the character is standing on, so the character is carried by
+        // these platforms even if it isn’t moving by itself.
         let grounded_at_starting_pos = self.detect_grounded_status_and_apply_friction(
             dt,
             bodies,
@@ -206,14 +209,13 @@ impl KinematicCharacterController {
             &character_pos,
             &dims,
             filter,
-            None,
-            None,
+            Some(&mut kinematic_friction_translation),
+            Some(&mut translation_remaining),
         );
 
         // println!("Init grounded status: {grounded_at_starting_pos}");
 
         let mut max_iters = 20;
-        let mut kinematic_friction_translation = Vector::zeros();
         let offset = self.offset.eval(dims.y);
 
         while let Some((translation_dir, translation_dist)) =
@@ -744,3 +746,56 @@ impl KinematicCharacterController {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn character_controller_is_carried_by_kinematic_platforms() {
+        use crate::control::KinematicCharacterController;
+        use crate::geometry::Ball;
+        use crate::math::Isometry;
+        use crate::pipeline::{PhysicsState, QueryFilter};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let platform = RigidBodyBuilder::kinematic_velocity_based()
+            .linvel(Vector::x() * 2.0)
+            .build();
+        let platform = state.bodies.insert(platform);
+        let platform_shape = cuboid(5.0, 0.5);
+        state
+            .colliders
+            .insert_with_parent(platform_shape, platform, &mut state.bodies);
+        state.step(&mut pipeline, &(), &());
+
+        // A character standing still on top of the platform.
+        let controller = KinematicCharacterController::default();
+        let character_pos = Isometry::translation(
+            state.bodies[platform].translation().x,
+            1.005,
+            #[cfg(feature = "dim3")]
+            0.0,
+        );
+        let movement = controller.move_shape(
+            state.integration_parameters.dt,
+            &state.bodies,
+            &state.colliders,
+            &state.query_pipeline,
+            &Ball::new(0.5),
+            &character_pos,
+            Vector::zeros(),
+            QueryFilter::default(),
+            |_| {},
+        );
+
+        assert!(movement.grounded);
+        assert!((movement.translation.x - 2.0 * state.integration_parameters.dt).abs() < 1.0e-4);
+        assert!(movement.translation.y.abs() < 1.0e-2);
+    }
+}
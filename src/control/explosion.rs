@@ -0,0 +1,228 @@
+//! A helper applying the radial impulses of an explosion to the dynamic rigid-bodies around it.
+
+use crate::dynamics::{RigidBodyHandle, RigidBodySet};
+use crate::geometry::{Aabb, ColliderSet, Ray};
+use crate::math::{Point, Real, Vector};
+use crate::pipeline::{QueryFilter, QueryPipeline};
+
+/// How the impulse of an [`Explosion`] decreases with the distance to its center.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExplosionFalloff {
+    /// The impulse is the same anywhere within the explosion radius.
+    Constant,
+    /// The impulse decreases linearly, from its maximum at the center to zero at the explosion
+    /// radius.
+    Linear,
+    /// The impulse decreases quadratically, from its maximum at the center to zero at the
+    /// explosion radius.
+    Quadratic,
+}
+
+impl ExplosionFalloff {
+    fn eval(self, dist: Real, radius: Real) -> Real {
+        let ratio = 1.0 - dist / radius;
+        match self {
+            Self::Constant => 1.0,
+            Self::Linear => ratio,
+            Self::Quadratic => ratio * ratio,
+        }
+    }
+}
+
+/// An explosion pushing the dynamic rigid-bodies around it away from its center.
+///
+/// Each collider within the explosion radius receives an impulse at its point closest to the
+/// center, directed away from the center. A rigid-body with several colliders in range thus
+/// receives one impulse per collider.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Explosion {
+    /// The center of the explosion.
+    pub center: Point<Real>,
+    /// The distance beyond which the colliders aren’t affected by the explosion.
+    pub radius: Real,
+    /// The magnitude of the impulse applied to a collider touching the center of the explosion.
+    pub impulse: Real,
+    /// How the impulse decreases with the distance to the center.
+    pub falloff: ExplosionFalloff,
+    /// If `true`, the colliders hidden from the center of the explosion by another collider
+    /// aren’t affected.
+    ///
+    /// This is tested by casting a ray from the center to the closest point of each collider.
+    pub occlusion: bool,
+}
+
+impl Explosion {
+    /// An explosion with a linear falloff and without occlusion.
+    pub fn new(center: Point<Real>, radius: Real, impulse: Real) -> Self {
+        Self {
+            center,
+            radius,
+            impulse,
+            falloff: ExplosionFalloff::Linear,
+            occlusion: false,
+        }
+    }
+
+    /// Applies the impulses of this explosion to the dynamic rigid-bodies within its radius.
+    ///
+    /// This does nothing if the radius isn’t positive.
+    ///
+    /// The `queries` must be up-to-date with the `colliders`. The `filter` selects both the
+    /// colliders affected by the explosion and the colliders that may occlude them. Only the
+    /// colliders attached to dynamic rigid-bodies are affected.
+    pub fn apply(
+        &self,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        queries: &QueryPipeline,
+        filter: QueryFilter,
+    ) {
+        if self.radius <= 0.0 {
+            // The falloff isn’t defined for an empty explosion.
+            return;
+        }
+
+        let mut impulses: Vec<(RigidBodyHandle, Vector<Real>, Point<Real>)> = vec![];
+        let aabb = Aabb::from_half_extents(self.center, Vector::repeat(self.radius));
+
+        queries.colliders_with_aabb_intersecting_aabb(&aabb, |handle| {
+            let co = match colliders.get(*handle) {
+                Some(co) => co,
+                None => return true,
+            };
+            let parent = match co.parent() {
+                Some(parent) if bodies[parent].is_dynamic() => parent,
+                _ => return true,
+            };
+
+            if !filter.test(bodies, *handle, co) {
+                return true;
+            }
+
+            let proj = co.shape.project_point(&co.pos, &self.center, true);
+            let mut dir = proj.point - self.center;
+            let dist = dir.norm();
+
+            if dist > self.radius {
+                return true;
+            }
+
+            if self.occlusion && dist > 0.0 {
+                let ray = Ray::new(self.center, dir / dist);
+                if let Some((hit, _)) =
+                    queries.cast_ray(bodies, colliders, &ray, dist, true, filter)
+                {
+                    // The collider is occluded if the ray hits a collider of another body first.
+                    if colliders[hit].parent() != Some(parent) {
+                        return true;
+                    }
+                }
+            }
+
+            if dist == 0.0 {
+                // The center is inside of the collider, so we push it away from its center of mass.
+                dir = bodies[parent].center_of_mass() - self.center;
+            }
+
+            if let Some(dir) = dir.try_normalize(Real::EPSILON) {
+                let magnitude = self.impulse * self.falloff.eval(dist, self.radius);
+                impulses.push((parent, dir * magnitude, proj.point));
+            }
+
+            true
+        });
+
+        for (handle, impulse, point) in impulses {
+            bodies[handle].apply_impulse_at_point(impulse, point, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Explosion;
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState, QueryFilter};
+
+    #[test]
+    fn explosions_with_a_zero_radius_do_nothing() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // A ball touching the center of the explosion.
+        let body = RigidBodyBuilder::dynamic().translation(Vector::x() * 0.5);
+        let body = state.bodies.insert(body);
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        state.step(&mut pipeline, &(), &());
+
+        Explosion::new(Point::origin(), 0.0, 10.0).apply(
+            &mut state.bodies,
+            &state.colliders,
+            &state.query_pipeline,
+            QueryFilter::default(),
+        );
+        assert_eq!(*state.bodies[body].linvel(), Vector::zeros());
+    }
+
+    #[test]
+    fn explosions_push_the_bodies_in_range_that_are_not_occluded() {
+        use crate::control::Explosion;
+        use crate::math::Point;
+        use crate::pipeline::{PhysicsState, QueryFilter};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let mut ball_at = |x: Real| {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x)
+                .build();
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+            body
+        };
+        let visible = ball_at(2.0);
+        let occluded = ball_at(-2.0);
+        let out_of_range = ball_at(10.0);
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.1, 2.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.1, 2.0, 2.0);
+        state.colliders.insert(wall.translation(Vector::x() * -1.0));
+        state.step(&mut pipeline, &(), &());
+
+        let mut explosion = Explosion::new(Point::origin(), 5.0, 10.0);
+        explosion.occlusion = true;
+        explosion.apply(
+            &mut state.bodies,
+            &state.colliders,
+            &state.query_pipeline,
+            QueryFilter::default(),
+        );
+
+        // The closest point of the visible ball is at a distance 1.5 from the center.
+        let visible = &state.bodies[visible];
+        let expected_vel = 10.0 * (1.0 - 1.5 / 5.0) / visible.mass();
+        assert!((visible.linvel() - Vector::x() * expected_vel).norm() < 1.0e-4);
+        assert_eq!(*state.bodies[occluded].linvel(), Vector::zeros());
+        assert_eq!(*state.bodies[out_of_range].linvel(), Vector::zeros());
+
+        // Without occlusion, the ball behind the wall is pushed too.
+        explosion.occlusion = false;
+        explosion.apply(
+            &mut state.bodies,
+            &state.colliders,
+            &state.query_pipeline,
+            QueryFilter::default(),
+        );
+        assert!(state.bodies[occluded].linvel().x < 0.0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::counters::Timer;
+use crate::math::Real;
 use std::fmt::{Display, Formatter, Result};
 
 /// Performance counters related to constraints resolution.
@@ -8,6 +9,17 @@ pub struct SolverCounters {
     pub nconstraints: usize,
     /// Number of contacts found.
     pub ncontacts: usize,
+    /// The largest number of velocity iterations executed by an island.
+    ///
+    /// This is smaller than `IntegrationParameters::max_velocity_iterations` if the velocity
+    /// iterations stopped early because they converged.
+    pub velocity_iterations: usize,
+    /// The largest change of the delta-velocities of a rigid-body during the last velocity
+    /// iteration of an island.
+    ///
+    /// This is a measure of the convergence error of the velocity iterations: the closer to
+    /// zero, the closer the constraints are to being solved exactly.
+    pub velocity_error: Real,
     /// Time spent for the resolution of the constraints (force computation).
     pub velocity_resolution_time: Timer,
     /// Time spent for the assembly of all the velocity constraints.
@@ -26,6 +38,8 @@ impl SolverCounters {
         SolverCounters {
             nconstraints: 0,
             ncontacts: 0,
+            velocity_iterations: 0,
+            velocity_error: 0.0,
             velocity_assembly_time: Timer::new(),
             velocity_resolution_time: Timer::new(),
             velocity_update_time: Timer::new(),
@@ -38,6 +52,8 @@ impl SolverCounters {
     pub fn reset(&mut self) {
         self.nconstraints = 0;
         self.ncontacts = 0;
+        self.velocity_iterations = 0;
+        self.velocity_error = 0.0;
         self.velocity_resolution_time.reset();
         self.velocity_assembly_time.reset();
         self.velocity_update_time.reset();
@@ -50,6 +66,12 @@ impl Display for SolverCounters {
     fn fmt(&self, f: &mut Formatter) -> Result {
         writeln!(f, "Number of contacts: {}", self.ncontacts)?;
         writeln!(f, "Number of constraints: {}", self.nconstraints)?;
+        writeln!(
+            f,
+            "Number of velocity iterations: {}",
+            self.velocity_iterations
+        )?;
+        writeln!(f, "Velocity error: {}", self.velocity_error)?;
         writeln!(f, "Velocity assembly time: {}", self.velocity_assembly_time)?;
         writeln!(
             f,
@@ -732,6 +732,33 @@ impl<T> Arena<T> {
         }
     }
 
+    /// Iterate in parallel over exclusive references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index, &mut T)` items.
+    ///
+    /// Order of iteration is not defined.
+    #[cfg(feature = "parallel")]
+    pub fn par_iter_mut(&mut self) -> impl rayon::iter::ParallelIterator<Item = (Index, &mut T)>
+    where
+        T: Send,
+    {
+        use rayon::prelude::*;
+
+        self.items
+            .par_iter_mut()
+            .enumerate()
+            .filter_map(|(index, entry)| match entry {
+                Entry::Occupied { generation, value } => Some((
+                    Index {
+                        index: index as u32,
+                        generation: *generation,
+                    },
+                    value,
+                )),
+                Entry::Free { .. } => None,
+            })
+    }
+
     /// Iterate over elements of the arena and remove them.
     ///
     /// Yields pairs of `(Index, T)` items.
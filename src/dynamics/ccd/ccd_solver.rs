@@ -141,6 +141,10 @@ impl CCDSolver {
                     &rb1.integrated_vels,
                     &rb1.mprops,
                 );
+                let predicted_angle1 = rb1
+                    .forces
+                    .integrate(dt, &rb1.integrated_vels, &rb1.mprops)
+                    .rotation_angle(dt);
 
                 for ch1 in &rb1.colliders.0 {
                     let co1 = &colliders[*ch1];
@@ -154,9 +158,11 @@ impl CCDSolver {
                     }
 
                     let predicted_collider_pos1 = predicted_body_pos1 * co1_parent.pos_wrt_parent;
-                    let aabb1 = co1
-                        .shape
-                        .compute_swept_aabb(&co1.pos, &predicted_collider_pos1);
+                    let aabb1 = co1.compute_rotating_swept_aabb(
+                        &predicted_collider_pos1,
+                        &rb1.mprops.world_com,
+                        predicted_angle1,
+                    );
 
                     self.query_pipeline
                         .colliders_with_aabb_intersecting_aabb(&aabb1, |ch2| {
@@ -265,6 +271,10 @@ impl CCDSolver {
                     &rb1.integrated_vels,
                     &rb1.mprops,
                 );
+                let predicted_angle1 = rb1
+                    .forces
+                    .integrate(dt, &rb1.integrated_vels, &rb1.mprops)
+                    .rotation_angle(dt);
 
                 for ch1 in &rb1.colliders.0 {
                     let co1 = &colliders[*ch1];
@@ -274,9 +284,11 @@ impl CCDSolver {
                         .expect("Could not find the ColliderParent component.");
 
                     let predicted_collider_pos1 = predicted_body_pos1 * co_parent1.pos_wrt_parent;
-                    let aabb1 = co1
-                        .shape
-                        .compute_swept_aabb(&co1.pos, &predicted_collider_pos1);
+                    let aabb1 = co1.compute_rotating_swept_aabb(
+                        &predicted_collider_pos1,
+                        &rb1.mprops.world_com,
+                        predicted_angle1,
+                    );
 
                     self.query_pipeline
                         .colliders_with_aabb_intersecting_aabb(&aabb1, |ch2| {
@@ -411,7 +423,11 @@ impl CCDSolver {
                 let rb1 = &bodies[co1_parent.handle];
 
                 let co_next_pos1 = rb1.pos.next_position * co1_parent.pos_wrt_parent;
-                let aabb = co1.shape.compute_swept_aabb(&co1.pos, &co_next_pos1);
+                let aabb = co1.compute_rotating_swept_aabb(
+                    &co_next_pos1,
+                    &rb1.mprops.world_com,
+                    rb1.integrated_vels.rotation_angle(dt),
+                );
 
                 self.query_pipeline
                     .colliders_with_aabb_intersecting_aabb(&aabb, |ch2| {
@@ -559,3 +575,130 @@ impl CCDSolver {
         PredictedImpacts::Impacts(frozen)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn ccd_prevents_tunneling_through_thin_walls() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.05, 10.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.05, 10.0, 10.0);
+        state.colliders.insert(wall);
+
+        // Both bullets travel 5 units per timestep, but only the second one has CCD enabled.
+        let mut bullets = vec![];
+        for (ccd_enabled, y) in [(false, -5.0), (true, 5.0)] {
+            let bullet = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * -2.0 + Vector::y() * y)
+                .linvel(Vector::x() * 300.0)
+                .ccd_enabled(ccd_enabled)
+                .build();
+            let bullet = state.bodies.insert(bullet);
+            let collider = ColliderBuilder::ball(0.1);
+            state
+                .colliders
+                .insert_with_parent(collider, bullet, &mut state.bodies);
+            bullets.push(bullet);
+        }
+
+        step_n(&mut state, &mut pipeline, 10);
+
+        assert!(state.bodies[bullets[0]].translation().x > 0.0);
+        assert!(state.bodies[bullets[1]].translation().x < 0.0);
+    }
+
+    #[test]
+    fn predictive_contacts_prevent_tunneling() {
+        // A small ball shot toward a thin plate, moving by more than its size at each timestep.
+        let simulate = |prediction_distance| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.gravity = Vector::zeros();
+            state.integration_parameters.prediction_distance = prediction_distance;
+
+            let plate = cuboid(10.0, 0.05);
+            state.colliders.insert(plate);
+
+            let ball = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.3)
+                .linvel(Vector::y() * -30.0)
+                .build();
+            let ball = state.bodies.insert(ball);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.1), ball, &mut state.bodies);
+
+            let mut min_dist = Real::MAX;
+            for _ in 0..10 {
+                state.step(&mut pipeline, &(), &());
+
+                for pair in state.narrow_phase.contact_pairs() {
+                    for manifold in &pair.manifolds {
+                        for contact in &manifold.data.solver_contacts {
+                            min_dist = min_dist.min(contact.dist);
+                        }
+                    }
+                }
+            }
+
+            (state.bodies[ball].translation().y, min_dist)
+        };
+
+        // With the default prediction distance, the ball goes through the plate.
+        let (y, _) = simulate(IntegrationParameters::default().prediction_distance);
+        assert!(y < -0.15, "{}", y);
+
+        // With a prediction distance larger than the distance traveled in one timestep, the
+        // predictive contact stops the ball before it penetrates the plate.
+        let (y, min_dist) = simulate(1.0);
+        assert!(y > 0.14, "{}", y);
+        assert!(min_dist > -1.0e-3, "{}", min_dist);
+    }
+
+    #[test]
+    fn ccd_handles_shapes_rotating_by_a_half_turn_in_a_single_step() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // A thin propeller doing a half-turn per timestep, so its AABBs at the beginning and at
+        // the end of the timestep are the same and don’t contain the obstacle.
+        let angvel = std::f32::consts::PI as Real / state.integration_parameters.dt;
+        #[cfg(feature = "dim2")]
+        let propeller = RigidBodyBuilder::dynamic().angvel(angvel);
+        #[cfg(feature = "dim3")]
+        let propeller = RigidBodyBuilder::dynamic().angvel(Vector::z() * angvel);
+        let propeller = state.bodies.insert(propeller.ccd_enabled(true));
+        #[cfg(feature = "dim2")]
+        let blade = ColliderBuilder::cuboid(2.0, 0.05);
+        #[cfg(feature = "dim3")]
+        let blade = ColliderBuilder::cuboid(2.0, 0.05, 0.05);
+        state
+            .colliders
+            .insert_with_parent(blade, propeller, &mut state.bodies);
+        state
+            .colliders
+            .insert(ColliderBuilder::ball(0.2).translation(Vector::y() * 1.5));
+
+        state.step(&mut pipeline, &(), &());
+
+        // The propeller is stopped by the obstacle before being vertical.
+        let angle = state.bodies[propeller].rotation().angle();
+        assert!(
+            angle.abs() < std::f32::consts::FRAC_PI_2 as Real,
+            "{}",
+            angle
+        );
+    }
+}
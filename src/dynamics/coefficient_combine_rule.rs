@@ -38,3 +38,65 @@ impl CoefficientCombineRule {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn coefficient_combine_rules_are_applied_per_collider() {
+        use crate::dynamics::CoefficientCombineRule;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A rubber ground with ice balls on top of it.
+        let ground = cuboid(20.0, 0.5);
+        let ground = state.colliders.insert(
+            ground
+                .translation(Vector::y() * -0.5)
+                .friction(1.0)
+                .restitution(0.5),
+        );
+
+        let rules = [
+            (CoefficientCombineRule::Average, 0.55, 0.3),
+            (CoefficientCombineRule::Min, 0.1, 0.1),
+            (CoefficientCombineRule::Multiply, 0.1, 0.05),
+            (CoefficientCombineRule::Max, 1.0, 0.5),
+        ];
+        let mut balls = vec![];
+        for (i, (rule, _, _)) in rules.iter().enumerate() {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 3.0 * i as Real + Vector::y() * 0.5);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5)
+                .friction(0.1)
+                .restitution(0.1)
+                .friction_combine_rule(*rule)
+                .restitution_combine_rule(*rule);
+            balls.push(
+                state
+                    .colliders
+                    .insert_with_parent(collider, body, &mut state.bodies),
+            );
+        }
+
+        state.step(&mut pipeline, &(), &());
+
+        for (ball, (rule, friction, restitution)) in balls.iter().zip(rules.iter()) {
+            let pair = state.narrow_phase.contact_pair(ground, *ball).unwrap();
+            let contact = &pair.manifolds[0].data.solver_contacts[0];
+            assert!((contact.friction - friction).abs() < 1.0e-5, "{:?}", rule);
+            assert!(
+                (contact.restitution - restitution).abs() < 1.0e-5,
+                "{:?}",
+                rule
+            );
+        }
+    }
+}
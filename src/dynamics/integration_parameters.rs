@@ -41,14 +41,49 @@ pub struct IntegrationParameters {
     pub allowed_linear_error: Real,
     /// Maximum amount of penetration the solver will attempt to resolve in one timestep.
     pub max_penetration_correction: Real,
+    /// The penetration depth below which the penetrations are corrected more gently (default: `0.0`).
+    ///
+    /// The ERP of a rigid contact penetrating by less than this depth (beyond the
+    /// `allowed_linear_error`) is scaled down proportionally to its depth. This avoids the jitter
+    /// caused by shallow penetrations being corrected back and forth at each timestep. Set to
+    /// `0.0` so that all the penetrations are corrected with the full `erp`. The contacts
+    /// involving multibodies aren’t affected.
+    pub shallow_penetration_depth: Real,
+    /// The maximum velocity the solver applies to correct the penetration of a rigid contact
+    /// (default: `Real::MAX`).
+    ///
+    /// This keeps deep penetrations, e.g., of objects spawned inside each other, from being
+    /// resolved explosively. They are instead corrected over several timesteps. The contacts
+    /// involving multibodies aren’t affected.
+    pub max_corrective_velocity: Real,
     /// The maximal distance separating two objects that will generate predictive contacts (default: `0.002`).
+    ///
+    /// Contacts between colliders separated by less than this distance are given to the
+    /// constraints solver with a positive `SolverContact::dist`. The solver then only lets them
+    /// get closer by this distance during the next timestep, which prevents tunneling for
+    /// objects moving by less than `prediction_distance` per timestep, and avoids the popping
+    /// artifacts happening when contacts start being solved only after the objects penetrate.
+    /// Larger values make the collision-detection costlier, and may result in objects slowing
+    /// down slightly before they actually touch.
     pub prediction_distance: Real,
     /// Maximum number of iterations performed to solve non-penetration and joint constraints (default: `4`).
     pub max_velocity_iterations: usize,
     /// Maximum number of iterations performed to solve friction constraints (default: `8`).
     pub max_velocity_friction_iterations: usize,
     /// Maximum number of iterations performed to remove the energy introduced by penetration corrections  (default: `1`).
+    ///
+    /// These are the position iterations of the solver: they run after the positions were
+    /// integrated with the velocities correcting the penetrations and joint errors, and solve the
+    /// constraints again without this correction so that it doesn’t remain in the velocities of
+    /// the bodies. Increase them for tall stacks where the bodies keep bouncing off each other,
+    /// and increase `max_velocity_iterations` for complex machinery.
     pub max_stabilization_iterations: usize,
+    /// The velocity iterations of an island stop as soon as none of its delta-velocities change by
+    /// more than this amount during one iteration (default: `0.0`, i.e., never stop early).
+    pub velocity_iterations_tolerance: Real,
+    /// The stabilization iterations of an island stop as soon as none of its delta-velocities change
+    /// by more than this amount during one iteration (default: `0.0`, i.e., never stop early).
+    pub stabilization_iterations_tolerance: Real,
     /// If `false`, friction and non-penetration constraints will be solved in the same loop. Otherwise,
     /// non-penetration constraints are solved first, and friction constraints are solved after (default: `true`).
     pub interleave_restitution_and_friction_resolution: bool,
@@ -56,6 +91,54 @@ pub struct IntegrationParameters {
     pub min_island_size: usize,
     /// Maximum number of substeps performed by the  solver (default: `1`).
     pub max_ccd_substeps: usize,
+    /// Number of substeps the constraints resolution is split into (default: `1`).
+    ///
+    /// With `n` substeps, the constraints are rebuilt and solved `n` times, each time for a
+    /// timestep of length `dt / n`, and the rigid-bodies are integrated after each substep. The
+    /// collision detection is only run once per timestep though: between substeps, the distances
+    /// of the contacts are updated from the relative velocities of the bodies. This makes
+    /// stacks with large mass ratios and long joint chains much more stable than increasing
+    /// `max_velocity_iterations`, at the cost of a slower constraints resolution.
+    ///
+    /// The impulses stored in the contacts and joints are the ones applied during the last
+    /// substep.
+    pub num_solver_substeps: usize,
+    /// 0-1: multiplier applied to the non-penetration impulses of the previous timestep to
+    /// initialize the constraints solver (default: `1.0`).
+    ///
+    /// These impulses are stored in the contacts, and are part of the
+    /// [`PhysicsState`](crate::pipeline::PhysicsState) and of the
+    /// [`DynamicsSnapshot`](crate::pipeline::DynamicsSnapshot). Warm starting the solver greatly
+    /// improves the convergence of resting contacts, e.g. in stacks. Set this to `0.0` to disable
+    /// warm starting of the contacts, so that their result doesn’t depend on the impulses of the
+    /// previous timestep. The friction impulses, and the contacts involving multibodies, are
+    /// never warm-started. The joints are warm-started with `joint_warmstart_coefficient`
+    /// instead.
+    pub warmstart_coefficient: Real,
+    /// 0-1: multiplier applied to the joint impulses of the previous timestep to initialize the
+    /// constraints solver (default: `0.5`).
+    ///
+    /// These impulses are stored in [`ImpulseJoint::impulses`](crate::dynamics::ImpulseJoint::impulses),
+    /// and in the limits and motors of the joints. Warm starting the joints reduces the
+    /// stretching of long joint chains. Values close to `1.0` can make rigid joints chains
+    /// oscillate when the solver doesn’t converge within the `max_velocity_iterations`, because
+    /// the impulses correcting the joint errors then accumulate from one timestep to the next.
+    /// The friction and coupling impulses of the joints are never warm-started. Set this to
+    /// `0.0` to disable the warm starting of the joints.
+    pub joint_warmstart_coefficient: Real,
+    /// If `true`, a shock-propagation pass is run after the velocity iterations to stabilize
+    /// stacks and piles (default: `false`).
+    ///
+    /// This pass processes the contacts from the bottom of each stack to its top, i.e., by
+    /// increasing number of contacts separating their bodies from a fixed or kinematic body,
+    /// and treats the lower body of each contact as if it had an infinite mass. This lets tall
+    /// stacks stand still with few solver iterations. Because this pass only pushes the upper
+    /// bodies, it doesn’t conserve momentum, so it is best left disabled if the simulation
+    /// doesn’t involve stacking. Soft contacts and contacts involving multibodies aren’t affected.
+    pub shock_propagation: bool,
+    /// If `false`, rigid-bodies are never automatically put to sleep, even if they remain still
+    /// long enough (default: `true`).
+    pub sleeping_enabled: bool,
 }
 
 impl IntegrationParameters {
@@ -157,10 +240,14 @@ impl Default for IntegrationParameters {
             joint_damping_ratio: 1.0,
             allowed_linear_error: 0.001, // 0.005
             max_penetration_correction: Real::MAX,
+            shallow_penetration_depth: 0.0,
+            max_corrective_velocity: Real::MAX,
             prediction_distance: 0.002,
             max_velocity_iterations: 4,
             max_velocity_friction_iterations: 8,
             max_stabilization_iterations: 1,
+            velocity_iterations_tolerance: 0.0,
+            stabilization_iterations_tolerance: 0.0,
             interleave_restitution_and_friction_resolution: true, // Enabling this makes a big difference for 2D stability.
             // TODO: what is the optimal value for min_island_size?
             // It should not be too big so that we don't end up with
@@ -169,6 +256,302 @@ impl Default for IntegrationParameters {
             // tons of islands, reducing SIMD parallelism opportunities.
             min_island_size: 128,
             max_ccd_substeps: 1,
+            num_solver_substeps: 1,
+            warmstart_coefficient: 1.0,
+            joint_warmstart_coefficient: 0.5,
+            shock_propagation: false,
+            sleeping_enabled: true,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{IntegrationParameters, RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn solver_substeps_stabilize_large_mass_ratios() {
+        use crate::pipeline::PhysicsState;
+
+        // A heavy box resting on a small and light box lying on the ground. Returns how deep the
+        // heavy box sank into the small one.
+        fn sinking_depth(num_solver_substeps: usize) -> Real {
+            let mut state = PhysicsState::new();
+            state.integration_parameters.num_solver_substeps = num_solver_substeps;
+            #[cfg(feature = "dim2")]
+            let (ground, light, heavy) = (
+                ColliderBuilder::cuboid(20.0, 0.5),
+                ColliderBuilder::cuboid(0.1, 0.1),
+                ColliderBuilder::cuboid(0.5, 0.5),
+            );
+            #[cfg(feature = "dim3")]
+            let (ground, light, heavy) = (
+                ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+                ColliderBuilder::cuboid(0.1, 0.1, 0.1),
+                ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            );
+            state.colliders.insert(ground);
+
+            let light_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 0.6)
+                .build();
+            let light_body = state.bodies.insert(light_body);
+            state
+                .colliders
+                .insert_with_parent(light, light_body, &mut state.bodies);
+
+            let heavy_body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.2)
+                .build();
+            let heavy_body = state.bodies.insert(heavy_body);
+            state
+                .colliders
+                .insert_with_parent(heavy, heavy_body, &mut state.bodies);
+
+            let mut pipeline = PhysicsPipeline::new();
+            let mut max_depth: Real = 0.0;
+            for _ in 0..120 {
+                state.step(&mut pipeline, &(), &());
+                let y = state.bodies[heavy_body].translation().y;
+                max_depth = max_depth.max(1.2 - y);
+            }
+
+            max_depth
+        }
+
+        let single_step_depth = sinking_depth(1);
+        let substepped_depth = sinking_depth(8);
+        assert!(substepped_depth < single_step_depth);
+        assert!(substepped_depth < 0.02, "sank by {}", substepped_depth);
+    }
+
+    #[test]
+    fn iterations_stop_early_once_they_converged() {
+        use crate::pipeline::PhysicsState;
+
+        // A heavy box resting on a small and light box lying on the ground. Returns how deep the
+        // heavy box sank into the small one.
+        fn sinking_depth(integration_parameters: IntegrationParameters) -> Real {
+            let mut state = PhysicsState::new();
+            state.integration_parameters = integration_parameters;
+            #[cfg(feature = "dim2")]
+            let (ground, light, heavy) = (
+                ColliderBuilder::cuboid(20.0, 0.5),
+                ColliderBuilder::cuboid(0.1, 0.1),
+                ColliderBuilder::cuboid(0.5, 0.5),
+            );
+            #[cfg(feature = "dim3")]
+            let (ground, light, heavy) = (
+                ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+                ColliderBuilder::cuboid(0.1, 0.1, 0.1),
+                ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+            );
+            state.colliders.insert(ground);
+
+            let bodies = [(light, 0.6), (heavy.density(0.2), 1.2)].map(|(collider, y)| {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * y)
+                    .build();
+                let body = state.bodies.insert(body);
+                state
+                    .colliders
+                    .insert_with_parent(collider, body, &mut state.bodies);
+                body
+            });
+            let heavy_body = bodies[1];
+
+            let mut pipeline = PhysicsPipeline::new();
+            let mut max_depth: Real = 0.0;
+            for _ in 0..120 {
+                state.step(&mut pipeline, &(), &());
+                let y = state.bodies[heavy_body].translation().y;
+                max_depth = max_depth.max(1.2 - y);
+            }
+            max_depth
+        }
+
+        // With a huge tolerance, the solver stops after the first iteration.
+        let early_out_depth = sinking_depth(IntegrationParameters {
+            max_velocity_iterations: 4,
+            max_velocity_friction_iterations: 1,
+            velocity_iterations_tolerance: 1.0e6,
+            ..IntegrationParameters::default()
+        });
+        let single_iteration_depth = sinking_depth(IntegrationParameters {
+            max_velocity_iterations: 1,
+            max_velocity_friction_iterations: 1,
+            ..IntegrationParameters::default()
+        });
+        assert_eq!(early_out_depth, single_iteration_depth);
+
+        // The same goes for the stabilization iterations.
+        let early_out_depth = sinking_depth(IntegrationParameters {
+            max_stabilization_iterations: 10,
+            stabilization_iterations_tolerance: 1.0e6,
+            ..IntegrationParameters::default()
+        });
+        let single_iteration_depth = sinking_depth(IntegrationParameters {
+            max_stabilization_iterations: 1,
+            ..IntegrationParameters::default()
+        });
+        assert_eq!(early_out_depth, single_iteration_depth);
+        assert_ne!(
+            single_iteration_depth,
+            sinking_depth(IntegrationParameters {
+                max_stabilization_iterations: 10,
+                ..IntegrationParameters::default()
+            })
+        );
+    }
+
+    #[test]
+    fn warmstarting_improves_the_convergence_of_stacks() {
+        use crate::math::Real;
+        use crate::pipeline::PhysicsState;
+
+        let top_height = |warmstart_coefficient: Real| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.integration_parameters.warmstart_coefficient = warmstart_coefficient;
+
+            let ground = cuboid(100.0, 0.5);
+            state.colliders.insert(ground);
+
+            let mut top = None;
+            for i in 0..8 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (1.0 + i as Real))
+                    .build();
+                let body = state.bodies.insert(body);
+                let cube = cuboid(0.5, 0.5);
+                state
+                    .colliders
+                    .insert_with_parent(cube, body, &mut state.bodies);
+                top = Some(body);
+            }
+
+            step_n(&mut state, &mut pipeline, 120);
+
+            state.bodies[top.unwrap()].translation().y
+        };
+
+        let warmstarted = top_height(1.0);
+        let cold = top_height(0.0);
+        // Without warm starting, the few solver iterations can't propagate the weight of the
+        // whole stack down to the ground, so the cubes sink further into each other.
+        assert!((warmstarted - 8.0).abs() < 0.1);
+        assert!(cold < warmstarted - 0.01);
+    }
+
+    #[test]
+    fn joint_warmstarting_improves_the_convergence_of_chains() {
+        use crate::pipeline::PhysicsState;
+
+        let max_anchor_distance = |joint_warmstart_coefficient: Real| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.integration_parameters.joint_warmstart_coefficient = joint_warmstart_coefficient;
+
+            // A chain of balls hanging from a fixed body.
+            let mut parent = state.bodies.insert(RigidBodyBuilder::fixed().build());
+            let mut joints = vec![];
+            for i in 1..=10 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(-Vector::y() * (i as Real * 0.5))
+                    .build();
+                let body = state.bodies.insert(body);
+                state.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.2),
+                    body,
+                    &mut state.bodies,
+                );
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = RevoluteJointBuilder::new(Vector::z_axis());
+                let joint = joint.local_anchor1(Point::from(-Vector::y() * 0.5));
+                joints.push(state.impulse_joints.insert(parent, body, joint, true));
+                parent = body;
+            }
+
+            step_n(&mut state, &mut pipeline, 120);
+
+            joints
+                .iter()
+                .map(|handle| {
+                    let joint = state.impulse_joints.get(*handle).unwrap();
+                    let anchor1 = state.bodies[joint.body1].position()
+                        * Point::from(joint.data.local_frame1.translation.vector);
+                    let anchor2 = state.bodies[joint.body2].position()
+                        * Point::from(joint.data.local_frame2.translation.vector);
+                    na::distance(&anchor1, &anchor2)
+                })
+                .fold(0.0, Real::max)
+        };
+
+        let warmstarted = max_anchor_distance(0.5);
+        let cold = max_anchor_distance(0.0);
+        // Without warm starting, the few solver iterations can't propagate the weight of the
+        // whole chain up to its fixed body, so the chain stretches more.
+        assert!(warmstarted < cold * 0.75, "{} vs. {}", warmstarted, cold);
+    }
+
+    #[test]
+    fn penetration_correction_is_gentle_for_shallow_and_capped_for_deep_penetrations() {
+        use crate::dynamics::IntegrationParameters;
+        use crate::math::Real;
+        use crate::pipeline::PhysicsState;
+
+        // The distance a cube penetrating the ground is pushed back by during one timestep.
+        let correction = |penetration: Real, params: IntegrationParameters| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.gravity = Vector::zeros();
+            state.integration_parameters = params;
+
+            let ground = cuboid(100.0, 0.5);
+            state.colliders.insert(ground);
+
+            let y = 1.0 - penetration;
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * y)
+                .build();
+            let body = state.bodies.insert(body);
+            let cube = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(cube, body, &mut state.bodies);
+
+            state.step(&mut pipeline, &(), &());
+            state.bodies[body].translation().y - y
+        };
+
+        let default_params = IntegrationParameters::default();
+
+        // Deep penetrations are resolved over several timesteps.
+        let capped_params = IntegrationParameters {
+            max_corrective_velocity: 1.0,
+            ..default_params
+        };
+        assert!(correction(0.3, default_params) > 0.1);
+        let capped_correction = correction(0.3, capped_params);
+        assert!(capped_correction > 0.0);
+        assert!(capped_correction <= capped_params.dt * 1.001);
+
+        // Shallow penetrations are corrected more gently.
+        let ramp_params = IntegrationParameters {
+            shallow_penetration_depth: 0.1,
+            ..default_params
+        };
+        let full_correction = correction(0.01, default_params);
+        let gentle_correction = correction(0.01, ramp_params);
+        assert!(gentle_correction > 0.0);
+        assert!(gentle_correction < full_correction * 0.2);
+        // Deeper penetrations are still corrected with the full ERP.
+        assert!((correction(0.3, ramp_params) - correction(0.3, default_params)).abs() < 1.0e-5);
+    }
+}
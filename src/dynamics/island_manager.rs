@@ -5,6 +5,7 @@ use crate::dynamics::{
 use crate::geometry::{ColliderSet, NarrowPhase};
 use crate::math::Real;
 use crate::utils::WDot;
+use std::collections::HashSet;
 
 /// Structure responsible for maintaining the set of active rigid-bodies, and
 /// putting non-moving rigid-bodies to sleep to save computation times.
@@ -14,7 +15,7 @@ pub struct IslandManager {
     pub(crate) active_dynamic_set: Vec<RigidBodyHandle>,
     pub(crate) active_kinematic_set: Vec<RigidBodyHandle>,
     pub(crate) active_islands: Vec<usize>,
-    active_set_timestamp: u32,
+    pub(crate) active_set_timestamp: u32,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     can_sleep: Vec<RigidBodyHandle>, // Workspace.
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
@@ -34,8 +35,15 @@ impl IslandManager {
         }
     }
 
-    pub(crate) fn num_islands(&self) -> usize {
-        self.active_islands.len() - 1
+    /// The number of active islands computed during the last timestep.
+    ///
+    /// An island is a set of awake dynamic rigid-bodies interacting with each other through
+    /// contacts or joints. Note that islands smaller than
+    /// [`IntegrationParameters::min_island_size`](crate::dynamics::IntegrationParameters::min_island_size)
+    /// are merged together, so one active island may contain several independent groups of
+    /// rigid-bodies. This includes one empty island if no dynamic rigid-body is awake.
+    pub fn num_islands(&self) -> usize {
+        self.active_islands.len().saturating_sub(1)
     }
 
     /// Update this data-structure after one or multiple rigid-bodies have been removed for `bodies`.
@@ -112,6 +120,52 @@ impl IslandManager {
         }
     }
 
+    /// Iterates through all the non-empty active islands computed during the last timestep.
+    ///
+    /// See [`IslandManager::num_islands`] for details about islands.
+    pub fn active_islands(&self) -> impl Iterator<Item = &[RigidBodyHandle]> {
+        (0..self.num_islands())
+            .map(move |island_id| self.active_island(island_id))
+            .filter(|island| !island.is_empty())
+    }
+
+    /// Wakes up the given dynamic rigid-body, as well as all the dynamic rigid-bodies it touches
+    /// or is attached to with a joint, directly or indirectly.
+    ///
+    /// The sleeping state isn’t propagated through non-dynamic rigid-bodies: two dynamic
+    /// rigid-bodies only touching a common fixed ground aren’t woken up together.
+    /// If `strong` is `true` then it is assured that the rigid-bodies will
+    /// remain awake during multiple subsequent timesteps.
+    pub fn wake_up_island(
+        &mut self,
+        bodies: &mut RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+        impulse_joints: &ImpulseJointSet,
+        multibody_joints: &MultibodyJointSet,
+        handle: RigidBodyHandle,
+        strong: bool,
+    ) {
+        let mut visited = HashSet::new();
+        let mut stack = vec![handle];
+
+        while let Some(handle) = stack.pop() {
+            let rb = match bodies.get(handle) {
+                Some(rb) if rb.is_dynamic() && visited.insert(handle) => rb,
+                _ => continue,
+            };
+
+            push_contacting_bodies(&rb.colliders, colliders, narrow_phase, &mut stack);
+
+            for inter in impulse_joints.attached_enabled_joints(handle) {
+                stack.push(crate::utils::select_other((inter.0, inter.1), handle));
+            }
+
+            stack.extend(multibody_joints.bodies_attached_with_enabled_joint(handle));
+            self.wake_up(bodies, handle, strong);
+        }
+    }
+
     /// Iter through all the active kinematic rigid-bodies on this set.
     pub fn active_kinematic_bodies(&self) -> &[RigidBodyHandle] {
         &self.active_kinematic_set[..]
@@ -122,7 +176,11 @@ impl IslandManager {
         &self.active_dynamic_set[..]
     }
 
-    pub(crate) fn active_island(&self, island_id: usize) -> &[RigidBodyHandle] {
+    /// The awake dynamic rigid-bodies of the `island_id`-th active island, with
+    /// `island_id < self.num_islands()`.
+    ///
+    /// See [`IslandManager::num_islands`] for details about islands.
+    pub fn active_island(&self, island_id: usize) -> &[RigidBodyHandle] {
         let island_range = self.active_islands[island_id]..self.active_islands[island_id + 1];
         &self.active_dynamic_set[island_range]
     }
@@ -149,6 +207,7 @@ impl IslandManager {
         impulse_joints: &ImpulseJointSet,
         multibody_joints: &MultibodyJointSet,
         min_island_size: usize,
+        sleeping_enabled: bool,
     ) {
         assert!(
             min_island_size > 0,
@@ -176,7 +235,8 @@ impl IslandManager {
 
             update_energy(&mut rb.activation, sq_linvel, sq_angvel, dt);
 
-            if rb.activation.time_since_can_sleep >= RigidBodyActivation::default_time_until_sleep()
+            if sleeping_enabled
+                && rb.activation.time_since_can_sleep >= rb.activation.time_until_sleep
             {
                 // Mark them as sleeping for now. This will
                 // be set to false during the graph traversal
@@ -188,32 +248,6 @@ impl IslandManager {
             }
         }
 
-        // Read all the contacts and push objects touching touching this rigid-body.
-        #[inline(always)]
-        fn push_contacting_bodies(
-            rb_colliders: &RigidBodyColliders,
-            colliders: &ColliderSet,
-            narrow_phase: &NarrowPhase,
-            stack: &mut Vec<RigidBodyHandle>,
-        ) {
-            for collider_handle in &rb_colliders.0 {
-                for inter in narrow_phase.contacts_with(*collider_handle) {
-                    for manifold in &inter.manifolds {
-                        if !manifold.data.solver_contacts.is_empty() {
-                            let other = crate::utils::select_other(
-                                (inter.collider1, inter.collider2),
-                                *collider_handle,
-                            );
-                            if let Some(other_body) = colliders[other].parent {
-                                stack.push(other_body.handle);
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
         // Now iterate on all active kinematic bodies and push all the bodies
         // touching them to the stack so they can be woken up.
         for h in self.active_kinematic_set.iter() {
@@ -300,6 +334,32 @@ impl IslandManager {
     }
 }
 
+// Read all the contacts and push objects touching touching this rigid-body.
+#[inline(always)]
+fn push_contacting_bodies(
+    rb_colliders: &RigidBodyColliders,
+    colliders: &ColliderSet,
+    narrow_phase: &NarrowPhase,
+    stack: &mut Vec<RigidBodyHandle>,
+) {
+    for collider_handle in &rb_colliders.0 {
+        for inter in narrow_phase.contacts_with(*collider_handle) {
+            for manifold in &inter.manifolds {
+                if !manifold.data.solver_contacts.is_empty() {
+                    let other = crate::utils::select_other(
+                        (inter.collider1, inter.collider2),
+                        *collider_handle,
+                    );
+                    if let Some(other_body) = colliders[other].parent {
+                        stack.push(other_body.handle);
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
 fn update_energy(activation: &mut RigidBodyActivation, sq_linvel: Real, sq_angvel: Real, dt: Real) {
     if sq_linvel < activation.linear_threshold * activation.linear_threshold.abs()
         && sq_angvel < activation.angular_threshold * activation.angular_threshold.abs()
@@ -309,3 +369,87 @@ fn update_energy(activation: &mut RigidBodyActivation, sq_linvel: Real, sq_angve
         activation.time_since_can_sleep = 0.0;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn islands_can_be_iterated_and_woken_up() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.integration_parameters.min_island_size = 1;
+
+        #[cfg(feature = "dim2")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(20.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        // A stack of three cubes, and a cube resting on its own.
+        let mut cube_at = |pos: Vector<Real>| {
+            let body = state
+                .bodies
+                .insert(RigidBodyBuilder::dynamic().translation(pos));
+            state
+                .colliders
+                .insert_with_parent(cube.clone(), body, &mut state.bodies);
+            body
+        };
+        let stack: Vec<_> = (0..3)
+            .map(|i| cube_at(Vector::y() * (0.5 + i as Real)))
+            .collect();
+        let single = cube_at(Vector::x() * 10.0 + Vector::y() * 0.5);
+
+        // The stack and the single cube are independent islands.
+        step_n(&mut state, &mut pipeline, 1);
+        let mut island_sizes: Vec<_> = state
+            .islands
+            .active_islands()
+            .map(|island| island.len())
+            .collect();
+        island_sizes.sort();
+        assert_eq!(island_sizes, vec![1, 3]);
+        assert_eq!(state.islands.num_islands(), 2);
+
+        // Wait for everything to fall asleep.
+        step_n(&mut state, &mut pipeline, 300);
+        assert!(state
+            .bodies
+            .iter()
+            .all(|(_, rb)| rb.is_sleeping() || rb.is_fixed()));
+        assert!(state.islands.active_dynamic_bodies().is_empty());
+        assert_eq!(state.islands.active_islands().count(), 0);
+
+        // Waking the top of the stack wakes the whole stack, but not the other cube.
+        state.islands.wake_up_island(
+            &mut state.bodies,
+            &state.colliders,
+            &state.narrow_phase,
+            &state.impulse_joints,
+            &state.multibody_joints,
+            stack[2],
+            true,
+        );
+        assert!(stack.iter().all(|h| !state.bodies[*h].is_sleeping()));
+        assert!(state.bodies[single].is_sleeping());
+
+        step_n(&mut state, &mut pipeline, 1);
+        assert_eq!(state.islands.num_islands(), 1);
+        let mut island = state.islands.active_island(0).to_vec();
+        island.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(island, stack);
+    }
+}
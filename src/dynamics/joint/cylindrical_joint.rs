@@ -0,0 +1,351 @@
+use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
+use crate::dynamics::{JointAxis, JointLimits, JointMotor, MotorModel};
+use crate::math::{Point, Real, UnitVector};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// A cylindrical joint, locks all relative motion between two bodies except for translation and
+/// rotation along the joint’s principal axis.
+///
+/// Its translational degree of freedom is identified by `JointAxis::X` and its rotational degree
+/// of freedom by `JointAxis::AngX`.
+pub struct CylindricalJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl CylindricalJoint {
+    /// Creates a new cylindrical joint allowing only relative translations and rotations along
+    /// the specified axis.
+    ///
+    /// This axis is expressed in the local-space of both rigid-bodies.
+    pub fn new(axis: UnitVector<Real>) -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::LOCKED_CYLINDRICAL_AXES)
+            .local_axis1(axis)
+            .local_axis2(axis)
+            .build();
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    pub fn data(&self) -> &GenericJoint {
+        &self.data
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(&self) -> Point<Real> {
+        self.data.local_anchor1()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    pub fn set_local_anchor1(&mut self, anchor1: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(&self) -> Point<Real> {
+        self.data.local_anchor2()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    pub fn set_local_anchor2(&mut self, anchor2: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// The principal axis of the joint, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_axis1(&self) -> UnitVector<Real> {
+        self.data.local_axis1()
+    }
+
+    /// Sets the principal axis of the joint, expressed in the local-space of the first rigid-body.
+    pub fn set_local_axis1(&mut self, axis1: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis1(axis1);
+        self
+    }
+
+    /// The principal axis of the joint, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_axis2(&self) -> UnitVector<Real> {
+        self.data.local_axis2()
+    }
+
+    /// Sets the principal axis of the joint, expressed in the local-space of the second rigid-body.
+    pub fn set_local_axis2(&mut self, axis2: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis2(axis2);
+        self
+    }
+
+    /// The motor affecting the joint’s degree of freedom along the specified axis.
+    #[must_use]
+    pub fn motor(&self, axis: JointAxis) -> Option<&JointMotor> {
+        self.data.motor(axis)
+    }
+
+    /// Set the spring-like model used by the motor to reach the desired target velocity and position.
+    pub fn set_motor_model(&mut self, axis: JointAxis, model: MotorModel) -> &mut Self {
+        self.data.set_motor_model(axis, model);
+        self
+    }
+
+    /// Sets the target velocity this motor needs to reach.
+    pub fn set_motor_velocity(
+        &mut self,
+        axis: JointAxis,
+        target_vel: Real,
+        factor: Real,
+    ) -> &mut Self {
+        self.data.set_motor_velocity(axis, target_vel, factor);
+        self
+    }
+
+    /// Sets the target position (distance or angle) this motor needs to reach.
+    pub fn set_motor_position(
+        &mut self,
+        axis: JointAxis,
+        target_pos: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor_position(axis, target_pos, stiffness, damping);
+        self
+    }
+
+    /// Configure both the target position and target velocity of the motor.
+    pub fn set_motor(
+        &mut self,
+        axis: JointAxis,
+        target_pos: Real,
+        target_vel: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> &mut Self {
+        self.data
+            .set_motor(axis, target_pos, target_vel, stiffness, damping);
+        self
+    }
+
+    /// Sets the maximum force the motor can deliver along the specified axis.
+    pub fn set_motor_max_force(&mut self, axis: JointAxis, max_force: Real) -> &mut Self {
+        self.data.set_motor_max_force(axis, max_force);
+        self
+    }
+
+    /// The limits of the relative motion along the specified axis.
+    #[must_use]
+    pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
+        self.data.limits(axis)
+    }
+
+    /// Sets the `[min,max]` limit distances (for `JointAxis::X`) or angles (for `JointAxis::AngX`)
+    /// of the relative motion along the specified axis.
+    pub fn set_limits(&mut self, axis: JointAxis, limits: [Real; 2]) -> &mut Self {
+        self.data.set_limits(axis, limits);
+        self
+    }
+}
+
+impl Into<GenericJoint> for CylindricalJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create cylindrical joints using the builder pattern.
+///
+/// A cylindrical joint locks all relative motion except for translations and rotations along the
+/// joint’s principal axis.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CylindricalJointBuilder(pub CylindricalJoint);
+
+impl CylindricalJointBuilder {
+    /// Creates a new builder for cylindrical joints.
+    ///
+    /// This axis is expressed in the local-space of both rigid-bodies.
+    pub fn new(axis: UnitVector<Real>) -> Self {
+        Self(CylindricalJoint::new(axis))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.0.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.0.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// Sets the principal axis of the joint, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_axis1(mut self, axis1: UnitVector<Real>) -> Self {
+        self.0.set_local_axis1(axis1);
+        self
+    }
+
+    /// Sets the principal axis of the joint, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_axis2(mut self, axis2: UnitVector<Real>) -> Self {
+        self.0.set_local_axis2(axis2);
+        self
+    }
+
+    /// Set the spring-like model used by the motor to reach the desired target velocity and position.
+    #[must_use]
+    pub fn motor_model(mut self, axis: JointAxis, model: MotorModel) -> Self {
+        self.0.set_motor_model(axis, model);
+        self
+    }
+
+    /// Sets the target velocity this motor needs to reach.
+    #[must_use]
+    pub fn motor_velocity(mut self, axis: JointAxis, target_vel: Real, factor: Real) -> Self {
+        self.0.set_motor_velocity(axis, target_vel, factor);
+        self
+    }
+
+    /// Sets the target position (distance or angle) this motor needs to reach.
+    #[must_use]
+    pub fn motor_position(
+        mut self,
+        axis: JointAxis,
+        target_pos: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        self.0
+            .set_motor_position(axis, target_pos, stiffness, damping);
+        self
+    }
+
+    /// Configure both the target position and target velocity of the motor.
+    #[must_use]
+    pub fn motor(
+        mut self,
+        axis: JointAxis,
+        target_pos: Real,
+        target_vel: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        self.0
+            .set_motor(axis, target_pos, target_vel, stiffness, damping);
+        self
+    }
+
+    /// Sets the maximum force the motor can deliver along the specified axis.
+    #[must_use]
+    pub fn motor_max_force(mut self, axis: JointAxis, max_force: Real) -> Self {
+        self.0.set_motor_max_force(axis, max_force);
+        self
+    }
+
+    /// Sets the `[min,max]` limits of the relative motion along the specified axis.
+    #[must_use]
+    pub fn limits(mut self, axis: JointAxis, limits: [Real; 2]) -> Self {
+        self.0.set_limits(axis, limits);
+        self
+    }
+
+    /// Builds the cylindrical joint.
+    #[must_use]
+    pub fn build(self) -> CylindricalJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for CylindricalJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+    #[test]
+    fn cylindrical_and_universal_joints_free_axes() {
+        use crate::dynamics::{CylindricalJointBuilder, GenericJoint, UniversalJointBuilder};
+        use crate::math::Real;
+
+        let joints: [(GenericJoint, Vector<Real>, Vector<Real>); 2] = [
+            (
+                CylindricalJointBuilder::new(Vector::x_axis()).into(),
+                Vector::x(),
+                Vector::x(),
+            ),
+            (
+                UniversalJointBuilder::new(Vector::x_axis()).into(),
+                Vector::zeros(),
+                Vector::new(0.0, 1.0, 1.0),
+            ),
+        ];
+
+        for (joint, expected_linvel, expected_angvel) in joints {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.gravity = Vector::zeros();
+
+            let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+            let body = RigidBodyBuilder::dynamic()
+                .linvel(Vector::repeat(1.0))
+                .angvel(Vector::repeat(1.0))
+                .build();
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).build();
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            state.impulse_joints.insert(ground, body, joint, true);
+
+            state.step(&mut pipeline, &(), &());
+
+            // Only the velocities along the free axes are preserved.
+            let linvel = state.bodies[body].linvel();
+            let angvel = state.bodies[body].angvel();
+            assert!(
+                (linvel - expected_linvel).norm() < 1.0e-2,
+                "linvel: {}",
+                linvel
+            );
+            assert!(
+                (angvel - expected_angvel).norm() < 1.0e-2,
+                "angvel: {}",
+                angvel
+            );
+        }
+    }
+}
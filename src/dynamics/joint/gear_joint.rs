@@ -0,0 +1,256 @@
+use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask, JointCoupling};
+use crate::math::Real;
+
+#[cfg(feature = "dim3")]
+use crate::math::UnitVector;
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// A gear joint, couples the angular velocities of two bodies by a ratio.
+///
+/// The angular velocities of both bodies along their respective joint axis are constrained
+/// such that `angvel1 + ratio * angvel2 = 0`. This is typically used together with revolute
+/// joints attaching each gear to a common fixed body.
+///
+/// Gear joints don’t lock any degree of freedom, and only constrain velocities: their angles
+/// may drift apart over time.
+pub struct GearJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl GearJoint {
+    /// Creates a new gear joint with the given ratio.
+    pub fn new(ratio: Real) -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::FREE_FIXED_AXES)
+            .coupling(JointCoupling::Gear { ratio })
+            .build();
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    pub fn data(&self) -> &GenericJoint {
+        &self.data
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The gear ratio.
+    #[must_use]
+    pub fn ratio(&self) -> Real {
+        match self.data.coupling {
+            Some(JointCoupling::Gear { ratio }) => ratio,
+            _ => 0.0,
+        }
+    }
+
+    /// Sets the gear ratio.
+    pub fn set_ratio(&mut self, ratio: Real) -> &mut Self {
+        self.data.set_coupling(Some(JointCoupling::Gear { ratio }));
+        self
+    }
+
+    /// The rotation axis of the first gear, expressed in the local-space of the first rigid-body.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn local_axis1(&self) -> UnitVector<Real> {
+        self.data.local_axis1()
+    }
+
+    /// Sets the rotation axis of the first gear, expressed in the local-space of the first rigid-body.
+    #[cfg(feature = "dim3")]
+    pub fn set_local_axis1(&mut self, axis1: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis1(axis1);
+        self
+    }
+
+    /// The rotation axis of the second gear, expressed in the local-space of the second rigid-body.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn local_axis2(&self) -> UnitVector<Real> {
+        self.data.local_axis2()
+    }
+
+    /// Sets the rotation axis of the second gear, expressed in the local-space of the second rigid-body.
+    #[cfg(feature = "dim3")]
+    pub fn set_local_axis2(&mut self, axis2: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis2(axis2);
+        self
+    }
+}
+
+impl Into<GenericJoint> for GearJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create gear joints using the builder pattern.
+///
+/// A gear joint couples the angular velocities of two bodies by a ratio.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GearJointBuilder(pub GearJoint);
+
+impl GearJointBuilder {
+    /// Creates a new builder for gear joints.
+    pub fn new(ratio: Real) -> Self {
+        Self(GearJoint::new(ratio))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the gear ratio.
+    #[must_use]
+    pub fn ratio(mut self, ratio: Real) -> Self {
+        self.0.set_ratio(ratio);
+        self
+    }
+
+    /// Sets the rotation axis of the first gear, expressed in the local-space of the first rigid-body.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn local_axis1(mut self, axis1: UnitVector<Real>) -> Self {
+        self.0.set_local_axis1(axis1);
+        self
+    }
+
+    /// Sets the rotation axis of the second gear, expressed in the local-space of the second rigid-body.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn local_axis2(mut self, axis2: UnitVector<Real>) -> Self {
+        self.0.set_local_axis2(axis2);
+        self
+    }
+
+    /// Builds the gear joint.
+    #[must_use]
+    pub fn build(self) -> GearJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for GearJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+    #[test]
+    fn gear_and_pulley_joints_couple_bodies() {
+        use crate::dynamics::{GearJointBuilder, PulleyJointBuilder};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+
+        // Two wheels attached to the ground, coupled by a gear.
+        let mut wheels = [ground; 2];
+        for (i, x) in [-2.0, 2.0].into_iter().enumerate() {
+            let wheel = RigidBodyBuilder::dynamic().translation(Vector::x() * x);
+            wheels[i] = state.bodies.insert(wheel.build());
+            state.colliders.insert_with_parent(
+                ColliderBuilder::ball(0.5),
+                wheels[i],
+                &mut state.bodies,
+            );
+
+            #[cfg(feature = "dim2")]
+            let joint = RevoluteJointBuilder::new();
+            #[cfg(feature = "dim3")]
+            let joint = RevoluteJointBuilder::new(Vector::z_axis());
+            let joint = joint.local_anchor1(Point::from(Vector::x() * x));
+            state.impulse_joints.insert(ground, wheels[i], joint, true);
+        }
+
+        let ratio = 2.0;
+        let gear = GearJointBuilder::new(ratio);
+        #[cfg(feature = "dim3")]
+        let gear = gear
+            .local_axis1(Vector::z_axis())
+            .local_axis2(Vector::z_axis());
+        state
+            .impulse_joints
+            .insert(wheels[0], wheels[1], gear, true);
+
+        #[cfg(feature = "dim2")]
+        state.bodies[wheels[0]].set_angvel(2.0, true);
+        #[cfg(feature = "dim3")]
+        state.bodies[wheels[0]].set_angvel(Vector::z() * 2.0, true);
+
+        // Two hanging bodies coupled by a pulley. The second one is heavier so it goes down.
+        let ground_anchor1 = Point::from(Vector::x() * 9.0 + Vector::y() * 10.0);
+        let ground_anchor2 = Point::from(Vector::x() * 11.0 + Vector::y() * 10.0);
+        let mut weights = [ground; 2];
+        for (i, (x, density)) in [(9.0, 1.0), (11.0, 2.0)].into_iter().enumerate() {
+            let weight =
+                RigidBodyBuilder::dynamic().translation(Vector::x() * x + Vector::y() * 5.0);
+            weights[i] = state.bodies.insert(weight.build());
+            let collider = ColliderBuilder::ball(0.5).density(density);
+            state
+                .colliders
+                .insert_with_parent(collider, weights[i], &mut state.bodies);
+        }
+
+        let pulley = PulleyJointBuilder::new(ground_anchor1, ground_anchor2, 1.0, 10.0);
+        state
+            .impulse_joints
+            .insert(weights[0], weights[1], pulley, true);
+
+        for _ in 0..60 {
+            state.step(&mut pipeline, &(), &());
+
+            #[cfg(feature = "dim2")]
+            let angvels = [
+                state.bodies[wheels[0]].angvel(),
+                state.bodies[wheels[1]].angvel(),
+            ];
+            #[cfg(feature = "dim3")]
+            let angvels = [
+                state.bodies[wheels[0]].angvel().z,
+                state.bodies[wheels[1]].angvel().z,
+            ];
+            assert!(angvels[0].abs() > 0.1, "angvels: {:?}", angvels);
+            assert!(
+                (angvels[0] + ratio * angvels[1]).abs() < 1.0e-3,
+                "angvels: {:?}",
+                angvels
+            );
+
+            let length1 = (state.bodies[weights[0]].translation() - ground_anchor1.coords).norm();
+            let length2 = (state.bodies[weights[1]].translation() - ground_anchor2.coords).norm();
+            assert!(
+                (length1 + length2 - 10.0).abs() < 1.0e-2,
+                "lengths: {}, {}",
+                length1,
+                length2
+            );
+        }
+
+        assert!(state.bodies[weights[1]].translation().y < 4.5);
+        assert!(state.bodies[weights[0]].translation().y > 5.5);
+    }
+}
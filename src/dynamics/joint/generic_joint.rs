@@ -1,10 +1,11 @@
 use crate::dynamics::solver::MotorParameters;
+use crate::dynamics::IntegrationParameters;
 use crate::dynamics::{FixedJoint, MotorModel, PrismaticJoint, RevoluteJoint, RopeJoint};
 use crate::math::{Isometry, Point, Real, Rotation, UnitVector, Vector, SPATIAL_DIM};
 use crate::utils::{WBasis, WReal};
 
 #[cfg(feature = "dim3")]
-use crate::dynamics::SphericalJoint;
+use crate::dynamics::{CylindricalJoint, PlanarJoint, SphericalJoint, UniversalJoint};
 
 #[cfg(feature = "dim3")]
 bitflags::bitflags! {
@@ -31,6 +32,12 @@ bitflags::bitflags! {
         const LOCKED_FIXED_AXES = Self::X.bits | Self::Y.bits | Self::Z.bits | Self::ANG_X.bits | Self::ANG_Y.bits | Self::ANG_Z.bits;
         /// The set of degrees of freedom locked by a spherical joint.
         const LOCKED_SPHERICAL_AXES = Self::X.bits | Self::Y.bits | Self::Z.bits;
+        /// The set of degrees of freedom locked by a cylindrical joint.
+        const LOCKED_CYLINDRICAL_AXES = Self::Y.bits | Self::Z.bits | Self::ANG_Y.bits | Self::ANG_Z.bits;
+        /// The set of degrees of freedom locked by a universal joint.
+        const LOCKED_UNIVERSAL_AXES = Self::X.bits | Self::Y.bits | Self::Z.bits | Self::ANG_X.bits;
+        /// The set of degrees of freedom locked by a planar joint.
+        const LOCKED_PLANAR_AXES = Self::X.bits | Self::ANG_Y.bits | Self::ANG_Z.bits;
         /// The set of degrees of freedom left free by a revolute joint.
         const FREE_REVOLUTE_AXES = Self::ANG_X.bits;
         /// The set of degrees of freedom left free by a prismatic joint.
@@ -39,6 +46,12 @@ bitflags::bitflags! {
         const FREE_FIXED_AXES = 0;
         /// The set of degrees of freedom left free by a spherical joint.
         const FREE_SPHERICAL_AXES = Self::ANG_X.bits | Self::ANG_Y.bits | Self::ANG_Z.bits;
+        /// The set of degrees of freedom left free by a cylindrical joint.
+        const FREE_CYLINDRICAL_AXES = Self::X.bits | Self::ANG_X.bits;
+        /// The set of degrees of freedom left free by a universal joint.
+        const FREE_UNIVERSAL_AXES = Self::ANG_Y.bits | Self::ANG_Z.bits;
+        /// The set of degrees of freedom left free by a planar joint.
+        const FREE_PLANAR_AXES = Self::Y.bits | Self::Z.bits | Self::ANG_X.bits;
         /// The set of all translational degrees of freedom.
         const LIN_AXES = Self::X.bits() | Self::Y.bits() | Self::Z.bits();
         /// The set of all angular degrees of freedom.
@@ -182,6 +195,40 @@ impl JointMotor {
     }
 }
 
+/// The spring-like softness of a joint’s locked degrees of freedom.
+///
+/// The locked degrees of freedom of a soft joint behave as an acceleration-based spring, i.e.,
+/// `acceleration = -stiffness * error - damping * relative_velocity`, instead of being enforced
+/// rigidly.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointSoftness {
+    /// The stiffness coefficient of the spring.
+    pub stiffness: Real,
+    /// The damping coefficient of the spring.
+    pub damping: Real,
+}
+
+impl JointSoftness {
+    /// Creates a new joint softness with the given stiffness and damping.
+    pub fn new(stiffness: Real, damping: Real) -> Self {
+        Self { stiffness, damping }
+    }
+
+    /// The joint ERP and damping ratio equivalent to this softness, for the given timestep.
+    fn erp_and_damping_ratio(&self, dt: Real) -> (Real, Real) {
+        // NOTE: these yield the same `IntegrationParameters::joint_erp_inv_dt` and
+        //       `IntegrationParameters::joint_cfm_coeff` as the coefficients of an
+        //       acceleration-based motor (see `MotorModel::combine_coefficients`). Both
+        //       coefficients are kept positive so the CFM coefficient remains finite.
+        let stiffness = self.stiffness.max(Real::EPSILON);
+        let damping = self.damping.max(Real::EPSILON);
+        let erp = dt * stiffness / (dt * stiffness + damping);
+        let damping_ratio = damping / (2.0 * stiffness.sqrt());
+        (erp, damping_ratio)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 /// Enum indicating whether or not a joint is enabled.
@@ -195,6 +242,34 @@ pub enum JointEnabled {
     Disabled,
 }
 
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// A constraint coupling the motions of the two rigid-bodies attached to a joint through a ratio.
+///
+/// Couplings are velocity constraints solved alongside the joint’s other degrees of freedom.
+pub enum JointCoupling {
+    /// Couples the angular velocities of both rigid-bodies along the `JointAxis::AngX` axis
+    /// of their respective joint frame, such that `angvel1 + ratio * angvel2 = 0`.
+    ///
+    /// With a positive ratio, both bodies rotate in opposite directions, like meshing gears.
+    Gear {
+        /// The gear ratio.
+        ratio: Real,
+    },
+    /// Couples the distances between each body’s anchor and a fixed world-space ground
+    /// anchor, such that `length1 + ratio * length2` remains equal to `length`.
+    Pulley {
+        /// The world-space point the rope attached to the first body goes through.
+        ground_anchor1: Point<Real>,
+        /// The world-space point the rope attached to the second body goes through.
+        ground_anchor2: Point<Real>,
+        /// The pulley ratio.
+        ratio: Real,
+        /// The total length of the rope, i.e., `length1 + ratio * length2`.
+        length: Real,
+    },
+}
+
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 /// A generic joint.
@@ -219,6 +294,21 @@ pub struct GenericJoint {
     ///
     /// Note that the mostor must also be explicitly enabled by the `motors` bitmask.
     pub motors: [JointMotor; SPATIAL_DIM],
+    /// The maximum force (along linear axes) or torque (along angular axes) applied by this
+    /// joint’s friction to resist the relative motion along each degree of freedom.
+    ///
+    /// Friction is disabled along axes set to zero, and ignored along locked or coupled axes.
+    pub frictions: [Real; SPATIAL_DIM],
+    /// The gear or pulley coupling between the attached rigid-bodies, if any.
+    pub coupling: Option<JointCoupling>,
+    /// The Error Reduction Parameter of this joint, overriding [`IntegrationParameters::joint_erp`]
+    /// if set.
+    pub erp: Option<Real>,
+    /// The damping ratio of this joint, overriding [`IntegrationParameters::joint_damping_ratio`]
+    /// if set.
+    pub damping_ratio: Option<Real>,
+    /// The softness of this joint, overriding both its ERP and damping ratio if set.
+    pub softness: Option<JointSoftness>,
     /// Are contacts between the attached rigid-bodies enabled?
     pub contacts_enabled: bool,
     /// Whether or not the joint is enabled.
@@ -236,6 +326,11 @@ impl Default for GenericJoint {
             coupled_axes: JointAxesMask::empty(),
             limits: [JointLimits::default(); SPATIAL_DIM],
             motors: [JointMotor::default(); SPATIAL_DIM],
+            frictions: [0.0; SPATIAL_DIM],
+            coupling: None,
+            erp: None,
+            damping_ratio: None,
+            softness: None,
             contacts_enabled: true,
             enabled: JointEnabled::Enabled,
         }
@@ -252,7 +347,70 @@ impl GenericJoint {
     #[cfg(feature = "simd-is-enabled")]
     /// Can this joint use SIMD-accelerated constraint formulations?
     pub(crate) fn supports_simd_constraints(&self) -> bool {
-        self.limit_axes.is_empty() && self.motor_axes.is_empty()
+        self.limit_axes.is_empty()
+            && self.motor_axes.is_empty()
+            && self.friction_axes() == 0
+            && self.coupling.is_none()
+            && self.erp.is_none()
+            && self.damping_ratio.is_none()
+            && self.softness.is_none()
+    }
+
+    /// The integration parameters used to solve the constraints of this joint, i.e., the global
+    /// integration parameters with this joint’s softness, ERP, and damping ratio overrides applied.
+    pub(crate) fn integration_parameters(
+        &self,
+        params: &IntegrationParameters,
+    ) -> IntegrationParameters {
+        let (joint_erp, joint_damping_ratio) = match &self.softness {
+            Some(softness) => softness.erp_and_damping_ratio(params.dt),
+            None => (
+                self.erp.unwrap_or(params.joint_erp),
+                self.damping_ratio.unwrap_or(params.joint_damping_ratio),
+            ),
+        };
+
+        IntegrationParameters {
+            joint_erp,
+            joint_damping_ratio,
+            ..*params
+        }
+    }
+
+    /// Does this joint need the generic constraint formulation, even if it isn’t attached
+    /// to any multibody?
+    ///
+    /// This is the case for pulleys because their linear jacobians differ between both bodies.
+    pub(crate) fn requires_generic_constraints(&self) -> bool {
+        matches!(self.coupling, Some(JointCoupling::Pulley { .. }))
+    }
+
+    /// The bitmask of the free and uncoupled axes with a non-zero friction.
+    pub(crate) fn friction_axes(&self) -> u8 {
+        let mut axes = 0;
+        for i in 0..SPATIAL_DIM {
+            if self.frictions[i] != 0.0 {
+                axes |= 1 << i;
+            }
+        }
+        axes & !(self.locked_axes.bits() | self.coupled_axes.bits())
+    }
+
+    /// An upper bound of the number of velocity constraints generated for this joint.
+    pub(crate) fn max_num_velocity_constraints(&self) -> usize {
+        (self.locked_axes.bits().count_ones()
+            + self.limit_axes.bits().count_ones()
+            + self.motor_axes.bits().count_ones()
+            + self.friction_axes().count_ones()
+            + self.coupling.is_some() as u32) as usize
+    }
+
+    /// The parameters of the bounded velocity constraint simulating the friction along `axis`.
+    pub(crate) fn friction_params(&self, axis: usize, dt: Real) -> MotorParameters<Real> {
+        MotorParameters {
+            max_impulse: self.frictions[axis] * dt,
+            ..MotorParameters::default()
+        }
     }
 
     #[doc(hidden)]
@@ -472,6 +630,89 @@ impl GenericJoint {
         self.motors[i].damping = damping;
         self
     }
+
+    /// The maximum friction force or torque resisting the relative motion along the specified axis.
+    #[must_use]
+    pub fn friction(&self, axis: JointAxis) -> Real {
+        self.frictions[axis as usize]
+    }
+
+    /// Sets the maximum friction force or torque resisting the relative motion along the specified axis.
+    ///
+    /// Setting it to zero disables friction along this axis.
+    pub fn set_friction(&mut self, axis: JointAxis, max_force: Real) -> &mut Self {
+        self.frictions[axis as usize] = max_force;
+        self
+    }
+
+    /// The gear or pulley coupling between the attached rigid-bodies, if any.
+    #[must_use]
+    pub fn coupling(&self) -> Option<&JointCoupling> {
+        self.coupling.as_ref()
+    }
+
+    /// Sets the gear or pulley coupling between the attached rigid-bodies.
+    pub fn set_coupling(&mut self, coupling: Option<JointCoupling>) -> &mut Self {
+        self.coupling = coupling;
+        self
+    }
+
+    /// The Error Reduction Parameter of this joint, if it overrides
+    /// [`IntegrationParameters::joint_erp`].
+    #[must_use]
+    pub fn erp(&self) -> Option<Real> {
+        self.erp
+    }
+
+    /// Sets the Error Reduction Parameter of this joint.
+    ///
+    /// Setting it to `None` makes this joint use [`IntegrationParameters::joint_erp`] instead.
+    pub fn set_erp(&mut self, erp: Option<Real>) -> &mut Self {
+        self.erp = erp;
+        self
+    }
+
+    /// The damping ratio of this joint, if it overrides
+    /// [`IntegrationParameters::joint_damping_ratio`].
+    #[must_use]
+    pub fn damping_ratio(&self) -> Option<Real> {
+        self.damping_ratio
+    }
+
+    /// Sets the damping ratio of this joint.
+    ///
+    /// Setting it to `None` makes this joint use [`IntegrationParameters::joint_damping_ratio`]
+    /// instead.
+    pub fn set_damping_ratio(&mut self, damping_ratio: Option<Real>) -> &mut Self {
+        self.damping_ratio = damping_ratio;
+        self
+    }
+
+    /// The softness of this joint, if it overrides both its ERP and damping ratio.
+    #[must_use]
+    pub fn softness(&self) -> Option<&JointSoftness> {
+        self.softness.as_ref()
+    }
+
+    /// Sets the softness of this joint.
+    ///
+    /// If set, the softness overrides both [`IntegrationParameters::joint_erp`] and
+    /// [`IntegrationParameters::joint_damping_ratio`], as well as this joint’s own ERP and damping
+    /// ratio. Setting it to `None` makes this joint rigid again.
+    pub fn set_softness(&mut self, softness: Option<JointSoftness>) -> &mut Self {
+        self.softness = softness;
+        self
+    }
+
+    /// Disables the motor along the specified axis.
+    ///
+    /// The degree of freedom along this axis becomes free (unless it is locked or limited) and
+    /// the motor’s configuration is kept so it can be re-enabled by setting its target again.
+    pub fn disable_motor(&mut self, axis: JointAxis) -> &mut Self {
+        self.motor_axes.remove(axis.into());
+        self.motors[axis as usize].impulse = 0.0;
+        self
+    }
 }
 
 macro_rules! joint_conversion_methods(
@@ -482,7 +723,7 @@ macro_rules! joint_conversion_methods(
             if self.locked_axes == $axes {
                 // SAFETY: this is OK because the target joint type is
                 //         a `repr(transparent)` newtype of `Joint`.
-                Some(unsafe { std::mem::transmute(self) })
+                Some(unsafe { &*(self as *const Self as *const $Joint) })
             } else {
                 None
             }
@@ -494,7 +735,7 @@ macro_rules! joint_conversion_methods(
             if self.locked_axes == $axes {
                 // SAFETY: this is OK because the target joint type is
                 //         a `repr(transparent)` newtype of `Joint`.
-                Some(unsafe { std::mem::transmute(self) })
+                Some(unsafe { &mut *(self as *mut Self as *mut $Joint) })
             } else {
                 None
             }
@@ -535,6 +776,30 @@ impl GenericJoint {
         SphericalJoint,
         JointAxesMask::LOCKED_SPHERICAL_AXES
     );
+
+    #[cfg(feature = "dim3")]
+    joint_conversion_methods!(
+        as_cylindrical,
+        as_cylindrical_mut,
+        CylindricalJoint,
+        JointAxesMask::LOCKED_CYLINDRICAL_AXES
+    );
+
+    #[cfg(feature = "dim3")]
+    joint_conversion_methods!(
+        as_universal,
+        as_universal_mut,
+        UniversalJoint,
+        JointAxesMask::LOCKED_UNIVERSAL_AXES
+    );
+
+    #[cfg(feature = "dim3")]
+    joint_conversion_methods!(
+        as_planar,
+        as_planar_mut,
+        PlanarJoint,
+        JointAxesMask::LOCKED_PLANAR_AXES
+    );
 }
 
 /// Create generic joints using the builder pattern.
@@ -563,6 +828,15 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets whether the joint is enabled.
+    ///
+    /// A disabled joint is ignored by the constraints solver, but keeps its configuration.
+    #[must_use]
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.0.set_enabled(enabled);
+        self
+    }
+
     /// Sets the joint’s frame, expressed in the first rigid-body’s local-space.
     #[must_use]
     pub fn local_frame1(mut self, local_frame: Isometry<Real>) -> Self {
@@ -669,6 +943,46 @@ impl GenericJointBuilder {
         self
     }
 
+    /// Sets the maximum friction force or torque resisting the relative motion along the specified axis.
+    #[must_use]
+    pub fn friction(mut self, axis: JointAxis, max_force: Real) -> Self {
+        self.0.set_friction(axis, max_force);
+        self
+    }
+
+    /// Sets the gear or pulley coupling between the attached rigid-bodies.
+    #[must_use]
+    pub fn coupling(mut self, coupling: JointCoupling) -> Self {
+        self.0.set_coupling(Some(coupling));
+        self
+    }
+
+    /// Sets the Error Reduction Parameter of this joint, overriding
+    /// [`IntegrationParameters::joint_erp`].
+    #[must_use]
+    pub fn erp(mut self, erp: Real) -> Self {
+        self.0.set_erp(Some(erp));
+        self
+    }
+
+    /// Sets the damping ratio of this joint, overriding
+    /// [`IntegrationParameters::joint_damping_ratio`].
+    #[must_use]
+    pub fn damping_ratio(mut self, damping_ratio: Real) -> Self {
+        self.0.set_damping_ratio(Some(damping_ratio));
+        self
+    }
+
+    /// Makes this joint soft, with the given stiffness and damping.
+    ///
+    /// See [`GenericJoint::set_softness`] for more details.
+    #[must_use]
+    pub fn softness(mut self, stiffness: Real, damping: Real) -> Self {
+        self.0
+            .set_softness(Some(JointSoftness::new(stiffness, damping)));
+        self
+    }
+
     /// Builds the generic joint.
     #[must_use]
     pub fn build(self) -> GenericJoint {
@@ -681,3 +995,89 @@ impl Into<GenericJoint> for GenericJointBuilder {
         self.0
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{PrismaticJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn soft_joints_sag_under_gravity() {
+        let (stiffness, damping) = (100.0, 20.0);
+        let soft_prismatic =
+            PrismaticJointBuilder::new(Vector::x_axis()).softness(stiffness, damping);
+        let rigid_prismatic = PrismaticJointBuilder::new(Vector::x_axis());
+        #[cfg(feature = "dim3")]
+        let soft_spherical =
+            crate::dynamics::SphericalJointBuilder::new().softness(stiffness, damping);
+        let joints = [
+            (soft_prismatic.build().data, true),
+            (rigid_prismatic.build().data, false),
+            #[cfg(feature = "dim3")]
+            (soft_spherical.build().data, true),
+        ];
+
+        for (joint, is_soft) in joints {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+
+            let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+            let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+            let collider = ColliderBuilder::ball(0.5).density(10.0).build();
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            state.impulse_joints.insert(ground, body, joint, true);
+
+            step_n(&mut state, &mut pipeline, 600);
+
+            // The softness is acceleration-based, so the sag doesn’t depend on the body’s mass.
+            let expected = if is_soft { -9.81 / stiffness } else { 0.0 };
+            let sag = state.bodies[body].translation().y;
+            assert!((sag - expected).abs() < 1.0e-2, "sag: {}", sag);
+        }
+    }
+
+    #[test]
+    fn joint_erp_override_softens_error_correction() {
+        use crate::dynamics::{GenericJointBuilder, JointAxesMask};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // Both bodies start one unit away from their joint’s anchor. Only the second joint
+        // overrides the global joint ERP.
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let mut attach = |erp: Option<Real>, y: Real| {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * y + Vector::x())
+                .build();
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.1).build();
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+
+            let mut joint = GenericJointBuilder::new(JointAxesMask::LOCKED_FIXED_AXES)
+                .local_anchor1(Point::from(Vector::y() * y));
+            if let Some(erp) = erp {
+                joint = joint.erp(erp).damping_ratio(1.0);
+            }
+            state.impulse_joints.insert(ground, body, joint, true);
+            body
+        };
+        let rigid = attach(None, 0.0);
+        let soft = attach(Some(0.05), 10.0);
+
+        step_n(&mut state, &mut pipeline, 5);
+
+        let rigid_error = state.bodies[rigid].translation().norm();
+        let soft_error = (state.bodies[soft].translation() - Vector::y() * 10.0).norm();
+        assert!(rigid_error < 1.0e-2, "rigid error: {}", rigid_error);
+        assert!(soft_error > 0.5, "soft error: {}", soft_error);
+    }
+}
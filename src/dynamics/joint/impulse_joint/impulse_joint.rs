@@ -1,8 +1,9 @@
+use crate::dynamics::JointAxesMask;
 use crate::dynamics::{GenericJoint, ImpulseJointHandle, RigidBodyHandle};
-use crate::math::{Real, SpacialVector};
+use crate::math::{AngVector, Real, SpacialVector, Vector, DIM, SPATIAL_DIM};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 /// An impulse-based joint attached to two bodies.
 pub struct ImpulseJoint {
     /// Handle to the first body attached to this joint.
@@ -14,8 +15,183 @@ pub struct ImpulseJoint {
     pub data: GenericJoint,
 
     /// The impulses applied by this joint.
+    ///
+    /// These are the impulses computed by the constraints solver for each locked axis. Because the
+    /// solver works on an orthogonalized set of constraints, they don’t necessarily match the
+    /// impulse applied along each individual axis. Use [`ImpulseJoint::reaction_impulses`] to
+    /// obtain the actual impulses applied by this joint.
     pub impulses: SpacialVector<Real>,
 
+    // The world-space linear impulse, and angular impulse about the joint’s anchor, applied on
+    // the second rigid-body by each constraint of this joint during the last timestep.
+    // Indexed by `WritebackId::reaction_slot`. This is a solver output, so it is neither
+    // serialized nor compared.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    pub(crate) reactions: [SpacialVector<Real>; 4 * SPATIAL_DIM],
+
     // A joint needs to know its handle to simplify its removal.
     pub(crate) handle: ImpulseJointHandle,
 }
+
+impl PartialEq for ImpulseJoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.body1 == other.body1
+            && self.body2 == other.body2
+            && self.data == other.data
+            && self.impulses == other.impulses
+            && self.handle == other.handle
+    }
+}
+
+impl ImpulseJoint {
+    /// The world-space linear and angular impulses applied by this joint on its second rigid-body
+    /// during the last timestep it was simulated.
+    ///
+    /// This accounts for all the locked axes, limits, motors, and friction of this joint. The linear impulse
+    /// is applied at the joint’s anchor, and the angular impulse is expressed about that anchor.
+    /// The impulses applied on the first rigid-body are the opposite of these.
+    ///
+    /// Reaction impulses don’t include the impulses applied by a gear or pulley coupling.
+    #[must_use]
+    pub fn reaction_impulses(&self) -> (Vector<Real>, AngVector<Real>) {
+        let locked_axes = self.data.locked_axes.bits();
+        let coupled_axes = self.data.coupled_axes.bits();
+        let limit_axes = self.data.limit_axes.bits() & !locked_axes;
+        let motor_axes = self.data.motor_axes.bits() & !locked_axes;
+        let friction_axes = self.data.friction_axes();
+
+        // Limits and motors acting on coupled axes are only registered on the first coupled axis.
+        let mut coupled_slots = 0;
+        for coupled_group in [JointAxesMask::LIN_AXES, JointAxesMask::ANG_AXES] {
+            let group = coupled_axes & coupled_group.bits();
+            if group != 0 {
+                coupled_slots |= 1 << group.trailing_zeros();
+            }
+        }
+
+        let mut total = SpacialVector::zeros();
+
+        for i in 0..SPATIAL_DIM {
+            let active = if coupled_axes & (1 << i) != 0 {
+                coupled_slots & (1 << i) != 0
+            } else {
+                true
+            };
+
+            if locked_axes & (1 << i) != 0 {
+                total += self.reactions[i];
+            }
+            if active && limit_axes & (1 << i) != 0 {
+                total += self.reactions[SPATIAL_DIM + i];
+            }
+            if active && motor_axes & (1 << i) != 0 {
+                total += self.reactions[2 * SPATIAL_DIM + i];
+            }
+            if friction_axes & (1 << i) != 0 {
+                total += self.reactions[3 * SPATIAL_DIM + i];
+            }
+        }
+
+        let lin_impulse = total.fixed_rows::<DIM>(0).into_owned();
+        #[cfg(feature = "dim2")]
+        let ang_impulse = total[DIM];
+        #[cfg(feature = "dim3")]
+        let ang_impulse = total.fixed_rows::<3>(DIM).into_owned();
+
+        (lin_impulse, ang_impulse)
+    }
+
+    /// The world-space linear and angular forces applied by this joint on its second rigid-body
+    /// during the last timestep of length `dt`.
+    ///
+    /// See [`ImpulseJoint::reaction_impulses`] for details.
+    #[must_use]
+    pub fn reaction_forces(&self, dt: Real) -> (Vector<Real>, AngVector<Real>) {
+        let (lin_impulse, ang_impulse) = self.reaction_impulses();
+        let inv_dt = crate::utils::inv(dt);
+        (lin_impulse * inv_dt, ang_impulse * inv_dt)
+    }
+
+    pub(crate) fn set_reaction(
+        &mut self,
+        slot: usize,
+        lin_impulse: Vector<Real>,
+        ang_impulse: AngVector<Real>,
+    ) {
+        let reaction = &mut self.reactions[slot];
+        reaction.fixed_rows_mut::<DIM>(0).copy_from(&lin_impulse);
+        #[cfg(feature = "dim2")]
+        {
+            reaction[DIM] = ang_impulse;
+        }
+        #[cfg(feature = "dim3")]
+        reaction.fixed_rows_mut::<3>(DIM).copy_from(&ang_impulse);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn fixed_joint_reaction_force_compensates_gravity() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() - Vector::y())
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        // The anchor is offset from the body’s center of mass, so the joint has to compensate
+        // for the torque of the gravity too.
+        let joint = FixedJointBuilder::new()
+            .local_anchor1(Point::from(-Vector::y()))
+            .local_anchor2(Point::from(-Vector::x()));
+        let joint_handle = state.impulse_joints.insert(ground, body, joint, true);
+
+        step_n(&mut state, &mut pipeline, 100);
+
+        let (force, torque) = state
+            .impulse_joints
+            .get(joint_handle)
+            .unwrap()
+            .reaction_forces(state.integration_parameters.dt);
+        let expected_force = -state.gravity * state.bodies[body].mass();
+        let lever_arm = state.bodies[body].translation() + Vector::y();
+        assert!((force - expected_force).norm() < 1.0e-2, "force: {}", force);
+        #[cfg(feature = "dim2")]
+        {
+            let expected_torque = lever_arm.perp(&expected_force);
+            assert!(
+                (torque - expected_torque).abs() < 1.0e-2,
+                "torque: {}",
+                torque
+            );
+        }
+        #[cfg(feature = "dim3")]
+        {
+            let expected_torque = lever_arm.cross(&expected_force);
+            assert!(
+                (torque - expected_torque).norm() < 1.0e-2,
+                "torque: {}",
+                torque
+            );
+        }
+
+        // The reactions are solver outputs, ignored when comparing joints.
+        let joint = state.impulse_joints.get(joint_handle).unwrap();
+        let mut reset = joint.clone();
+        reset.reactions.iter_mut().for_each(|r| r.fill(0.0));
+        assert_eq!(&reset, joint);
+    }
+}
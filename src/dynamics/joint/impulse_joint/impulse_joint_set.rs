@@ -4,6 +4,7 @@ use crate::geometry::{InteractionGraph, RigidBodyGraphIndex, TemporaryInteractio
 use crate::data::arena::Arena;
 use crate::data::Coarena;
 use crate::dynamics::{GenericJoint, IslandManager, RigidBodyHandle, RigidBodySet};
+use crate::math::SPATIAL_DIM;
 
 /// The unique identifier of a joint added to the joint set.
 /// The unique identifier of a collider added to a collider set.
@@ -145,11 +146,36 @@ impl ImpulseJointSet {
     }
 
     /// Gets a mutable reference to the joint with the given handle.
+    ///
+    /// The bodies attached to this joint aren’t woken up, so modifying it has no effect until they
+    /// are if they are sleeping. Use [`Self::get_mut_with_wake_up`] to wake them up.
     pub fn get_mut(&mut self, handle: ImpulseJointHandle) -> Option<&mut ImpulseJoint> {
         let id = self.joint_ids.get(handle.0)?;
         self.joint_graph.graph.edge_weight_mut(*id)
     }
 
+    /// Gets a mutable reference to the joint with the given handle.
+    ///
+    /// The joint’s description (anchors, axes, limits, motors, etc.) can be modified freely
+    /// through this reference: its constraints are rebuilt from it at the next timestep.
+    ///
+    /// If `wake_up` is set to `true`, then the bodies attached to this joint will be
+    /// automatically woken up during the next timestep. Sleeping bodies aren’t simulated, so they
+    /// wouldn’t react to the modification of the joint otherwise.
+    pub fn get_mut_with_wake_up(
+        &mut self,
+        handle: ImpulseJointHandle,
+        wake_up: bool,
+    ) -> Option<&mut ImpulseJoint> {
+        let id = self.joint_ids.get(handle.0)?;
+        let joint = self.joint_graph.graph.edge_weight_mut(*id)?;
+        if wake_up {
+            self.to_wake_up.push(joint.body1);
+            self.to_wake_up.push(joint.body2);
+        }
+        Some(joint)
+    }
+
     /// Gets the joint with the given handle without a known generation.
     ///
     /// This is useful when you know you want the joint at index `i` but
@@ -214,7 +240,6 @@ impl ImpulseJointSet {
     //     //     .map(|e| &mut e.weight)
     // }
 
-    #[cfg(not(feature = "parallel"))]
     pub(crate) fn joints_mut(&mut self) -> &mut [JointGraphEdge] {
         &mut self.joint_graph.graph.edges[..]
     }
@@ -242,6 +267,7 @@ impl ImpulseJointSet {
             body2,
             data,
             impulses: na::zero(),
+            reactions: [na::zero(); 4 * SPATIAL_DIM],
             handle: ImpulseJointHandle(handle),
         };
 
@@ -386,3 +412,90 @@ impl ImpulseJointSet {
         deleted
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+    #[test]
+    fn joint_anchor_modification_wakes_up_bodies() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+        let joint_handle =
+            state
+                .impulse_joints
+                .insert(ground, body, FixedJointBuilder::new(), true);
+        let new_anchor = Point::from(Vector::y());
+
+        for i in 0..300 {
+            if i == 240 {
+                // Move the anchor once the body fell asleep.
+                assert!(state.bodies[body].is_sleeping());
+                state
+                    .impulse_joints
+                    .get_mut_with_wake_up(joint_handle, true)
+                    .unwrap()
+                    .data
+                    .set_local_anchor1(new_anchor);
+            }
+
+            state.step(&mut pipeline, &(), &());
+        }
+
+        let translation = state.bodies[body].translation();
+        assert!(
+            (translation - new_anchor.coords).norm() < 1.0e-2,
+            "translation: {}",
+            translation
+        );
+    }
+
+    #[test]
+    fn disabled_joint_is_ignored_until_reenabled() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+        let joint_handle =
+            state
+                .impulse_joints
+                .insert(ground, body, FixedJointBuilder::new(), true);
+
+        for i in 0..300 {
+            if i == 10 || i == 60 {
+                // Release the body, then attach it again with the same joint.
+                state
+                    .impulse_joints
+                    .get_mut_with_wake_up(joint_handle, true)
+                    .unwrap()
+                    .data
+                    .set_enabled(i == 60);
+            }
+
+            state.step(&mut pipeline, &(), &());
+
+            if i == 59 {
+                assert!(state.bodies[body].translation().y < -1.0);
+            }
+        }
+
+        let translation = state.bodies[body].translation();
+        assert!(translation.norm() < 1.0e-2, "translation: {}", translation);
+    }
+}
@@ -1,7 +1,7 @@
 use crate::dynamics::solver::AnyJointVelocityConstraint;
 use crate::dynamics::{
-    joint, FixedJointBuilder, GenericJoint, IntegrationParameters, Multibody, MultibodyLink,
-    RigidBodyVelocity,
+    joint, FixedJointBuilder, GenericJoint, IntegrationParameters, JointAxesMask, Multibody,
+    MultibodyLink, RigidBodyVelocity,
 };
 use crate::math::{
     Isometry, JacobianSliceMut, Real, Rotation, SpacialVector, Translation, Vector, ANG_DIM, DIM,
@@ -14,6 +14,10 @@ use na::{UnitQuaternion, Vector3};
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug)]
 /// An joint attached to two bodies based on the reduced coordinates formalism.
+///
+/// Contrary to impulse joints, multibody joints don’t report any reaction impulse: the relative
+/// motions they forbid are excluded from the coordinates of the multibody instead of being
+/// prevented by constraints.
 pub struct MultibodyJoint {
     /// The joint’s description.
     pub data: GenericJoint,
@@ -57,6 +61,16 @@ impl MultibodyJoint {
         DIM - (locked_bits & ((1 << DIM) - 1)).count_ones() as usize
     }
 
+    /// The reduced coordinates of this multibody_joint.
+    ///
+    /// The first `DIM` entries are the translations along each free linear axis of the joint.
+    /// If the joint has a single free angular axis, the corresponding remaining entry is the
+    /// rotation angle along that axis. Entries of locked axes are zero, and angular entries are
+    /// not tracked if the joint has several free angular axes (see [`Self::body_to_parent`]).
+    pub fn coords(&self) -> &SpacialVector<Real> {
+        &self.coords
+    }
+
     /// The number of degrees of freedom allowed by the multibody_joint.
     pub fn ndofs(&self) -> usize {
         SPATIAL_DIM - self.data.locked_axes.bits().count_ones() as usize
@@ -227,12 +241,22 @@ impl MultibodyJoint {
         }
     }
 
+    // The limited axes enforced by `velocity_constraints`.
+    //
+    // NOTE: the coupled angular limits (e.g. the swing limit of a spherical joint) aren’t
+    //       supported by multibody joints yet. Enforcing them per-axis would be wrong, so they
+    //       are ignored instead.
+    fn limit_bits(&self) -> u8 {
+        self.data.limit_axes.bits()
+            & !(self.data.coupled_axes.bits() & JointAxesMask::ANG_AXES.bits())
+    }
+
     /// Maximum number of velocity constrains that can be generated by this multibody_joint.
     pub fn num_velocity_constraints(&self) -> usize {
         let locked_bits = self.data.locked_axes.bits();
-        let limit_bits = self.data.limit_axes.bits();
+        let limit_bits = self.limit_bits();
         let motor_bits = self.data.motor_axes.bits();
-        let mut num_constraints = 0;
+        let mut num_constraints = self.data.friction_axes().count_ones() as usize;
 
         for i in 0..SPATIAL_DIM {
             if (locked_bits & (1 << i)) == 0 {
@@ -248,7 +272,8 @@ impl MultibodyJoint {
         num_constraints
     }
 
-    /// Initialize and generate velocity constraints to enforce, e.g., multibody_joint limits and motors.
+    /// Initialize and generate velocity constraints to enforce, e.g., multibody_joint limits, motors,
+    /// and friction.
     pub fn velocity_constraints(
         &self,
         params: &IntegrationParameters,
@@ -260,9 +285,11 @@ impl MultibodyJoint {
         constraints: &mut Vec<AnyJointVelocityConstraint>,
         insert_at: &mut Option<usize>,
     ) {
+        let params = &self.data.integration_parameters(params);
         let locked_bits = self.data.locked_axes.bits();
-        let limit_bits = self.data.limit_axes.bits();
+        let limit_bits = self.limit_bits();
         let motor_bits = self.data.motor_axes.bits();
+        let friction_bits = self.data.friction_axes();
         let mut curr_free_dof = 0;
 
         for i in 0..DIM {
@@ -303,6 +330,19 @@ impl MultibodyJoint {
                         insert_at,
                     );
                 }
+
+                if (friction_bits & (1 << i)) != 0 {
+                    joint::unit_joint_friction_constraint(
+                        multibody,
+                        link,
+                        self.data.friction_params(i, params.dt).max_impulse,
+                        dof_id + curr_free_dof,
+                        j_id,
+                        jacobians,
+                        constraints,
+                        insert_at,
+                    );
+                }
                 curr_free_dof += 1;
             }
         }
@@ -357,8 +397,101 @@ impl MultibodyJoint {
                         insert_at,
                     );
                 }
+
+                if (friction_bits & (1 << i)) != 0 {
+                    joint::unit_joint_friction_constraint(
+                        multibody,
+                        link,
+                        self.data.friction_params(i, params.dt).max_impulse,
+                        dof_id + curr_free_dof,
+                        j_id,
+                        jacobians,
+                        constraints,
+                        insert_at,
+                    );
+                }
                 curr_free_dof += 1;
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn multibody_revolute_joint_friction_decelerates_rotation() {
+        use crate::dynamics::JointAxis;
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let friction = 0.1;
+        let joint = joint.friction(friction).motor_velocity(2.0, 1.0e6);
+        let joint_handle = state
+            .multibody_joints
+            .insert(ground, body, joint, true)
+            .unwrap();
+
+        // Spin the link up with a motor first, then let the friction stop it.
+        step_n(&mut state, &mut pipeline, 10);
+        let (multibody, link_id) = state.multibody_joints.get_mut(joint_handle).unwrap();
+        multibody
+            .link_mut(link_id)
+            .unwrap()
+            .joint
+            .data
+            .disable_motor(JointAxis::AngX);
+        state.step(&mut pipeline, &(), &());
+
+        #[cfg(feature = "dim2")]
+        let angvel = |state: &PhysicsState| state.bodies[body].angvel();
+        #[cfg(feature = "dim3")]
+        let angvel = |state: &PhysicsState| state.bodies[body].angvel().z;
+        #[cfg(feature = "dim2")]
+        let inertia = state.bodies[body].mass_properties().principal_inertia();
+        #[cfg(feature = "dim3")]
+        let inertia = state.bodies[body].mass_properties().principal_inertia().z;
+
+        let initial_angvel = angvel(&state);
+        assert!(
+            (initial_angvel - 2.0).abs() < 5.0e-2,
+            "angvel: {}",
+            initial_angvel
+        );
+
+        // The friction torque is constant so the angular velocity decreases linearly.
+        let num_steps = 50;
+        step_n(&mut state, &mut pipeline, num_steps);
+        let expected_angvel = initial_angvel
+            - friction / inertia * state.integration_parameters.dt * num_steps as Real;
+        let angvel_after = angvel(&state);
+        assert!(
+            (angvel_after - expected_angvel).abs() < 2.0e-2,
+            "angvel: {}, expected: {}",
+            angvel_after,
+            expected_angvel
+        );
+
+        step_n(&mut state, &mut pipeline, 1000);
+        assert!(angvel(&state).abs() < 1.0e-3);
+    }
+}
@@ -420,3 +420,91 @@ impl std::ops::Index<MultibodyIndex> for MultibodyJointSet {
 //         &self.multibodies[index.0]
 //     }
 // }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn reaction_forces_of_joints_attached_to_multibodies() {
+        use crate::pipeline::PhysicsState;
+
+        // The impulse joint is solved with the generic formulation because its second body is
+        // the root of a multibody. Swapping the bodies also checks the sign of the reaction when
+        // the fixed body becomes the second one.
+        for swap_bodies in [false, true] {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+
+            let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+            let root = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() - Vector::y())
+                .build();
+            let root = state.bodies.insert(root);
+            let link = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 2.0 - Vector::y())
+                .build();
+            let link = state.bodies.insert(link);
+            for body in [root, link] {
+                let collider = ColliderBuilder::ball(0.4).build();
+                state
+                    .colliders
+                    .insert_with_parent(collider, body, &mut state.bodies);
+            }
+
+            let link_joint = FixedJointBuilder::new().local_anchor2(Point::from(-Vector::x()));
+            state
+                .multibody_joints
+                .insert(root, link, link_joint, true)
+                .unwrap();
+
+            let joint_handle = if swap_bodies {
+                let joint = FixedJointBuilder::new()
+                    .local_anchor1(Point::from(-Vector::x()))
+                    .local_anchor2(Point::from(-Vector::y()));
+                state.impulse_joints.insert(root, ground, joint, true)
+            } else {
+                let joint = FixedJointBuilder::new()
+                    .local_anchor1(Point::from(-Vector::y()))
+                    .local_anchor2(Point::from(-Vector::x()));
+                state.impulse_joints.insert(ground, root, joint, true)
+            };
+
+            step_n(&mut state, &mut pipeline, 100);
+
+            let (force, torque) = state
+                .impulse_joints
+                .get(joint_handle)
+                .unwrap()
+                .reaction_forces(state.integration_parameters.dt);
+            let mass = state.bodies[root].mass() + state.bodies[link].mass();
+            let sign = if swap_bodies { -1.0 } else { 1.0 };
+            let expected_force = -state.gravity * mass * sign;
+            // The center of mass of both bodies is 1.5 units away from the joint’s anchor.
+            let lever_arm = Vector::x() * 1.5;
+            assert!((force - expected_force).norm() < 1.0e-2, "force: {}", force);
+            #[cfg(feature = "dim2")]
+            {
+                let expected_torque = lever_arm.perp(&expected_force);
+                assert!(
+                    (torque - expected_torque).abs() < 1.0e-2,
+                    "torque: {}",
+                    torque
+                );
+            }
+            #[cfg(feature = "dim3")]
+            {
+                let expected_torque = lever_arm.cross(&expected_force);
+                assert!(
+                    (torque - expected_torque).norm() < 1.0e-2,
+                    "torque: {}",
+                    torque
+                );
+            }
+        }
+    }
+}
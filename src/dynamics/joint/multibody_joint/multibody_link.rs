@@ -79,6 +79,17 @@ impl MultibodyLink {
         self.internal_id
     }
 
+    /// The index of the first degree of freedom of this link’s joint in the generalized
+    /// coordinates of the multibody (e.g. in [`Multibody::generalized_velocity`]).
+    ///
+    /// The joint’s degrees of freedom occupy the next `self.joint().ndofs()` entries.
+    ///
+    /// [`Multibody::generalized_velocity`]: crate::dynamics::Multibody::generalized_velocity
+    #[inline]
+    pub fn assembly_id(&self) -> usize {
+        self.assembly_id
+    }
+
     /// The handle of the parent link.
     #[inline]
     pub fn parent_id(&self) -> Option<usize> {
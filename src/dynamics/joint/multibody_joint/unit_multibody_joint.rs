@@ -58,6 +58,9 @@ pub fn unit_joint_limit_constraint(
         rhs_wo_bias,
         cfm_coeff,
         cfm_gain: 0.0,
+        lin_jac: na::zero(),
+        reaction_ang_jac: na::zero(),
+        reaction_sign: 1.0,
         writeback_id: WritebackId::Limit(dof_id),
     };
 
@@ -132,6 +135,9 @@ pub fn unit_joint_motor_constraint(
         inv_lhs: crate::utils::inv(lhs),
         rhs: rhs_wo_bias,
         rhs_wo_bias,
+        lin_jac: na::zero(),
+        reaction_ang_jac: na::zero(),
+        reaction_sign: 1.0,
         writeback_id: WritebackId::Limit(dof_id),
     };
 
@@ -145,3 +151,58 @@ pub fn unit_joint_motor_constraint(
     }
     *j_id += 2 * ndofs;
 }
+
+/// Initializes and generate the velocity constraint simulating the friction resisting the motion
+/// of the multibody link along the given degree of freedom of this multibody_joint.
+pub fn unit_joint_friction_constraint(
+    multibody: &Multibody,
+    link: &MultibodyLink,
+    max_impulse: Real,
+    dof_id: usize,
+    j_id: &mut usize,
+    jacobians: &mut DVector<Real>,
+    constraints: &mut Vec<AnyJointVelocityConstraint>,
+    insert_at: &mut Option<usize>,
+) {
+    let ndofs = multibody.ndofs();
+    let joint_velocity = multibody.joint_velocity(link);
+
+    let dof_j_id = *j_id + dof_id + link.assembly_id;
+    jacobians.rows_mut(*j_id, ndofs * 2).fill(0.0);
+    jacobians[dof_j_id] = 1.0;
+    jacobians[dof_j_id + ndofs] = 1.0;
+    multibody
+        .inv_augmented_mass()
+        .solve_mut(&mut jacobians.rows_mut(*j_id + ndofs, ndofs));
+
+    let lhs = jacobians[dof_j_id + ndofs]; // = J^t * M^-1 J
+    let rhs_wo_bias = joint_velocity[dof_id];
+
+    let constraint = JointGenericVelocityGroundConstraint {
+        mj_lambda2: multibody.solver_id,
+        ndofs2: ndofs,
+        j_id2: *j_id,
+        joint_id: usize::MAX,
+        impulse: 0.0,
+        impulse_bounds: [-max_impulse, max_impulse],
+        cfm_coeff: 0.0,
+        cfm_gain: 0.0,
+        inv_lhs: crate::utils::inv(lhs),
+        rhs: rhs_wo_bias,
+        rhs_wo_bias,
+        lin_jac: na::zero(),
+        reaction_ang_jac: na::zero(),
+        reaction_sign: 1.0,
+        writeback_id: WritebackId::Friction(dof_id),
+    };
+
+    if let Some(at) = insert_at {
+        constraints[*at] = AnyJointVelocityConstraint::JointGenericGroundConstraint(constraint);
+        *at += 1;
+    } else {
+        constraints.push(AnyJointVelocityConstraint::JointGenericGroundConstraint(
+            constraint,
+        ));
+    }
+    *j_id += 2 * ndofs;
+}
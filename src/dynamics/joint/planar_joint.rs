@@ -0,0 +1,209 @@
+use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
+use crate::math::{Point, Real, UnitVector};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// A planar joint, locks all relative motion between two bodies except for translations along a
+/// plane and rotations along the plane’s normal.
+///
+/// The joint’s principal axis is the normal of the plane. Its translational degrees of freedom
+/// are identified by `JointAxis::Y` and `JointAxis::Z`, and its rotational degree of freedom by
+/// `JointAxis::AngX`.
+///
+/// This is typically used for 2.5D simulations: attaching a dynamic body to a fixed body with a
+/// planar joint keeps it on the plane going through the fixed body’s anchor.
+pub struct PlanarJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl PlanarJoint {
+    /// Creates a new planar joint allowing only relative translations orthogonal to the specified
+    /// normal, and relative rotations along this normal.
+    ///
+    /// This normal is expressed in the local-space of both rigid-bodies.
+    pub fn new(normal: UnitVector<Real>) -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::LOCKED_PLANAR_AXES)
+            .local_axis1(normal)
+            .local_axis2(normal)
+            .build();
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    pub fn data(&self) -> &GenericJoint {
+        &self.data
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(&self) -> Point<Real> {
+        self.data.local_anchor1()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    pub fn set_local_anchor1(&mut self, anchor1: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(&self) -> Point<Real> {
+        self.data.local_anchor2()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    pub fn set_local_anchor2(&mut self, anchor2: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// The normal of the plane, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_normal1(&self) -> UnitVector<Real> {
+        self.data.local_axis1()
+    }
+
+    /// Sets the normal of the plane, expressed in the local-space of the first rigid-body.
+    pub fn set_local_normal1(&mut self, normal1: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis1(normal1);
+        self
+    }
+
+    /// The normal of the plane, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_normal2(&self) -> UnitVector<Real> {
+        self.data.local_axis2()
+    }
+
+    /// Sets the normal of the plane, expressed in the local-space of the second rigid-body.
+    pub fn set_local_normal2(&mut self, normal2: UnitVector<Real>) -> &mut Self {
+        self.data.set_local_axis2(normal2);
+        self
+    }
+}
+
+impl Into<GenericJoint> for PlanarJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create planar joints using the builder pattern.
+///
+/// A planar joint locks all relative motion except for translations along a plane and rotations
+/// along the plane’s normal.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlanarJointBuilder(pub PlanarJoint);
+
+impl PlanarJointBuilder {
+    /// Creates a new builder for planar joints.
+    ///
+    /// This normal is expressed in the local-space of both rigid-bodies.
+    pub fn new(normal: UnitVector<Real>) -> Self {
+        Self(PlanarJoint::new(normal))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.0.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.0.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// Sets the normal of the plane, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_normal1(mut self, normal1: UnitVector<Real>) -> Self {
+        self.0.set_local_normal1(normal1);
+        self
+    }
+
+    /// Sets the normal of the plane, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_normal2(mut self, normal2: UnitVector<Real>) -> Self {
+        self.0.set_local_normal2(normal2);
+        self
+    }
+
+    /// Builds the planar joint.
+    #[must_use]
+    pub fn build(self) -> PlanarJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for PlanarJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn planar_joints_keep_bodies_on_their_plane() {
+        use crate::dynamics::PlanarJointBuilder;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::new(0.0, -9.81, -9.81);
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::new(1.0, 2.0, 0.0))
+            .linvel(Vector::repeat(1.0))
+            .angvel(Vector::repeat(1.0))
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::cuboid(0.5, 0.2, 0.1).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+        let joint = PlanarJointBuilder::new(Vector::z_axis()).local_anchor2(Point::origin());
+        state.impulse_joints.insert(ground, body, joint, true);
+
+        step_n(&mut state, &mut pipeline, 300);
+
+        // The body moved along the plane, but never left it nor tilted out of it.
+        let rb = &state.bodies[body];
+        assert!(rb.translation().y < -10.0);
+        assert!(rb.translation().z.abs() < 1.0e-3, "{}", rb.translation());
+        let normal = rb.rotation() * Vector::z();
+        assert!((normal - Vector::z()).norm() < 1.0e-3, "{}", normal);
+        assert!(rb.angvel().z.abs() > 0.5);
+    }
+}
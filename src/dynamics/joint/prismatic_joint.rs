@@ -2,7 +2,7 @@ use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
 use crate::dynamics::{JointAxis, MotorModel};
 use crate::math::{Point, Real, UnitVector};
 
-use super::{JointLimits, JointMotor};
+use super::{JointLimits, JointMotor, JointSoftness};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -139,6 +139,29 @@ impl PrismaticJoint {
         self
     }
 
+    /// Disables the motor, letting the attached bodies translate freely along the joint’s
+    /// principal axis.
+    ///
+    /// The motor can be enabled again by setting its target velocity or position.
+    pub fn disable_motor(&mut self) -> &mut Self {
+        self.data.disable_motor(JointAxis::X);
+        self
+    }
+
+    /// The maximum friction force resisting the relative translations along the joint’s principal axis.
+    #[must_use]
+    pub fn friction(&self) -> Real {
+        self.data.friction(JointAxis::X)
+    }
+
+    /// Sets the maximum friction force resisting the relative translations along the joint’s principal axis.
+    ///
+    /// Setting it to zero (the default) makes the joint frictionless.
+    pub fn set_friction(&mut self, max_force: Real) -> &mut Self {
+        self.data.set_friction(JointAxis::X, max_force);
+        self
+    }
+
     /// The limit distance attached bodies can translate along the joint’s principal axis.
     #[must_use]
     pub fn limits(&self) -> Option<&JointLimits<Real>> {
@@ -150,6 +173,20 @@ impl PrismaticJoint {
         self.data.set_limits(JointAxis::X, limits);
         self
     }
+
+    /// The softness of this joint’s locked degrees of freedom, if any.
+    #[must_use]
+    pub fn softness(&self) -> Option<&JointSoftness> {
+        self.data.softness()
+    }
+
+    /// Sets the softness of this joint’s locked degrees of freedom.
+    ///
+    /// See [`GenericJoint::set_softness`] for more details.
+    pub fn set_softness(&mut self, softness: Option<JointSoftness>) -> &mut Self {
+        self.data.set_softness(softness);
+        self
+    }
 }
 
 impl Into<GenericJoint> for PrismaticJoint {
@@ -249,6 +286,13 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Sets the maximum friction force resisting the relative translations along the joint’s principal axis.
+    #[must_use]
+    pub fn friction(mut self, max_force: Real) -> Self {
+        self.0.set_friction(max_force);
+        self
+    }
+
     /// Sets the `[min,max]` limit distances attached bodies can translate along the joint’s principal axis.
     #[must_use]
     pub fn limits(mut self, limits: [Real; 2]) -> Self {
@@ -256,6 +300,16 @@ impl PrismaticJointBuilder {
         self
     }
 
+    /// Makes this joint soft, with the given stiffness and damping.
+    ///
+    /// See [`GenericJoint::set_softness`] for more details.
+    #[must_use]
+    pub fn softness(mut self, stiffness: Real, damping: Real) -> Self {
+        self.0
+            .set_softness(Some(JointSoftness::new(stiffness, damping)));
+        self
+    }
+
     /// Builds the prismatic joint.
     #[must_use]
     pub fn build(self) -> PrismaticJoint {
@@ -268,3 +322,48 @@ impl Into<GenericJoint> for PrismaticJointBuilder {
         self.0.into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{PrismaticJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+    #[test]
+    fn prismatic_joint_motor_drives_and_free_spins() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        let joint = PrismaticJointBuilder::new(Vector::y_axis())
+            .motor_velocity(1.0, 1.0e3)
+            .motor_max_force(1.0e3);
+        let joint_handle = state.impulse_joints.insert(ground, body, joint, true);
+
+        for i in 0..120 {
+            if i == 60 {
+                let joint = state.impulse_joints.get_mut(joint_handle).unwrap();
+                assert!((state.bodies[body].linvel().y - 1.0).abs() < 1.0e-2);
+                joint.data.as_prismatic_mut().unwrap().disable_motor();
+            }
+
+            state.step(&mut pipeline, &(), &());
+        }
+
+        // Once the motor is disabled, the body falls freely along the joint’s axis.
+        let expected_vel = 1.0 + state.gravity.y * state.integration_parameters.dt * 60.0;
+        let linvel = state.bodies[body].linvel();
+        assert!(
+            (linvel.y - expected_vel).abs() < 1.0e-2,
+            "linvel: {}",
+            linvel
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask, JointCoupling};
+use crate::math::{Point, Real};
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// A pulley joint, couples the distances of two bodies to their respective ground anchor.
+///
+/// Each body is attached to a rope going through a fixed world-space ground anchor. The
+/// distances `length1` and `length2` between each body’s anchor and its ground anchor are
+/// constrained such that `length1 + ratio * length2 = length`.
+///
+/// The rope is treated as rigid: it resists compression as well as stretching.
+pub struct PulleyJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl PulleyJoint {
+    /// Creates a new pulley joint with the given world-space ground anchors, ratio, and total
+    /// rope length.
+    pub fn new(
+        ground_anchor1: Point<Real>,
+        ground_anchor2: Point<Real>,
+        ratio: Real,
+        length: Real,
+    ) -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::FREE_FIXED_AXES)
+            .coupling(JointCoupling::Pulley {
+                ground_anchor1,
+                ground_anchor2,
+                ratio,
+                length,
+            })
+            .build();
+        Self { data }
+    }
+
+    /// The underlying generic joint.
+    pub fn data(&self) -> &GenericJoint {
+        &self.data
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(&self) -> Point<Real> {
+        self.data.local_anchor1()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    pub fn set_local_anchor1(&mut self, anchor1: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(&self) -> Point<Real> {
+        self.data.local_anchor2()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    pub fn set_local_anchor2(&mut self, anchor2: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// The world-space point the rope attached to the first body goes through.
+    #[must_use]
+    pub fn ground_anchor1(&self) -> Point<Real> {
+        match self.data.coupling {
+            Some(JointCoupling::Pulley { ground_anchor1, .. }) => ground_anchor1,
+            _ => Point::origin(),
+        }
+    }
+
+    /// Sets the world-space point the rope attached to the first body goes through.
+    pub fn set_ground_anchor1(&mut self, anchor1: Point<Real>) -> &mut Self {
+        if let Some(JointCoupling::Pulley { ground_anchor1, .. }) = &mut self.data.coupling {
+            *ground_anchor1 = anchor1;
+        }
+        self
+    }
+
+    /// The world-space point the rope attached to the second body goes through.
+    #[must_use]
+    pub fn ground_anchor2(&self) -> Point<Real> {
+        match self.data.coupling {
+            Some(JointCoupling::Pulley { ground_anchor2, .. }) => ground_anchor2,
+            _ => Point::origin(),
+        }
+    }
+
+    /// Sets the world-space point the rope attached to the second body goes through.
+    pub fn set_ground_anchor2(&mut self, anchor2: Point<Real>) -> &mut Self {
+        if let Some(JointCoupling::Pulley { ground_anchor2, .. }) = &mut self.data.coupling {
+            *ground_anchor2 = anchor2;
+        }
+        self
+    }
+
+    /// The pulley ratio.
+    #[must_use]
+    pub fn ratio(&self) -> Real {
+        match self.data.coupling {
+            Some(JointCoupling::Pulley { ratio, .. }) => ratio,
+            _ => 0.0,
+        }
+    }
+
+    /// Sets the pulley ratio.
+    pub fn set_ratio(&mut self, new_ratio: Real) -> &mut Self {
+        if let Some(JointCoupling::Pulley { ratio, .. }) = &mut self.data.coupling {
+            *ratio = new_ratio;
+        }
+        self
+    }
+
+    /// The total length of the rope, i.e., `length1 + ratio * length2`.
+    #[must_use]
+    pub fn length(&self) -> Real {
+        match self.data.coupling {
+            Some(JointCoupling::Pulley { length, .. }) => length,
+            _ => 0.0,
+        }
+    }
+
+    /// Sets the total length of the rope, i.e., `length1 + ratio * length2`.
+    pub fn set_length(&mut self, new_length: Real) -> &mut Self {
+        if let Some(JointCoupling::Pulley { length, .. }) = &mut self.data.coupling {
+            *length = new_length;
+        }
+        self
+    }
+}
+
+impl Into<GenericJoint> for PulleyJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create pulley joints using the builder pattern.
+///
+/// A pulley joint couples the distances of two bodies to their respective ground anchor.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PulleyJointBuilder(pub PulleyJoint);
+
+impl PulleyJointBuilder {
+    /// Creates a new builder for pulley joints.
+    pub fn new(
+        ground_anchor1: Point<Real>,
+        ground_anchor2: Point<Real>,
+        ratio: Real,
+        length: Real,
+    ) -> Self {
+        Self(PulleyJoint::new(
+            ground_anchor1,
+            ground_anchor2,
+            ratio,
+            length,
+        ))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.0.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.0.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// Builds the pulley joint.
+    #[must_use]
+    pub fn build(self) -> PulleyJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for PulleyJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
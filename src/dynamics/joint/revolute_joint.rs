@@ -1,6 +1,6 @@
 use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
 use crate::dynamics::{JointAxis, JointLimits, JointMotor, MotorModel};
-use crate::math::{Point, Real};
+use crate::math::{Point, Real, Rotation};
 
 #[cfg(feature = "dim3")]
 use crate::math::UnitVector;
@@ -74,6 +74,27 @@ impl RevoluteJoint {
         self
     }
 
+    /// The angle along the free degree of freedom of this revolute joint in `[-π, π]`.
+    ///
+    /// This is the quantity driven by the joint’s position motor and bounded by its limits.
+    ///
+    /// # Parameters
+    /// - `rb_rot1`: the rotation of the first rigid-body attached to this revolute joint.
+    /// - `rb_rot2`: the rotation of the second rigid-body attached to this revolute joint.
+    #[must_use]
+    pub fn angle(&self, rb_rot1: &Rotation<Real>, rb_rot2: &Rotation<Real>) -> Real {
+        let joint_rot1 = rb_rot1 * self.data.local_frame1.rotation;
+        let joint_rot2 = rb_rot2 * self.data.local_frame2.rotation;
+        let ang_err = joint_rot1.inverse() * joint_rot2;
+
+        #[cfg(feature = "dim3")]
+        if ang_err.i * ang_err.w < 0.0 {
+            return -ang_err.angle();
+        }
+
+        ang_err.angle()
+    }
+
     /// The motor affecting the joint’s rotational degree of freedom.
     #[must_use]
     pub fn motor(&self) -> Option<&JointMotor> {
@@ -124,13 +145,27 @@ impl RevoluteJoint {
         self
     }
 
-    /// The limit angle attached bodies can translate along the joint’s principal axis.
+    /// The maximum friction torque resisting the relative rotations along the joint’s principal axis.
+    #[must_use]
+    pub fn friction(&self) -> Real {
+        self.data.friction(JointAxis::AngX)
+    }
+
+    /// Sets the maximum friction torque resisting the relative rotations along the joint’s principal axis.
+    ///
+    /// Setting it to zero (the default) makes the joint frictionless.
+    pub fn set_friction(&mut self, max_torque: Real) -> &mut Self {
+        self.data.set_friction(JointAxis::AngX, max_torque);
+        self
+    }
+
+    /// The limit angle attached bodies can rotate along the joint’s principal axis.
     #[must_use]
     pub fn limits(&self) -> Option<&JointLimits<Real>> {
         self.data.limits(JointAxis::AngX)
     }
 
-    /// Sets the `[min,max]` limit angle attached bodies can translate along the joint’s principal axis.
+    /// Sets the `[min,max]` limit angles attached bodies can rotate along the joint’s principal axis.
     pub fn set_limits(&mut self, limits: [Real; 2]) -> &mut Self {
         self.data.set_limits(JointAxis::AngX, limits);
         self
@@ -227,6 +262,13 @@ impl RevoluteJointBuilder {
         self
     }
 
+    /// Sets the maximum friction torque resisting the relative rotations along the joint’s principal axis.
+    #[must_use]
+    pub fn friction(mut self, max_torque: Real) -> Self {
+        self.0.set_friction(max_torque);
+        self
+    }
+
     /// Sets the `[min,max]` limit angles attached bodies can rotate along the joint’s principal axis.
     #[must_use]
     pub fn limits(mut self, limits: [Real; 2]) -> Self {
@@ -246,3 +288,137 @@ impl Into<GenericJoint> for RevoluteJointBuilder {
         self.0.into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn revolute_joint_position_motor_reaches_target() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        // Use a target far from zero: it used to be approximated by its sine.
+        let target_angle = 2.5;
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint.motor_position(target_angle, 100.0, 20.0);
+        let joint_handle = state.impulse_joints.insert(ground, body, joint, true);
+
+        step_n(&mut state, &mut pipeline, 600);
+
+        let joint = state.impulse_joints.get(joint_handle).unwrap();
+        let angle = joint.data.as_revolute().unwrap().angle(
+            state.bodies[ground].rotation(),
+            state.bodies[body].rotation(),
+        );
+        assert!(
+            (angle - target_angle).abs() < 1.0e-2,
+            "angle: {}, target: {}",
+            angle,
+            target_angle
+        );
+    }
+
+    #[test]
+    fn revolute_joint_limits_are_enforced() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        #[cfg(feature = "dim2")]
+        let body = RigidBodyBuilder::dynamic().angvel(5.0);
+        #[cfg(feature = "dim3")]
+        let body = RigidBodyBuilder::dynamic().angvel(Vector::z() * 5.0);
+        let body = state.bodies.insert(body.build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        let limits = [-0.5, 0.5];
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint_handle = state
+            .impulse_joints
+            .insert(ground, body, joint.limits(limits), true);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        let joint = state.impulse_joints.get(joint_handle).unwrap();
+        let angle = joint.data.as_revolute().unwrap().angle(
+            state.bodies[ground].rotation(),
+            state.bodies[body].rotation(),
+        );
+        assert!((angle - limits[1]).abs() < 1.0e-2, "angle: {}", angle);
+    }
+
+    #[test]
+    fn revolute_joint_friction_decelerates_rotation() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        #[cfg(feature = "dim2")]
+        let body = RigidBodyBuilder::dynamic().angvel(2.0);
+        #[cfg(feature = "dim3")]
+        let body = RigidBodyBuilder::dynamic().angvel(Vector::z() * 2.0);
+        let body = state.bodies.insert(body.build());
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let friction = 0.1;
+        state
+            .impulse_joints
+            .insert(ground, body, joint.friction(friction), true);
+
+        #[cfg(feature = "dim2")]
+        let inertia = state.bodies[body].mass_properties().principal_inertia();
+        #[cfg(feature = "dim3")]
+        let inertia = state.bodies[body].mass_properties().principal_inertia().z;
+        let stop_time = 2.0 * inertia / friction;
+        let num_steps = (stop_time / state.integration_parameters.dt) as usize;
+
+        for i in 0..num_steps * 2 {
+            if i == num_steps / 2 {
+                // The friction torque is constant so the angular velocity decreases linearly.
+                #[cfg(feature = "dim2")]
+                let angvel = state.bodies[body].angvel();
+                #[cfg(feature = "dim3")]
+                let angvel = state.bodies[body].angvel().z;
+                assert!((angvel - 1.0).abs() < 2.0e-2, "angvel: {}", angvel);
+            }
+
+            state.step(&mut pipeline, &(), &());
+        }
+
+        #[cfg(feature = "dim2")]
+        assert!(state.bodies[body].angvel().abs() < 1.0e-3);
+        #[cfg(feature = "dim3")]
+        assert!(state.bodies[body].angvel().norm() < 1.0e-3);
+    }
+}
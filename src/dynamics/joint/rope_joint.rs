@@ -7,17 +7,21 @@ use super::{JointLimits, JointMotor};
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
 #[repr(transparent)]
-/// A rope joint, limits the maximum distance between two bodies
+/// A rope joint, limits the maximum distance between two bodies.
+///
+/// Unlike a spherical joint, it doesn’t pin the anchors together: it only applies impulses
+/// when the distance between both anchors gets out of the `[min, max]` range.
 pub struct RopeJoint {
     /// The underlying joint data.
     pub data: GenericJoint,
 }
 
 impl RopeJoint {
-    /// Creates a new rope joint limiting the max distance between to bodies
-    pub fn new() -> Self {
+    /// Creates a new rope joint limiting the max distance between two bodies.
+    pub fn new(max_dist: Real) -> Self {
         let data = GenericJointBuilder::new(JointAxesMask::FREE_FIXED_AXES)
             .coupled_axes(JointAxesMask::LIN_AXES)
+            .limits(JointAxis::X, [0.0, max_dist])
             .build();
         Self { data }
     }
@@ -86,18 +90,28 @@ impl RopeJoint {
         self
     }
 
-    /// The motor affecting the joint’s translational degree of freedom.
+    /// The maximum distance allowed between the attached bodies.
+    #[must_use]
+    pub fn max_distance(&self) -> Option<Real> {
+        self.data.limits(JointAxis::X).map(|l| l.max)
+    }
+
+    /// Sets the maximum distance allowed between the attached bodies.
+    pub fn set_max_distance(&mut self, max_dist: Real) -> &mut Self {
+        let min_dist = self.data.limits(JointAxis::X).map(|l| l.min).unwrap_or(0.0);
+        self.data.set_limits(JointAxis::X, [min_dist, max_dist]);
+        self
+    }
+
+    /// The motor affecting the distance between the attached bodies.
     #[must_use]
-    pub fn motor(&self, axis: JointAxis) -> Option<&JointMotor> {
-        self.data.motor(axis)
+    pub fn motor(&self) -> Option<&JointMotor> {
+        self.data.motor(JointAxis::X)
     }
 
     /// Set the spring-like model used by the motor to reach the desired target velocity and position.
     pub fn set_motor_model(&mut self, model: MotorModel) -> &mut Self {
         self.data.set_motor_model(JointAxis::X, model);
-        self.data.set_motor_model(JointAxis::Y, model);
-        #[cfg(feature = "dim3")]
-        self.data.set_motor_model(JointAxis::Z, model);
         self
     }
 
@@ -105,15 +119,10 @@ impl RopeJoint {
     pub fn set_motor_velocity(&mut self, target_vel: Real, factor: Real) -> &mut Self {
         self.data
             .set_motor_velocity(JointAxis::X, target_vel, factor);
-        self.data
-            .set_motor_velocity(JointAxis::Y, target_vel, factor);
-        #[cfg(feature = "dim3")]
-        self.data
-            .set_motor_velocity(JointAxis::Z, target_vel, factor);
         self
     }
 
-    /// Sets the target angle this motor needs to reach.
+    /// Sets the target distance this motor needs to reach.
     pub fn set_motor_position(
         &mut self,
         target_pos: Real,
@@ -122,15 +131,10 @@ impl RopeJoint {
     ) -> &mut Self {
         self.data
             .set_motor_position(JointAxis::X, target_pos, stiffness, damping);
-        self.data
-            .set_motor_position(JointAxis::Y, target_pos, stiffness, damping);
-        #[cfg(feature = "dim3")]
-        self.data
-            .set_motor_position(JointAxis::Z, target_pos, stiffness, damping);
         self
     }
 
-    /// Configure both the target angle and target velocity of the motor.
+    /// Configure both the target distance and target velocity of the motor.
     pub fn set_motor(
         &mut self,
         target_pos: Real,
@@ -140,35 +144,24 @@ impl RopeJoint {
     ) -> &mut Self {
         self.data
             .set_motor(JointAxis::X, target_pos, target_vel, stiffness, damping);
-        self.data
-            .set_motor(JointAxis::Y, target_pos, target_vel, stiffness, damping);
-        #[cfg(feature = "dim3")]
-        self.data
-            .set_motor(JointAxis::Y, target_pos, target_vel, stiffness, damping);
         self
     }
 
     /// Sets the maximum force the motor can deliver.
     pub fn set_motor_max_force(&mut self, max_force: Real) -> &mut Self {
         self.data.set_motor_max_force(JointAxis::X, max_force);
-        self.data.set_motor_max_force(JointAxis::Y, max_force);
-        #[cfg(feature = "dim3")]
-        self.data.set_motor_max_force(JointAxis::Z, max_force);
         self
     }
 
-    /// The limit maximum distance attached bodies can translate.
+    /// The `[min,max]` distances allowed between the attached bodies.
     #[must_use]
-    pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
-        self.data.limits(axis)
+    pub fn limits(&self) -> Option<&JointLimits<Real>> {
+        self.data.limits(JointAxis::X)
     }
 
-    /// Sets the `[min,max]` limit distances attached bodies can translate.
+    /// Sets the `[min,max]` distances allowed between the attached bodies.
     pub fn set_limits(&mut self, limits: [Real; 2]) -> &mut Self {
         self.data.set_limits(JointAxis::X, limits);
-        self.data.set_limits(JointAxis::Y, limits);
-        #[cfg(feature = "dim3")]
-        self.data.set_limits(JointAxis::Z, limits);
         self
     }
 }
@@ -187,11 +180,9 @@ impl Into<GenericJoint> for RopeJoint {
 pub struct RopeJointBuilder(pub RopeJoint);
 
 impl RopeJointBuilder {
-    /// Creates a new builder for rope joints.
-    ///
-    /// This axis is expressed in the local-space of both rigid-bodies.
-    pub fn new() -> Self {
-        Self(RopeJoint::new())
+    /// Creates a new builder for rope joints limiting the max distance between two bodies.
+    pub fn new(max_dist: Real) -> Self {
+        Self(RopeJoint::new(max_dist))
     }
 
     /// Sets whether contacts between the attached rigid-bodies are enabled.
@@ -243,16 +234,16 @@ impl RopeJointBuilder {
         self
     }
 
-    /// Sets the target angle this motor needs to reach.
+    /// Sets the target distance this motor needs to reach.
     #[must_use]
     pub fn motor_position(mut self, target_pos: Real, stiffness: Real, damping: Real) -> Self {
         self.0.set_motor_position(target_pos, stiffness, damping);
         self
     }
 
-    /// Configure both the target angle and target velocity of the motor.
+    /// Configure both the target distance and target velocity of the motor.
     #[must_use]
-    pub fn set_motor(
+    pub fn motor(
         mut self,
         target_pos: Real,
         target_vel: Real,
@@ -270,7 +261,14 @@ impl RopeJointBuilder {
         self
     }
 
-    /// Sets the `[min,max]` limit distances attached bodies can translate.
+    /// Sets the maximum distance allowed between the attached bodies.
+    #[must_use]
+    pub fn max_distance(mut self, max_dist: Real) -> Self {
+        self.0.set_max_distance(max_dist);
+        self
+    }
+
+    /// Sets the `[min,max]` distances allowed between the attached bodies.
     #[must_use]
     pub fn limits(mut self, limits: [Real; 2]) -> Self {
         self.0.set_limits(limits);
@@ -289,3 +287,47 @@ impl Into<GenericJoint> for RopeJointBuilder {
         self.0.into()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RopeJointBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+    #[test]
+    fn rope_joint_limits_max_distance() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 0.5)
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.1).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        let max_dist = 2.0;
+        state
+            .impulse_joints
+            .insert(ground, body, RopeJointBuilder::new(max_dist), true);
+
+        for i in 0..300 {
+            state.step(&mut pipeline, &(), &());
+
+            let dist = state.bodies[body].translation().norm();
+            assert!(dist < max_dist + 1.0e-2, "dist: {}", dist);
+
+            if i == 10 {
+                // The rope is still slack: the body must be falling freely.
+                assert!(state.bodies[body].linvel().x.abs() < 1.0e-5);
+            }
+        }
+
+        let dist = state.bodies[body].translation().norm();
+        assert!((dist - max_dist).abs() < 1.0e-2, "dist: {}", dist);
+    }
+}
@@ -2,7 +2,7 @@ use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
 use crate::dynamics::{JointAxis, JointMotor, MotorModel};
 use crate::math::{Point, Real};
 
-use super::JointLimits;
+use super::{JointLimits, JointSoftness};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -122,17 +122,77 @@ impl SphericalJoint {
         self
     }
 
-    /// The limit distance attached bodies can translate along the specified axis.
+    /// The limit angle attached bodies can rotate along the specified axis.
     #[must_use]
     pub fn limits(&self, axis: JointAxis) -> Option<&JointLimits<Real>> {
         self.data.limits(axis)
     }
 
-    /// Sets the `[min,max]` limit angles attached bodies can translate along the joint’s principal axis.
+    /// Sets the `[min,max]` limit angles attached bodies can rotate along the specified axis.
     pub fn set_limits(&mut self, axis: JointAxis, limits: [Real; 2]) -> &mut Self {
         self.data.set_limits(axis, limits);
         self
     }
+
+    /// The softness of this joint’s locked degrees of freedom, if any.
+    #[must_use]
+    pub fn softness(&self) -> Option<&JointSoftness> {
+        self.data.softness()
+    }
+
+    /// Sets the softness of this joint’s locked degrees of freedom.
+    ///
+    /// See [`GenericJoint::set_softness`] for more details.
+    pub fn set_softness(&mut self, softness: Option<JointSoftness>) -> &mut Self {
+        self.data.set_softness(softness);
+        self
+    }
+
+    /// The maximum angle between the X axes of the joint’s local frames, if a swing limit is set.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn swing_limit(&self) -> Option<Real> {
+        let swing_axes = JointAxesMask::ANG_Y | JointAxesMask::ANG_Z;
+        if self.data.coupled_axes.contains(swing_axes)
+            && self.data.limit_axes.intersects(swing_axes)
+        {
+            let max_y = self.data.limits[JointAxis::AngY as usize].max;
+            let max_z = self.data.limits[JointAxis::AngZ as usize].max;
+            Some((max_y * max_y + max_z * max_z).sqrt())
+        } else {
+            None
+        }
+    }
+
+    /// Limits the angle between the X axes of the joint’s local frames to `max_angle`, i.e.,
+    /// constrains the X axis of the second body to a cone around the X axis of the first body.
+    ///
+    /// This couples the `AngY` and `AngZ` axes, so their per-axis limits, motors, and friction
+    /// are replaced by this single swing limit. Multibody joints don’t support swing limits yet
+    /// and ignore them.
+    #[cfg(feature = "dim3")]
+    pub fn set_swing_limit(&mut self, max_angle: Real) -> &mut Self {
+        self.data.coupled_axes |= JointAxesMask::ANG_Y | JointAxesMask::ANG_Z;
+        // NOTE: the coupled limit is the norm of the limits of the coupled axes.
+        self.data.set_limits(JointAxis::AngY, [0.0, max_angle]);
+        self.data.set_limits(JointAxis::AngZ, [0.0, 0.0]);
+        self
+    }
+
+    /// The `[min,max]` limit angles of the rotation around the X axis of the joint’s local frames.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn twist_limits(&self) -> Option<&JointLimits<Real>> {
+        self.data.limits(JointAxis::AngX)
+    }
+
+    /// Sets the `[min,max]` limit angles of the rotation around the X axis of the joint’s local
+    /// frames.
+    #[cfg(feature = "dim3")]
+    pub fn set_twist_limits(&mut self, limits: [Real; 2]) -> &mut Self {
+        self.data.set_limits(JointAxis::AngX, limits);
+        self
+    }
 }
 
 impl Into<GenericJoint> for SphericalJoint {
@@ -229,13 +289,42 @@ impl SphericalJointBuilder {
         self
     }
 
-    /// Sets the `[min,max]` limit distances attached bodies can rotate along the specified axis.
+    /// Sets the `[min,max]` limit angles attached bodies can rotate along the specified axis.
     #[must_use]
     pub fn limits(mut self, axis: JointAxis, limits: [Real; 2]) -> Self {
         self.0.set_limits(axis, limits);
         self
     }
 
+    /// Makes this joint soft, with the given stiffness and damping.
+    ///
+    /// See [`GenericJoint::set_softness`] for more details.
+    #[must_use]
+    pub fn softness(mut self, stiffness: Real, damping: Real) -> Self {
+        self.0
+            .set_softness(Some(JointSoftness::new(stiffness, damping)));
+        self
+    }
+
+    /// Limits the angle between the X axes of the joint’s local frames to `max_angle`.
+    ///
+    /// See [`SphericalJoint::set_swing_limit`].
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn swing_limit(mut self, max_angle: Real) -> Self {
+        self.0.set_swing_limit(max_angle);
+        self
+    }
+
+    /// Sets the `[min,max]` limit angles of the rotation around the X axis of the joint’s local
+    /// frames.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn twist_limits(mut self, limits: [Real; 2]) -> Self {
+        self.0.set_twist_limits(limits);
+        self
+    }
+
     /// Builds the spherical joint.
     #[must_use]
     pub fn build(self) -> SphericalJoint {
@@ -248,3 +337,89 @@ impl Into<GenericJoint> for SphericalJointBuilder {
         self.0.into()
     }
 }
+
+#[cfg(all(test, feature = "dim3"))]
+mod test {
+    use crate::dynamics::{FixedJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+
+    #[test]
+    fn spherical_joint_swing_and_twist_limits() {
+        use crate::dynamics::SphericalJointBuilder;
+        use crate::pipeline::PhysicsState;
+
+        let swing_limit = 0.5;
+        let twist_limits = [-0.2, 0.2];
+        let joint = SphericalJointBuilder::new()
+            .local_anchor2(Point::from(-Vector::x()))
+            .swing_limit(swing_limit)
+            .twist_limits(twist_limits)
+            .build();
+        assert_eq!(joint.swing_limit(), Some(swing_limit));
+        assert_eq!(
+            joint.twist_limits().map(|l| [l.min, l.max]),
+            Some(twist_limits)
+        );
+
+        // The second case solves the joint with the generic formulation, because the limited
+        // body is the root of a multibody.
+        for attach_to_multibody in [false, true] {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+
+            let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+            let body = RigidBodyBuilder::dynamic().translation(Vector::x()).build();
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.2).build();
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            state.impulse_joints.insert(ground, body, joint, true);
+
+            if attach_to_multibody {
+                let link = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * 1.5)
+                    .build();
+                let link = state.bodies.insert(link);
+                let collider = ColliderBuilder::ball(0.2).build();
+                state
+                    .colliders
+                    .insert_with_parent(collider, link, &mut state.bodies);
+                let link_joint = FixedJointBuilder::new().local_anchor1(Point::new(0.5, 0.0, 0.0));
+                state
+                    .multibody_joints
+                    .insert(body, link, link_joint, true)
+                    .unwrap();
+            }
+
+            // The gravity swings the body down while a torque spins it around the joint’s X axis.
+            state.bodies[body].add_torque(Vector::x() * 1.0e-2, true);
+            let mut max_twist: Real = 0.0;
+
+            for _ in 0..200 {
+                state.step(&mut pipeline, &(), &());
+
+                let rot = state.bodies[body].rotation();
+                let swing = (rot * Vector::x()).angle(&Vector::x());
+                let twist = 2.0 * rot.i.atan2(rot.w);
+                max_twist = max_twist.max(twist.abs());
+                // NOTE: the generic constraints aren’t softened, so the multibody case can
+                //       briefly overshoot the swing limit when reaching it.
+                assert!(swing < swing_limit + 0.1, "swing: {}", swing);
+                assert!(twist.abs() < twist_limits[1] + 5.0e-2, "twist: {}", twist);
+            }
+
+            // Both limits were reached, and the gravity keeps the body against the swing limit.
+            let rot = state.bodies[body].rotation();
+            let swing = (rot * Vector::x()).angle(&Vector::x());
+            assert!((swing - swing_limit).abs() < 5.0e-2, "swing: {}", swing);
+            assert!(
+                max_twist > twist_limits[1] - 5.0e-2,
+                "max twist: {}",
+                max_twist
+            );
+        }
+    }
+}
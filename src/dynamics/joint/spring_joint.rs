@@ -0,0 +1,248 @@
+use crate::dynamics::joint::{GenericJoint, GenericJointBuilder, JointAxesMask};
+use crate::dynamics::{JointAxis, MotorModel};
+use crate::math::{Point, Real};
+
+use super::JointMotor;
+
+#[cfg(feature = "dim2")]
+const LIN_AXES: [JointAxis; 2] = [JointAxis::X, JointAxis::Y];
+#[cfg(feature = "dim3")]
+const LIN_AXES: [JointAxis; 3] = [JointAxis::X, JointAxis::Y, JointAxis::Z];
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+/// A spring-damper joint, applies a force proportional to the distance between two anchors.
+///
+/// Unlike the other joints, it doesn’t lock any degree of freedom: the distance between both
+/// anchors is softly pulled toward the spring’s rest length instead. The spring is solved
+/// implicitly so it remains stable even with a large stiffness, but it is subject to some
+/// numerical damping.
+///
+/// Spring joints are only supported as impulse joints: multibody joints ignore coupled axes.
+pub struct SpringJoint {
+    /// The underlying joint data.
+    pub data: GenericJoint,
+}
+
+impl SpringJoint {
+    /// Creates a new spring joint with the given rest length, stiffness, and damping.
+    ///
+    /// By default, the spring follows a force-based model: see [`SpringJoint::set_spring_model`].
+    pub fn new(rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        let data = GenericJointBuilder::new(JointAxesMask::FREE_FIXED_AXES).build();
+        let mut result = Self { data };
+        result
+            .set_spring(rest_length, stiffness, damping)
+            .set_spring_model(MotorModel::ForceBased);
+        result
+    }
+
+    /// The underlying generic joint.
+    pub fn data(&self) -> &GenericJoint {
+        &self.data
+    }
+
+    /// Are contacts between the attached rigid-bodies enabled?
+    pub fn contacts_enabled(&self) -> bool {
+        self.data.contacts_enabled
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    pub fn set_contacts_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.data.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(&self) -> Point<Real> {
+        self.data.local_anchor1()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    pub fn set_local_anchor1(&mut self, anchor1: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// The joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(&self) -> Point<Real> {
+        self.data.local_anchor2()
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    pub fn set_local_anchor2(&mut self, anchor2: Point<Real>) -> &mut Self {
+        self.data.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// The motor simulating the spring.
+    ///
+    /// Its `target_pos` is the spring’s rest length.
+    #[must_use]
+    pub fn spring(&self) -> Option<&JointMotor> {
+        self.data.motor(JointAxis::X)
+    }
+
+    /// Set the spring model used by this joint to reach the desired target velocity and position.
+    ///
+    /// Setting this to `MotorModel::ForceBased` (which is the default value for this joint) makes the spring constants
+    /// (stiffness and damping) parameter understood as in the regular spring-mass-damper system. With
+    /// `MotorModel::AccelerationBased`, the spring constants will be automatically scaled by the attached masses,
+    /// making the spring more mass-independent.
+    pub fn set_spring_model(&mut self, model: MotorModel) -> &mut Self {
+        for axis in LIN_AXES {
+            self.data.set_motor_model(axis, model);
+        }
+        self
+    }
+
+    /// Sets the rest length, stiffness, and damping of the spring.
+    pub fn set_spring(&mut self, rest_length: Real, stiffness: Real, damping: Real) -> &mut Self {
+        if rest_length == 0.0 {
+            // NOTE: the direction between both anchors is undefined once they coincide, so a
+            //       spring with a zero rest length pulls along each linear axis independently
+            //       instead, which results in the same force.
+            self.data.coupled_axes.remove(JointAxesMask::LIN_AXES);
+            for axis in LIN_AXES {
+                self.data.set_motor_position(axis, 0.0, stiffness, damping);
+            }
+        } else {
+            self.data.coupled_axes.insert(JointAxesMask::LIN_AXES);
+            for axis in &LIN_AXES[1..] {
+                self.data.disable_motor(*axis);
+            }
+            self.data
+                .set_motor_position(JointAxis::X, rest_length, stiffness, damping);
+        }
+        self
+    }
+}
+
+impl Into<GenericJoint> for SpringJoint {
+    fn into(self) -> GenericJoint {
+        self.data
+    }
+}
+
+/// Create spring joints using the builder pattern.
+///
+/// A spring joint applies a force proportional to the distance between two anchors.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SpringJointBuilder(pub SpringJoint);
+
+impl SpringJointBuilder {
+    /// Creates a new builder for spring joints.
+    pub fn new(rest_length: Real, stiffness: Real, damping: Real) -> Self {
+        Self(SpringJoint::new(rest_length, stiffness, damping))
+    }
+
+    /// Sets whether contacts between the attached rigid-bodies are enabled.
+    #[must_use]
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.0.set_contacts_enabled(enabled);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the first rigid-body.
+    #[must_use]
+    pub fn local_anchor1(mut self, anchor1: Point<Real>) -> Self {
+        self.0.set_local_anchor1(anchor1);
+        self
+    }
+
+    /// Sets the joint’s anchor, expressed in the local-space of the second rigid-body.
+    #[must_use]
+    pub fn local_anchor2(mut self, anchor2: Point<Real>) -> Self {
+        self.0.set_local_anchor2(anchor2);
+        self
+    }
+
+    /// Set the spring model used by this joint to reach the desired target velocity and position.
+    ///
+    /// See [`SpringJoint::set_spring_model`] for more details.
+    #[must_use]
+    pub fn spring_model(mut self, model: MotorModel) -> Self {
+        self.0.set_spring_model(model);
+        self
+    }
+
+    /// Builds the spring joint.
+    #[must_use]
+    pub fn build(self) -> SpringJoint {
+        self.0
+    }
+}
+
+impl Into<GenericJoint> for SpringJointBuilder {
+    fn into(self) -> GenericJoint {
+        self.0.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, SpringJointBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn spring_joint_reaches_equilibrium() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::y() * -1.0)
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        let (rest_length, stiffness) = (1.0, 100.0);
+        let joint = SpringJointBuilder::new(rest_length, stiffness, 5.0);
+        state.impulse_joints.insert(ground, body, joint, true);
+
+        step_n(&mut state, &mut pipeline, 600);
+
+        // At equilibrium, the spring force compensates the state.gravity.
+        let expected = rest_length + state.bodies[body].mass() * 9.81 / stiffness;
+        let dist = state.bodies[body].translation().norm();
+        assert!((dist - expected).abs() < 1.0e-2, "dist: {}", dist);
+    }
+
+    #[test]
+    fn zero_length_spring_joint_pulls_the_anchors_together() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        // The body orbits around the ground anchor: since the anchors are pulled together along
+        // every axis, the damping must also slow down the tangential motion.
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x())
+            .linvel(Vector::y() * 10.0)
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        let joint = SpringJointBuilder::new(0.0, 100.0, 10.0);
+        state.impulse_joints.insert(ground, body, joint, true);
+
+        step_n(&mut state, &mut pipeline, 600);
+
+        let dist = state.bodies[body].translation().norm();
+        assert!(dist < 1.0e-3, "dist: {}", dist);
+    }
+}
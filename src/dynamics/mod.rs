@@ -7,8 +7,8 @@ pub use self::island_manager::IslandManager;
 pub(crate) use self::joint::JointGraphEdge;
 pub(crate) use self::joint::JointIndex;
 pub use self::joint::*;
+pub use self::ragdoll::{Ragdoll, RagdollBone, RagdollBuilder};
 pub use self::rigid_body_components::*;
-#[cfg(not(feature = "parallel"))]
 pub(crate) use self::solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::solver::ParallelIslandSolver;
@@ -22,6 +22,7 @@ mod coefficient_combine_rule;
 mod integration_parameters;
 mod island_manager;
 mod joint;
+mod ragdoll;
 mod rigid_body_components;
 mod solver;
 
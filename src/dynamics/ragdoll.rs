@@ -0,0 +1,214 @@
+//! Utilities to build ragdolls from a skeleton description.
+
+use crate::dynamics::{
+    GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet, JointAxesMask, JointAxis,
+    RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{ColliderBuilder, ColliderHandle, ColliderSet};
+use crate::math::{Isometry, Real, Vector, ANG_DIM};
+
+#[cfg(feature = "dim2")]
+const LIMITED_AXES: [JointAxis; ANG_DIM] = [JointAxis::AngX];
+#[cfg(feature = "dim3")]
+const LIMITED_AXES: [JointAxis; ANG_DIM] = [JointAxis::AngX, JointAxis::AngY, JointAxis::AngZ];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// The description of one bone of a ragdoll.
+///
+/// A bone is simulated by a capsule starting at its position and extending along its local
+/// positive Y axis. It is attached to its parent bone (if any) with a joint located at its
+/// position: a revolute joint in 2D, and a spherical joint in 3D.
+pub struct RagdollBone {
+    /// The index of the parent bone, or `None` if this is the root bone.
+    ///
+    /// The parent must be added to the [`RagdollBuilder`] before its children.
+    pub parent: Option<usize>,
+    /// The world-space position of the start of the bone at the ragdoll’s rest pose.
+    pub position: Isometry<Real>,
+    /// The length of the bone.
+    pub length: Real,
+    /// The radius of the capsule simulating the bone.
+    pub radius: Real,
+    /// The mass of the bone.
+    pub mass: Real,
+    /// The `[min, max]` angles the bone can rotate relative to its parent, along each angular
+    /// axis of the joint attaching them.
+    ///
+    /// The angles are measured from the rest pose, and expressed in the local-space of the bone.
+    pub joint_limits: [[Real; 2]; ANG_DIM],
+}
+
+impl RagdollBone {
+    /// The default joint limits of a bone, allowing rotations of up to 45 degrees along each
+    /// axis relative to its parent.
+    pub const DEFAULT_JOINT_LIMITS: [[Real; 2]; ANG_DIM] = [[
+        -std::f32::consts::FRAC_PI_4 as Real,
+        std::f32::consts::FRAC_PI_4 as Real,
+    ]; ANG_DIM];
+
+    /// Creates the description of a bone with the given world-space position at rest pose, length,
+    /// radius, and mass.
+    ///
+    /// The joint attaching this bone to its parent is limited to [`Self::DEFAULT_JOINT_LIMITS`].
+    pub fn new(
+        parent: Option<usize>,
+        position: Isometry<Real>,
+        length: Real,
+        radius: Real,
+        mass: Real,
+    ) -> Self {
+        Self {
+            parent,
+            position,
+            length,
+            radius,
+            mass,
+            joint_limits: Self::DEFAULT_JOINT_LIMITS,
+        }
+    }
+
+    /// Sets the `[min, max]` angles the bone can rotate relative to its parent along each axis.
+    #[must_use]
+    pub fn joint_limits(mut self, limits: [[Real; 2]; ANG_DIM]) -> Self {
+        self.joint_limits = limits;
+        self
+    }
+}
+
+/// The rigid-bodies, colliders, and joints created by [`RagdollBuilder::build`].
+#[derive(Clone, Debug, Default)]
+pub struct Ragdoll {
+    /// The rigid-body simulating each bone, in the same order as the bones of the builder.
+    pub bodies: Vec<RigidBodyHandle>,
+    /// The capsule collider attached to each bone, in the same order as the bones of the builder.
+    pub colliders: Vec<ColliderHandle>,
+    /// The joint attaching each bone to its parent, or `None` for the root bone(s).
+    pub joints: Vec<Option<ImpulseJointHandle>>,
+}
+
+/// Create ragdolls from a skeleton description, using the builder pattern.
+#[derive(Clone, Debug, Default)]
+pub struct RagdollBuilder {
+    bones: Vec<RagdollBone>,
+}
+
+impl RagdollBuilder {
+    /// Creates a new ragdoll builder without any bone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a bone to this ragdoll.
+    ///
+    /// The index of this bone is the number of bones added before it.
+    ///
+    /// # Panics
+    /// Panics if the bone’s parent wasn’t added before it.
+    #[must_use]
+    pub fn bone(mut self, bone: RagdollBone) -> Self {
+        if let Some(parent) = bone.parent {
+            assert!(
+                parent < self.bones.len(),
+                "The parent of a ragdoll bone must be added before it."
+            );
+        }
+
+        self.bones.push(bone);
+        self
+    }
+
+    /// The bones added to this builder.
+    pub fn bones(&self) -> &[RagdollBone] {
+        &self.bones
+    }
+
+    /// Creates the rigid-bodies, colliders, and joints of this ragdoll, and inserts them into the
+    /// given sets.
+    ///
+    /// Contacts between a bone and its parent are disabled.
+    pub fn build(
+        &self,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+    ) -> Ragdoll {
+        let mut result = Ragdoll::default();
+
+        for bone in &self.bones {
+            let body = bodies.insert(RigidBodyBuilder::dynamic().position(bone.position));
+            let half_length = bone.length / 2.0;
+            let collider = ColliderBuilder::capsule_y(half_length, bone.radius)
+                .translation(Vector::y() * half_length)
+                .mass(bone.mass);
+            let collider = colliders.insert_with_parent(collider, body, bodies);
+
+            let joint = bone.parent.map(|parent| {
+                let parent_pos = self.bones[parent].position;
+                // NOTE: locking only the linear axes results in a revolute joint in 2D and a
+                //       spherical joint in 3D.
+                let mut joint = GenericJointBuilder::new(JointAxesMask::LIN_AXES)
+                    .local_frame1(parent_pos.inv_mul(&bone.position))
+                    .local_frame2(Isometry::identity())
+                    .contacts_enabled(false);
+
+                for (axis, limits) in LIMITED_AXES.iter().zip(bone.joint_limits.iter()) {
+                    joint = joint.limits(*axis, *limits);
+                }
+
+                impulse_joints.insert(result.bodies[parent], body, joint, true)
+            });
+
+            result.bodies.push(body);
+            result.colliders.push(collider);
+            result.joints.push(joint);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn ragdoll_bones_stay_attached_within_limits() {
+        use crate::dynamics::{RagdollBone, RagdollBuilder, RigidBodyType};
+        use crate::math::{Isometry, Real};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A vertical bone, with a horizontal child bone attached at its end.
+        let angle = -std::f32::consts::FRAC_PI_2 as Real;
+        #[cfg(feature = "dim2")]
+        let child_pos = Isometry::new(Vector::y(), angle);
+        #[cfg(feature = "dim3")]
+        let child_pos = Isometry::new(Vector::y(), Vector::z() * angle);
+        let ragdoll = RagdollBuilder::new()
+            .bone(RagdollBone::new(None, Isometry::identity(), 1.0, 0.1, 1.0))
+            .bone(RagdollBone::new(Some(0), child_pos, 1.0, 0.1, 1.0))
+            .build(
+                &mut state.bodies,
+                &mut state.colliders,
+                &mut state.impulse_joints,
+            );
+        assert_eq!(ragdoll.bodies.len(), 2);
+        assert!(ragdoll.joints[0].is_none() && ragdoll.joints[1].is_some());
+        state.bodies[ragdoll.bodies[0]].set_body_type(RigidBodyType::Fixed, true);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        // The child bone swung down until the default 45 degrees limit.
+        let child = &state.bodies[ragdoll.bodies[1]];
+        assert!((child.translation() - Vector::y()).norm() < 1.0e-2);
+        let swing = child_pos.rotation.angle_to(child.rotation()).abs();
+        assert!(
+            (swing - std::f32::consts::FRAC_PI_4 as Real).abs() < 5.0e-2,
+            "swing: {}",
+            swing
+        );
+    }
+}
@@ -129,6 +129,34 @@ impl RigidBody {
         self.damping.angular_damping = damping
     }
 
+    /// The maximum norm of the linear velocity of this rigid-body.
+    #[inline]
+    pub fn max_linear_velocity(&self) -> Real {
+        self.damping.max_linear_velocity
+    }
+
+    /// Sets the maximum norm of the linear velocity of this rigid-body.
+    ///
+    /// The linear velocity is clamped to this value each time it is updated by the solver.
+    #[inline]
+    pub fn set_max_linear_velocity(&mut self, max_velocity: Real) {
+        self.damping.max_linear_velocity = max_velocity;
+    }
+
+    /// The maximum norm of the angular velocity of this rigid-body.
+    #[inline]
+    pub fn max_angular_velocity(&self) -> Real {
+        self.damping.max_angular_velocity
+    }
+
+    /// Sets the maximum norm of the angular velocity of this rigid-body.
+    ///
+    /// The angular velocity is clamped to this value each time it is updated by the solver.
+    #[inline]
+    pub fn set_max_angular_velocity(&mut self, max_velocity: Real) {
+        self.damping.max_angular_velocity = max_velocity;
+    }
+
     /// The type of this rigid-body.
     pub fn body_type(&self) -> RigidBodyType {
         self.body_type
@@ -171,6 +199,12 @@ impl RigidBody {
         self.dominance.effective_group(&self.body_type)
     }
 
+    /// The axes along which this rigid-body cannot translate or rotate.
+    #[inline]
+    pub fn locked_axes(&self) -> LockedAxes {
+        self.mprops.flags
+    }
+
     /// Sets the axes along which this rigid-body cannot translate or rotate.
     #[inline]
     pub fn set_locked_axes(&mut self, locked_axes: LockedAxes, wake_up: bool) {
@@ -187,16 +221,9 @@ impl RigidBody {
     #[inline]
     /// Locks or unlocks all the rotations of this rigid-body.
     pub fn lock_rotations(&mut self, locked: bool, wake_up: bool) {
-        if !self.mprops.flags.contains(LockedAxes::ROTATION_LOCKED) {
-            if self.is_dynamic() && wake_up {
-                self.wake_up(true);
-            }
-
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_X, locked);
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Y, locked);
-            self.mprops.flags.set(LockedAxes::ROTATION_LOCKED_Z, locked);
-            self.update_world_mass_properties();
-        }
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::ROTATION_LOCKED, locked);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     #[inline]
@@ -247,18 +274,11 @@ impl RigidBody {
     }
 
     #[inline]
-    /// Locks or unlocks all the rotations of this rigid-body.
+    /// Locks or unlocks all the translations of this rigid-body.
     pub fn lock_translations(&mut self, locked: bool, wake_up: bool) {
-        if !self.mprops.flags.contains(LockedAxes::TRANSLATION_LOCKED) {
-            if self.is_dynamic() && wake_up {
-                self.wake_up(true);
-            }
-
-            self.mprops
-                .flags
-                .set(LockedAxes::TRANSLATION_LOCKED, locked);
-            self.update_world_mass_properties();
-        }
+        let mut locked_axes = self.mprops.flags;
+        locked_axes.set(LockedAxes::TRANSLATION_LOCKED, locked);
+        self.set_locked_axes(locked_axes, wake_up);
     }
 
     #[inline]
@@ -352,6 +372,22 @@ impl RigidBody {
         ]
     }
 
+    /// Enables or disables gyroscopic forces for this rigid-body.
+    ///
+    /// Gyroscopic forces make fast-rotating rigid-bodies (e.g. spinning tops or wheels) precess
+    /// correctly. They are ignored for rigid-bodies attached to multibody joints, since multibodies
+    /// always take them into account.
+    #[cfg(feature = "dim3")]
+    pub fn enable_gyroscopic_forces(&mut self, enabled: bool) {
+        self.forces.gyroscopic_forces_enabled = enabled;
+    }
+
+    /// Are gyroscopic forces enabled for this rigid-body?
+    #[cfg(feature = "dim3")]
+    pub fn is_gyroscopic_forces_enabled(&self) -> bool {
+        self.forces.gyroscopic_forces_enabled
+    }
+
     /// Enables of disable CCD (continuous collision-detection) for this rigid-body.
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
@@ -766,7 +802,7 @@ impl RigidBody {
         }
     }
 
-    /// If this rigid body is kinematic, sets its future orientation after the next timestep integration.
+    /// If this rigid body is kinematic, sets its future translation after the next timestep integration.
     pub fn set_next_kinematic_translation(&mut self, translation: Vector<Real>) {
         if self.is_kinematic() {
             self.pos.next_position.translation = translation.into();
@@ -816,7 +852,7 @@ impl RigidBody {
         }
     }
 
-    /// Adds to this rigid-body a constant force applied at its center-of-mass.ç
+    /// Adds to this rigid-body a constant force applied at its center-of-mass.
     ///
     /// This does nothing on non-dynamic bodies.
     pub fn add_force(&mut self, force: Vector<Real>, wake_up: bool) {
@@ -1005,6 +1041,10 @@ pub struct RigidBodyBuilder {
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body, `0.0` by default.
     pub angular_damping: Real,
+    /// The maximum norm of the linear velocity of the rigid-body, `Real::MAX` by default.
+    pub max_linear_velocity: Real,
+    /// The maximum norm of the angular velocity of the rigid-body, `Real::MAX` by default.
+    pub max_angular_velocity: Real,
     body_type: RigidBodyType,
     mprops_flags: LockedAxes,
     /// The additional mass-properties of the rigid-body being built. See [`RigidBodyBuilder::additional_mass_properties`] for more information.
@@ -1017,6 +1057,9 @@ pub struct RigidBodyBuilder {
     ///
     /// CCD prevents tunneling, but may still allow limited interpenetration of colliders.
     pub ccd_enabled: bool,
+    /// Whether gyroscopic forces are enabled for the rigid-body to be built.
+    #[cfg(feature = "dim3")]
+    pub gyroscopic_forces_enabled: bool,
     /// The dominance group of the rigid-body to be built.
     pub dominance_group: i8,
     /// Will the rigid-body being built be enabled?
@@ -1035,12 +1078,16 @@ impl RigidBodyBuilder {
             gravity_scale: 1.0,
             linear_damping: 0.0,
             angular_damping: 0.0,
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
             body_type,
             mprops_flags: LockedAxes::empty(),
             additional_mass_properties: RigidBodyAdditionalMassProps::default(),
             can_sleep: true,
             sleeping: false,
             ccd_enabled: false,
+            #[cfg(feature = "dim3")]
+            gyroscopic_forces_enabled: false,
             dominance_group: 0,
             enabled: true,
             user_data: 0,
@@ -1257,6 +1304,18 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the maximum norm of the linear velocity of the rigid-body to be created.
+    pub fn max_linear_velocity(mut self, max_velocity: Real) -> Self {
+        self.max_linear_velocity = max_velocity;
+        self
+    }
+
+    /// Sets the maximum norm of the angular velocity of the rigid-body to be created.
+    pub fn max_angular_velocity(mut self, max_velocity: Real) -> Self {
+        self.max_angular_velocity = max_velocity;
+        self
+    }
+
     /// Sets the initial linear velocity of the rigid-body to be created.
     pub fn linvel(mut self, linvel: Vector<Real>) -> Self {
         self.linvel = linvel;
@@ -1283,6 +1342,16 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets whether or not gyroscopic forces are enabled for this rigid-body.
+    ///
+    /// Gyroscopic forces make fast-rotating rigid-bodies (e.g. spinning tops or wheels) precess
+    /// correctly.
+    #[cfg(feature = "dim3")]
+    pub fn gyroscopic_forces_enabled(mut self, enabled: bool) -> Self {
+        self.gyroscopic_forces_enabled = enabled;
+        self
+    }
+
     /// Sets whether or not the rigid-body is to be created asleep.
     pub fn sleeping(mut self, sleeping: bool) -> Self {
         self.sleeping = sleeping;
@@ -1315,10 +1384,14 @@ impl RigidBodyBuilder {
         rb.mprops.flags = self.mprops_flags;
         rb.damping.linear_damping = self.linear_damping;
         rb.damping.angular_damping = self.angular_damping;
+        rb.damping.max_linear_velocity = self.max_linear_velocity;
+        rb.damping.max_angular_velocity = self.max_angular_velocity;
         rb.forces.gravity_scale = self.gravity_scale;
         rb.dominance = RigidBodyDominance(self.dominance_group);
         rb.enabled = self.enabled;
         rb.enable_ccd(self.ccd_enabled);
+        #[cfg(feature = "dim3")]
+        rb.enable_gyroscopic_forces(self.gyroscopic_forces_enabled);
 
         if self.can_sleep && self.sleeping {
             rb.sleep();
@@ -1338,3 +1411,456 @@ impl Into<RigidBody> for RigidBodyBuilder {
         self.build()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{LockedAxes, RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn kinematic_platforms_carry_dynamic_bodies() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // One platform moved by setting its next position, the other by setting its velocity.
+        let speed = 1.0;
+        let position_based = state
+            .bodies
+            .insert(RigidBodyBuilder::kinematic_position_based().build());
+        let velocity_based = RigidBodyBuilder::kinematic_velocity_based()
+            .translation(Vector::y() * 10.0)
+            .linvel(Vector::x() * speed)
+            .build();
+        let velocity_based = state.bodies.insert(velocity_based);
+
+        let mut boxes = vec![];
+        for platform in [position_based, velocity_based] {
+            let collider = cuboid(10.0, 0.5);
+            state
+                .colliders
+                .insert_with_parent(collider.friction(1.0), platform, &mut state.bodies);
+
+            let body = RigidBodyBuilder::dynamic()
+                .translation(state.bodies[platform].translation() + Vector::y() * 0.9)
+                .build();
+            let body = state.bodies.insert(body);
+            let collider = cuboid(0.4, 0.4);
+            state
+                .colliders
+                .insert_with_parent(collider.friction(1.0), body, &mut state.bodies);
+            boxes.push(body);
+        }
+
+        for i in 0..200 {
+            let t = (i + 1) as Real * state.integration_parameters.dt;
+            state.bodies[position_based].set_next_kinematic_translation(Vector::x() * speed * t);
+
+            state.step(&mut pipeline, &(), &());
+        }
+
+        // The velocity of the position-based platform is inferred from its motion.
+        let platform_vel = state.bodies[position_based].linvel();
+        assert!(
+            (platform_vel.x - speed).abs() < 1.0e-3,
+            "vel: {}",
+            platform_vel
+        );
+
+        for body in boxes {
+            let linvel = state.bodies[body].linvel();
+            assert!((linvel.x - speed).abs() < 0.1, "vel: {}", linvel);
+        }
+    }
+
+    #[test]
+    fn damping_slows_down_bodies_until_they_sleep() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let damping = 2.0;
+        let body = RigidBodyBuilder::dynamic()
+            .linvel(Vector::x() * 10.0)
+            .linear_damping(damping)
+            .angular_damping(damping)
+            .build();
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::ball(0.5).build();
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 60);
+
+        let expected = 10.0 / (1.0 + state.integration_parameters.dt * damping).powi(60);
+        let linvel = state.bodies[body].linvel();
+        assert!((linvel.x - expected).abs() < 1.0e-3, "vel: {}", linvel);
+
+        step_n(&mut state, &mut pipeline, 600);
+
+        assert!(state.bodies[body].is_sleeping());
+    }
+
+    #[test]
+    fn locked_axes_can_be_unlocked() {
+        use num::Zero;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let body = bodies.insert(RigidBodyBuilder::dynamic().build());
+        colliders.insert_with_parent(ColliderBuilder::ball(0.5), body, &mut bodies);
+
+        // An off-center impulse only translates a body with locked rotations.
+        let rb = &mut bodies[body];
+        rb.lock_rotations(true, true);
+        rb.apply_impulse_at_point(Vector::x(), Point::from(Vector::y()), true);
+        assert!(rb.linvel().norm() > 0.1);
+        assert!(rb.angvel().is_zero());
+
+        // And only rotates a body with locked translations.
+        rb.set_linvel(Vector::zeros(), true);
+        rb.lock_rotations(false, true);
+        rb.lock_translations(true, true);
+        rb.apply_impulse_at_point(Vector::x(), Point::from(Vector::y()), true);
+        assert!(rb.linvel().is_zero());
+        assert!(!rb.angvel().is_zero());
+
+        rb.lock_translations(false, true);
+        assert_eq!(rb.locked_axes(), LockedAxes::empty());
+    }
+
+    #[test]
+    fn impulse_at_point_rotates_around_center_of_mass() {
+        use crate::math::AngVector;
+        use crate::utils::{WCross, WDot};
+
+        let norm = |v: AngVector<Real>| v.gdot(v).sqrt();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 5.0)
+            .build();
+        let body = bodies.insert(body);
+        // Offset the collider so the center of mass doesn’t match the body’s origin.
+        let collider = ColliderBuilder::ball(0.5).translation(Vector::y());
+        colliders.insert_with_parent(collider, body, &mut bodies);
+
+        let rb = &mut bodies[body];
+        let com = *rb.center_of_mass();
+        assert!((com - Point::from(Vector::x() * 5.0 + Vector::y())).norm() < 1.0e-5);
+
+        // An impulse at the center of mass doesn’t induce any rotation.
+        rb.apply_impulse_at_point(Vector::x(), com, true);
+        assert!((rb.linvel() - Vector::x() / rb.mass()).norm() < 1.0e-5);
+        assert!(norm(rb.vels.angvel) < 1.0e-5);
+
+        // An impulse applied away from the center of mass is equivalent to an impulse and a
+        // torque impulse applied at the center of mass.
+        let mut expected = rb.clone();
+        let point = com + Vector::y();
+        rb.apply_impulse_at_point(Vector::x(), point, true);
+        expected.apply_impulse(Vector::x(), true);
+        expected.apply_torque_impulse((point - com).gcross(Vector::x()), true);
+        assert!((rb.linvel() - expected.linvel()).norm() < 1.0e-5);
+        assert!(norm(rb.vels.angvel - expected.vels.angvel) < 1.0e-5);
+        assert!(norm(rb.vels.angvel) > 1.0e-3);
+    }
+
+    #[test]
+    fn sleep_time_and_sleeping_switch() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // Two still bodies, far enough from each other to end up in different islands.
+        let mut rb = RigidBodyBuilder::dynamic().build();
+        rb.activation_mut().time_until_sleep = 0.5;
+        let light_sleeper = state.bodies.insert(rb);
+        let rb = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 10.0)
+            .build();
+        let deep_sleeper = state.bodies.insert(rb);
+
+        step_n(&mut state, &mut pipeline, 60);
+
+        assert!(state.bodies[light_sleeper].is_sleeping());
+        assert!(!state.bodies[deep_sleeper].is_sleeping());
+
+        // Once sleeping is disabled, bodies remain awake.
+        state.integration_parameters.sleeping_enabled = false;
+        state.bodies[light_sleeper].wake_up(true);
+
+        step_n(&mut state, &mut pipeline, 300);
+
+        assert!(!state.bodies[light_sleeper].is_sleeping());
+        assert!(!state.bodies[deep_sleeper].is_sleeping());
+    }
+
+    #[test]
+    fn dominant_bodies_are_not_pushed_back() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // A light character with a higher dominance hits a crate a hundred times heavier.
+        let character = RigidBodyBuilder::dynamic()
+            .linvel(Vector::x() * 2.0)
+            .dominance_group(1)
+            .build();
+        let character = state.bodies.insert(character);
+        state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            character,
+            &mut state.bodies,
+        );
+
+        let crate_body = RigidBodyBuilder::dynamic()
+            .translation(Vector::x() * 1.5)
+            .build();
+        let crate_body = state.bodies.insert(crate_body);
+        let collider = ColliderBuilder::ball(0.5).density(100.0);
+        state
+            .colliders
+            .insert_with_parent(collider, crate_body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 60);
+
+        let character_vel = state.bodies[character].linvel();
+        assert!((character_vel - Vector::x() * 2.0).norm() < 1.0e-3);
+        assert!(state.bodies[crate_body].linvel().x > 1.0);
+    }
+
+    #[test]
+    fn velocities_are_clamped_to_their_maximum() {
+        use crate::utils::WDot;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let (max_linvel, max_angvel) = (10.0, 2.0);
+        let mut rb = RigidBodyBuilder::dynamic()
+            .max_linear_velocity(max_linvel)
+            .max_angular_velocity(max_angvel)
+            .build();
+        // Velocities set manually are only clamped by the next step.
+        rb.set_linvel(Vector::x() * 1000.0, true);
+        #[cfg(feature = "dim2")]
+        rb.set_angvel(1000.0, true);
+        #[cfg(feature = "dim3")]
+        rb.set_angvel(Vector::repeat(1000.0), true);
+        let body = state.bodies.insert(rb);
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+
+        let start = *state.bodies[body].translation();
+
+        for _ in 0..120 {
+            state.step(&mut pipeline, &(), &());
+
+            let rb = &state.bodies[body];
+            assert!(rb.linvel().norm() <= max_linvel + 1.0e-3);
+            assert!(rb.vels.angvel.gdot(rb.vels.angvel).sqrt() <= max_angvel + 1.0e-3);
+        }
+
+        // The body moved at most at its maximum velocity during the two seconds.
+        let traveled = (state.bodies[body].translation() - start).norm();
+        assert!(
+            traveled <= max_linvel * 2.0 + 1.0e-2,
+            "traveled: {}",
+            traveled
+        );
+    }
+
+    #[test]
+    fn body_type_can_change_after_insertion() {
+        use crate::dynamics::RigidBodyType;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        let collider = cuboid(10.0, 0.5);
+        state
+            .colliders
+            .insert_with_parent(collider, ground, &mut state.bodies);
+
+        // A piece of a destructible structure, initially fixed.
+        let piece = RigidBodyBuilder::fixed()
+            .translation(Vector::y() * 3.0)
+            .build();
+        let piece = state.bodies.insert(piece);
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), piece, &mut state.bodies);
+
+        for i in 0..300 {
+            match i {
+                60 => state.bodies[piece].set_body_type(RigidBodyType::Dynamic, true),
+                240 => {
+                    state.bodies[piece].set_body_type(RigidBodyType::KinematicVelocityBased, true)
+                }
+                _ => {}
+            }
+
+            state.step(&mut pipeline, &(), &());
+
+            let y = state.bodies[piece].translation().y;
+            match i {
+                59 => assert_eq!(y, 3.0),
+                // The piece fell on the ground, which it can’t go through.
+                239 => assert!((y - 1.0).abs() < 1.0e-2, "y: {}", y),
+                _ => {}
+            }
+        }
+
+        // Kinematic bodies aren’t affected by gravity.
+        let rb = &state.bodies[piece];
+        assert!((rb.translation().y - 1.0).abs() < 1.0e-2);
+        assert!(rb.linvel().norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn disabled_bodies_are_not_simulated() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A pooled bullet, overlapping another body while it is disabled.
+        let bullet = RigidBodyBuilder::dynamic().enabled(false).build();
+        let bullet = state.bodies.insert(bullet);
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), bullet, &mut state.bodies);
+        let other = state.bodies.insert(RigidBodyBuilder::fixed().build());
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), other, &mut state.bodies);
+
+        for i in 0..20 {
+            if i == 10 {
+                let rb = &mut state.bodies[bullet];
+                rb.set_translation(Vector::x() * 10.0, true);
+                rb.set_enabled(true);
+            }
+
+            state.step(&mut pipeline, &(), &());
+
+            if i < 10 {
+                assert_eq!(*state.bodies[bullet].translation(), Vector::zeros());
+                assert_eq!(state.narrow_phase.contact_pairs().count(), 0);
+            }
+        }
+
+        assert!(state.bodies[bullet].translation().y < 0.0);
+    }
+
+    #[test]
+    fn mass_properties_follow_attached_colliders() {
+        use crate::geometry::SharedShape;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let body = RigidBodyBuilder::dynamic().additional_mass(1.0).build();
+        let body = state.bodies.insert(body);
+        let capsule1 = ColliderBuilder::capsule_y(0.5, 0.2).density(2.0);
+        let capsule1 = state
+            .colliders
+            .insert_with_parent(capsule1, body, &mut state.bodies);
+        let mut capsule2 = None;
+
+        for phase in 0..4 {
+            match phase {
+                1 => {
+                    let capsule = ColliderBuilder::capsule_x(0.5, 0.2).translation(Vector::y());
+                    capsule2 = Some(state.colliders.insert_with_parent(
+                        capsule,
+                        body,
+                        &mut state.bodies,
+                    ));
+                }
+                2 => state.colliders[capsule1].set_shape(SharedShape::ball(1.0)),
+                3 => {
+                    let _ = state.colliders.remove(
+                        capsule2.unwrap(),
+                        &mut state.islands,
+                        &mut state.bodies,
+                        true,
+                    );
+                }
+                _ => {}
+            }
+
+            state.step(&mut pipeline, &(), &());
+
+            // The mass is the sum of the colliders’ masses and of the additional mass.
+            let expected = 1.0
+                + state
+                    .colliders
+                    .iter()
+                    .filter(|(_, co)| co.parent() == Some(body))
+                    .map(|(_, co)| co.mass())
+                    .sum::<Real>();
+            let mass = state.bodies[body].mass();
+            assert!(
+                (mass - expected).abs() < 1.0e-4,
+                "phase {}: {}",
+                phase,
+                mass
+            );
+            assert_eq!(
+                state.colliders.len(),
+                if phase == 1 || phase == 2 { 2 } else { 1 }
+            );
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn gyroscopic_forces_conserve_angular_momentum() {
+        use crate::dynamics::RigidBodyHandle;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // Two boxes spinning along an axis that isn’t one of their principal axes.
+        let mut handles = vec![];
+        for (i, enabled) in [false, true].into_iter().enumerate() {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 10.0 * i as Real)
+                .angvel(Vector::new(2.0, 5.0, 1.0))
+                .gyroscopic_forces_enabled(enabled)
+                .build();
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::cuboid(1.0, 0.5, 0.2);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            handles.push(body);
+        }
+
+        let angular_momentum = |bodies: &RigidBodySet, body: RigidBodyHandle| {
+            let rb = &bodies[body];
+            let rot = rb.rotation().to_rotation_matrix();
+            let inertia = rot * rb.mass_properties().reconstruct_inertia_matrix() * rot.transpose();
+            inertia * rb.angvel()
+        };
+        let initial_momentum = angular_momentum(&state.bodies, handles[1]);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        let error = |body| {
+            (angular_momentum(&state.bodies, body) - initial_momentum).norm()
+                / initial_momentum.norm()
+        };
+        // NOTE: the implicit integration is slightly dissipative, so the momentum isn’t
+        //       conserved exactly.
+        assert!(error(handles[0]) > 0.3, "error: {}", error(handles[0]));
+        assert!(error(handles[1]) < 0.15, "error: {}", error(handles[1]));
+    }
+}
@@ -7,6 +7,8 @@ use crate::math::{
     AngVector, AngularInertia, Isometry, Point, Real, Rotation, Translation, Vector,
 };
 use crate::parry::partitioning::IndexedData;
+#[cfg(feature = "dim3")]
+use crate::utils::WCrossMatrix;
 use crate::utils::{WAngularInertia, WCross, WDot};
 use num::Zero;
 
@@ -537,12 +539,25 @@ impl RigidBodyVelocity {
     }
 
     /// Returns the update velocities after applying the given damping.
+    ///
+    /// The damped velocities are then clamped to the maximum linear and angular velocities
+    /// allowed by `damping`.
     #[must_use]
     pub fn apply_damping(&self, dt: Real, damping: &RigidBodyDamping) -> Self {
-        RigidBodyVelocity {
-            linvel: self.linvel * (1.0 / (1.0 + dt * damping.linear_damping)),
-            angvel: self.angvel * (1.0 / (1.0 + dt * damping.angular_damping)),
+        let mut linvel = self.linvel * (1.0 / (1.0 + dt * damping.linear_damping));
+        let mut angvel = self.angvel * (1.0 / (1.0 + dt * damping.angular_damping));
+
+        let linspeed = linvel.norm();
+        if linspeed > damping.max_linear_velocity {
+            linvel *= damping.max_linear_velocity / linspeed;
+        }
+
+        let angspeed = angvel.gdot(angvel).sqrt();
+        if angspeed > damping.max_angular_velocity {
+            angvel *= damping.max_angular_velocity / angspeed;
         }
+
+        RigidBodyVelocity { linvel, angvel }
     }
 
     /// The velocity of the given world-space point on this rigid-body.
@@ -569,6 +584,50 @@ impl RigidBodyVelocity {
         result
     }
 
+    /// The angular impulse applied by the gyroscopic forces on a rigid-body with these velocities,
+    /// the given orientation, and the given local mass-properties, during a timestep of length `dt`.
+    ///
+    /// The gyroscopic forces are integrated implicitly, using one Newton iteration, so they
+    /// remain stable for fast-rotating rigid-bodies.
+    #[cfg(feature = "dim3")]
+    #[must_use]
+    pub fn gyroscopic_torque_impulse(
+        &self,
+        dt: Real,
+        rotation: &Rotation<Real>,
+        local_mprops: &MassProperties,
+    ) -> AngVector<Real> {
+        use na::Matrix3;
+
+        // Work in the principal inertia frame where the angular inertia is diagonal.
+        let frame = rotation * local_mprops.principal_inertia_local_frame;
+        let inertia = local_mprops.principal_inertia();
+        let angvel = frame.inverse_transform_vector(&self.angvel);
+        let momentum = inertia.component_mul(&angvel);
+
+        // Solve `inertia * (new_angvel - angvel) + dt * new_angvel × (inertia * new_angvel) = 0`.
+        let residual = angvel.cross(&momentum) * dt;
+        let jacobian = Matrix3::from_diagonal(&inertia)
+            + (angvel.gcross_matrix() * Matrix3::from_diagonal(&inertia)
+                - momentum.gcross_matrix())
+                * dt;
+        let delta_angvel = jacobian
+            .try_inverse()
+            .map(|inv| -(inv * residual))
+            .unwrap_or_else(Vector::zeros);
+
+        frame * inertia.component_mul(&delta_angvel)
+    }
+
+    /// The angle rotated by a rigid-body with these velocities during a timestep of length `dt`.
+    #[must_use]
+    pub(crate) fn rotation_angle(&self, dt: Real) -> Real {
+        #[cfg(feature = "dim2")]
+        return self.angvel.abs() * dt;
+        #[cfg(feature = "dim3")]
+        return self.angvel.norm() * dt;
+    }
+
     /// Are these velocities exactly equal to zero?
     #[must_use]
     pub fn is_zero(&self) -> bool {
@@ -681,6 +740,10 @@ pub struct RigidBodyDamping {
     pub linear_damping: Real,
     /// Damping factor for gradually slowing down the angular motion of the rigid-body.
     pub angular_damping: Real,
+    /// The maximum norm of the linear velocity of the rigid-body.
+    pub max_linear_velocity: Real,
+    /// The maximum norm of the angular velocity of the rigid-body.
+    pub max_angular_velocity: Real,
 }
 
 impl Default for RigidBodyDamping {
@@ -688,6 +751,8 @@ impl Default for RigidBodyDamping {
         Self {
             linear_damping: 0.0,
             angular_damping: 0.0,
+            max_linear_velocity: Real::MAX,
+            max_angular_velocity: Real::MAX,
         }
     }
 }
@@ -707,6 +772,9 @@ pub struct RigidBodyForces {
     pub user_force: Vector<Real>,
     /// Torque applied by the user.
     pub user_torque: AngVector<Real>,
+    /// Are gyroscopic forces enabled for this rigid-body?
+    #[cfg(feature = "dim3")]
+    pub gyroscopic_forces_enabled: bool,
 }
 
 impl Default for RigidBodyForces {
@@ -717,6 +785,8 @@ impl Default for RigidBodyForces {
             gravity_scale: 1.0,
             user_force: na::zero(),
             user_torque: na::zero(),
+            #[cfg(feature = "dim3")]
+            gyroscopic_forces_enabled: false,
         }
     }
 }
@@ -978,6 +1048,9 @@ pub struct RigidBodyActivation {
     pub linear_threshold: Real,
     /// The angular linear velocity bellow which the body can fall asleep.
     pub angular_threshold: Real,
+    /// The amount of time the rigid-body must remain bellow its linear and angular velocity
+    /// thresholds before falling asleep.
+    pub time_until_sleep: Real,
     /// Since how much time can this body sleep?
     pub time_since_can_sleep: Real,
     /// Is this body sleeping?
@@ -1012,6 +1085,7 @@ impl RigidBodyActivation {
         RigidBodyActivation {
             linear_threshold: Self::default_linear_threshold(),
             angular_threshold: Self::default_angular_threshold(),
+            time_until_sleep: Self::default_time_until_sleep(),
             time_since_can_sleep: 0.0,
             sleeping: false,
         }
@@ -1022,6 +1096,7 @@ impl RigidBodyActivation {
         RigidBodyActivation {
             linear_threshold: Self::default_linear_threshold(),
             angular_threshold: Self::default_angular_threshold(),
+            time_until_sleep: Self::default_time_until_sleep(),
             sleeping: true,
             time_since_can_sleep: Self::default_time_until_sleep(),
         }
@@ -1055,6 +1130,6 @@ impl RigidBodyActivation {
     #[inline]
     pub fn sleep(&mut self) {
         self.sleeping = true;
-        self.time_since_can_sleep = Self::default_time_until_sleep();
+        self.time_since_can_sleep = self.time_until_sleep;
     }
 }
@@ -206,6 +206,24 @@ impl RigidBodySet {
         })
     }
 
+    /// Iterates mutably and in parallel through all the rigid-bodies on this set.
+    ///
+    /// Just like [`Self::iter_mut`], all the rigid-bodies are marked as modified, even if the
+    /// iterator isn’t fully consumed.
+    #[cfg(all(feature = "parallel", not(feature = "dev-remove-slow-accessors")))]
+    pub fn par_iter_mut(
+        &mut self,
+    ) -> impl rayon::iter::ParallelIterator<Item = (RigidBodyHandle, &mut RigidBody)> {
+        use rayon::prelude::*;
+
+        self.modified_bodies.clear();
+        self.modified_bodies
+            .extend(self.bodies.iter().map(|(h, _)| RigidBodyHandle(h)));
+        self.bodies
+            .par_iter_mut()
+            .map(|(h, b)| (RigidBodyHandle(h), b))
+    }
+
     /// Update colliders positions after rigid-bodies moved.
     ///
     /// When a rigid-body moves, the positions of the colliders attached to it need to be updated.
@@ -251,3 +269,89 @@ impl IndexMut<RigidBodyHandle> for RigidBodySet {
         rb
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        FixedJointBuilder, ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder,
+        RigidBodySet,
+    };
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_iterators_mark_objects_as_modified() {
+        use crate::math::{Real, Vector};
+        use crate::pipeline::{PhysicsPipeline, PhysicsState};
+        use rayon::prelude::*;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        for _ in 0..100 {
+            let body = state.bodies.insert(RigidBodyBuilder::fixed());
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        }
+
+        state.bodies.par_iter_mut().for_each(|(handle, rb)| {
+            let i = handle.into_raw_parts().0 as Real;
+            rb.set_translation(Vector::x() * i * 2.0, true);
+        });
+        state
+            .colliders
+            .par_iter_mut()
+            .for_each(|(_, co)| co.set_friction(0.25));
+
+        state.step(&mut pipeline, &(), &());
+
+        for (_, co) in state.colliders.iter() {
+            let rb = &state.bodies[co.parent().unwrap()];
+            assert_eq!(co.translation(), rb.translation());
+            assert_eq!(co.friction(), 0.25);
+        }
+        assert!(state
+            .bodies
+            .iter()
+            .all(|(h, rb)| rb.translation().x == h.into_raw_parts().0 as Real * 2.0));
+    }
+
+    #[test]
+    fn stale_handles_do_not_alias_new_objects() {
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::new();
+
+        let body1 = bodies.insert(RigidBodyBuilder::dynamic().build());
+        let body2 = bodies.insert(RigidBodyBuilder::dynamic().build());
+        let collider = colliders.insert_with_parent(ColliderBuilder::ball(0.5), body2, &mut bodies);
+        let joint = impulse_joints.insert(body1, body2, FixedJointBuilder::new(), true);
+
+        let _ = bodies.remove(
+            body2,
+            &mut islands,
+            &mut colliders,
+            &mut impulse_joints,
+            &mut multibody_joints,
+            true,
+        );
+
+        // The new objects reuse the storage slots of the removed ones, but not their handles.
+        let new_body = bodies.insert(RigidBodyBuilder::dynamic().build());
+        let new_collider =
+            colliders.insert_with_parent(ColliderBuilder::ball(0.5), new_body, &mut bodies);
+        let new_joint = impulse_joints.insert(body1, new_body, FixedJointBuilder::new(), true);
+
+        assert_eq!(new_body.into_raw_parts().0, body2.into_raw_parts().0);
+        assert_ne!(new_body, body2);
+        assert_ne!(new_collider, collider);
+        assert_ne!(new_joint, joint);
+        assert!(bodies.get(body2).is_none());
+        assert!(colliders.get(collider).is_none());
+        assert!(impulse_joints.get(joint).is_none());
+    }
+}
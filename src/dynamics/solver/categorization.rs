@@ -63,6 +63,8 @@ pub(crate) fn categorize_joints(
             }
         } else if !rb1.is_dynamic() || !rb2.is_dynamic() {
             ground_joints.push(*joint_i);
+        } else if joint.data.requires_generic_constraints() {
+            generic_nonground_joints.push(*joint_i);
         } else {
             nonground_joints.push(*joint_i);
         }
@@ -1,11 +1,14 @@
 use crate::dynamics::solver::{GenericRhs, VelocityConstraint};
 use crate::dynamics::{IntegrationParameters, MultibodyJointSet, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+#[cfg(feature = "dim3")]
+use crate::math::Vector;
 use crate::math::{Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{WAngularInertia, WCross, WDot};
 
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    contact_softness, AnyVelocityConstraint, DeltaVel, VelocityConstraintAngularFrictionPart,
+    VelocityConstraintElement, VelocityConstraintNormalPart,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
@@ -73,8 +76,16 @@ impl GenericVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_anisotropy) = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &vels1.linvel,
+            &vels2.linvel,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            manifold.data.friction_anisotropy,
+        );
 
         let multibodies_ndof = multibody1.map(|m| m.0.ndofs()).unwrap_or(0)
             + multibody2.map(|m| m.0.ndofs()).unwrap_or(0);
@@ -83,7 +94,7 @@ impl GenericVelocityConstraint {
         let required_jacobian_len =
             *jacobian_id + manifold.data.solver_contacts.len() * multibodies_ndof * 2 * DIM;
 
-        if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
+        if jacobians.nrows() < required_jacobian_len {
             jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
         }
 
@@ -99,7 +110,11 @@ impl GenericVelocityConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                // NOTE: the rolling and twist friction aren’t supported by multibodies yet.
+                angular_friction_part: VelocityConstraintAngularFrictionPart::zero(),
                 im1: if type1.is_dynamic() {
                     mprops1.effective_inv_mass
                 } else {
@@ -197,8 +212,16 @@ impl GenericVelocityConstraint {
                         * (vel1 - vel2).dot(&force_dir1);
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
+                    let (contact_erp_inv_dt, contact_cfm_factor) = contact_softness(
+                        params.dt,
+                        erp_inv_dt,
+                        manifold_point.stiffness,
+                        manifold_point.damping,
+                        r,
+                    );
+                    // NOTE: the adaptive penetration correction isn’t applied to multibody contacts.
                     let rhs_bias =
-                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
+                        /* is_resting * */ contact_erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact = is_fast_contact || (-rhs * params.dt > ccd_thickness * 0.5);
@@ -210,6 +233,7 @@ impl GenericVelocityConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -346,6 +370,8 @@ impl GenericVelocityConstraint {
             &self.velocity_constraint.dir1,
             #[cfg(feature = "dim3")]
             &self.velocity_constraint.tangent1,
+            #[cfg(feature = "dim3")]
+            self.velocity_constraint.friction_anisotropy,
             &self.velocity_constraint.im1,
             &self.velocity_constraint.im2,
             self.velocity_constraint.limit,
@@ -262,7 +262,8 @@ impl VelocityConstraintNormalPart<Real> {
             + mj_lambda2.dvel(j_id2, ndofs2, jacobians, &-dir1, &self.gcross2, mj_lambdas)
             + self.rhs;
 
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).max(0.0);
+        let new_impulse =
+            self.effective_cfm_factor(cfm_factor) * (self.impulse - self.r * dvel).max(0.0);
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -297,6 +298,7 @@ impl VelocityConstraintElement<Real> {
         jacobians: &DVector<Real>,
         dir1: &Vector<Real>,
         #[cfg(feature = "dim3")] tangent1: &Vector<Real>,
+        #[cfg(feature = "dim3")] friction_anisotropy: Real,
         im1: &Vector<Real>,
         im2: &Vector<Real>,
         limit: Real,
@@ -330,7 +332,7 @@ impl VelocityConstraintElement<Real> {
         // Solve friction.
         if solve_friction {
             #[cfg(feature = "dim3")]
-            let tangents1 = [tangent1, &dir1.cross(&tangent1)];
+            let tangents1 = [tangent1, &(dir1.cross(&tangent1) * friction_anisotropy)];
             #[cfg(feature = "dim2")]
             let tangents1 = [&dir1.orthonormal_vector()];
             let mut tng_j_id = tangent_j_id(j_id, ndofs1, ndofs2);
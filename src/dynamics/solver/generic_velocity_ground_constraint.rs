@@ -1,11 +1,14 @@
 use crate::dynamics::solver::VelocityGroundConstraint;
 use crate::dynamics::{IntegrationParameters, MultibodyJointSet, RigidBodySet, RigidBodyVelocity};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+#[cfg(feature = "dim3")]
+use crate::math::Vector;
 use crate::math::{Point, Real, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::WCross;
 
 use super::{
-    AnyVelocityConstraint, VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
+    contact_softness, AnyVelocityConstraint, VelocityGroundConstraintAngularFrictionPart,
+    VelocityGroundConstraintElement, VelocityGroundConstraintNormalPart,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
@@ -66,8 +69,16 @@ impl GenericVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_anisotropy) = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &vels1.linvel,
+            &vels2.linvel,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            manifold.data.friction_anisotropy,
+        );
 
         let multibodies_ndof = mb2.ndofs();
         // For each solver contact we generate DIM constraints, and each constraints appends
@@ -75,7 +86,7 @@ impl GenericVelocityGroundConstraint {
         let required_jacobian_len =
             *jacobian_id + manifold.data.solver_contacts.len() * multibodies_ndof * 2 * DIM;
 
-        if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
+        if jacobians.nrows() < required_jacobian_len {
             jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
         }
 
@@ -91,7 +102,11 @@ impl GenericVelocityGroundConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                // NOTE: the rolling and twist friction aren’t supported by multibodies yet.
+                angular_friction_part: VelocityGroundConstraintAngularFrictionPart::zero(),
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
                 limit: 0.0,
@@ -137,8 +152,16 @@ impl GenericVelocityGroundConstraint {
                     let mut rhs_wo_bias = (1.0 + is_bouncy * manifold_point.restitution) * dvel;
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
+                    let (contact_erp_inv_dt, contact_cfm_factor) = contact_softness(
+                        params.dt,
+                        erp_inv_dt,
+                        manifold_point.stiffness,
+                        manifold_point.damping,
+                        r,
+                    );
+                    // NOTE: the adaptive penetration correction isn’t applied to multibody contacts.
                     let rhs_bias =
-                        /* is_resting * */ erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
+                        /* is_resting * */ contact_erp_inv_dt * manifold_point.dist.clamp(-params.max_penetration_correction, 0.0);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -150,6 +173,7 @@ impl GenericVelocityGroundConstraint {
                         rhs_wo_bias,
                         impulse: na::zero(),
                         r,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -6,6 +6,7 @@ use crate::dynamics::solver::{
 use crate::dynamics::IslandManager;
 use crate::dynamics::{IntegrationParameters, JointGraphEdge, JointIndex, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::Real;
 use crate::prelude::MultibodyJointSet;
 
 pub struct IslandSolver {
@@ -42,14 +43,6 @@ impl IslandSolver {
         joint_indices: &[JointIndex],
         multibody_joints: &mut MultibodyJointSet,
     ) {
-        // Init the solver id for multibody_joints.
-        // We need that for building the constraints.
-        let mut solver_id = 0;
-        for (_, multibody) in multibody_joints.multibodies.iter_mut() {
-            multibody.solver_id = solver_id;
-            solver_id += multibody.ndofs();
-        }
-
         counters.solver.velocity_assembly_time.resume();
         self.contact_constraints.init(
             island_id,
@@ -71,20 +64,37 @@ impl IslandSolver {
         );
         counters.solver.velocity_assembly_time.pause();
 
-        counters.solver.velocity_resolution_time.resume();
         self.velocity_solver.solve(
             island_id,
+            counters,
             params,
             islands,
             bodies,
             multibody_joints,
             manifolds,
+            manifold_indices,
             impulse_joints,
             &mut self.contact_constraints.velocity_constraints,
             &self.contact_constraints.generic_jacobians,
             &mut self.joint_constraints.velocity_constraints,
             &self.joint_constraints.generic_jacobians,
         );
-        counters.solver.velocity_resolution_time.pause();
+    }
+
+    /// The number of contact and joint constraints generated by the last call to
+    /// `init_and_solve`.
+    pub fn num_constraints(&self) -> usize {
+        self.contact_constraints.velocity_constraints.len()
+            + self.joint_constraints.velocity_constraints.len()
+    }
+
+    /// The number of velocity iterations executed by the last call to `init_and_solve`.
+    pub fn num_velocity_iterations(&self) -> usize {
+        self.velocity_solver.num_velocity_iterations
+    }
+
+    /// The convergence error of the velocity iterations of the last call to `init_and_solve`.
+    pub fn velocity_error(&self) -> Real {
+        self.velocity_solver.velocity_error
     }
 }
@@ -4,6 +4,7 @@ use crate::dynamics::solver::joint_constraint::joint_generic_velocity_constraint
 use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
     JointVelocityConstraint, JointVelocityGroundConstraint, SolverBody,
 };
+use crate::dynamics::solver::joint_constraint::joint_velocity_constraint_builder::CouplingJacobians;
 use crate::dynamics::solver::DeltaVel;
 use crate::dynamics::{
     ImpulseJoint, IntegrationParameters, JointGraphEdge, JointIndex, RigidBodySet,
@@ -12,12 +13,18 @@ use crate::math::{Real, SPATIAL_DIM};
 use crate::prelude::MultibodyJointSet;
 use na::DVector;
 
-#[cfg(feature = "simd-is-enabled")]
-use crate::math::{Isometry, SimdReal, SIMD_WIDTH};
-
 #[cfg(feature = "parallel")]
 use crate::dynamics::JointAxesMask;
 
+#[cfg(feature = "simd-is-enabled")]
+use {
+    crate::{
+        math::{Isometry, SimdReal, SIMD_WIDTH},
+        utils,
+    },
+    na::{SimdPartialOrd, SimdValue},
+};
+
 pub enum AnyJointVelocityConstraint {
     JointConstraint(JointVelocityConstraint<Real, 1>),
     JointGroundConstraint(JointVelocityGroundConstraint<Real, 1>),
@@ -45,8 +52,12 @@ impl AnyJointVelocityConstraint {
             + locked_axes.count_ones() as usize
             + (limit_axes & !coupled_axes).count_ones() as usize
             + ((limit_axes & coupled_axes) & JointAxesMask::ANG_AXES.bits() != 0) as usize
-            + ((limit_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0) as usize;
-        (num_constraints, num_constraints)
+            + ((limit_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0) as usize
+            + joint.friction_axes().count_ones() as usize
+            + joint.coupling.is_some() as usize;
+        // NOTE: reserve as many jacobian lines as `Self::from_joint` requires, so it never
+        //       resizes the jacobians shared between threads.
+        (num_constraints, joint.max_num_velocity_constraints())
     }
 
     pub fn from_joint(
@@ -60,6 +71,7 @@ impl AnyJointVelocityConstraint {
         out: &mut Vec<Self>,
         insert_at: Option<usize>,
     ) {
+        let params = &joint.data.integration_parameters(params);
         let local_frame1 = joint.data.local_frame1;
         let local_frame2 = joint.data.local_frame2;
         let rb1 = &bodies[joint.body1];
@@ -91,7 +103,17 @@ impl AnyJointVelocityConstraint {
             .rigid_body_link(joint.body2)
             .map(|link| (&multibodies[link.multibody], link.id));
 
-        if mb1.is_some() || mb2.is_some() {
+        let coupling = joint.data.coupling.map(|coupling| {
+            CouplingJacobians::new(
+                &coupling,
+                &frame1,
+                &frame2,
+                &body1.world_com,
+                &body2.world_com,
+            )
+        });
+
+        if mb1.is_some() || mb2.is_some() || joint.data.requires_generic_constraints() {
             let multibodies_ndof = mb1.map(|m| m.0.ndofs()).unwrap_or(SPATIAL_DIM)
                 + mb2.map(|m| m.0.ndofs()).unwrap_or(SPATIAL_DIM);
 
@@ -100,19 +122,18 @@ impl AnyJointVelocityConstraint {
                 return;
             }
 
-            // For each solver contact we generate up to SPATIAL_DIM constraints, and each
-            // constraints appends the multibodies jacobian and weighted jacobians.
+            // Each constraint appends the multibodies jacobian and weighted jacobians.
             // Also note that for impulse_joints, the rigid-bodies will also add their jacobians
             // to the generic DVector.
-            // TODO: is this count correct when we take both motors and limits into account?
-            let required_jacobian_len = *j_id + multibodies_ndof * 2 * SPATIAL_DIM;
+            let required_jacobian_len =
+                *j_id + multibodies_ndof * 2 * joint.data.max_num_velocity_constraints();
 
-            if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
+            if jacobians.nrows() < required_jacobian_len {
                 jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
             }
 
             // TODO: find a way to avoid the temporary buffer.
-            let mut out_tmp = [JointGenericVelocityConstraint::invalid(); 12];
+            let mut out_tmp = [JointGenericVelocityConstraint::invalid(); 19];
             let out_tmp_len = JointGenericVelocityConstraint::lock_axes(
                 params,
                 joint_id,
@@ -123,11 +144,21 @@ impl AnyJointVelocityConstraint {
                 &frame1,
                 &frame2,
                 &joint.data,
+                coupling.as_ref(),
                 jacobians,
                 j_id,
                 &mut out_tmp,
             );
 
+            for c in &mut out_tmp[..out_tmp_len] {
+                c.impulse = na::clamp(
+                    c.writeback_id.previous_impulse(joint) * params.joint_warmstart_coefficient,
+                    c.impulse_bounds[0],
+                    c.impulse_bounds[1],
+                );
+                keep_soft_joint_bias(joint, c.rhs, &mut c.rhs_wo_bias);
+            }
+
             if let Some(at) = insert_at {
                 for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                     out[at + i] = AnyJointVelocityConstraint::JointGenericConstraint(c);
@@ -139,7 +170,7 @@ impl AnyJointVelocityConstraint {
             }
         } else {
             // TODO: find a way to avoid the temporary buffer.
-            let mut out_tmp = [JointVelocityConstraint::invalid(); 12];
+            let mut out_tmp = [JointVelocityConstraint::invalid(); 19];
             let out_tmp_len = JointVelocityConstraint::<Real, 1>::lock_axes(
                 params,
                 joint_id,
@@ -148,9 +179,19 @@ impl AnyJointVelocityConstraint {
                 &frame1,
                 &frame2,
                 &joint.data,
+                coupling.as_ref(),
                 &mut out_tmp,
             );
 
+            for c in &mut out_tmp[..out_tmp_len] {
+                c.impulse = na::clamp(
+                    c.writeback_id.previous_impulse(joint) * params.joint_warmstart_coefficient,
+                    c.impulse_bounds[0],
+                    c.impulse_bounds[1],
+                );
+                keep_soft_joint_bias(joint, c.rhs, &mut c.rhs_wo_bias);
+            }
+
             if let Some(at) = insert_at {
                 for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                     out[at + i] = AnyJointVelocityConstraint::JointConstraint(c);
@@ -216,7 +257,9 @@ impl AnyJointVelocityConstraint {
             linvel: gather![|ii| rb_vel1[ii].linvel].into(),
             angvel: gather![|ii| rb_vel1[ii].angvel].into(),
             im: gather![|ii| rb_mprops1[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt].into(),
+            sqrt_ii: utils::simd_angular_inertia(gather![
+                |ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt
+            ]),
             world_com: gather![|ii| rb_mprops1[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids1[ii].active_set_offset],
         };
@@ -224,13 +267,15 @@ impl AnyJointVelocityConstraint {
             linvel: gather![|ii| rb_vel2[ii].linvel].into(),
             angvel: gather![|ii| rb_vel2[ii].angvel].into(),
             im: gather![|ii| rb_mprops2[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt].into(),
+            sqrt_ii: utils::simd_angular_inertia(gather![
+                |ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt
+            ]),
             world_com: gather![|ii| rb_mprops2[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids2[ii].active_set_offset],
         };
 
         // TODO: find a way to avoid the temporary buffer.
-        let mut out_tmp = [JointVelocityConstraint::invalid(); 12];
+        let mut out_tmp = [JointVelocityConstraint::invalid(); 18];
         let out_tmp_len = JointVelocityConstraint::<SimdReal, SIMD_WIDTH>::lock_axes(
             params,
             joint_id,
@@ -242,6 +287,14 @@ impl AnyJointVelocityConstraint {
             &mut out_tmp,
         );
 
+        let warmstart_coefficient = SimdReal::splat(params.joint_warmstart_coefficient);
+        for c in &mut out_tmp[..out_tmp_len] {
+            let impulses: SimdReal =
+                gather![|ii| c.writeback_id.previous_impulse(impulse_joints[ii])].into();
+            c.impulse = (impulses * warmstart_coefficient)
+                .simd_clamp(c.impulse_bounds[0], c.impulse_bounds[1]);
+        }
+
         if let Some(at) = insert_at {
             for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                 out[at + i] = AnyJointVelocityConstraint::JointConstraintSimd(c);
@@ -264,6 +317,7 @@ impl AnyJointVelocityConstraint {
         out: &mut Vec<Self>,
         insert_at: Option<usize>,
     ) {
+        let params = &joint.data.integration_parameters(params);
         let mut handle1 = joint.body1;
         let mut handle2 = joint.body2;
         let flipped = !bodies[handle2].is_dynamic();
@@ -281,6 +335,28 @@ impl AnyJointVelocityConstraint {
         let frame1 = rb1.pos.position * local_frame1;
         let frame2 = rb2.pos.position * local_frame2;
 
+        let coupling = joint.data.coupling.map(|coupling| {
+            if flipped {
+                // NOTE: the coupling is defined in terms of the original order of the bodies.
+                CouplingJacobians::new(
+                    &coupling,
+                    &frame2,
+                    &frame1,
+                    &rb2.mprops.world_com,
+                    &rb1.mprops.world_com,
+                )
+                .flipped()
+            } else {
+                CouplingJacobians::new(
+                    &coupling,
+                    &frame1,
+                    &frame2,
+                    &rb1.mprops.world_com,
+                    &rb2.mprops.world_com,
+                )
+            }
+        });
+
         let body1 = SolverBody {
             linvel: rb1.vels.linvel,
             angvel: rb1.vels.angvel,
@@ -309,19 +385,18 @@ impl AnyJointVelocityConstraint {
                 return;
             }
 
-            // For each solver contact we generate up to SPATIAL_DIM constraints, and each
-            // constraints appends the multibodies jacobian and weighted jacobians.
+            // Each constraint appends the multibodies jacobian and weighted jacobians.
             // Also note that for impulse_joints, the rigid-bodies will also add their jacobians
             // to the generic DVector.
-            // TODO: is this count correct when we take both motors and limits into account?
-            let required_jacobian_len = *j_id + multibodies_ndof * 2 * SPATIAL_DIM;
+            let required_jacobian_len =
+                *j_id + multibodies_ndof * 2 * joint.data.max_num_velocity_constraints();
 
-            if jacobians.nrows() < required_jacobian_len && !cfg!(feature = "parallel") {
+            if jacobians.nrows() < required_jacobian_len {
                 jacobians.resize_vertically_mut(required_jacobian_len, 0.0);
             }
 
             // TODO: find a way to avoid the temporary buffer.
-            let mut out_tmp = [JointGenericVelocityGroundConstraint::invalid(); 12];
+            let mut out_tmp = [JointGenericVelocityGroundConstraint::invalid(); 19];
             let out_tmp_len = JointGenericVelocityGroundConstraint::lock_axes(
                 params,
                 joint_id,
@@ -331,11 +406,27 @@ impl AnyJointVelocityConstraint {
                 &frame1,
                 &frame2,
                 &joint.data,
+                coupling.as_ref(),
                 jacobians,
                 j_id,
                 &mut out_tmp,
             );
 
+            for c in &mut out_tmp[..out_tmp_len] {
+                c.impulse = na::clamp(
+                    c.writeback_id.previous_impulse(joint) * params.joint_warmstart_coefficient,
+                    c.impulse_bounds[0],
+                    c.impulse_bounds[1],
+                );
+                keep_soft_joint_bias(joint, c.rhs, &mut c.rhs_wo_bias);
+            }
+
+            if flipped {
+                for c in &mut out_tmp[..out_tmp_len] {
+                    c.reaction_sign = -1.0;
+                }
+            }
+
             if let Some(at) = insert_at {
                 for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                     out[at + i] = AnyJointVelocityConstraint::JointGenericGroundConstraint(c);
@@ -347,7 +438,7 @@ impl AnyJointVelocityConstraint {
             }
         } else {
             // TODO: find a way to avoid the temporary buffer.
-            let mut out_tmp = [JointVelocityGroundConstraint::invalid(); 12];
+            let mut out_tmp = [JointVelocityGroundConstraint::invalid(); 19];
             let out_tmp_len = JointVelocityGroundConstraint::<Real, 1>::lock_axes(
                 params,
                 joint_id,
@@ -356,9 +447,25 @@ impl AnyJointVelocityConstraint {
                 &frame1,
                 &frame2,
                 &joint.data,
+                coupling.as_ref(),
                 &mut out_tmp,
             );
 
+            for c in &mut out_tmp[..out_tmp_len] {
+                c.impulse = na::clamp(
+                    c.writeback_id.previous_impulse(joint) * params.joint_warmstart_coefficient,
+                    c.impulse_bounds[0],
+                    c.impulse_bounds[1],
+                );
+                keep_soft_joint_bias(joint, c.rhs, &mut c.rhs_wo_bias);
+            }
+
+            if flipped {
+                for c in &mut out_tmp[..out_tmp_len] {
+                    c.reaction_sign = -1.0;
+                }
+            }
+
             if let Some(at) = insert_at {
                 for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                     out[at + i] = AnyJointVelocityConstraint::JointGroundConstraint(c);
@@ -442,7 +549,9 @@ impl AnyJointVelocityConstraint {
             linvel: gather![|ii| rb_vel1[ii].linvel].into(),
             angvel: gather![|ii| rb_vel1[ii].angvel].into(),
             im: gather![|ii| rb_mprops1[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt].into(),
+            sqrt_ii: utils::simd_angular_inertia(gather![
+                |ii| rb_mprops1[ii].effective_world_inv_inertia_sqrt
+            ]),
             world_com: gather![|ii| rb_mprops1[ii].world_com].into(),
             mj_lambda: [crate::INVALID_USIZE; SIMD_WIDTH],
         };
@@ -450,13 +559,15 @@ impl AnyJointVelocityConstraint {
             linvel: gather![|ii| rb_vel2[ii].linvel].into(),
             angvel: gather![|ii| rb_vel2[ii].angvel].into(),
             im: gather![|ii| rb_mprops2[ii].effective_inv_mass].into(),
-            sqrt_ii: gather![|ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt].into(),
+            sqrt_ii: utils::simd_angular_inertia(gather![
+                |ii| rb_mprops2[ii].effective_world_inv_inertia_sqrt
+            ]),
             world_com: gather![|ii| rb_mprops2[ii].world_com].into(),
             mj_lambda: gather![|ii| rb_ids2[ii].active_set_offset],
         };
 
         // TODO: find a way to avoid the temporary buffer.
-        let mut out_tmp = [JointVelocityGroundConstraint::invalid(); 12];
+        let mut out_tmp = [JointVelocityGroundConstraint::invalid(); 18];
         let out_tmp_len = JointVelocityGroundConstraint::<SimdReal, SIMD_WIDTH>::lock_axes(
             params,
             joint_id,
@@ -468,6 +579,19 @@ impl AnyJointVelocityConstraint {
             &mut out_tmp,
         );
 
+        let warmstart_coefficient = SimdReal::splat(params.joint_warmstart_coefficient);
+        for c in &mut out_tmp[..out_tmp_len] {
+            let impulses: SimdReal =
+                gather![|ii| c.writeback_id.previous_impulse(impulse_joints[ii])].into();
+            c.impulse = (impulses * warmstart_coefficient)
+                .simd_clamp(c.impulse_bounds[0], c.impulse_bounds[1]);
+        }
+
+        let reaction_sign: SimdReal = gather![|ii| if flipped[ii] { -1.0 } else { 1.0 }].into();
+        for c in &mut out_tmp[..out_tmp_len] {
+            c.reaction_sign = reaction_sign;
+        }
+
         if let Some(at) = insert_at {
             for (i, c) in out_tmp.into_iter().take(out_tmp_len).enumerate() {
                 out[at + i] = AnyJointVelocityConstraint::JointGroundConstraintSimd(c);
@@ -493,6 +617,29 @@ impl AnyJointVelocityConstraint {
         }
     }
 
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
+        match self {
+            AnyJointVelocityConstraint::JointConstraint(c) => c.warmstart(mj_lambdas),
+            AnyJointVelocityConstraint::JointGroundConstraint(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyJointVelocityConstraint::JointConstraintSimd(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyJointVelocityConstraint::JointGroundConstraintSimd(c) => c.warmstart(mj_lambdas),
+            AnyJointVelocityConstraint::JointGenericConstraint(c) => {
+                c.warmstart(jacobians, mj_lambdas, generic_mj_lambdas)
+            }
+            AnyJointVelocityConstraint::JointGenericGroundConstraint(c) => {
+                c.warmstart(jacobians, mj_lambdas, generic_mj_lambdas)
+            }
+            AnyJointVelocityConstraint::Empty => unreachable!(),
+        }
+    }
+
     pub fn solve(
         &mut self,
         jacobians: &DVector<Real>,
@@ -538,3 +685,11 @@ impl AnyJointVelocityConstraint {
         }
     }
 }
+
+/// Soft joints act as springs: unlike the bias of rigid joints, their bias is kept during the
+/// stabilization.
+fn keep_soft_joint_bias(joint: &ImpulseJoint, rhs: Real, rhs_wo_bias: &mut Real) {
+    if joint.data.softness.is_some() {
+        *rhs_wo_bias = rhs;
+    }
+}
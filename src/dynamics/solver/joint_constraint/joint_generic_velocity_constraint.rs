@@ -1,10 +1,13 @@
 use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::WritebackId;
+use crate::dynamics::solver::joint_constraint::joint_velocity_constraint_builder::CouplingJacobians;
 use crate::dynamics::solver::joint_constraint::{JointVelocityConstraintBuilder, SolverBody};
 use crate::dynamics::solver::DeltaVel;
+#[cfg(feature = "dim3")]
+use crate::dynamics::JointAxesMask;
 use crate::dynamics::{GenericJoint, IntegrationParameters, JointGraphEdge, JointIndex, Multibody};
-use crate::math::{Isometry, Real, DIM};
+use crate::math::{AngVector, Isometry, Real, Vector, ANG_DIM, DIM};
 use crate::prelude::SPATIAL_DIM;
-use na::{DVector, DVectorSlice, DVectorSliceMut};
+use na::{DVector, DVectorSlice, DVectorSliceMut, SVector};
 
 #[derive(Debug, Copy, Clone)]
 pub struct JointGenericVelocityConstraint {
@@ -28,6 +31,11 @@ pub struct JointGenericVelocityConstraint {
     pub cfm_coeff: Real,
     pub cfm_gain: Real,
 
+    // The world-space jacobians of the second body, used to compute the joint’s reaction
+    // impulses. The angular part is expressed about the joint’s anchor.
+    pub lin_jac: Vector<Real>,
+    pub reaction_ang_jac: SVector<Real, ANG_DIM>,
+
     pub writeback_id: WritebackId,
 }
 
@@ -56,6 +64,8 @@ impl JointGenericVelocityConstraint {
             rhs_wo_bias: 0.0,
             cfm_coeff: 0.0,
             cfm_gain: 0.0,
+            lin_jac: Vector::zeros(),
+            reaction_ang_jac: na::zero(),
             writeback_id: WritebackId::Dof(0),
         }
     }
@@ -70,6 +80,7 @@ impl JointGenericVelocityConstraint {
         frame1: &Isometry<Real>,
         frame2: &Isometry<Real>,
         joint: &GenericJoint,
+        coupling: Option<&CouplingJacobians>,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         out: &mut [Self],
@@ -78,6 +89,12 @@ impl JointGenericVelocityConstraint {
         let locked_axes = joint.locked_axes.bits();
         let motor_axes = joint.motor_axes.bits();
         let limit_axes = joint.limit_axes.bits();
+        // NOTE: the coupled linear limits aren’t supported by the generic constraints yet, so
+        //       only the coupled angular axes are excluded from the per-axis limits.
+        #[cfg(feature = "dim2")]
+        let ang_coupled_axes = 0;
+        #[cfg(feature = "dim3")]
+        let ang_coupled_axes = joint.coupled_axes.bits() & JointAxesMask::ANG_AXES.bits();
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -125,6 +142,44 @@ impl JointGenericVelocityConstraint {
                 len += 1;
             }
         }
+
+        let friction_axes = joint.friction_axes();
+        for i in DIM..SPATIAL_DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_angular_generic(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    body2,
+                    mb1,
+                    mb2,
+                    i - DIM,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
+        for i in 0..DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_linear_generic(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    body2,
+                    mb1,
+                    mb2,
+                    i,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
         JointVelocityConstraintBuilder::finalize_generic_constraints(
             jacobians,
             &mut out[start..len],
@@ -167,7 +222,7 @@ impl JointGenericVelocityConstraint {
         }
 
         for i in DIM..SPATIAL_DIM {
-            if limit_axes & (1 << i) != 0 {
+            if (limit_axes & !ang_coupled_axes) & (1 << i) != 0 {
                 out[len] = builder.limit_angular_generic(
                     params,
                     jacobians,
@@ -203,10 +258,42 @@ impl JointGenericVelocityConstraint {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if limit_axes & ang_coupled_axes != 0 {
+            // NOTE: the coupled angular limits are configured on the first coupled axis.
+            let limit_axis = ang_coupled_axes.trailing_zeros() as usize;
+            out[len] = builder.limit_angular_coupled_generic(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                body2,
+                mb1,
+                mb2,
+                limit_axes & ang_coupled_axes,
+                &joint.limits,
+                WritebackId::Limit(limit_axis),
+            );
+            len += 1;
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints(
             jacobians,
             &mut out[start..len],
         );
+
+        if let Some(coupling) = coupling {
+            out[len] = builder.coupling_generic(
+                params, jacobians, j_id, joint_id, body1, body2, mb1, mb2, coupling,
+            );
+            JointVelocityConstraintBuilder::finalize_generic_constraints(
+                jacobians,
+                &mut out[len..len + 1],
+            );
+            len += 1;
+        }
+
         len
     }
 
@@ -291,13 +378,38 @@ impl JointGenericVelocityConstraint {
         let delta_impulse = total_impulse - self.impulse;
         self.impulse = total_impulse;
 
+        self.apply_impulse(delta_impulse, jacobians, mj_lambdas, generic_mj_lambdas);
+    }
+
+    /// Applies the initial impulse of this constraint, i.e., the warm-start impulse, to the bodies.
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
+        self.apply_impulse(
+            self.impulse,
+            jacobians.as_slice(),
+            mj_lambdas,
+            generic_mj_lambdas,
+        );
+    }
+
+    fn apply_impulse(
+        &self,
+        impulse: Real,
+        jacobians: &[Real],
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
         let mut mj_lambda1 = self.mj_lambda1_mut(mj_lambdas, generic_mj_lambdas);
         let wj1 = DVectorSlice::from_slice(&jacobians[self.wj_id1()..], self.ndofs1);
-        mj_lambda1.axpy(delta_impulse, &wj1, 1.0);
+        mj_lambda1.axpy(impulse, &wj1, 1.0);
 
         let mut mj_lambda2 = self.mj_lambda2_mut(mj_lambdas, generic_mj_lambdas);
         let wj2 = DVectorSlice::from_slice(&jacobians[self.wj_id2()..], self.ndofs2);
-        mj_lambda2.axpy(-delta_impulse, &wj2, 1.0);
+        mj_lambda2.axpy(-impulse, &wj2, 1.0);
     }
 
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
@@ -306,6 +418,15 @@ impl JointGenericVelocityConstraint {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Friction(_) | WritebackId::Coupling => {}
+        }
+
+        if let Some(slot) = self.writeback_id.reaction_slot() {
+            joint.set_reaction(
+                slot,
+                -self.lin_jac * self.impulse,
+                -reaction_ang_jac(&self.reaction_ang_jac) * self.impulse,
+            );
         }
     }
 
@@ -330,6 +451,12 @@ pub struct JointGenericVelocityGroundConstraint {
     pub cfm_coeff: Real,
     pub cfm_gain: Real,
 
+    // See `JointGenericVelocityConstraint::lin_jac`.
+    pub lin_jac: Vector<Real>,
+    pub reaction_ang_jac: SVector<Real, ANG_DIM>,
+    // -1 if the joint’s bodies were swapped to make the ground the first body.
+    pub reaction_sign: Real,
+
     pub writeback_id: WritebackId,
 }
 
@@ -353,6 +480,9 @@ impl JointGenericVelocityGroundConstraint {
             rhs_wo_bias: 0.0,
             cfm_coeff: 0.0,
             cfm_gain: 0.0,
+            lin_jac: Vector::zeros(),
+            reaction_ang_jac: na::zero(),
+            reaction_sign: 1.0,
             writeback_id: WritebackId::Dof(0),
         }
     }
@@ -366,6 +496,7 @@ impl JointGenericVelocityGroundConstraint {
         frame1: &Isometry<Real>,
         frame2: &Isometry<Real>,
         joint: &GenericJoint,
+        coupling: Option<&CouplingJacobians>,
         jacobians: &mut DVector<Real>,
         j_id: &mut usize,
         out: &mut [Self],
@@ -374,6 +505,12 @@ impl JointGenericVelocityGroundConstraint {
         let locked_axes = joint.locked_axes.bits();
         let motor_axes = joint.motor_axes.bits();
         let limit_axes = joint.limit_axes.bits();
+        // NOTE: the coupled linear limits aren’t supported by the generic constraints yet, so
+        //       only the coupled angular axes are excluded from the per-axis limits.
+        #[cfg(feature = "dim2")]
+        let ang_coupled_axes = 0;
+        #[cfg(feature = "dim3")]
+        let ang_coupled_axes = joint.coupled_axes.bits() & JointAxesMask::ANG_AXES.bits();
 
         let builder = JointVelocityConstraintBuilder::new(
             frame1,
@@ -421,6 +558,42 @@ impl JointGenericVelocityGroundConstraint {
             }
         }
 
+        let friction_axes = joint.friction_axes();
+        for i in DIM..SPATIAL_DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_angular_generic_ground(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    body2,
+                    mb2,
+                    i - DIM,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
+        for i in 0..DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_linear_generic_ground(
+                    params,
+                    jacobians,
+                    j_id,
+                    joint_id,
+                    body1,
+                    body2,
+                    mb2,
+                    i,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints_ground(
             jacobians,
             &mut out[start..len],
@@ -459,7 +632,7 @@ impl JointGenericVelocityGroundConstraint {
         }
 
         for i in DIM..SPATIAL_DIM {
-            if limit_axes & (1 << i) != 0 {
+            if (limit_axes & !ang_coupled_axes) & (1 << i) != 0 {
                 out[len] = builder.limit_angular_generic_ground(
                     params,
                     jacobians,
@@ -491,10 +664,39 @@ impl JointGenericVelocityGroundConstraint {
             }
         }
 
+        #[cfg(feature = "dim3")]
+        if limit_axes & ang_coupled_axes != 0 {
+            // NOTE: the coupled angular limits are configured on the first coupled axis.
+            let limit_axis = ang_coupled_axes.trailing_zeros() as usize;
+            out[len] = builder.limit_angular_coupled_generic_ground(
+                params,
+                jacobians,
+                j_id,
+                joint_id,
+                body1,
+                mb2,
+                limit_axes & ang_coupled_axes,
+                &joint.limits,
+                WritebackId::Limit(limit_axis),
+            );
+            len += 1;
+        }
+
         JointVelocityConstraintBuilder::finalize_generic_constraints_ground(
             jacobians,
             &mut out[start..len],
         );
+
+        if let Some(coupling) = coupling {
+            out[len] = builder
+                .coupling_generic_ground(params, jacobians, j_id, joint_id, body1, mb2, coupling);
+            JointVelocityConstraintBuilder::finalize_generic_constraints_ground(
+                jacobians,
+                &mut out[len..len + 1],
+            );
+            len += 1;
+        }
+
         len
     }
 
@@ -539,9 +741,34 @@ impl JointGenericVelocityGroundConstraint {
         let delta_impulse = total_impulse - self.impulse;
         self.impulse = total_impulse;
 
+        self.apply_impulse(delta_impulse, jacobians, mj_lambdas, generic_mj_lambdas);
+    }
+
+    /// Applies the initial impulse of this constraint, i.e., the warm-start impulse, to the body.
+    pub fn warmstart(
+        &self,
+        jacobians: &DVector<Real>,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
+        self.apply_impulse(
+            self.impulse,
+            jacobians.as_slice(),
+            mj_lambdas,
+            generic_mj_lambdas,
+        );
+    }
+
+    fn apply_impulse(
+        &self,
+        impulse: Real,
+        jacobians: &[Real],
+        mj_lambdas: &mut [DeltaVel<Real>],
+        generic_mj_lambdas: &mut DVector<Real>,
+    ) {
         let mut mj_lambda2 = self.mj_lambda2_mut(mj_lambdas, generic_mj_lambdas);
         let wj2 = DVectorSlice::from_slice(&jacobians[self.wj_id2()..], self.ndofs2);
-        mj_lambda2.axpy(-delta_impulse, &wj2, 1.0);
+        mj_lambda2.axpy(-impulse, &wj2, 1.0);
     }
 
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
@@ -552,6 +779,16 @@ impl JointGenericVelocityGroundConstraint {
                 WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+                WritebackId::Friction(_) | WritebackId::Coupling => {}
+            }
+
+            if let Some(slot) = self.writeback_id.reaction_slot() {
+                let impulse = self.impulse * self.reaction_sign;
+                joint.set_reaction(
+                    slot,
+                    -self.lin_jac * impulse,
+                    -reaction_ang_jac(&self.reaction_ang_jac) * impulse,
+                );
             }
         }
     }
@@ -560,3 +797,13 @@ impl JointGenericVelocityGroundConstraint {
         self.rhs = self.rhs_wo_bias;
     }
 }
+
+#[cfg(feature = "dim2")]
+fn reaction_ang_jac(ang_jac: &SVector<Real, ANG_DIM>) -> AngVector<Real> {
+    ang_jac[0]
+}
+
+#[cfg(feature = "dim3")]
+fn reaction_ang_jac(ang_jac: &SVector<Real, ANG_DIM>) -> AngVector<Real> {
+    *ang_jac
+}
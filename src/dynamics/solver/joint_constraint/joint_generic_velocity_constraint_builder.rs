@@ -2,14 +2,16 @@ use crate::dynamics::solver::joint_constraint::joint_generic_velocity_constraint
     JointGenericVelocityConstraint, JointGenericVelocityGroundConstraint,
 };
 use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::WritebackId;
+use crate::dynamics::solver::joint_constraint::joint_velocity_constraint_builder::CouplingJacobians;
 use crate::dynamics::solver::joint_constraint::{JointVelocityConstraintBuilder, SolverBody};
 use crate::dynamics::solver::MotorParameters;
 use crate::dynamics::{IntegrationParameters, JointIndex, Multibody};
 use crate::math::{Real, Vector, ANG_DIM, DIM, SPATIAL_DIM};
-use crate::utils::IndexMut2;
-use crate::utils::WDot;
+use crate::utils::{smallest_abs_diff_between_angles, IndexMut2, WDot};
 use na::{DVector, SVector};
 
+#[cfg(feature = "dim3")]
+use crate::dynamics::JointLimits;
 #[cfg(feature = "dim3")]
 use crate::utils::WAngularInertia;
 
@@ -70,6 +72,34 @@ impl JointVelocityConstraintBuilder<Real> {
         lin_jac: Vector<Real>,
         ang_jac1: SVector<Real, ANG_DIM>,
         ang_jac2: SVector<Real, ANG_DIM>,
+    ) -> JointGenericVelocityConstraint {
+        self.jacobians_generic(
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            writeback_id,
+            (lin_jac, ang_jac1),
+            (lin_jac, ang_jac2),
+        )
+    }
+
+    // Same as `lock_jacobians_generic` but with different linear jacobians for both bodies.
+    pub fn jacobians_generic(
+        &self,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        writeback_id: WritebackId,
+        (lin_jac1, ang_jac1): (Vector<Real>, SVector<Real, ANG_DIM>),
+        (lin_jac2, ang_jac2): (Vector<Real>, SVector<Real, ANG_DIM>),
     ) -> JointGenericVelocityConstraint {
         let is_rigid_body1 = mb1.is_none();
         let is_rigid_body2 = mb2.is_none();
@@ -79,18 +109,18 @@ impl JointVelocityConstraintBuilder<Real> {
 
         let j_id1 = *j_id;
         let vel1 = if let Some((mb1, link_id1)) = mb1 {
-            mb1.fill_jacobians(link_id1, lin_jac, ang_jac1, j_id, jacobians)
+            mb1.fill_jacobians(link_id1, lin_jac1, ang_jac1, j_id, jacobians)
                 .1
         } else {
-            body1.fill_jacobians(lin_jac, ang_jac1, j_id, jacobians)
+            body1.fill_jacobians(lin_jac1, ang_jac1, j_id, jacobians)
         };
 
         let j_id2 = *j_id;
         let vel2 = if let Some((mb2, link_id2)) = mb2 {
-            mb2.fill_jacobians(link_id2, lin_jac, ang_jac2, j_id, jacobians)
+            mb2.fill_jacobians(link_id2, lin_jac2, ang_jac2, j_id, jacobians)
                 .1
         } else {
-            body2.fill_jacobians(lin_jac, ang_jac2, j_id, jacobians)
+            body2.fill_jacobians(lin_jac2, ang_jac2, j_id, jacobians)
         };
 
         if is_rigid_body1 {
@@ -135,6 +165,8 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs_wo_bias,
             cfm_coeff: 0.0,
             cfm_gain: 0.0,
+            lin_jac: lin_jac2,
+            reaction_ang_jac: na::zero(),
             writeback_id,
         }
     }
@@ -297,7 +329,10 @@ impl JointVelocityConstraintBuilder<Real> {
         locked_axis: usize,
         writeback_id: WritebackId,
     ) -> JointGenericVelocityConstraint {
+        #[cfg(feature = "dim2")]
         let ang_jac = self.ang_basis.column(locked_axis).into_owned();
+        #[cfg(feature = "dim3")]
+        let ang_jac = self.locked_ang_jac(locked_axis);
 
         let mut constraint = self.lock_jacobians_generic(
             params,
@@ -313,12 +348,13 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
-        let rhs_bias = self.ang_err.imag()[locked_axis] * erp_inv_dt;
+        let rhs_bias = self.locked_ang_err(locked_axis) * erp_inv_dt;
         constraint.rhs += rhs_bias;
         constraint
     }
@@ -353,10 +389,11 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let s_limits = [(limits[0] / 2.0).sin(), (limits[1] / 2.0).sin()];
         #[cfg(feature = "dim2")]
-        let s_ang = self.ang_err.im;
+        let s_ang = (self.ang_err.angle() / 2.0).sin();
         #[cfg(feature = "dim3")]
         let s_ang = self.ang_err.imag()[limited_axis];
         let min_enabled = s_ang < s_limits[0];
@@ -375,6 +412,46 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    #[cfg(feature = "dim3")]
+    pub fn limit_angular_coupled_generic(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        limited_coupled_axes: u8,
+        limits: &[JointLimits<Real>],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityConstraint {
+        let (ang_jac, impulse_bounds, limit_err) =
+            self.coupled_angular_limit(limited_coupled_axes, limits);
+
+        let mut constraint = self.lock_jacobians_generic(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            writeback_id,
+            na::zero(),
+            ang_jac,
+            ang_jac,
+        );
+        constraint.reaction_ang_jac = ang_jac;
+
+        let erp_inv_dt = params.joint_erp_inv_dt();
+        constraint.rhs += limit_err * erp_inv_dt;
+        constraint.impulse_bounds = impulse_bounds;
+        constraint
+    }
+
     pub fn motor_angular_generic(
         &self,
         params: &IntegrationParameters,
@@ -409,15 +486,17 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let mut rhs_wo_bias = 0.0;
         if motor_params.erp_inv_dt != 0.0 {
             #[cfg(feature = "dim2")]
-            let s_ang_dist = self.ang_err.im;
+            let ang_dist = self.ang_err.angle();
             #[cfg(feature = "dim3")]
-            let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.sin();
-            rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
+            let ang_dist = self.ang_err.imag()[_motor_axis].asin() * 2.0;
+            let target_ang = motor_params.target_pos;
+            rhs_wo_bias +=
+                smallest_abs_diff_between_angles(ang_dist, target_ang) * motor_params.erp_inv_dt;
         }
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
@@ -431,6 +510,43 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    pub fn coupling_generic(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        mb1: Option<(&Multibody, usize)>,
+        mb2: Option<(&Multibody, usize)>,
+        coupling: &CouplingJacobians,
+    ) -> JointGenericVelocityConstraint {
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (
+            na::Vector1::new(coupling.ang_jac1),
+            na::Vector1::new(coupling.ang_jac2),
+        );
+        #[cfg(feature = "dim3")]
+        let (ang_jac1, ang_jac2) = (coupling.ang_jac1, coupling.ang_jac2);
+
+        let mut c = self.jacobians_generic(
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            body2,
+            mb1,
+            mb2,
+            WritebackId::Coupling,
+            (coupling.lin_jac1, ang_jac1),
+            (coupling.lin_jac2, ang_jac2),
+        );
+
+        c.rhs += coupling.error * params.joint_erp_inv_dt();
+        c
+    }
+
     pub fn finalize_generic_constraints(
         jacobians: &mut DVector<Real>,
         constraints: &mut [JointGenericVelocityConstraint],
@@ -510,14 +626,38 @@ impl JointVelocityConstraintBuilder<Real> {
         lin_jac: Vector<Real>,
         ang_jac1: SVector<Real, ANG_DIM>,
         ang_jac2: SVector<Real, ANG_DIM>,
+    ) -> JointGenericVelocityGroundConstraint {
+        self.jacobians_generic_ground(
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            (mb2, link_id2),
+            writeback_id,
+            (lin_jac, ang_jac1),
+            (lin_jac, ang_jac2),
+        )
+    }
+
+    // Same as `lock_jacobians_generic_ground` but with different linear jacobians for both bodies.
+    pub fn jacobians_generic_ground(
+        &self,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        (mb2, link_id2): (&Multibody, usize),
+        writeback_id: WritebackId,
+        (lin_jac1, ang_jac1): (Vector<Real>, SVector<Real, ANG_DIM>),
+        (lin_jac2, ang_jac2): (Vector<Real>, SVector<Real, ANG_DIM>),
     ) -> JointGenericVelocityGroundConstraint {
         let ndofs2 = mb2.ndofs();
 
-        let vel1 = lin_jac.dot(&body1.linvel) + ang_jac1.gdot(body1.angvel);
+        let vel1 = lin_jac1.dot(&body1.linvel) + ang_jac1.gdot(body1.angvel);
 
         let j_id2 = *j_id;
         let vel2 = mb2
-            .fill_jacobians(link_id2, lin_jac, ang_jac2, j_id, jacobians)
+            .fill_jacobians(link_id2, lin_jac2, ang_jac2, j_id, jacobians)
             .1;
         let rhs_wo_bias = vel2 - vel1;
 
@@ -535,6 +675,9 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs_wo_bias,
             cfm_coeff: 0.0,
             cfm_gain: 0.0,
+            lin_jac: lin_jac2,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: 1.0,
             writeback_id,
         }
     }
@@ -684,7 +827,10 @@ impl JointVelocityConstraintBuilder<Real> {
         locked_axis: usize,
         writeback_id: WritebackId,
     ) -> JointGenericVelocityGroundConstraint {
+        #[cfg(feature = "dim2")]
         let ang_jac = self.ang_basis.column(locked_axis).into_owned();
+        #[cfg(feature = "dim3")]
+        let ang_jac = self.locked_ang_jac(locked_axis);
 
         let mut constraint = self.lock_jacobians_generic_ground(
             params,
@@ -698,12 +844,13 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
-        let rhs_bias = self.ang_err.imag()[locked_axis] * erp_inv_dt;
+        let rhs_bias = self.locked_ang_err(locked_axis) * erp_inv_dt;
         constraint.rhs += rhs_bias;
         constraint
     }
@@ -734,10 +881,11 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let s_limits = [(limits[0] / 2.0).sin(), (limits[1] / 2.0).sin()];
         #[cfg(feature = "dim2")]
-        let s_ang = self.ang_err.im;
+        let s_ang = (self.ang_err.angle() / 2.0).sin();
         #[cfg(feature = "dim3")]
         let s_ang = self.ang_err.imag()[limited_axis];
         let min_enabled = s_ang < s_limits[0];
@@ -756,6 +904,42 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    #[cfg(feature = "dim3")]
+    pub fn limit_angular_coupled_generic_ground(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        mb2: (&Multibody, usize),
+        limited_coupled_axes: u8,
+        limits: &[JointLimits<Real>],
+        writeback_id: WritebackId,
+    ) -> JointGenericVelocityGroundConstraint {
+        let (ang_jac, impulse_bounds, limit_err) =
+            self.coupled_angular_limit(limited_coupled_axes, limits);
+
+        let mut constraint = self.lock_jacobians_generic_ground(
+            params,
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            mb2,
+            writeback_id,
+            na::zero(),
+            ang_jac,
+            ang_jac,
+        );
+        constraint.reaction_ang_jac = ang_jac;
+
+        let erp_inv_dt = params.joint_erp_inv_dt();
+        constraint.rhs += limit_err * erp_inv_dt;
+        constraint.impulse_bounds = impulse_bounds;
+        constraint
+    }
+
     pub fn motor_angular_generic_ground(
         &self,
         params: &IntegrationParameters,
@@ -787,15 +971,16 @@ impl JointVelocityConstraintBuilder<Real> {
             ang_jac,
             ang_jac,
         );
+        constraint.reaction_ang_jac = ang_jac;
 
         let mut rhs = 0.0;
         if motor_params.erp_inv_dt != 0.0 {
             #[cfg(feature = "dim2")]
-            let s_ang_dist = self.ang_err.im;
+            let ang_dist = self.ang_err.angle();
             #[cfg(feature = "dim3")]
-            let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.sin();
-            rhs += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
+            let ang_dist = self.ang_err.imag()[_motor_axis].asin() * 2.0;
+            let target_ang = motor_params.target_pos;
+            rhs += smallest_abs_diff_between_angles(ang_dist, target_ang) * motor_params.erp_inv_dt;
         }
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
@@ -809,6 +994,39 @@ impl JointVelocityConstraintBuilder<Real> {
         constraint
     }
 
+    pub fn coupling_generic_ground(
+        &self,
+        params: &IntegrationParameters,
+        jacobians: &mut DVector<Real>,
+        j_id: &mut usize,
+        joint_id: JointIndex,
+        body1: &SolverBody<Real, 1>,
+        mb2: (&Multibody, usize),
+        coupling: &CouplingJacobians,
+    ) -> JointGenericVelocityGroundConstraint {
+        #[cfg(feature = "dim2")]
+        let (ang_jac1, ang_jac2) = (
+            na::Vector1::new(coupling.ang_jac1),
+            na::Vector1::new(coupling.ang_jac2),
+        );
+        #[cfg(feature = "dim3")]
+        let (ang_jac1, ang_jac2) = (coupling.ang_jac1, coupling.ang_jac2);
+
+        let mut c = self.jacobians_generic_ground(
+            jacobians,
+            j_id,
+            joint_id,
+            body1,
+            mb2,
+            WritebackId::Coupling,
+            (coupling.lin_jac1, ang_jac1),
+            (coupling.lin_jac2, ang_jac2),
+        );
+
+        c.rhs += coupling.error * params.joint_erp_inv_dt();
+        c
+    }
+
     pub fn finalize_generic_constraints_ground(
         jacobians: &mut DVector<Real>,
         constraints: &mut [JointGenericVelocityGroundConstraint],
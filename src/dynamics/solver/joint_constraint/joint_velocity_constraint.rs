@@ -1,7 +1,8 @@
+use crate::dynamics::solver::joint_constraint::joint_velocity_constraint_builder::CouplingJacobians;
 use crate::dynamics::solver::joint_constraint::JointVelocityConstraintBuilder;
 use crate::dynamics::solver::DeltaVel;
 use crate::dynamics::{
-    GenericJoint, IntegrationParameters, JointAxesMask, JointGraphEdge, JointIndex,
+    GenericJoint, ImpulseJoint, IntegrationParameters, JointAxesMask, JointGraphEdge, JointIndex,
 };
 use crate::math::{AngVector, AngularInertia, Isometry, Point, Real, Vector, DIM, SPATIAL_DIM};
 use crate::utils::{WDot, WReal};
@@ -40,6 +41,35 @@ pub enum WritebackId {
     Dof(usize),
     Limit(usize),
     Motor(usize),
+    Friction(usize),
+    Coupling,
+}
+
+impl WritebackId {
+    /// The index of the reaction impulse stored by this constraint in `ImpulseJoint::reactions`.
+    ///
+    /// Couplings don’t apply opposite impulses on both bodies so they have no reaction slot.
+    pub fn reaction_slot(self) -> Option<usize> {
+        match self {
+            WritebackId::Dof(i) => Some(i),
+            WritebackId::Limit(i) => Some(SPATIAL_DIM + i),
+            WritebackId::Motor(i) => Some(2 * SPATIAL_DIM + i),
+            WritebackId::Friction(i) => Some(3 * SPATIAL_DIM + i),
+            WritebackId::Coupling => None,
+        }
+    }
+
+    /// The impulse written back by the constraint with this id at the end of the last timestep.
+    ///
+    /// Friction and coupling impulses aren’t stored, so they are always zero.
+    pub fn previous_impulse(self, joint: &ImpulseJoint) -> Real {
+        match self {
+            WritebackId::Dof(i) => joint.impulses[i],
+            WritebackId::Limit(i) => joint.data.limits[i].impulse,
+            WritebackId::Motor(i) => joint.data.motors[i].impulse,
+            WritebackId::Friction(_) | WritebackId::Coupling => 0.0,
+        }
+    }
 }
 
 // TODO: right now we only use this for impulse_joints.
@@ -68,6 +98,9 @@ pub struct JointVelocityConstraint<N: WReal, const LANES: usize> {
     pub lin_jac: Vector<N>,
     pub ang_jac1: AngVector<N>,
     pub ang_jac2: AngVector<N>,
+    // The angular part of the jacobian, unscaled by the inertia, and
+    // expressed about the joint’s anchor on the second body.
+    pub reaction_ang_jac: AngVector<N>,
 
     pub inv_lhs: N,
     pub rhs: N,
@@ -92,6 +125,7 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
             lin_jac: Vector::zeros(),
             ang_jac1: na::zero(),
             ang_jac2: na::zero(),
+            reaction_ang_jac: na::zero(),
             inv_lhs: N::zero(),
             cfm_gain: N::zero(),
             cfm_coeff: N::zero(),
@@ -114,9 +148,23 @@ impl<N: WReal, const LANES: usize> JointVelocityConstraint<N, LANES> {
         let delta_impulse = total_impulse - self.impulse;
         self.impulse = total_impulse;
 
-        let lin_impulse = self.lin_jac * delta_impulse;
-        let ang_impulse1 = self.ang_jac1 * delta_impulse;
-        let ang_impulse2 = self.ang_jac2 * delta_impulse;
+        self.apply_impulse(delta_impulse, mj_lambda1, mj_lambda2);
+    }
+
+    /// Applies the initial impulse of this constraint, i.e., the warm-start impulse, to the bodies.
+    pub fn warmstart_generic(&self, mj_lambda1: &mut DeltaVel<N>, mj_lambda2: &mut DeltaVel<N>) {
+        self.apply_impulse(self.impulse, mj_lambda1, mj_lambda2);
+    }
+
+    fn apply_impulse(
+        &self,
+        impulse: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) {
+        let lin_impulse = self.lin_jac * impulse;
+        let ang_impulse1 = self.ang_jac1 * impulse;
+        let ang_impulse2 = self.ang_jac2 * impulse;
 
         mj_lambda1.linear += lin_impulse.component_mul(&self.im1);
         mj_lambda1.angular += ang_impulse1;
@@ -138,6 +186,7 @@ impl JointVelocityConstraint<Real, 1> {
         frame1: &Isometry<Real>,
         frame2: &Isometry<Real>,
         joint: &GenericJoint,
+        coupling: Option<&CouplingJacobians>,
         out: &mut [Self],
     ) -> usize {
         let mut len = 0;
@@ -195,17 +244,56 @@ impl JointVelocityConstraint<Real, 1> {
         }
 
         if (motor_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0 {
-            // TODO: coupled linear motor constraint.
-            // out[len] = builder.motor_linear_coupled(
-            //     params,
-            //     [joint_id],
-            //     body1,
-            //     body2,
-            //     limit_axes & coupled_axes,
-            //     &joint.limits,
-            //     WritebackId::Limit(0), // TODO: writeback
-            // );
-            // len += 1;
+            // NOTE: the coupled linear motor is configured on the first coupled axis.
+            let motor_axis =
+                (coupled_axes & JointAxesMask::LIN_AXES.bits()).trailing_zeros() as usize;
+            let limits = if limit_axes & (1 << motor_axis) != 0 {
+                Some([joint.limits[motor_axis].min, joint.limits[motor_axis].max])
+            } else {
+                None
+            };
+
+            out[len] = builder.motor_linear_coupled(
+                params,
+                [joint_id],
+                body1,
+                body2,
+                coupled_axes,
+                &joint.motors[motor_axis].motor_params(params.dt),
+                limits,
+                WritebackId::Motor(motor_axis),
+            );
+            len += 1;
+        }
+
+        let friction_axes = joint.friction_axes();
+        for i in DIM..SPATIAL_DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_angular(
+                    [joint_id],
+                    body1,
+                    body2,
+                    i - DIM,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
+        for i in 0..DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_linear(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    i,
+                    &joint.friction_params(i, params.dt),
+                    None,
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
         }
 
         JointVelocityConstraintBuilder::finalize_constraints(&mut out[start..len]);
@@ -263,6 +351,9 @@ impl JointVelocityConstraint<Real, 1> {
 
         #[cfg(feature = "dim3")]
         if (limit_axes & coupled_axes) & JointAxesMask::ANG_AXES.bits() != 0 {
+            // NOTE: the coupled angular limits are configured on the first coupled axis.
+            let limit_axis =
+                (coupled_axes & JointAxesMask::ANG_AXES.bits()).trailing_zeros() as usize;
             out[len] = builder.limit_angular_coupled(
                 params,
                 [joint_id],
@@ -270,25 +361,40 @@ impl JointVelocityConstraint<Real, 1> {
                 body2,
                 limit_axes & coupled_axes,
                 &joint.limits,
-                WritebackId::Limit(0), // TODO: writeback
+                WritebackId::Limit(limit_axis),
             );
             len += 1;
         }
 
         if (limit_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0 {
+            // NOTE: the coupled linear limits are configured on the first coupled axis.
+            let limit_axis =
+                (coupled_axes & JointAxesMask::LIN_AXES.bits()).trailing_zeros() as usize;
             out[len] = builder.limit_linear_coupled(
                 params,
                 [joint_id],
                 body1,
                 body2,
-                limit_axes & coupled_axes,
-                &joint.limits,
-                WritebackId::Limit(0), // TODO: writeback
+                coupled_axes,
+                [joint.limits[limit_axis].min, joint.limits[limit_axis].max],
+                WritebackId::Limit(limit_axis),
             );
             len += 1;
         }
         JointVelocityConstraintBuilder::finalize_constraints(&mut out[start..len]);
 
+        if let Some(coupling) = coupling {
+            out[len] = JointVelocityConstraintBuilder::coupling(
+                params,
+                [joint_id],
+                body1,
+                body2,
+                coupling,
+            );
+            JointVelocityConstraintBuilder::finalize_constraints(&mut out[len..len + 1]);
+            len += 1;
+        }
+
         len
     }
 
@@ -302,12 +408,31 @@ impl JointVelocityConstraint<Real, 1> {
         mj_lambdas[self.mj_lambda2[0] as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda1 = mj_lambdas[self.mj_lambda1[0]];
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2[0]];
+
+        self.warmstart_generic(&mut mj_lambda1, &mut mj_lambda2);
+
+        mj_lambdas[self.mj_lambda1[0]] = mj_lambda1;
+        mj_lambdas[self.mj_lambda2[0]] = mj_lambda2;
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let joint = &mut joints_all[self.joint_id[0]].weight;
         match self.writeback_id {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Friction(_) | WritebackId::Coupling => {}
+        }
+
+        if let Some(slot) = self.writeback_id.reaction_slot() {
+            joint.set_reaction(
+                slot,
+                -self.lin_jac * self.impulse,
+                -self.reaction_ang_jac * self.impulse,
+            );
         }
     }
 }
@@ -359,21 +484,42 @@ impl JointVelocityConstraint<SimdReal, SIMD_WIDTH> {
     }
 
     pub fn solve(&mut self, mj_lambdas: &mut [DeltaVel<Real>]) {
-        let mut mj_lambda1 = DeltaVel {
+        let (mut mj_lambda1, mut mj_lambda2) = self.gather_mj_lambdas(mj_lambdas);
+        self.solve_generic(&mut mj_lambda1, &mut mj_lambda2);
+        self.scatter_mj_lambdas(mj_lambdas, &mj_lambda1, &mj_lambda2);
+    }
+
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let (mut mj_lambda1, mut mj_lambda2) = self.gather_mj_lambdas(mj_lambdas);
+        self.warmstart_generic(&mut mj_lambda1, &mut mj_lambda2);
+        self.scatter_mj_lambdas(mj_lambdas, &mj_lambda1, &mj_lambda2);
+    }
+
+    fn gather_mj_lambdas(
+        &self,
+        mj_lambdas: &[DeltaVel<Real>],
+    ) -> (DeltaVel<SimdReal>, DeltaVel<SimdReal>) {
+        let mj_lambda1 = DeltaVel {
             linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda1[ii] as usize].linear]),
             angular: AngVector::from(gather![
                 |ii| mj_lambdas[self.mj_lambda1[ii] as usize].angular
             ]),
         };
-        let mut mj_lambda2 = DeltaVel {
+        let mj_lambda2 = DeltaVel {
             linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda2[ii] as usize].linear]),
             angular: AngVector::from(gather![
                 |ii| mj_lambdas[self.mj_lambda2[ii] as usize].angular
             ]),
         };
+        (mj_lambda1, mj_lambda2)
+    }
 
-        self.solve_generic(&mut mj_lambda1, &mut mj_lambda2);
-
+    fn scatter_mj_lambdas(
+        &self,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        mj_lambda1: &DeltaVel<SimdReal>,
+        mj_lambda2: &DeltaVel<SimdReal>,
+    ) {
         for ii in 0..SIMD_WIDTH {
             mj_lambdas[self.mj_lambda1[ii] as usize].linear = mj_lambda1.linear.extract(ii);
             mj_lambdas[self.mj_lambda1[ii] as usize].angular = mj_lambda1.angular.extract(ii);
@@ -392,6 +538,15 @@ impl JointVelocityConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                WritebackId::Friction(_) | WritebackId::Coupling => {}
+            }
+
+            if let Some(slot) = self.writeback_id.reaction_slot() {
+                joint.set_reaction(
+                    slot,
+                    -self.lin_jac.extract(ii) * impulses[ii],
+                    -self.reaction_ang_jac.extract(ii) * impulses[ii],
+                );
             }
         }
     }
@@ -407,6 +562,11 @@ pub struct JointVelocityGroundConstraint<N: WReal, const LANES: usize> {
     pub impulse_bounds: [N; 2],
     pub lin_jac: Vector<N>,
     pub ang_jac2: AngVector<N>,
+    // The angular part of the jacobian, unscaled by the inertia, and
+    // expressed about the joint’s anchor on the second body.
+    pub reaction_ang_jac: AngVector<N>,
+    // -1 if the bodies were swapped when building this constraint, 1 otherwise.
+    pub reaction_sign: N,
 
     pub inv_lhs: N,
     pub cfm_coeff: N,
@@ -428,6 +588,8 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
             impulse_bounds: [N::zero(), N::zero()],
             lin_jac: Vector::zeros(),
             ang_jac2: na::zero(),
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
             inv_lhs: N::zero(),
             cfm_coeff: N::zero(),
             cfm_gain: N::zero(),
@@ -448,8 +610,17 @@ impl<N: WReal, const LANES: usize> JointVelocityGroundConstraint<N, LANES> {
         let delta_impulse = total_impulse - self.impulse;
         self.impulse = total_impulse;
 
-        let lin_impulse = self.lin_jac * delta_impulse;
-        let ang_impulse = self.ang_jac2 * delta_impulse;
+        self.apply_impulse(delta_impulse, mj_lambda2);
+    }
+
+    /// Applies the initial impulse of this constraint, i.e., the warm-start impulse, to the body.
+    pub fn warmstart_generic(&self, mj_lambda2: &mut DeltaVel<N>) {
+        self.apply_impulse(self.impulse, mj_lambda2);
+    }
+
+    fn apply_impulse(&self, impulse: N, mj_lambda2: &mut DeltaVel<N>) {
+        let lin_impulse = self.lin_jac * impulse;
+        let ang_impulse = self.ang_jac2 * impulse;
 
         mj_lambda2.linear -= lin_impulse.component_mul(&self.im2);
         mj_lambda2.angular -= ang_impulse;
@@ -469,6 +640,7 @@ impl JointVelocityGroundConstraint<Real, 1> {
         frame1: &Isometry<Real>,
         frame2: &Isometry<Real>,
         joint: &GenericJoint,
+        coupling: Option<&CouplingJacobians>,
         out: &mut [Self],
     ) -> usize {
         let mut len = 0;
@@ -526,22 +698,56 @@ impl JointVelocityGroundConstraint<Real, 1> {
         }
 
         if (motor_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0 {
-            /*
-            // TODO: coupled linear motor constraint.
+            // NOTE: the coupled linear motor is configured on the first coupled axis.
+            let motor_axis =
+                (coupled_axes & JointAxesMask::LIN_AXES.bits()).trailing_zeros() as usize;
+            let limits = if limit_axes & (1 << motor_axis) != 0 {
+                Some([joint.limits[motor_axis].min, joint.limits[motor_axis].max])
+            } else {
+                None
+            };
+
             out[len] = builder.motor_linear_coupled_ground(
                 params,
                 [joint_id],
                 body1,
                 body2,
-                motor_axes & coupled_axes,
-                &joint.motors,
-                limit_axes & coupled_axes,
-                &joint.limits,
-                WritebackId::Limit(0), // TODO: writeback
+                coupled_axes,
+                &joint.motors[motor_axis].motor_params(params.dt),
+                limits,
+                WritebackId::Motor(motor_axis),
             );
             len += 1;
-            */
-            todo!()
+        }
+
+        let friction_axes = joint.friction_axes();
+        for i in DIM..SPATIAL_DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_angular_ground(
+                    [joint_id],
+                    body1,
+                    body2,
+                    i - DIM,
+                    &joint.friction_params(i, params.dt),
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
+        }
+        for i in 0..DIM {
+            if friction_axes & (1 << i) != 0 {
+                out[len] = builder.motor_linear_ground(
+                    params,
+                    [joint_id],
+                    body1,
+                    body2,
+                    i,
+                    &joint.friction_params(i, params.dt),
+                    None,
+                    WritebackId::Friction(i),
+                );
+                len += 1;
+            }
         }
 
         JointVelocityConstraintBuilder::finalize_ground_constraints(&mut out[start..len]);
@@ -605,6 +811,9 @@ impl JointVelocityGroundConstraint<Real, 1> {
 
         #[cfg(feature = "dim3")]
         if (limit_axes & coupled_axes) & JointAxesMask::ANG_AXES.bits() != 0 {
+            // NOTE: the coupled angular limits are configured on the first coupled axis.
+            let limit_axis =
+                (coupled_axes & JointAxesMask::ANG_AXES.bits()).trailing_zeros() as usize;
             out[len] = builder.limit_angular_coupled_ground(
                 params,
                 [joint_id],
@@ -612,25 +821,40 @@ impl JointVelocityGroundConstraint<Real, 1> {
                 body2,
                 limit_axes & coupled_axes,
                 &joint.limits,
-                WritebackId::Limit(0), // TODO: writeback
+                WritebackId::Limit(limit_axis),
             );
             len += 1;
         }
 
         if (limit_axes & coupled_axes) & JointAxesMask::LIN_AXES.bits() != 0 {
+            // NOTE: the coupled linear limits are configured on the first coupled axis.
+            let limit_axis =
+                (coupled_axes & JointAxesMask::LIN_AXES.bits()).trailing_zeros() as usize;
             out[len] = builder.limit_linear_coupled_ground(
                 params,
                 [joint_id],
                 body1,
                 body2,
-                limit_axes & coupled_axes,
-                &joint.limits,
-                WritebackId::Limit(0), // TODO: writeback
+                coupled_axes,
+                [joint.limits[limit_axis].min, joint.limits[limit_axis].max],
+                WritebackId::Limit(limit_axis),
             );
             len += 1;
         }
         JointVelocityConstraintBuilder::finalize_ground_constraints(&mut out[start..len]);
 
+        if let Some(coupling) = coupling {
+            out[len] = JointVelocityConstraintBuilder::coupling_ground(
+                params,
+                [joint_id],
+                body1,
+                body2,
+                coupling,
+            );
+            JointVelocityConstraintBuilder::finalize_ground_constraints(&mut out[len..len + 1]);
+            len += 1;
+        }
+
         len
     }
 
@@ -640,12 +864,28 @@ impl JointVelocityGroundConstraint<Real, 1> {
         mj_lambdas[self.mj_lambda2[0] as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2[0]];
+        self.warmstart_generic(&mut mj_lambda2);
+        mj_lambdas[self.mj_lambda2[0]] = mj_lambda2;
+    }
+
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let joint = &mut joints_all[self.joint_id[0]].weight;
         match self.writeback_id {
             WritebackId::Dof(i) => joint.impulses[i] = self.impulse,
             WritebackId::Limit(i) => joint.data.limits[i].impulse = self.impulse,
             WritebackId::Motor(i) => joint.data.motors[i].impulse = self.impulse,
+            WritebackId::Friction(_) | WritebackId::Coupling => {}
+        }
+
+        if let Some(slot) = self.writeback_id.reaction_slot() {
+            let impulse = self.impulse * self.reaction_sign;
+            joint.set_reaction(
+                slot,
+                -self.lin_jac * impulse,
+                -self.reaction_ang_jac * impulse,
+            );
         }
     }
 }
@@ -703,15 +943,31 @@ impl JointVelocityGroundConstraint<SimdReal, SIMD_WIDTH> {
     }
 
     pub fn solve(&mut self, mj_lambdas: &mut [DeltaVel<Real>]) {
-        let mut mj_lambda2 = DeltaVel {
+        let mut mj_lambda2 = self.gather_mj_lambda2(mj_lambdas);
+        self.solve_generic(&mut mj_lambda2);
+        self.scatter_mj_lambda2(mj_lambdas, &mj_lambda2);
+    }
+
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = self.gather_mj_lambda2(mj_lambdas);
+        self.warmstart_generic(&mut mj_lambda2);
+        self.scatter_mj_lambda2(mj_lambdas, &mj_lambda2);
+    }
+
+    fn gather_mj_lambda2(&self, mj_lambdas: &[DeltaVel<Real>]) -> DeltaVel<SimdReal> {
+        DeltaVel {
             linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda2[ii] as usize].linear]),
             angular: AngVector::from(gather![
                 |ii| mj_lambdas[self.mj_lambda2[ii] as usize].angular
             ]),
-        };
-
-        self.solve_generic(&mut mj_lambda2);
+        }
+    }
 
+    fn scatter_mj_lambda2(
+        &self,
+        mj_lambdas: &mut [DeltaVel<Real>],
+        mj_lambda2: &DeltaVel<SimdReal>,
+    ) {
         for ii in 0..SIMD_WIDTH {
             mj_lambdas[self.mj_lambda2[ii] as usize].linear = mj_lambda2.linear.extract(ii);
             mj_lambdas[self.mj_lambda2[ii] as usize].angular = mj_lambda2.angular.extract(ii);
@@ -720,6 +976,7 @@ impl JointVelocityGroundConstraint<SimdReal, SIMD_WIDTH> {
 
     pub fn writeback_impulses(&self, joints_all: &mut [JointGraphEdge]) {
         let impulses: [_; SIMD_WIDTH] = self.impulse.into();
+        let signs: [_; SIMD_WIDTH] = self.reaction_sign.into();
 
         // TODO: should we move the iteration on ii deeper in the mested match?
         for ii in 0..SIMD_WIDTH {
@@ -728,6 +985,16 @@ impl JointVelocityGroundConstraint<SimdReal, SIMD_WIDTH> {
                 WritebackId::Dof(i) => joint.impulses[i] = impulses[ii],
                 WritebackId::Limit(i) => joint.data.limits[i].impulse = impulses[ii],
                 WritebackId::Motor(i) => joint.data.motors[i].impulse = impulses[ii],
+                WritebackId::Friction(_) | WritebackId::Coupling => {}
+            }
+
+            if let Some(slot) = self.writeback_id.reaction_slot() {
+                let impulse = impulses[ii] * signs[ii];
+                joint.set_reaction(
+                    slot,
+                    -self.lin_jac.extract(ii) * impulse,
+                    -self.reaction_ang_jac.extract(ii) * impulse,
+                );
             }
         }
     }
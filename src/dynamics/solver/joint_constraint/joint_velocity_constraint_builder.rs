@@ -3,10 +3,14 @@ use crate::dynamics::solver::joint_constraint::joint_velocity_constraint::{
 };
 use crate::dynamics::solver::joint_constraint::SolverBody;
 use crate::dynamics::solver::MotorParameters;
-use crate::dynamics::{IntegrationParameters, JointIndex, JointLimits};
+#[cfg(feature = "dim3")]
+use crate::dynamics::JointLimits;
+use crate::dynamics::{IntegrationParameters, JointCoupling, JointIndex};
 use crate::math::{AngVector, Isometry, Matrix, Point, Real, Rotation, Vector, ANG_DIM, DIM};
-use crate::utils::{IndexMut2, WCrossMatrix, WDot, WQuat, WReal};
-use na::SMatrix;
+use crate::utils::{
+    smallest_abs_diff_between_angles, IndexMut2, WCross, WCrossMatrix, WDot, WQuat, WReal,
+};
+use na::{SMatrix, Unit};
 
 #[cfg(feature = "dim3")]
 use crate::utils::WBasis;
@@ -20,6 +24,10 @@ pub struct JointVelocityConstraintBuilder<N: WReal> {
     pub ang_basis: SMatrix<N, ANG_DIM, ANG_DIM>,
     pub lin_err: Vector<N>,
     pub ang_err: Rotation<N>,
+    // The sign applied to `ang_basis` and `ang_err` so that `ang_err` is the shortest rotation
+    // between both frames.
+    #[cfg(feature = "dim3")]
+    pub ang_sign: N,
 }
 
 impl<N: WReal> JointVelocityConstraintBuilder<N> {
@@ -61,11 +69,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         #[allow(unused_mut)] // The mut is needed for 3D
         let mut ang_err = frame1.rotation.inverse() * frame2.rotation;
 
+        #[cfg(feature = "dim3")]
+        let ang_sign = N::one().simd_copysign(frame1.rotation.dot(&frame2.rotation));
         #[cfg(feature = "dim3")]
         {
-            let sgn = N::one().simd_copysign(frame1.rotation.dot(&frame2.rotation));
-            ang_basis *= sgn;
-            *ang_err.as_mut_unchecked() *= sgn;
+            ang_basis *= ang_sign;
+            *ang_err.as_mut_unchecked() *= ang_sign;
         }
 
         Self {
@@ -76,6 +85,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             ang_basis,
             lin_err,
             ang_err,
+            #[cfg(feature = "dim3")]
+            ang_sign,
         }
     }
 
@@ -117,18 +128,17 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         joint_id: [JointIndex; LANES],
         body1: &SolverBody<N, LANES>,
         body2: &SolverBody<N, LANES>,
-        limited_coupled_axes: u8,
-        limits: &[JointLimits<N>],
+        coupled_axes: u8,
+        limits: [N; 2],
         writeback_id: WritebackId,
     ) -> JointVelocityConstraint<N, LANES> {
         let zero = N::zero();
         let mut lin_jac = Vector::zeros();
         let mut ang_jac1: AngVector<N> = na::zero();
         let mut ang_jac2: AngVector<N> = na::zero();
-        let mut limit = N::zero();
 
         for i in 0..DIM {
-            if limited_coupled_axes & (1 << i) != 0 {
+            if coupled_axes & (1 << i) != 0 {
                 let coeff = self.basis.column(i).dot(&self.lin_err);
                 lin_jac += self.basis.column(i) * coeff;
                 #[cfg(feature = "dim2")]
@@ -141,11 +151,9 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                     ang_jac1 += self.cmat1_basis.column(i) * coeff;
                     ang_jac2 += self.cmat2_basis.column(i) * coeff;
                 }
-                limit += limits[i].max * limits[i].max;
             }
         }
 
-        limit = limit.simd_sqrt();
         let dist = lin_jac.norm();
         let inv_dist = crate::utils::simd_inv(dist);
         lin_jac *= inv_dist;
@@ -154,16 +162,26 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
 
         let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
             + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
-        let rhs_wo_bias = dvel + (dist - limit).simd_min(zero) * N::splat(params.inv_dt());
+
+        // NOTE: the upper limit is handled speculatively (the constraint is always active to
+        //       prevent the distance from exceeding it within a single step), unless the lower
+        //       limit is currently violated.
+        let min_enabled = dist.simd_lt(limits[0]);
+        let max_rhs = (dist - limits[1]).simd_min(zero) * N::splat(params.inv_dt());
+        let rhs_wo_bias = dvel + zero.select(min_enabled, max_rhs);
 
         ang_jac1 = body1.sqrt_ii * ang_jac1;
         ang_jac2 = body2.sqrt_ii * ang_jac2;
 
         let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
         let cfm_coeff = N::splat(params.joint_cfm_coeff());
-        let rhs_bias = (dist - limit).simd_max(zero) * erp_inv_dt;
+        let rhs_bias =
+            ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
         let rhs = rhs_wo_bias + rhs_bias;
-        let impulse_bounds = [N::zero(), N::splat(Real::INFINITY)];
+        let impulse_bounds = [
+            N::splat(-Real::INFINITY).select(min_enabled, zero),
+            zero.select(min_enabled, N::splat(Real::INFINITY)),
+        ];
 
         JointVelocityConstraint {
             joint_id,
@@ -182,6 +200,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
         }
     }
 
@@ -225,6 +244,84 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         constraint
     }
 
+    pub fn motor_linear_coupled<const LANES: usize>(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        motor_params: &MotorParameters<N>,
+        limits: Option<[N; 2]>,
+        writeback_id: WritebackId,
+    ) -> JointVelocityConstraint<N, LANES> {
+        let inv_dt = N::splat(params.inv_dt());
+        let mut lin_jac = Vector::zeros();
+        let mut ang_jac1: AngVector<N> = na::zero();
+        let mut ang_jac2: AngVector<N> = na::zero();
+
+        for i in 0..DIM {
+            if coupled_axes & (1 << i) != 0 {
+                let coeff = self.basis.column(i).dot(&self.lin_err);
+                lin_jac += self.basis.column(i) * coeff;
+                #[cfg(feature = "dim2")]
+                {
+                    ang_jac1 += self.cmat1_basis[i] * coeff;
+                    ang_jac2 += self.cmat2_basis[i] * coeff;
+                }
+                #[cfg(feature = "dim3")]
+                {
+                    ang_jac1 += self.cmat1_basis.column(i) * coeff;
+                    ang_jac2 += self.cmat2_basis.column(i) * coeff;
+                }
+            }
+        }
+
+        let dist = lin_jac.norm();
+        let inv_dist = crate::utils::simd_inv(dist);
+        lin_jac *= inv_dist;
+        ang_jac1 *= inv_dist;
+        ang_jac2 *= inv_dist;
+
+        let mut rhs_wo_bias = N::zero();
+        if motor_params.erp_inv_dt != N::zero() {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.erp_inv_dt;
+        }
+
+        let mut target_vel = motor_params.target_vel;
+        if let Some(limits) = limits {
+            target_vel =
+                target_vel.simd_clamp((limits[0] - dist) * inv_dt, (limits[1] - dist) * inv_dt);
+        };
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        rhs_wo_bias += dvel - target_vel;
+
+        ang_jac1 = body1.sqrt_ii * ang_jac1;
+        ang_jac2 = body2.sqrt_ii * ang_jac2;
+
+        JointVelocityConstraint {
+            joint_id,
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: N::zero(),
+            impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
+            lin_jac,
+            ang_jac1,
+            ang_jac2,
+            inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_coeff: motor_params.cfm_coeff,
+            cfm_gain: motor_params.cfm_gain,
+            rhs: rhs_wo_bias,
+            rhs_wo_bias,
+            writeback_id,
+            reaction_ang_jac: na::zero(),
+        }
+    }
+
     pub fn lock_linear<const LANES: usize>(
         &self,
         params: &IntegrationParameters,
@@ -272,6 +369,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
         }
     }
 
@@ -289,7 +387,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         let half = N::splat(0.5);
         let s_limits = [(limits[0] * half).simd_sin(), (limits[1] * half).simd_sin()];
         #[cfg(feature = "dim2")]
-        let s_ang = self.ang_err.im;
+        let s_ang = (self.ang_err.angle() * half).simd_sin();
         #[cfg(feature = "dim3")]
         let s_ang = self.ang_err.imag()[limited_axis];
         let min_enabled = s_ang.simd_lt(s_limits[0]);
@@ -333,6 +431,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
         }
     }
 
@@ -354,11 +453,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         let mut rhs_wo_bias = N::zero();
         if motor_params.erp_inv_dt != N::zero() {
             #[cfg(feature = "dim2")]
-            let s_ang_dist = self.ang_err.im;
+            let ang_dist = self.ang_err.angle();
             #[cfg(feature = "dim3")]
-            let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.simd_sin();
-            rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
+            let ang_dist = self.ang_err.imag()[_motor_axis].simd_asin() * N::splat(2.0);
+            let target_ang = motor_params.target_pos;
+            rhs_wo_bias +=
+                smallest_abs_diff_between_angles(ang_dist, target_ang) * motor_params.erp_inv_dt;
         }
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
@@ -384,6 +484,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
         }
     }
 
@@ -399,7 +500,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         #[cfg(feature = "dim2")]
         let ang_jac = self.ang_basis[locked_axis];
         #[cfg(feature = "dim3")]
-        let ang_jac = self.ang_basis.column(locked_axis).into_owned();
+        let ang_jac = self.locked_ang_jac(locked_axis);
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
         let rhs_wo_bias = dvel;
@@ -409,7 +510,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
-        let rhs_bias = self.ang_err.imag()[locked_axis] * erp_inv_dt;
+        let rhs_bias = self.locked_ang_err(locked_axis) * erp_inv_dt;
 
         let ang_jac1 = body1.sqrt_ii * ang_jac;
         let ang_jac2 = body2.sqrt_ii * ang_jac;
@@ -431,9 +532,29 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
         }
     }
 
+    /// The angular jacobian of the constraint locking the given angular axis.
+    ///
+    /// Flipping the sign of both the jacobian and the error of a locked axis doesn’t change the
+    /// constraint, so this undoes the flip made by `Self::new` to obtain the shortest rotation.
+    /// This keeps the sign of the impulse of this constraint the same when the relative rotation
+    /// of the frames crosses 180 degrees, so it can be used to warm-start the next timestep.
+    #[cfg(feature = "dim3")]
+    pub fn locked_ang_jac(&self, locked_axis: usize) -> AngVector<N> {
+        self.ang_basis.column(locked_axis) * self.ang_sign
+    }
+
+    /// The angular error of the constraint locking the given angular axis.
+    ///
+    /// See [`Self::locked_ang_jac`] for details about its sign.
+    #[cfg(feature = "dim3")]
+    pub fn locked_ang_err(&self, locked_axis: usize) -> N {
+        self.ang_err.imag()[locked_axis] * self.ang_sign
+    }
+
     /// Orthogonalize the constraints and set their inv_lhs field.
     pub fn finalize_constraints<const LANES: usize>(
         constraints: &mut [JointVelocityConstraint<N, LANES>],
@@ -477,6 +598,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                 c_i.ang_jac2 -= c_j.ang_jac2 * coeff;
                 c_i.rhs_wo_bias -= c_j.rhs_wo_bias * coeff;
                 c_i.rhs -= c_j.rhs * coeff;
+                c_i.reaction_ang_jac -= c_j.reaction_ang_jac * coeff;
             }
         }
     }
@@ -534,6 +656,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
         }
     }
 
@@ -543,18 +667,17 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         joint_id: [JointIndex; LANES],
         body1: &SolverBody<N, LANES>,
         body2: &SolverBody<N, LANES>,
-        limited_coupled_axes: u8,
-        limits: &[JointLimits<N>],
+        coupled_axes: u8,
+        limits: [N; 2],
         writeback_id: WritebackId,
     ) -> JointVelocityGroundConstraint<N, LANES> {
         let zero = N::zero();
         let mut lin_jac = Vector::zeros();
         let mut ang_jac1: AngVector<N> = na::zero();
         let mut ang_jac2: AngVector<N> = na::zero();
-        let mut limit = N::zero();
 
         for i in 0..DIM {
-            if limited_coupled_axes & (1 << i) != 0 {
+            if coupled_axes & (1 << i) != 0 {
                 let coeff = self.basis.column(i).dot(&self.lin_err);
                 lin_jac += self.basis.column(i) * coeff;
                 #[cfg(feature = "dim2")]
@@ -567,11 +690,9 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                     ang_jac1 += self.cmat1_basis.column(i) * coeff;
                     ang_jac2 += self.cmat2_basis.column(i) * coeff;
                 }
-                limit += limits[i].max * limits[i].max;
             }
         }
 
-        limit = limit.simd_sqrt();
         let dist = lin_jac.norm();
         let inv_dist = crate::utils::simd_inv(dist);
         lin_jac *= inv_dist;
@@ -580,15 +701,25 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
 
         let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
             + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
-        let rhs_wo_bias = dvel + (dist - limit).simd_min(zero) * N::splat(params.inv_dt());
+
+        // NOTE: the upper limit is handled speculatively (the constraint is always active to
+        //       prevent the distance from exceeding it within a single step), unless the lower
+        //       limit is currently violated.
+        let min_enabled = dist.simd_lt(limits[0]);
+        let max_rhs = (dist - limits[1]).simd_min(zero) * N::splat(params.inv_dt());
+        let rhs_wo_bias = dvel + zero.select(min_enabled, max_rhs);
 
         ang_jac2 = body2.sqrt_ii * ang_jac2;
 
         let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
         let cfm_coeff = N::splat(params.joint_cfm_coeff());
-        let rhs_bias = (dist - limit).simd_max(zero) * erp_inv_dt;
+        let rhs_bias =
+            ((dist - limits[1]).simd_max(zero) - (limits[0] - dist).simd_max(zero)) * erp_inv_dt;
         let rhs = rhs_wo_bias + rhs_bias;
-        let impulse_bounds = [N::zero(), N::splat(Real::INFINITY)];
+        let impulse_bounds = [
+            N::splat(-Real::INFINITY).select(min_enabled, zero),
+            zero.select(min_enabled, N::splat(Real::INFINITY)),
+        ];
 
         JointVelocityGroundConstraint {
             joint_id,
@@ -604,6 +735,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
         }
     }
 
@@ -660,79 +793,85 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
         }
     }
 
-    // pub fn motor_linear_coupled_ground<const LANES: usize>(
-    //     &self,
-    //     _joint_id: [JointIndex; LANES],
-    //     _body1: &SolverBody<N, LANES>,
-    //     _body2: &SolverBody<N, LANES>,
-    //     _motor_coupled_axes: u8,
-    //     _motors: &[MotorParameters<N>],
-    //     _limited_coupled_axes: u8,
-    //     _limits: &[JointLimits<N>],
-    //     _writeback_id: WritebackId,
-    // ) -> JointVelocityGroundConstraint<N, LANES> {
-    //     let zero = N::zero();
-    //     let mut lin_jac = Vector::zeros();
-    //     let mut ang_jac1: AngVector<N> = na::zero();
-    //     let mut ang_jac2: AngVector<N> = na::zero();
-    //     let mut limit = N::zero();
-
-    //     for i in 0..DIM {
-    //         if limited_coupled_axes & (1 << i) != 0 {
-    //             let coeff = self.basis.column(i).dot(&self.lin_err);
-    //             lin_jac += self.basis.column(i) * coeff;
-    //             #[cfg(feature = "dim2")]
-    //             {
-    //                 ang_jac1 += self.cmat1_basis[i] * coeff;
-    //                 ang_jac2 += self.cmat2_basis[i] * coeff;
-    //             }
-    //             #[cfg(feature = "dim3")]
-    //             {
-    //                 ang_jac1 += self.cmat1_basis.column(i) * coeff;
-    //                 ang_jac2 += self.cmat2_basis.column(i) * coeff;
-    //             }
-    //             limit += limits[i].max * limits[i].max;
-    //         }
-    //     }
-
-    //     limit = limit.simd_sqrt();
-    //     let dist = lin_jac.norm();
-    //     let inv_dist = crate::utils::simd_inv(dist);
-    //     lin_jac *= inv_dist;
-    //     ang_jac1 *= inv_dist;
-    //     ang_jac2 *= inv_dist;
-
-    //     let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
-    //         + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
-    //     let rhs_wo_bias = dvel + (dist - limit).simd_min(zero) * N::splat(params.inv_dt());
-
-    //     ang_jac2 = body2.sqrt_ii * ang_jac2;
-
-    //     let erp_inv_dt = N::splat(params.joint_erp_inv_dt());
-    //     let cfm_coeff = N::splat(params.joint_cfm_coeff());
-    //     let rhs_bias = (dist - limit).simd_max(zero) * erp_inv_dt;
-    //     let rhs = rhs_wo_bias + rhs_bias;
-    //     let impulse_bounds = [N::zero(), N::splat(Real::INFINITY)];
-
-    //     JointVelocityGroundConstraint {
-    //         joint_id,
-    //         mj_lambda2: body2.mj_lambda,
-    //         im2: body2.im,
-    //         impulse: N::zero(),
-    //         impulse_bounds,
-    //         lin_jac,
-    //         ang_jac2,
-    //         inv_lhs: N::zero(), // Will be set during ortogonalization.
-    //         cfm_coeff,
-    //         cfm_gain: N::zero(),
-    //         rhs,
-    //         rhs_wo_bias,
-    //         writeback_id,
-    //     }
-    // }
+    pub fn motor_linear_coupled_ground<const LANES: usize>(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; LANES],
+        body1: &SolverBody<N, LANES>,
+        body2: &SolverBody<N, LANES>,
+        coupled_axes: u8,
+        motor_params: &MotorParameters<N>,
+        limits: Option<[N; 2]>,
+        writeback_id: WritebackId,
+    ) -> JointVelocityGroundConstraint<N, LANES> {
+        let inv_dt = N::splat(params.inv_dt());
+        let mut lin_jac = Vector::zeros();
+        let mut ang_jac1: AngVector<N> = na::zero();
+        let mut ang_jac2: AngVector<N> = na::zero();
+
+        for i in 0..DIM {
+            if coupled_axes & (1 << i) != 0 {
+                let coeff = self.basis.column(i).dot(&self.lin_err);
+                lin_jac += self.basis.column(i) * coeff;
+                #[cfg(feature = "dim2")]
+                {
+                    ang_jac1 += self.cmat1_basis[i] * coeff;
+                    ang_jac2 += self.cmat2_basis[i] * coeff;
+                }
+                #[cfg(feature = "dim3")]
+                {
+                    ang_jac1 += self.cmat1_basis.column(i) * coeff;
+                    ang_jac2 += self.cmat2_basis.column(i) * coeff;
+                }
+            }
+        }
+
+        let dist = lin_jac.norm();
+        let inv_dist = crate::utils::simd_inv(dist);
+        lin_jac *= inv_dist;
+        ang_jac1 *= inv_dist;
+        ang_jac2 *= inv_dist;
+
+        let mut rhs_wo_bias = N::zero();
+        if motor_params.erp_inv_dt != N::zero() {
+            rhs_wo_bias += (dist - motor_params.target_pos) * motor_params.erp_inv_dt;
+        }
+
+        let mut target_vel = motor_params.target_vel;
+        if let Some(limits) = limits {
+            target_vel =
+                target_vel.simd_clamp((limits[0] - dist) * inv_dt, (limits[1] - dist) * inv_dt);
+        };
+
+        let dvel = lin_jac.dot(&(body2.linvel - body1.linvel))
+            + (ang_jac2.gdot(body2.angvel) - ang_jac1.gdot(body1.angvel));
+        rhs_wo_bias += dvel - target_vel;
+
+        ang_jac2 = body2.sqrt_ii * ang_jac2;
+
+        JointVelocityGroundConstraint {
+            joint_id,
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: N::zero(),
+            impulse_bounds: [-motor_params.max_impulse, motor_params.max_impulse],
+            lin_jac,
+            ang_jac2,
+            inv_lhs: N::zero(), // Will be set during ortogonalization.
+            cfm_coeff: motor_params.cfm_coeff,
+            cfm_gain: motor_params.cfm_gain,
+            rhs: rhs_wo_bias,
+            rhs_wo_bias,
+            writeback_id,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
+        }
+    }
 
     pub fn lock_linear_ground<const LANES: usize>(
         &self,
@@ -774,6 +913,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: N::one(),
         }
     }
 
@@ -795,11 +936,12 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         let mut rhs_wo_bias = N::zero();
         if motor_params.erp_inv_dt != N::zero() {
             #[cfg(feature = "dim2")]
-            let s_ang_dist = self.ang_err.im;
+            let ang_dist = self.ang_err.angle();
             #[cfg(feature = "dim3")]
-            let s_ang_dist = self.ang_err.imag()[_motor_axis];
-            let s_target_ang = motor_params.target_pos.simd_sin();
-            rhs_wo_bias += (s_ang_dist - s_target_ang) * motor_params.erp_inv_dt;
+            let ang_dist = self.ang_err.imag()[_motor_axis].simd_asin() * N::splat(2.0);
+            let target_ang = motor_params.target_pos;
+            rhs_wo_bias +=
+                smallest_abs_diff_between_angles(ang_dist, target_ang) * motor_params.erp_inv_dt;
         }
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
@@ -821,6 +963,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
+            reaction_sign: N::one(),
         }
     }
 
@@ -838,7 +982,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         let half = N::splat(0.5);
         let s_limits = [(limits[0] * half).simd_sin(), (limits[1] * half).simd_sin()];
         #[cfg(feature = "dim2")]
-        let s_ang = self.ang_err.im;
+        let s_ang = (self.ang_err.angle() * half).simd_sin();
         #[cfg(feature = "dim3")]
         let s_ang = self.ang_err.imag()[limited_axis];
         let min_enabled = s_ang.simd_lt(s_limits[0]);
@@ -878,6 +1022,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
+            reaction_sign: N::one(),
         }
     }
 
@@ -893,7 +1039,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         #[cfg(feature = "dim2")]
         let ang_jac = self.ang_basis[locked_axis];
         #[cfg(feature = "dim3")]
-        let ang_jac = self.ang_basis.column(locked_axis).into_owned();
+        let ang_jac = self.locked_ang_jac(locked_axis);
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
         let rhs_wo_bias = dvel;
 
@@ -902,7 +1048,7 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
         #[cfg(feature = "dim2")]
         let rhs_bias = self.ang_err.im * erp_inv_dt;
         #[cfg(feature = "dim3")]
-        let rhs_bias = self.ang_err.imag()[locked_axis] * erp_inv_dt;
+        let rhs_bias = self.locked_ang_err(locked_axis) * erp_inv_dt;
 
         let ang_jac2 = body2.sqrt_ii * ang_jac;
 
@@ -920,6 +1066,8 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
+            reaction_sign: N::one(),
         }
     }
 
@@ -965,25 +1113,24 @@ impl<N: WReal> JointVelocityConstraintBuilder<N> {
                 c_i.ang_jac2 -= c_j.ang_jac2 * coeff;
                 c_i.rhs_wo_bias -= c_j.rhs_wo_bias * coeff;
                 c_i.rhs -= c_j.rhs * coeff;
+                c_i.reaction_ang_jac -= c_j.reaction_ang_jac * coeff;
             }
         }
     }
 }
 
 impl JointVelocityConstraintBuilder<Real> {
-    // TODO: this method is almost identical to the ground version, except for the
-    //       return type. Could they share their implementation somehow?
+    /// The jacobian, impulse bounds, and error of the limit of the angle between the axis not
+    /// coupled by `limited_coupled_axes` on both frames, i.e., of a cone limit around this axis.
+    ///
+    /// The limit angles of this cone are the norms of the limits of the coupled axes. A minimum
+    /// angle of zero is ignored, since the angle between both axes can’t be negative.
     #[cfg(feature = "dim3")]
-    pub fn limit_angular_coupled(
+    pub fn coupled_angular_limit(
         &self,
-        params: &IntegrationParameters,
-        joint_id: [JointIndex; 1],
-        body1: &SolverBody<Real, 1>,
-        body2: &SolverBody<Real, 1>,
         limited_coupled_axes: u8,
         limits: &[JointLimits<Real>],
-        writeback_id: WritebackId,
-    ) -> JointVelocityConstraint<Real, 1> {
+    ) -> (Vector<Real>, [Real; 2], Real) {
         // NOTE: right now, this only supports exactly 2 coupled axes.
         let ang_coupled_axes = limited_coupled_axes >> DIM;
         assert_eq!(ang_coupled_axes.count_ones(), 2);
@@ -1009,21 +1156,40 @@ impl JointVelocityConstraintBuilder<Real> {
         ang_limits[0] = ang_limits[0].sqrt();
         ang_limits[1] = ang_limits[1].sqrt();
 
-        let min_enabled = angle <= ang_limits[0];
+        let min_enabled = ang_limits[0] > 0.0 && angle <= ang_limits[0];
         let max_enabled = ang_limits[1] <= angle;
 
         let impulse_bounds = [
             if min_enabled { -Real::INFINITY } else { 0.0 },
             if max_enabled { Real::INFINITY } else { 0.0 },
         ];
+        let limit_err = (angle - ang_limits[1]).max(0.0) - (ang_limits[0] - angle).max(0.0);
+
+        (ang_jac, impulse_bounds, limit_err)
+    }
+
+    // TODO: this method is almost identical to the ground version, except for the
+    //       return type. Could they share their implementation somehow?
+    #[cfg(feature = "dim3")]
+    pub fn limit_angular_coupled(
+        &self,
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; 1],
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        limited_coupled_axes: u8,
+        limits: &[JointLimits<Real>],
+        writeback_id: WritebackId,
+    ) -> JointVelocityConstraint<Real, 1> {
+        let (ang_jac, impulse_bounds, limit_err) =
+            self.coupled_angular_limit(limited_coupled_axes, limits);
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
         let rhs_wo_bias = dvel;
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         let cfm_coeff = params.joint_cfm_coeff();
-        let rhs_bias =
-            ((angle - ang_limits[1]).max(0.0) - (ang_limits[0] - angle).max(0.0)) * erp_inv_dt;
+        let rhs_bias = limit_err * erp_inv_dt;
 
         let ang_jac1 = body1.sqrt_ii * ang_jac;
         let ang_jac2 = body2.sqrt_ii * ang_jac;
@@ -1045,6 +1211,7 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
         }
     }
 
@@ -1059,46 +1226,15 @@ impl JointVelocityConstraintBuilder<Real> {
         limits: &[JointLimits<Real>],
         writeback_id: WritebackId,
     ) -> JointVelocityGroundConstraint<Real, 1> {
-        // NOTE: right now, this only supports exactly 2 coupled axes.
-        let ang_coupled_axes = limited_coupled_axes >> DIM;
-        assert_eq!(ang_coupled_axes.count_ones(), 2);
-        let not_coupled_index = ang_coupled_axes.trailing_ones() as usize;
-        let axis1 = self.basis.column(not_coupled_index).into_owned();
-        let axis2 = self.basis2.column(not_coupled_index).into_owned();
-
-        let rot = Rotation::rotation_between(&axis1, &axis2).unwrap_or_else(Rotation::identity);
-        let (ang_jac, angle) = rot
-            .axis_angle()
-            .map(|(axis, angle)| (axis.into_inner(), angle))
-            .unwrap_or_else(|| (axis1.orthonormal_basis()[0], 0.0));
-        let mut ang_limits = [0.0, 0.0];
-
-        for k in 0..3 {
-            if (ang_coupled_axes & (1 << k)) != 0 {
-                let limit = &limits[DIM + k];
-                ang_limits[0] += limit.min * limit.min;
-                ang_limits[1] += limit.max * limit.max;
-            }
-        }
-
-        ang_limits[0] = ang_limits[0].sqrt();
-        ang_limits[1] = ang_limits[1].sqrt();
-
-        let min_enabled = angle <= ang_limits[0];
-        let max_enabled = ang_limits[1] <= angle;
-
-        let impulse_bounds = [
-            if min_enabled { -Real::INFINITY } else { 0.0 },
-            if max_enabled { Real::INFINITY } else { 0.0 },
-        ];
+        let (ang_jac, impulse_bounds, limit_err) =
+            self.coupled_angular_limit(limited_coupled_axes, limits);
 
         let dvel = ang_jac.gdot(body2.angvel) - ang_jac.gdot(body1.angvel);
         let rhs_wo_bias = dvel;
 
         let erp_inv_dt = params.joint_erp_inv_dt();
         let cfm_coeff = params.joint_cfm_coeff();
-        let rhs_bias =
-            ((angle - ang_limits[1]).max(0.0) - (ang_limits[0] - angle).max(0.0)) * erp_inv_dt;
+        let rhs_bias = limit_err * erp_inv_dt;
 
         let ang_jac2 = body2.sqrt_ii * ang_jac;
 
@@ -1116,6 +1252,154 @@ impl JointVelocityConstraintBuilder<Real> {
             rhs: rhs_wo_bias + rhs_bias,
             rhs_wo_bias,
             writeback_id,
+            reaction_ang_jac: ang_jac,
+            reaction_sign: 1.0,
+        }
+    }
+
+    pub fn coupling(
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; 1],
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        jac: &CouplingJacobians,
+    ) -> JointVelocityConstraint<Real, 1> {
+        // NOTE: non-ground constraints share the same linear jacobian for both bodies.
+        //       Couplings that don’t satisfy this (e.g. pulleys) must use the generic
+        //       constraints instead.
+        debug_assert_eq!(jac.lin_jac1, jac.lin_jac2);
+        let rhs_wo_bias = jac.velocity(body1, body2);
+        let rhs_bias = jac.error * params.joint_erp_inv_dt();
+
+        JointVelocityConstraint {
+            joint_id,
+            mj_lambda1: body1.mj_lambda,
+            mj_lambda2: body2.mj_lambda,
+            im1: body1.im,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds: [-Real::MAX, Real::MAX],
+            lin_jac: jac.lin_jac2,
+            ang_jac1: body1.sqrt_ii * jac.ang_jac1,
+            ang_jac2: body2.sqrt_ii * jac.ang_jac2,
+            inv_lhs: 0.0, // Will be set during ortogonalization.
+            cfm_coeff: params.joint_cfm_coeff(),
+            cfm_gain: 0.0,
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id: WritebackId::Coupling,
+            reaction_ang_jac: na::zero(),
+        }
+    }
+
+    pub fn coupling_ground(
+        params: &IntegrationParameters,
+        joint_id: [JointIndex; 1],
+        body1: &SolverBody<Real, 1>,
+        body2: &SolverBody<Real, 1>,
+        jac: &CouplingJacobians,
+    ) -> JointVelocityGroundConstraint<Real, 1> {
+        let rhs_wo_bias = jac.velocity(body1, body2);
+        let rhs_bias = jac.error * params.joint_erp_inv_dt();
+
+        JointVelocityGroundConstraint {
+            joint_id,
+            mj_lambda2: body2.mj_lambda,
+            im2: body2.im,
+            impulse: 0.0,
+            impulse_bounds: [-Real::MAX, Real::MAX],
+            lin_jac: jac.lin_jac2,
+            ang_jac2: body2.sqrt_ii * jac.ang_jac2,
+            inv_lhs: 0.0, // Will be set during ortogonalization.
+            cfm_coeff: params.joint_cfm_coeff(),
+            cfm_gain: 0.0,
+            rhs: rhs_wo_bias + rhs_bias,
+            rhs_wo_bias,
+            writeback_id: WritebackId::Coupling,
+            reaction_ang_jac: na::zero(),
+            reaction_sign: 1.0,
+        }
+    }
+}
+
+/// The jacobians of a gear or pulley coupling.
+///
+/// The velocity of the coupling is `lin_jac2·linvel2 + ang_jac2·angvel2 - lin_jac1·linvel1 - ang_jac1·angvel1`.
+#[derive(Debug, Copy, Clone)]
+pub struct CouplingJacobians {
+    pub lin_jac1: Vector<Real>,
+    pub ang_jac1: AngVector<Real>,
+    pub lin_jac2: Vector<Real>,
+    pub ang_jac2: AngVector<Real>,
+    pub error: Real,
+}
+
+impl CouplingJacobians {
+    pub fn new(
+        coupling: &JointCoupling,
+        frame1: &Isometry<Real>,
+        frame2: &Isometry<Real>,
+        world_com1: &Point<Real>,
+        world_com2: &Point<Real>,
+    ) -> Self {
+        match *coupling {
+            JointCoupling::Gear { ratio } => {
+                #[cfg(feature = "dim2")]
+                let (axis1, axis2) = (1.0, 1.0);
+                #[cfg(feature = "dim3")]
+                let (axis1, axis2) = (frame1 * Vector::x(), frame2 * Vector::x());
+
+                Self {
+                    lin_jac1: na::zero(),
+                    ang_jac1: -axis1,
+                    lin_jac2: na::zero(),
+                    ang_jac2: axis2 * ratio,
+                    // NOTE: gears don’t track any position, so their drift isn’t corrected.
+                    error: 0.0,
+                }
+            }
+            JointCoupling::Pulley {
+                ground_anchor1,
+                ground_anchor2,
+                ratio,
+                length,
+            } => {
+                let anchor1 = Point::from(frame1.translation.vector);
+                let anchor2 = Point::from(frame2.translation.vector);
+                let (dir1, length1) = Unit::try_new_and_get(anchor1 - ground_anchor1, 0.0)
+                    .map(|(dir, length)| (dir.into_inner(), length))
+                    .unwrap_or((Vector::zeros(), 0.0));
+                let (dir2, length2) = Unit::try_new_and_get(anchor2 - ground_anchor2, 0.0)
+                    .map(|(dir, length)| (dir.into_inner(), length))
+                    .unwrap_or((Vector::zeros(), 0.0));
+                let r1 = anchor1 - world_com1;
+                let r2 = anchor2 - world_com2;
+
+                Self {
+                    lin_jac1: -dir1,
+                    ang_jac1: -r1.gcross(dir1),
+                    lin_jac2: dir2 * ratio,
+                    ang_jac2: r2.gcross(dir2) * ratio,
+                    error: length1 + length2 * ratio - length,
+                }
+            }
         }
     }
+
+    /// The same coupling, but with the role of both bodies swapped.
+    pub fn flipped(self) -> Self {
+        Self {
+            lin_jac1: -self.lin_jac2,
+            ang_jac1: -self.ang_jac2,
+            lin_jac2: -self.lin_jac1,
+            ang_jac2: -self.ang_jac1,
+            error: self.error,
+        }
+    }
+
+    pub fn velocity(&self, body1: &SolverBody<Real, 1>, body2: &SolverBody<Real, 1>) -> Real {
+        self.lin_jac2.dot(&body2.linvel) + self.ang_jac2.gdot(body2.angvel)
+            - self.lin_jac1.dot(&body1.linvel)
+            - self.ang_jac1.gdot(body1.angvel)
+    }
 }
@@ -1,4 +1,3 @@
-#[cfg(not(feature = "parallel"))]
 pub(crate) use self::island_solver::IslandSolver;
 #[cfg(feature = "parallel")]
 pub(crate) use self::parallel_island_solver::{ParallelIslandSolver, ThreadContext};
@@ -6,9 +5,8 @@ pub(crate) use self::parallel_island_solver::{ParallelIslandSolver, ThreadContex
 pub(self) use self::parallel_solver_constraints::ParallelSolverConstraints;
 #[cfg(feature = "parallel")]
 pub(self) use self::parallel_velocity_solver::ParallelVelocitySolver;
-#[cfg(not(feature = "parallel"))]
+pub(self) use self::shock_propagation::ShockPropagation;
 pub(self) use self::solver_constraints::SolverConstraints;
-#[cfg(not(feature = "parallel"))]
 pub(self) use self::velocity_solver::VelocitySolver;
 pub(self) use delta_vel::DeltaVel;
 pub(self) use generic_velocity_constraint::*;
@@ -33,7 +31,6 @@ mod generic_velocity_constraint_element;
 mod generic_velocity_ground_constraint;
 mod generic_velocity_ground_constraint_element;
 mod interaction_groups;
-#[cfg(not(feature = "parallel"))]
 mod island_solver;
 mod joint_constraint;
 #[cfg(feature = "parallel")]
@@ -42,7 +39,7 @@ mod parallel_island_solver;
 mod parallel_solver_constraints;
 #[cfg(feature = "parallel")]
 mod parallel_velocity_solver;
-#[cfg(not(feature = "parallel"))]
+mod shock_propagation;
 mod solver_constraints;
 mod velocity_constraint;
 mod velocity_constraint_element;
@@ -52,5 +49,4 @@ mod velocity_ground_constraint;
 mod velocity_ground_constraint_element;
 #[cfg(feature = "simd-is-enabled")]
 mod velocity_ground_constraint_wide;
-#[cfg(not(feature = "parallel"))]
 mod velocity_solver;
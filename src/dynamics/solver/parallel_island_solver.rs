@@ -10,6 +10,7 @@ use crate::dynamics::{
     RigidBodySet,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::Real;
 use na::DVector;
 
 use super::{DeltaVel, ParallelInteractionGroups, ParallelVelocitySolver};
@@ -96,6 +97,9 @@ pub(crate) struct ThreadContext {
     pub num_force_integrated_bodies: AtomicUsize,
     pub num_integrated_pos_bodies: AtomicUsize,
     pub num_integrated_vel_bodies: AtomicUsize,
+    pub velocity_error_reference_index: AtomicUsize,
+    pub velocity_error_index: AtomicUsize,
+    pub num_velocity_error_measures: AtomicUsize,
 }
 
 impl ThreadContext {
@@ -118,16 +122,19 @@ impl ThreadContext {
             body_integration_vel_index: AtomicUsize::new(0),
             num_integrated_pos_bodies: AtomicUsize::new(0),
             num_integrated_vel_bodies: AtomicUsize::new(0),
+            velocity_error_reference_index: AtomicUsize::new(0),
+            velocity_error_index: AtomicUsize::new(0),
+            num_velocity_error_measures: AtomicUsize::new(0),
         }
     }
 
     pub fn lock_until_ge(val: &AtomicUsize, target: usize) {
         if target > 0 {
-            //        let backoff = crossbeam::utils::Backoff::new();
             std::sync::atomic::fence(Ordering::SeqCst);
             while val.load(Ordering::Relaxed) < target {
-                //  backoff.spin();
-                // std::thread::yield_now();
+                // Yield so that we don't starve the threads we are waiting for when there
+                // are more threads than cores.
+                std::thread::yield_now();
             }
         }
     }
@@ -172,6 +179,7 @@ impl ParallelIslandSolver {
         impulse_joints: &'s mut Vec<JointGraphEdge>,
         joint_indices: &[JointIndex],
         multibodies: &mut MultibodyJointSet,
+        measure_velocity_error: bool,
     ) {
         let num_threads = rayon::current_num_threads();
         let num_task_per_island = num_threads; // (num_threads / num_islands).max(1); // TODO: not sure this is the best value. Also, perhaps it is better to interleave tasks of each island?
@@ -253,6 +261,7 @@ impl ParallelIslandSolver {
                 self.velocity_solver.generic_mj_lambdas.fill(0.0);
             }
 
+            self.velocity_solver.velocity_error = 0.0;
             self.velocity_solver.mj_lambdas.clear();
             self.velocity_solver
                 .mj_lambdas
@@ -354,8 +363,22 @@ impl ParallelIslandSolver {
                         impulse_joints,
                         parallel_contact_constraints,
                         parallel_joint_constraints,
+                        measure_velocity_error,
                 );
             })
         }
     }
+
+    /// The number of contact and joint constraints generated by the last call to
+    /// `init_and_solve`.
+    pub fn num_constraints(&self) -> usize {
+        self.parallel_contact_constraints.velocity_constraints.len()
+            + self.parallel_joint_constraints.velocity_constraints.len()
+    }
+
+    /// The change of the delta-velocities during the last velocity iteration of the last call to
+    /// `init_and_solve`. Only measured if requested by `init_and_solve`.
+    pub fn velocity_error(&self) -> Real {
+        self.velocity_solver.velocity_error
+    }
 }
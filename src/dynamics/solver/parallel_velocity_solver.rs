@@ -14,6 +14,12 @@ use std::sync::atomic::Ordering;
 pub(crate) struct ParallelVelocitySolver {
     pub mj_lambdas: Vec<DeltaVel<Real>>,
     pub generic_mj_lambdas: DVector<Real>,
+    // The change of the delta-velocities during the last velocity iteration of the last call
+    // to `solve`. Only measured if requested.
+    pub velocity_error: Real,
+    // The delta-velocities at the beginning of the last velocity iteration.
+    prev_mj_lambdas: Vec<DeltaVel<Real>>,
+    prev_generic_mj_lambdas: DVector<Real>,
 }
 
 impl ParallelVelocitySolver {
@@ -21,9 +27,30 @@ impl ParallelVelocitySolver {
         Self {
             mj_lambdas: Vec::new(),
             generic_mj_lambdas: DVector::zeros(0),
+            velocity_error: 0.0,
+            prev_mj_lambdas: Vec::new(),
+            prev_generic_mj_lambdas: DVector::zeros(0),
         }
     }
 
+    // The largest change of the delta-velocities since they were copied to `prev_mj_lambdas`.
+    fn delta_vel_change(&self) -> Real {
+        let mut max_change: Real = 0.0;
+
+        for (curr, prev) in self.mj_lambdas.iter().zip(self.prev_mj_lambdas.iter()) {
+            for change in (*curr - *prev).as_slice() {
+                max_change = max_change.max(change.abs());
+            }
+        }
+
+        if !self.generic_mj_lambdas.is_empty() {
+            max_change =
+                max_change.max((&self.generic_mj_lambdas - &self.prev_generic_mj_lambdas).amax());
+        }
+
+        max_change
+    }
+
     pub fn solve(
         &mut self,
         thread: &ThreadContext,
@@ -36,6 +63,7 @@ impl ParallelVelocitySolver {
         joints_all: &mut [JointGraphEdge],
         contact_constraints: &mut ParallelSolverConstraints<AnyVelocityConstraint>,
         joint_constraints: &mut ParallelSolverConstraints<AnyJointVelocityConstraint>,
+        measure_velocity_error: bool,
     ) {
         let mut start_index = thread
             .solve_interaction_index
@@ -52,6 +80,9 @@ impl ParallelVelocitySolver {
         // before starting the next one.
         macro_rules! solve {
             ($part: expr, $($solve_args: expr),*) => {
+                solve!(@call solve, $part, $($solve_args),*)
+            };
+            (@call $method: ident, $part: expr, $($solve_args: expr),*) => {
                 for group in $part.parallel_desc_groups.windows(2) {
                     let num_descs_in_group = group[1] - group[0];
                     target_num_desc += num_descs_in_group;
@@ -70,7 +101,7 @@ impl ParallelVelocitySolver {
                         };
 
                         for constraint in constraints {
-                            constraint.solve(
+                            constraint.$method(
                                 $($solve_args),*
                             );
                         }
@@ -100,11 +131,53 @@ impl ParallelVelocitySolver {
             };
         }
 
+        /*
+         * Warmstart constraints.
+         */
+        if params.joint_warmstart_coefficient != 0.0 {
+            solve!(
+                @call warmstart,
+                joint_constraints,
+                &joint_constraints.generic_jacobians,
+                &mut self.mj_lambdas,
+                &mut self.generic_mj_lambdas
+            );
+            shift += joint_descs.len();
+            start_index -= joint_descs.len();
+        }
+
+        if params.warmstart_coefficient != 0.0 {
+            solve!(@call warmstart, contact_constraints, &mut self.mj_lambdas);
+            shift += contact_descs.len();
+            start_index -= contact_descs.len();
+        }
+
         /*
          * Solve constraints.
          */
         {
             for i in 0..params.max_velocity_iterations {
+                let measure_error =
+                    measure_velocity_error && i + 1 == params.max_velocity_iterations;
+
+                // NOTE: the velocity error is measured by a single thread, while the others wait
+                //       for it so that the delta-velocities aren’t modified meanwhile.
+                if measure_error {
+                    if thread
+                        .velocity_error_reference_index
+                        .fetch_add(1, Ordering::SeqCst)
+                        == 0
+                    {
+                        self.prev_mj_lambdas.clone_from(&self.mj_lambdas);
+                        self.prev_generic_mj_lambdas
+                            .clone_from(&self.generic_mj_lambdas);
+                        thread
+                            .num_velocity_error_measures
+                            .fetch_add(1, Ordering::SeqCst);
+                    }
+                    ThreadContext::lock_until_ge(&thread.num_velocity_error_measures, 1);
+                }
+
                 let solve_friction = params.interleave_restitution_and_friction_resolution
                     && params.max_velocity_friction_iterations + i
                         >= params.max_velocity_iterations;
@@ -154,6 +227,16 @@ impl ParallelVelocitySolver {
                     shift += contact_descs.len();
                     start_index -= contact_descs.len();
                 }
+
+                if measure_error {
+                    if thread.velocity_error_index.fetch_add(1, Ordering::SeqCst) == 0 {
+                        self.velocity_error = self.delta_vel_change();
+                        thread
+                            .num_velocity_error_measures
+                            .fetch_add(1, Ordering::SeqCst);
+                    }
+                    ThreadContext::lock_until_ge(&thread.num_velocity_error_measures, 2);
+                }
             }
 
             // Solve the remaining friction iterations.
@@ -220,6 +303,7 @@ impl ParallelVelocitySolver {
                             &rb.pos.position,
                             &rb.mprops.local_mprops.local_com,
                         );
+                        rb.integrated_vels = new_vels;
                     }
                 }
             }
@@ -0,0 +1,275 @@
+use super::DeltaVel;
+use crate::dynamics::{
+    IntegrationParameters, IslandManager, MultibodyJointSet, RigidBodyHandle, RigidBodySet,
+};
+use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::{Real, Vector};
+use crate::utils::{self, WAngularInertia, WCross, WDot};
+use std::collections::VecDeque;
+
+/// A single pass correcting the contacts of an island from the bottom up, where the lower body of
+/// each contact is treated as if it had an infinite mass.
+///
+/// The depth of a dynamic rigid-body is the smallest number of contacts separating it from a
+/// fixed or kinematic body (or from a collider without parent). The contacts are processed by
+/// increasing depth of their lower body, and each contact only pushes its upper body away, so
+/// the corrections of the bottom of a stack propagate to its top in a single pass.
+pub(crate) struct ShockPropagation {
+    // The depth of each dynamic body of the island, indexed by active-set offset.
+    depths: Vec<u32>,
+    // The contact graph between the dynamic bodies of the island, in compressed sparse rows.
+    neighbor_offsets: Vec<usize>,
+    neighbors: Vec<usize>,
+    queue: VecDeque<usize>,
+    // The manifolds to process, with the depth of their lower body.
+    sorted_manifolds: Vec<(u32, ContactManifoldIndex)>,
+}
+
+impl ShockPropagation {
+    pub fn new() -> Self {
+        Self {
+            depths: vec![],
+            neighbor_offsets: vec![],
+            neighbors: vec![],
+            queue: VecDeque::new(),
+            sorted_manifolds: vec![],
+        }
+    }
+
+    pub fn solve(
+        &mut self,
+        island_id: usize,
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        multibodies: &MultibodyJointSet,
+        manifolds_all: &[&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
+        mj_lambdas: &mut [DeltaVel<Real>],
+    ) {
+        // The active-set offset of a rigid-body, or `None` if it isn’t dynamic.
+        let node = |handle: Option<RigidBodyHandle>| {
+            let rb = &bodies[handle?];
+            rb.is_dynamic().then_some(rb.ids.active_set_offset)
+        };
+        // NOTE: the contacts involving multibodies are solved by generic constraints, which
+        //       don’t participate to the shock propagation.
+        let is_multibody_link = |handle: Option<RigidBodyHandle>| {
+            handle.is_some_and(|h| multibodies.rigid_body_link(h).is_some())
+        };
+        let candidate_manifolds = manifold_indices.iter().copied().filter(|id| {
+            let data = &manifolds_all[*id].data;
+            !is_multibody_link(data.rigid_body1) && !is_multibody_link(data.rigid_body2)
+        });
+
+        /*
+         * Compute the depth of each body with a breadth-first traversal of the contact graph.
+         */
+        let num_bodies = islands.active_island(island_id).len();
+        self.depths.clear();
+        self.depths.resize(num_bodies, u32::MAX);
+        self.neighbor_offsets.clear();
+        self.neighbor_offsets.resize(num_bodies + 1, 0);
+        self.queue.clear();
+
+        for id in candidate_manifolds.clone() {
+            let data = &manifolds_all[id].data;
+            match (node(data.rigid_body1), node(data.rigid_body2)) {
+                (Some(node1), Some(node2)) => {
+                    self.neighbor_offsets[node1 + 1] += 1;
+                    self.neighbor_offsets[node2 + 1] += 1;
+                }
+                (Some(node), None) | (None, Some(node)) => {
+                    if self.depths[node] != 1 {
+                        self.depths[node] = 1;
+                        self.queue.push_back(node);
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        for i in 0..num_bodies {
+            self.neighbor_offsets[i + 1] += self.neighbor_offsets[i];
+        }
+
+        self.neighbors.clear();
+        self.neighbors.resize(self.neighbor_offsets[num_bodies], 0);
+        // NOTE: we use the offsets of the next body as insertion cursors, so they end up
+        //       being the offsets of the current body after the insertions.
+        for id in candidate_manifolds.clone() {
+            let data = &manifolds_all[id].data;
+            if let (Some(node1), Some(node2)) = (node(data.rigid_body1), node(data.rigid_body2)) {
+                self.neighbors[self.neighbor_offsets[node1 + 1] - 1] = node2;
+                self.neighbor_offsets[node1 + 1] -= 1;
+                self.neighbors[self.neighbor_offsets[node2 + 1] - 1] = node1;
+                self.neighbor_offsets[node2 + 1] -= 1;
+            }
+        }
+        for i in 0..num_bodies {
+            self.neighbor_offsets[i] = self.neighbor_offsets[i + 1];
+        }
+        self.neighbor_offsets[num_bodies] = self.neighbors.len();
+
+        while let Some(node) = self.queue.pop_front() {
+            let depth = self.depths[node] + 1;
+            for neighbor in
+                &self.neighbors[self.neighbor_offsets[node]..self.neighbor_offsets[node + 1]]
+            {
+                if self.depths[*neighbor] > depth {
+                    self.depths[*neighbor] = depth;
+                    self.queue.push_back(*neighbor);
+                }
+            }
+        }
+
+        /*
+         * Sort the manifolds by increasing depth of their lower body.
+         */
+        let depth = |node: Option<usize>| node.map_or(0, |node| self.depths[node]);
+        self.sorted_manifolds.clear();
+        self.sorted_manifolds.extend(candidate_manifolds.map(|id| {
+            let data = &manifolds_all[id].data;
+            let depth1 = depth(node(data.rigid_body1));
+            let depth2 = depth(node(data.rigid_body2));
+            (depth1.min(depth2), id)
+        }));
+        // NOTE: the sort is stable so the order of the manifolds with the same depth, and
+        //       thus the result of this pass, is deterministic.
+        self.sorted_manifolds.sort_by_key(|(depth, _)| *depth);
+
+        /*
+         * Push the upper body of each contact away from its lower body.
+         */
+        let inv_dt = params.inv_dt();
+        let erp_inv_dt = params.erp_inv_dt();
+
+        for (_, id) in &self.sorted_manifolds {
+            let data = &manifolds_all[*id].data;
+            let (node1, node2) = (node(data.rigid_body1), node(data.rigid_body2));
+
+            // The dominant body, or the lower one, is the one that is frozen.
+            let body1_is_lower = if data.relative_dominance != 0 {
+                data.relative_dominance > 0
+            } else {
+                let depth1 = depth(node1);
+                let depth2 = depth(node2);
+                if depth1 == depth2 {
+                    // The bodies side by side aren’t part of a stack.
+                    continue;
+                }
+                depth1 < depth2
+            };
+
+            let (lower, upper, upper_node, push_dir) = if body1_is_lower {
+                (data.rigid_body1, data.rigid_body2, node2, data.normal)
+            } else {
+                (data.rigid_body2, data.rigid_body1, node1, -data.normal)
+            };
+            let upper_node = match upper_node {
+                Some(node) => node,
+                None => continue,
+            };
+            let upper = &bodies[upper.unwrap()];
+            let lower = lower.map(|handle| &bodies[handle]);
+
+            for contact in &data.solver_contacts {
+                // Soft contacts are meant to penetrate, so they aren’t corrected.
+                if contact.stiffness > 0.0 {
+                    continue;
+                }
+
+                let target_vel = -(contact.dist.max(0.0) * inv_dt
+                    + erp_inv_dt
+                        * (contact.dist + params.allowed_linear_error)
+                            .clamp(-params.max_penetration_correction, 0.0));
+
+                let lower_vel = lower.map_or(0.0, |rb| {
+                    let dp = contact.point - rb.mprops.world_com;
+                    let mut vel = push_dir.dot(&(rb.vels.linvel + rb.vels.angvel.gcross(dp)));
+
+                    if rb.is_dynamic() {
+                        let dvel = &mj_lambdas[rb.ids.active_set_offset];
+                        let gcross = rb
+                            .mprops
+                            .effective_world_inv_inertia_sqrt
+                            .transform_vector(dp.gcross(push_dir));
+                        vel += push_dir.dot(&dvel.linear) + gcross.gdot(dvel.angular);
+                    }
+
+                    vel
+                });
+
+                let dp = contact.point - upper.mprops.world_com;
+                let im: Vector<Real> = upper.mprops.effective_inv_mass;
+                let gcross = upper
+                    .mprops
+                    .effective_world_inv_inertia_sqrt
+                    .transform_vector(dp.gcross(push_dir));
+                let dvel = &mut mj_lambdas[upper_node];
+                let upper_vel = push_dir.dot(&(upper.vels.linvel + upper.vels.angvel.gcross(dp)))
+                    + push_dir.dot(&dvel.linear)
+                    + gcross.gdot(dvel.angular);
+
+                let vel_error = target_vel - (upper_vel - lower_vel);
+                if vel_error > 0.0 {
+                    let impulse = vel_error
+                        * utils::inv(
+                            push_dir.dot(&im.component_mul(&push_dir)) + gcross.gdot(gcross),
+                        );
+                    dvel.linear += push_dir.component_mul(&im) * impulse;
+                    dvel.angular += gcross * impulse;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn shock_propagation_stabilizes_stacks_with_few_iterations() {
+        use crate::math::Real;
+        use crate::pipeline::PhysicsState;
+
+        let top_height = |shock_propagation: bool| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.integration_parameters.shock_propagation = shock_propagation;
+            state.integration_parameters.max_velocity_iterations = 1;
+            state.integration_parameters.warmstart_coefficient = 0.0;
+
+            let ground = cuboid(100.0, 0.5);
+            state.colliders.insert(ground);
+
+            let mut top = None;
+            for i in 0..12 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::y() * (1.0 + i as Real))
+                    .build();
+                let body = state.bodies.insert(body);
+                let cube = cuboid(0.5, 0.5);
+                state
+                    .colliders
+                    .insert_with_parent(cube, body, &mut state.bodies);
+                top = Some(body);
+            }
+
+            step_n(&mut state, &mut pipeline, 120);
+
+            state.bodies[top.unwrap()].translation().y
+        };
+
+        let with_shock_propagation = top_height(true);
+        let without_shock_propagation = top_height(false);
+        // Without shock propagation, a single velocity iteration can't carry the weight of the
+        // whole stack, so the cubes sink into each other.
+        assert!((with_shock_propagation - 12.0).abs() < 0.05);
+        assert!(without_shock_propagation < with_shock_propagation - 0.1);
+    }
+}
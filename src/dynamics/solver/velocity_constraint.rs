@@ -5,11 +5,14 @@ use crate::dynamics::solver::{
 use crate::dynamics::solver::{WVelocityConstraint, WVelocityGroundConstraint};
 use crate::dynamics::{IntegrationParameters, RigidBodySet};
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
-use crate::math::{Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+use crate::math::{AngVector, Real, Vector, ANG_DIM, DIM, MAX_MANIFOLD_POINTS};
 use crate::utils::{self, WAngularInertia, WBasis, WCross, WDot};
 use na::DVector;
 
-use super::{DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart};
+use super::{
+    contact_penetration_bias, contact_softness, DeltaVel, VelocityConstraintAngularFrictionPart,
+    VelocityConstraintElement, VelocityConstraintNormalPart,
+};
 
 //#[repr(align(64))]
 #[derive(Copy, Clone, Debug)]
@@ -99,6 +102,22 @@ impl AnyVelocityConstraint {
         }
     }
 
+    /// Applies the impulses this constraint was initialized with to the delta-velocities.
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        match self {
+            AnyVelocityConstraint::NongroupedGround(c) => c.warmstart(mj_lambdas),
+            AnyVelocityConstraint::Nongrouped(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyVelocityConstraint::GroupedGround(c) => c.warmstart(mj_lambdas),
+            #[cfg(feature = "simd-is-enabled")]
+            AnyVelocityConstraint::Grouped(c) => c.warmstart(mj_lambdas),
+            // NOTE: the contacts involving multibodies aren't warm-started.
+            AnyVelocityConstraint::NongroupedGeneric(_)
+            | AnyVelocityConstraint::NongroupedGenericGround(_) => {}
+            AnyVelocityConstraint::Empty => unreachable!(),
+        }
+    }
+
     pub fn writeback_impulses(&self, manifold_all: &mut [&mut ContactManifold]) {
         match self {
             AnyVelocityConstraint::NongroupedGround(c) => c.writeback_impulses(manifold_all),
@@ -119,6 +138,8 @@ pub(crate) struct VelocityConstraint {
     pub dir1: Vector<Real>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<Real>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_anisotropy: Real, // The scale of the second friction force direction.
     pub im1: Vector<Real>,
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
@@ -129,6 +150,7 @@ pub(crate) struct VelocityConstraint {
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
     pub num_contacts: u8,
     pub elements: [VelocityConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub angular_friction_part: VelocityConstraintAngularFrictionPart<Real>,
 }
 
 impl VelocityConstraint {
@@ -154,6 +176,7 @@ impl VelocityConstraint {
         let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.erp_inv_dt();
+        let warmstart_coeff = params.warmstart_coefficient;
 
         let handle1 = manifold.data.rigid_body1.unwrap();
         let handle2 = manifold.data.rigid_body2.unwrap();
@@ -171,8 +194,31 @@ impl VelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_anisotropy) = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &vels1.linvel,
+            &vels2.linvel,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            manifold.data.friction_anisotropy,
+        );
+
+        // The rolling axes, followed by the twist axis in 3D.
+        #[cfg(feature = "dim2")]
+        let angular_friction_axes: [AngVector<Real>; ANG_DIM] = [1.0];
+        #[cfg(feature = "dim3")]
+        let angular_friction_axes: [AngVector<Real>; ANG_DIM] =
+            [tangents1[0], force_dir1.cross(&tangents1[0]), force_dir1];
+        #[cfg(feature = "dim2")]
+        let angular_friction_coefficients = [manifold.data.rolling_friction];
+        #[cfg(feature = "dim3")]
+        let angular_friction_coefficients = [
+            manifold.data.rolling_friction,
+            manifold.data.rolling_friction,
+            manifold.data.twist_friction,
+        ];
 
         for (_l, manifold_points) in manifold
             .data
@@ -187,7 +233,10 @@ impl VelocityConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                angular_friction_part: VelocityConstraintAngularFrictionPart::zero(),
                 im1: mprops1.effective_inv_mass,
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
@@ -236,6 +285,7 @@ impl VelocityConstraint {
                 #[cfg(feature = "dim3")]
                 {
                     constraint.tangent1 = tangents1[0];
+                    constraint.friction_anisotropy = friction_anisotropy;
                 }
                 constraint.im1 = mprops1.effective_inv_mass;
                 constraint.im2 = mprops2.effective_inv_mass;
@@ -282,9 +332,19 @@ impl VelocityConstraint {
                         * (vel1 - vel2).dot(&force_dir1);
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
-                    let rhs_bias = /* is_resting
-                        * */  erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).clamp(-params.max_penetration_correction, 0.0);
+                    let (contact_erp_inv_dt, contact_cfm_factor) = contact_softness(
+                        params.dt,
+                        erp_inv_dt,
+                        manifold_point.stiffness,
+                        manifold_point.damping,
+                        projected_mass,
+                    );
+                    let rhs_bias = /* is_resting * */ contact_penetration_bias(
+                        params,
+                        manifold_point.dist,
+                        contact_erp_inv_dt,
+                        manifold_point.stiffness,
+                    );
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact = is_fast_contact || (-rhs * params.dt > ccd_thickness * 0.5);
@@ -294,8 +354,12 @@ impl VelocityConstraint {
                         gcross2,
                         rhs,
                         rhs_wo_bias,
-                        impulse: na::zero(),
+                        impulse: warmstart_coeff
+                            * manifold.points[manifold_point.contact_id as usize]
+                                .data
+                                .impulse,
                         r: projected_mass,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -338,6 +402,27 @@ impl VelocityConstraint {
                 }
             }
 
+            // Angular friction part.
+            {
+                let part = &mut constraint.angular_friction_part;
+                part.coefficients = angular_friction_coefficients;
+
+                for (i, axis) in angular_friction_axes.iter().enumerate() {
+                    let gcross1 = mprops1
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(*axis);
+                    let gcross2 = mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-*axis);
+
+                    part.gcross1[i] = gcross1;
+                    part.gcross2[i] = gcross2;
+                    part.rhs[i] = (vels1.angvel - vels2.angvel).gdot(*axis);
+                    part.impulse[i] = 0.0;
+                    part.r[i] = utils::inv(gcross1.gdot(gcross1) + gcross2.gdot(gcross2));
+                }
+            }
+
             constraint.cfm_factor = if is_fast_contact { 1.0 } else { cfm_factor };
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -364,9 +449,12 @@ impl VelocityConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_anisotropy,
             &self.im1,
             &self.im2,
             self.limit,
+            &mut self.angular_friction_part,
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
@@ -377,6 +465,23 @@ impl VelocityConstraint {
         mj_lambdas[self.mj_lambda2 as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda1 = mj_lambdas[self.mj_lambda1];
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2];
+
+        VelocityConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            &self.im1,
+            &self.im2,
+            &mut mj_lambda1,
+            &mut mj_lambda2,
+        );
+
+        mj_lambdas[self.mj_lambda1] = mj_lambda1;
+        mj_lambdas[self.mj_lambda2] = mj_lambda2;
+    }
+
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
 
@@ -400,17 +505,29 @@ impl VelocityConstraint {
         self.cfm_factor = 1.0;
         for elt in &mut self.elements {
             elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            elt.normal_part.cfm_factor = na::zero();
         }
     }
 }
 
+/// Computes the friction directions of a contact.
+///
+/// If `friction_dir` has a non-negligible component orthogonal to `force_dir1`, the first
+/// friction direction is aligned with it, and the second one is scaled by `friction_anisotropy`
+/// so that the friction coefficient across `friction_dir` is `friction_anisotropy` times the
+/// friction coefficient along it. Otherwise, the friction is isotropic and the first direction
+/// is aligned with the relative tangential velocity.
+///
+/// Returns the friction directions, and the scale applied to the second one.
 #[inline(always)]
 #[cfg(feature = "dim3")]
 pub(crate) fn compute_tangent_contact_directions<N>(
     force_dir1: &Vector<N>,
     linvel1: &Vector<N>,
     linvel2: &Vector<N>,
-) -> [Vector<N>; DIM - 1]
+    friction_dir: &Vector<N>,
+    friction_anisotropy: N,
+) -> ([Vector<N>; DIM - 1], N)
 where
     N: utils::WReal,
     Vector<N>: WBasis,
@@ -435,7 +552,160 @@ where
     let tangent_fallback = force_dir1.orthonormal_vector();
 
     let tangent1 = tangent_fallback.select(use_fallback, tangent_relative_linvel);
-    let bitangent1 = force_dir1.cross(&tangent1);
 
-    [tangent1, bitangent1]
+    // Align the first direction with the anisotropic friction direction, if there is one.
+    let mut tangent_friction_dir = friction_dir - force_dir1 * force_dir1.dot(friction_dir);
+    let tangent_friction_dir_norm = {
+        let _disable_fe_except =
+            crate::utils::DisableFloatingPointExceptionsFlags::disable_floating_point_exceptions();
+        tangent_friction_dir.normalize_mut()
+    };
+    let is_anisotropic = tangent_friction_dir_norm.simd_ge(N::splat(THRESHOLD));
+    let tangent1 = tangent_friction_dir.select(is_anisotropic, tangent1);
+    let bitangent_scale = friction_anisotropy.select(is_anisotropic, N::one());
+    let bitangent1 = force_dir1.cross(&tangent1) * bitangent_scale;
+
+    ([tangent1, bitangent1], bitangent_scale)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn box_stacks_do_not_creep() {
+        use crate::pipeline::PhysicsState;
+
+        // A stack of ten cubes that never falls asleep.
+        let mut state = PhysicsState::new();
+        state.integration_parameters.sleeping_enabled = false;
+        #[cfg(feature = "dim2")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(20.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cube) = (
+            ColliderBuilder::cuboid(20.0, 0.5, 20.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        state.colliders.insert(ground);
+
+        let mut top = None;
+        for i in 0..10 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * (1.0 + i as Real))
+                .build();
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(cube.clone(), body, &mut state.bodies);
+            top = Some(body);
+        }
+        let top = top.unwrap();
+
+        let mut pipeline = PhysicsPipeline::new();
+        step_n(&mut state, &mut pipeline, 600);
+        // The block solver keeps the stack from slowly drifting sideways and collapsing.
+        let translation = state.bodies[top].translation();
+        assert!(translation.y > 9.8);
+        assert!((translation - Vector::y() * translation.y).norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn rolling_and_twist_friction_stop_balls() {
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(100.0, 0.5);
+        state.colliders.insert(ground);
+
+        let mut balls = vec![];
+        for i in 0..2 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() - Vector::x() * 10.0 * i as Real)
+                .linvel(Vector::x() * 2.0)
+                .can_sleep(false);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).rolling_friction(0.05 * i as Real);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            balls.push(body);
+        }
+
+        #[cfg(feature = "dim3")]
+        let mut spinning_balls = vec![];
+        #[cfg(feature = "dim3")]
+        for i in 0..2 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() + Vector::z() * (10.0 + 10.0 * i as Real))
+                .angvel(Vector::y() * 10.0)
+                .can_sleep(false);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).twist_friction(0.05 * i as Real);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            spinning_balls.push(body);
+        }
+
+        step_n(&mut state, &mut pipeline, 300);
+
+        // Without rolling friction, the ball rolls forever.
+        assert!(state.bodies[balls[0]].linvel().x > 1.0);
+        assert!(state.bodies[balls[1]].linvel().x.abs() < 1.0e-3);
+
+        // Without twist friction, the ball spins forever.
+        #[cfg(feature = "dim3")]
+        {
+            assert!(state.bodies[spinning_balls[0]].angvel().y > 9.0);
+            assert!(state.bodies[spinning_balls[1]].angvel().y.abs() < 1.0e-3);
+        }
+    }
+
+    #[test]
+    fn soft_contacts_behave_like_springs() {
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(100.0, 0.5);
+        state.colliders.insert(ground);
+
+        // The stiffness is chosen so that the soft ball sinks by `expected_depth` at rest.
+        let expected_depth = 0.05;
+        let mut balls = vec![];
+        for i in 0..2 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.5 + Vector::x() * 3.0 * i as Real);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5);
+            let collider = state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            balls.push(body);
+
+            if i == 1 {
+                let mass = state.bodies[body].mass();
+                let stiffness = mass * 9.81 / expected_depth;
+                let damping = 2.0 * (stiffness * mass).sqrt();
+                state.colliders[collider].set_contact_stiffness(stiffness);
+                state.colliders[collider].set_contact_damping(damping);
+            }
+        }
+
+        step_n(&mut state, &mut pipeline, 300);
+
+        let depth = |i: usize| 1.0 - state.bodies[balls[i]].translation().y;
+        assert!(depth(0) < 0.005);
+        assert!((depth(1) - expected_depth).abs() < expected_depth * 0.1);
+    }
 }
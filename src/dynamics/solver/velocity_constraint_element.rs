@@ -1,5 +1,6 @@
 use super::DeltaVel;
-use crate::math::{AngVector, Vector, DIM};
+use crate::dynamics::IntegrationParameters;
+use crate::math::{AngVector, Vector, ANG_DIM, DIM};
 use crate::utils::{WBasis, WDot, WReal};
 
 #[derive(Copy, Clone, Debug)]
@@ -112,6 +113,8 @@ pub(crate) struct VelocityConstraintNormalPart<N: WReal> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub r: N,
+    // The CFM factor of this contact if it is soft, or zero if it is rigid.
+    pub cfm_factor: N,
 }
 
 impl<N: WReal> VelocityConstraintNormalPart<N> {
@@ -123,9 +126,17 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             r: na::zero(),
+            cfm_factor: na::zero(),
         }
     }
 
+    /// The CFM factor of this contact, given the CFM factor of the rigid contacts.
+    #[inline]
+    pub fn effective_cfm_factor(&self, cfm_factor: N) -> N {
+        self.cfm_factor
+            .select(self.cfm_factor.simd_gt(N::zero()), cfm_factor)
+    }
+
     #[inline]
     pub fn solve(
         &mut self,
@@ -142,7 +153,8 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
             - dir1.dot(&mj_lambda2.linear)
             + self.gcross2.gdot(mj_lambda2.angular)
             + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let new_impulse = self.effective_cfm_factor(cfm_factor)
+            * (self.impulse - self.r * dvel).simd_max(N::zero());
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
@@ -152,6 +164,218 @@ impl<N: WReal> VelocityConstraintNormalPart<N> {
         mj_lambda2.linear += dir1.component_mul(im2) * -dlambda;
         mj_lambda2.angular += self.gcross2 * dlambda;
     }
+
+    /// Solves the non-penetration constraints of two contact points simultaneously.
+    #[inline]
+    pub fn solve_pair(
+        part1: &mut Self,
+        part2: &mut Self,
+        cfm_factor: N,
+        dir1: &Vector<N>,
+        im1: &Vector<N>,
+        im2: &Vector<N>,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let dvel = |part: &Self| {
+            dir1.dot(&mj_lambda1.linear) + part.gcross1.gdot(mj_lambda1.angular)
+                - dir1.dot(&mj_lambda2.linear)
+                + part.gcross2.gdot(mj_lambda2.angular)
+                + part.rhs
+        };
+        let k12 = dir1.dot(&(im1 + im2).component_mul(dir1))
+            + part1.gcross1.gdot(part2.gcross1)
+            + part1.gcross2.gdot(part2.gcross2);
+        let [new_impulse1, new_impulse2] = solve_normal_pair(
+            [
+                part1.effective_cfm_factor(cfm_factor),
+                part2.effective_cfm_factor(cfm_factor),
+            ],
+            [part1.r, part2.r],
+            k12,
+            [part1.impulse, part2.impulse],
+            [dvel(part1), dvel(part2)],
+        );
+        let dlambda1 = new_impulse1 - part1.impulse;
+        let dlambda2 = new_impulse2 - part2.impulse;
+        part1.impulse = new_impulse1;
+        part2.impulse = new_impulse2;
+
+        mj_lambda1.linear += dir1.component_mul(im1) * (dlambda1 + dlambda2);
+        mj_lambda1.angular += part1.gcross1 * dlambda1 + part2.gcross1 * dlambda2;
+
+        mj_lambda2.linear += dir1.component_mul(im2) * -(dlambda1 + dlambda2);
+        mj_lambda2.angular += part1.gcross2 * dlambda1 + part2.gcross2 * dlambda2;
+    }
+}
+
+/// Computes the new impulses of two non-penetration constraints solved simultaneously.
+///
+/// This is the block solver from Box2D: the 2x2 linear complementarity problem is solved exactly
+/// by enumerating its four possible solutions, where each impulse is either zero or such that
+/// the relative velocity at its contact is zero. Contrary to the sequential resolution of both
+/// contacts, this doesn’t make a box resting on two points slowly jitter and creep. The diagonal
+/// of the 2x2 effective mass matrix is given by the inverses of `r`, and its off-diagonal term by
+/// `k12`. If the two constraints are almost redundant, this falls back to the sequential
+/// resolution.
+#[inline]
+pub(crate) fn solve_normal_pair<N: WReal>(
+    cfm_factor: [N; 2],
+    r: [N; 2],
+    k12: N,
+    impulse: [N; 2],
+    dvel: [N; 2],
+) -> [N; 2] {
+    let zero = N::zero();
+
+    // Sequential resolution, as done by `VelocityConstraintNormalPart::solve`.
+    let seq_impulse1 = cfm_factor[0] * (impulse[0] - r[0] * dvel[0]).simd_max(zero);
+    let seq_dvel2 = dvel[1] + k12 * (seq_impulse1 - impulse[0]);
+    let seq_impulse2 = cfm_factor[1] * (impulse[1] - r[1] * seq_dvel2).simd_max(zero);
+
+    // Both contacts are active: the relative velocities at both contacts become zero.
+    let one_minus_c = N::one() - r[0] * r[1] * k12 * k12;
+    let well_conditioned = one_minus_c.simd_gt(N::splat(1.0e-3));
+    let inv_one_minus_c = crate::utils::simd_inv(one_minus_c);
+    let both1 = impulse[0] - r[0] * (dvel[0] - r[1] * k12 * dvel[1]) * inv_one_minus_c;
+    let both2 = impulse[1] - r[1] * (dvel[1] - r[0] * k12 * dvel[0]) * inv_one_minus_c;
+    let both_valid = well_conditioned & both1.simd_ge(zero) & both2.simd_ge(zero);
+
+    // Only the first contact is active.
+    let first = impulse[0] - r[0] * (dvel[0] - k12 * impulse[1]);
+    let first_valid =
+        first.simd_ge(zero) & (r[1] * (dvel[1] + k12 * (first - impulse[0]))).simd_ge(impulse[1]);
+
+    // Only the second contact is active.
+    let second = impulse[1] - r[1] * (dvel[1] - k12 * impulse[0]);
+    let second_valid =
+        second.simd_ge(zero) & (r[0] * (dvel[0] + k12 * (second - impulse[1]))).simd_ge(impulse[0]);
+
+    // None of the contacts are active.
+    let none_valid = (r[0] * (dvel[0] - k12 * impulse[1])).simd_ge(impulse[0])
+        & (r[1] * (dvel[1] - k12 * impulse[0])).simd_ge(impulse[1]);
+
+    let mut new_impulse1 = seq_impulse1;
+    let mut new_impulse2 = seq_impulse2;
+    let block_valid = well_conditioned & none_valid;
+    new_impulse1 = zero.select(block_valid, new_impulse1);
+    new_impulse2 = zero.select(block_valid, new_impulse2);
+    let block_valid = well_conditioned & second_valid;
+    new_impulse1 = zero.select(block_valid, new_impulse1);
+    new_impulse2 = (cfm_factor[1] * second).select(block_valid, new_impulse2);
+    let block_valid = well_conditioned & first_valid;
+    new_impulse1 = (cfm_factor[0] * first).select(block_valid, new_impulse1);
+    new_impulse2 = zero.select(block_valid, new_impulse2);
+    new_impulse1 = (cfm_factor[0] * both1).select(both_valid, new_impulse1);
+    new_impulse2 = (cfm_factor[1] * both2).select(both_valid, new_impulse2);
+
+    [new_impulse1, new_impulse2]
+}
+
+/// The rolling friction, and the twist friction in 3D, of a contact manifold.
+///
+/// Its first `DIM - 1` axes resist the relative rolling of the bodies, and its last axis in 3D
+/// resists their relative spinning around the contact normal. The impulse along each axis is
+/// bounded by its coefficient multiplied by the total normal impulse of the manifold.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VelocityConstraintAngularFrictionPart<N: WReal> {
+    pub gcross1: [AngVector<N>; ANG_DIM],
+    pub gcross2: [AngVector<N>; ANG_DIM],
+    pub rhs: [N; ANG_DIM],
+    pub impulse: [N; ANG_DIM],
+    pub r: [N; ANG_DIM],
+    pub coefficients: [N; ANG_DIM],
+}
+
+impl<N: WReal> VelocityConstraintAngularFrictionPart<N> {
+    pub fn zero() -> Self {
+        Self {
+            gcross1: [na::zero(); ANG_DIM],
+            gcross2: [na::zero(); ANG_DIM],
+            rhs: [na::zero(); ANG_DIM],
+            impulse: [na::zero(); ANG_DIM],
+            r: [na::zero(); ANG_DIM],
+            coefficients: [na::zero(); ANG_DIM],
+        }
+    }
+
+    #[inline]
+    pub fn solve(
+        &mut self,
+        normal_impulse: N,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        for i in 0..ANG_DIM {
+            let limit = self.coefficients[i] * normal_impulse;
+            let dvel = self.gcross1[i].gdot(mj_lambda1.angular)
+                + self.gcross2[i].gdot(mj_lambda2.angular)
+                + self.rhs[i];
+            let new_impulse = (self.impulse[i] - self.r[i] * dvel).simd_clamp(-limit, limit);
+            let dlambda = new_impulse - self.impulse[i];
+            self.impulse[i] = new_impulse;
+
+            mj_lambda1.angular += self.gcross1[i] * dlambda;
+            mj_lambda2.angular += self.gcross2[i] * dlambda;
+        }
+    }
+}
+
+/// The (non-positive) bias velocity correcting the penetration of a contact at the distance `dist`.
+///
+/// The penetration beyond `params.allowed_linear_error` is corrected at the rate `erp_inv_dt`.
+/// For rigid contacts, with a zero `stiffness`, this rate is scaled down for penetrations
+/// shallower than `params.shallow_penetration_depth`, and the resulting velocity is capped by
+/// `params.max_corrective_velocity`. Soft contacts keep the correction of their spring-damper.
+#[inline]
+pub(crate) fn contact_penetration_bias<N: WReal>(
+    params: &IntegrationParameters,
+    dist: N,
+    erp_inv_dt: N,
+    stiffness: N,
+) -> N {
+    let depth = -(dist + N::splat(params.allowed_linear_error))
+        .simd_clamp(N::splat(-params.max_penetration_correction), N::zero());
+    let bias = depth * erp_inv_dt;
+
+    let adaptive_bias = if params.shallow_penetration_depth > 0.0 {
+        bias * (depth * N::splat(1.0 / params.shallow_penetration_depth)).simd_min(N::one())
+    } else {
+        bias
+    };
+    let adaptive_bias = adaptive_bias.simd_min(N::splat(params.max_corrective_velocity));
+
+    -adaptive_bias.select(stiffness.simd_le(N::zero()), bias)
+}
+
+/// The ERP, multiplied by the inverse timestep length, and the CFM factor of a contact.
+///
+/// A soft contact, with a non-zero `stiffness`, behaves like a spring-damper pushing the bodies
+/// apart. Its coefficients are derived from the implicit integration of this spring-damper
+/// acting on the projected mass of the contact. Rigid contacts keep the given `rigid_erp_inv_dt`,
+/// and a zero CFM factor so the CFM factor of their constraint is used instead.
+#[inline]
+pub(crate) fn contact_softness<N: WReal>(
+    dt: N,
+    rigid_erp_inv_dt: N,
+    stiffness: N,
+    damping: N,
+    projected_mass: N,
+) -> (N, N) {
+    let is_soft = stiffness.simd_gt(N::zero());
+    let dt_stiffness_plus_damping = dt * stiffness + damping;
+    let erp_inv_dt = stiffness * crate::utils::simd_inv(dt_stiffness_plus_damping);
+    let cfm_coeff = crate::utils::simd_inv(dt * dt_stiffness_plus_damping);
+    let cfm_factor = crate::utils::simd_inv(N::one() + cfm_coeff * projected_mass);
+
+    (
+        erp_inv_dt.select(is_soft, rigid_erp_inv_dt),
+        cfm_factor.select(is_soft, N::zero()),
+    )
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -168,15 +392,37 @@ impl<N: WReal> VelocityConstraintElement<N> {
         }
     }
 
+    /// Applies the warm-start non-penetration impulses of these elements to the bodies.
+    #[inline]
+    pub fn warmstart_group(
+        elements: &[Self],
+        dir1: &Vector<N>,
+        im1: &Vector<N>,
+        im2: &Vector<N>,
+        mj_lambda1: &mut DeltaVel<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) {
+        for element in elements {
+            let part = &element.normal_part;
+            mj_lambda1.linear += dir1.component_mul(im1) * part.impulse;
+            mj_lambda1.angular += part.gcross1 * part.impulse;
+
+            mj_lambda2.linear += dir1.component_mul(im2) * -part.impulse;
+            mj_lambda2.angular += part.gcross2 * part.impulse;
+        }
+    }
+
     #[inline]
     pub fn solve_group(
         cfm_factor: N,
         elements: &mut [Self],
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
+        #[cfg(feature = "dim3")] friction_anisotropy: N,
         im1: &Vector<N>,
         im2: &Vector<N>,
         limit: N,
+        angular_friction_part: &mut VelocityConstraintAngularFrictionPart<N>,
         mj_lambda1: &mut DeltaVel<N>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
@@ -185,27 +431,67 @@ impl<N: WReal> VelocityConstraintElement<N> {
         Vector<N>: WBasis,
         AngVector<N>: WDot<AngVector<N>, Result = N>,
     {
-        // Solve penetration.
+        // Solve penetration. Manifolds with two contacts are solved with a block solver. With
+        // four contacts (typically the corners of a face), the opposite corners are paired.
         if solve_normal {
-            for element in elements.iter_mut() {
-                element
-                    .normal_part
-                    .solve(cfm_factor, &dir1, im1, im2, mj_lambda1, mj_lambda2);
+            if let [element1, element2] = elements {
+                VelocityConstraintNormalPart::solve_pair(
+                    &mut element1.normal_part,
+                    &mut element2.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im1,
+                    im2,
+                    mj_lambda1,
+                    mj_lambda2,
+                );
+            } else if let [element1, element2, element3, element4] = elements {
+                VelocityConstraintNormalPart::solve_pair(
+                    &mut element1.normal_part,
+                    &mut element3.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im1,
+                    im2,
+                    mj_lambda1,
+                    mj_lambda2,
+                );
+                VelocityConstraintNormalPart::solve_pair(
+                    &mut element2.normal_part,
+                    &mut element4.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im1,
+                    im2,
+                    mj_lambda1,
+                    mj_lambda2,
+                );
+            } else {
+                for element in elements.iter_mut() {
+                    element
+                        .normal_part
+                        .solve(cfm_factor, dir1, im1, im2, mj_lambda1, mj_lambda2);
+                }
             }
         }
 
         // Solve friction.
         if solve_friction {
             #[cfg(feature = "dim3")]
-            let tangents1 = [tangent1, &dir1.cross(&tangent1)];
+            let tangents1 = [tangent1, &(dir1.cross(tangent1) * friction_anisotropy)];
             #[cfg(feature = "dim2")]
             let tangents1 = [&dir1.orthonormal_vector()];
 
+            let mut normal_impulse = N::zero();
+
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
+                normal_impulse += element.normal_part.impulse;
                 let part = &mut element.tangent_part;
                 part.solve(tangents1, im1, im2, limit, mj_lambda1, mj_lambda2);
             }
+
+            angular_friction_part.solve(normal_impulse, mj_lambda1, mj_lambda2);
         }
     }
 }
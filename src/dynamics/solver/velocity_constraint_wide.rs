@@ -1,18 +1,20 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityConstraintElement, VelocityConstraintNormalPart,
+    contact_penetration_bias, contact_softness, AnyVelocityConstraint, DeltaVel,
+    VelocityConstraintAngularFrictionPart, VelocityConstraintElement, VelocityConstraintNormalPart,
 };
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::SimdBool;
 use crate::math::{
-    AngVector, AngularInertia, Point, Real, SimdReal, Vector, DIM, MAX_MANIFOLD_POINTS, SIMD_WIDTH,
+    AngVector, AngularInertia, Point, Real, SimdReal, Vector, ANG_DIM, DIM, MAX_MANIFOLD_POINTS,
+    SIMD_WIDTH,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
 use crate::utils::{self, WAngularInertia, WCross, WDot};
 use num::Zero;
-use parry::math::SimdBool;
 use simba::simd::{SimdPartialOrd, SimdValue};
 
 #[derive(Copy, Clone, Debug)]
@@ -20,7 +22,10 @@ pub(crate) struct WVelocityConstraint {
     pub dir1: Vector<SimdReal>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<SimdReal>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_anisotropy: SimdReal, // The scale of the second friction force direction.
     pub elements: [VelocityConstraintElement<SimdReal>; MAX_MANIFOLD_POINTS],
+    pub angular_friction_part: VelocityConstraintAngularFrictionPart<SimdReal>,
     pub num_contacts: u8,
     pub im1: Vector<SimdReal>,
     pub im2: Vector<SimdReal>,
@@ -48,9 +53,8 @@ impl WVelocityConstraint {
         let cfm_factor = SimdReal::splat(params.cfm_factor());
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
-        let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
         let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
-        let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
+        let warmstart_coeff = SimdReal::splat(params.warmstart_coefficient);
 
         let handles1 = gather![|ii| manifolds[ii].data.rigid_body1.unwrap()];
         let handles2 = gather![|ii| manifolds[ii].data.rigid_body2.unwrap()];
@@ -69,7 +73,7 @@ impl WVelocityConstraint {
         let world_com1 = Point::from(gather![|ii| mprops1[ii].world_com]);
         let im1 = Vector::from(gather![|ii| mprops1[ii].effective_inv_mass]);
         let ii1: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops1[ii].effective_world_inv_inertia_sqrt]);
+            utils::simd_angular_inertia(gather![|ii| mprops1[ii].effective_world_inv_inertia_sqrt]);
 
         let linvel1 = Vector::from(gather![|ii| vels1[ii].linvel]);
         let angvel1 = AngVector::<SimdReal>::from(gather![|ii| vels1[ii].angvel]);
@@ -77,7 +81,7 @@ impl WVelocityConstraint {
         let world_com2 = Point::from(gather![|ii| mprops2[ii].world_com]);
         let im2 = Vector::from(gather![|ii| mprops2[ii].effective_inv_mass]);
         let ii2: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
+            utils::simd_angular_inertia(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
 
         let linvel2 = Vector::from(gather![|ii| vels2[ii].linvel]);
         let angvel2 = AngVector::<SimdReal>::from(gather![|ii| vels2[ii].angvel]);
@@ -92,7 +96,36 @@ impl WVelocityConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let (tangents1, friction_anisotropy) = {
+            let friction_dir = Vector::from(gather![|ii| manifolds[ii]
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros)]);
+            let friction_anisotropy =
+                SimdReal::from(gather![|ii| manifolds[ii].data.friction_anisotropy]);
+            super::compute_tangent_contact_directions(
+                &force_dir1,
+                &linvel1,
+                &linvel2,
+                &friction_dir,
+                friction_anisotropy,
+            )
+        };
+
+        let rolling_friction = SimdReal::from(gather![|ii| manifolds[ii].data.rolling_friction]);
+        #[cfg(feature = "dim3")]
+        let twist_friction = SimdReal::from(gather![|ii| manifolds[ii].data.twist_friction]);
+
+        // The rolling axes, followed by the twist axis in 3D.
+        #[cfg(feature = "dim2")]
+        let angular_friction_axes: [AngVector<SimdReal>; ANG_DIM] = [SimdReal::splat(1.0)];
+        #[cfg(feature = "dim3")]
+        let angular_friction_axes: [AngVector<SimdReal>; ANG_DIM] =
+            [tangents1[0], force_dir1.cross(&tangents1[0]), force_dir1];
+        #[cfg(feature = "dim2")]
+        let angular_friction_coefficients = [rolling_friction];
+        #[cfg(feature = "dim3")]
+        let angular_friction_coefficients = [rolling_friction, rolling_friction, twist_friction];
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points =
@@ -104,7 +137,10 @@ impl WVelocityConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                angular_friction_part: VelocityConstraintAngularFrictionPart::zero(),
                 im1,
                 im2,
                 cfm_factor,
@@ -140,6 +176,10 @@ impl WVelocityConstraint {
                 {
                     let gcross1 = ii1.transform_vector(dp1.gcross(force_dir1));
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
+                    let impulse = SimdReal::from(gather![|ii| manifolds[ii].points
+                        [manifold_points[ii][k].contact_id as usize]
+                        .data
+                        .impulse]);
 
                     let imsum = im1 + im2;
                     let projected_mass = utils::simd_inv(
@@ -153,9 +193,12 @@ impl WVelocityConstraint {
                         (SimdReal::splat(1.0) + is_bouncy * restitution) * projected_velocity;
                     rhs_wo_bias += dist.simd_max(SimdReal::zero()) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
-                    let rhs_bias = (dist + allowed_lin_err)
-                        .simd_clamp(-max_penetration_correction, SimdReal::zero())
-                        * (erp_inv_dt/* * is_resting */);
+                    let stiffness = SimdReal::from(gather![|ii| manifold_points[ii][k].stiffness]);
+                    let damping = SimdReal::from(gather![|ii| manifold_points[ii][k].damping]);
+                    let (contact_erp_inv_dt, contact_cfm_factor) =
+                        contact_softness(dt, erp_inv_dt, stiffness, damping, projected_mass);
+                    let rhs_bias = /* is_resting * */
+                        contact_penetration_bias(params, dist, contact_erp_inv_dt, stiffness);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -166,8 +209,9 @@ impl WVelocityConstraint {
                         gcross2,
                         rhs: rhs_wo_bias + rhs_bias,
                         rhs_wo_bias,
-                        impulse: SimdReal::splat(0.0),
+                        impulse: warmstart_coeff * impulse,
                         r: projected_mass,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -203,6 +247,22 @@ impl WVelocityConstraint {
                 }
             }
 
+            // Angular friction part.
+            {
+                let part = &mut constraint.angular_friction_part;
+                part.coefficients = angular_friction_coefficients;
+
+                for (i, axis) in angular_friction_axes.iter().enumerate() {
+                    let gcross1 = ii1.transform_vector(*axis);
+                    let gcross2 = ii2.transform_vector(-*axis);
+
+                    part.gcross1[i] = gcross1;
+                    part.gcross2[i] = gcross2;
+                    part.rhs[i] = (angvel1 - angvel2).gdot(*axis);
+                    part.r[i] = utils::simd_inv(gcross1.gdot(gcross1) + gcross2.gdot(gcross2));
+                }
+            }
+
             constraint.cfm_factor = SimdReal::splat(1.0).select(is_fast_contact, cfm_factor);
 
             if let Some(at) = insert_at {
@@ -240,9 +300,12 @@ impl WVelocityConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_anisotropy,
             &self.im1,
             &self.im2,
             self.limit,
+            &mut self.angular_friction_part,
             &mut mj_lambda1,
             &mut mj_lambda2,
             solve_normal,
@@ -259,6 +322,40 @@ impl WVelocityConstraint {
         }
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda1 = DeltaVel {
+            linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda1[ii] as usize].linear]),
+            angular: AngVector::from(gather![
+                |ii| mj_lambdas[self.mj_lambda1[ii] as usize].angular
+            ]),
+        };
+
+        let mut mj_lambda2 = DeltaVel {
+            linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda2[ii] as usize].linear]),
+            angular: AngVector::from(gather![
+                |ii| mj_lambdas[self.mj_lambda2[ii] as usize].angular
+            ]),
+        };
+
+        VelocityConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            &self.im1,
+            &self.im2,
+            &mut mj_lambda1,
+            &mut mj_lambda2,
+        );
+
+        for ii in 0..SIMD_WIDTH {
+            mj_lambdas[self.mj_lambda1[ii] as usize].linear = mj_lambda1.linear.extract(ii);
+            mj_lambdas[self.mj_lambda1[ii] as usize].angular = mj_lambda1.angular.extract(ii);
+        }
+        for ii in 0..SIMD_WIDTH {
+            mj_lambdas[self.mj_lambda2[ii] as usize].linear = mj_lambda2.linear.extract(ii);
+            mj_lambdas[self.mj_lambda2[ii] as usize].angular = mj_lambda2.angular.extract(ii);
+        }
+    }
+
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         for k in 0..self.num_contacts as usize {
             let impulses: [_; SIMD_WIDTH] = self.elements[k].normal_part.impulse.into();
@@ -289,6 +386,7 @@ impl WVelocityConstraint {
         self.cfm_factor = SimdReal::splat(1.0);
         for elt in &mut self.elements {
             elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            elt.normal_part.cfm_factor = na::zero();
         }
     }
 }
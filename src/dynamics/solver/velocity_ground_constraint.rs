@@ -1,8 +1,9 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
+    contact_penetration_bias, contact_softness, AnyVelocityConstraint, DeltaVel,
+    VelocityGroundConstraintAngularFrictionPart, VelocityGroundConstraintElement,
     VelocityGroundConstraintNormalPart,
 };
-use crate::math::{Point, Real, Vector, DIM, MAX_MANIFOLD_POINTS};
+use crate::math::{AngVector, Point, Real, Vector, ANG_DIM, DIM, MAX_MANIFOLD_POINTS};
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
 use crate::utils::{self, WAngularInertia, WCross, WDot};
@@ -16,10 +17,13 @@ pub(crate) struct VelocityGroundConstraint {
     pub dir1: Vector<Real>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<Real>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_anisotropy: Real, // The scale of the second friction force direction.
     pub im2: Vector<Real>,
     pub cfm_factor: Real,
     pub limit: Real,
     pub elements: [VelocityGroundConstraintElement<Real>; MAX_MANIFOLD_POINTS],
+    pub angular_friction_part: VelocityGroundConstraintAngularFrictionPart<Real>,
 
     pub manifold_id: ContactManifoldIndex,
     pub manifold_contact_id: [u8; MAX_MANIFOLD_POINTS],
@@ -38,6 +42,7 @@ impl VelocityGroundConstraint {
         let cfm_factor = params.cfm_factor();
         let inv_dt = params.inv_dt();
         let erp_inv_dt = params.erp_inv_dt();
+        let warmstart_coeff = params.warmstart_coefficient;
 
         let mut handle1 = manifold.data.rigid_body1;
         let mut handle2 = manifold.data.rigid_body2;
@@ -64,11 +69,34 @@ impl VelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 =
-            super::compute_tangent_contact_directions(&force_dir1, &vels1.linvel, &vels2.linvel);
+        let (tangents1, friction_anisotropy) = super::compute_tangent_contact_directions(
+            &force_dir1,
+            &vels1.linvel,
+            &vels2.linvel,
+            &manifold
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros),
+            manifold.data.friction_anisotropy,
+        );
 
         let mj_lambda2 = rb2.ids.active_set_offset;
 
+        // The rolling axes, followed by the twist axis in 3D.
+        #[cfg(feature = "dim2")]
+        let angular_friction_axes: [AngVector<Real>; ANG_DIM] = [1.0];
+        #[cfg(feature = "dim3")]
+        let angular_friction_axes: [AngVector<Real>; ANG_DIM] =
+            [tangents1[0], force_dir1.cross(&tangents1[0]), force_dir1];
+        #[cfg(feature = "dim2")]
+        let angular_friction_coefficients = [manifold.data.rolling_friction];
+        #[cfg(feature = "dim3")]
+        let angular_friction_coefficients = [
+            manifold.data.rolling_friction,
+            manifold.data.rolling_friction,
+            manifold.data.twist_friction,
+        ];
+
         for (_l, manifold_points) in manifold
             .data
             .solver_contacts
@@ -80,7 +108,10 @@ impl VelocityGroundConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                angular_friction_part: VelocityGroundConstraintAngularFrictionPart::zero(),
                 im2: mprops2.effective_inv_mass,
                 cfm_factor,
                 limit: 0.0,
@@ -127,6 +158,7 @@ impl VelocityGroundConstraint {
                 #[cfg(feature = "dim3")]
                 {
                     constraint.tangent1 = tangents1[0];
+                    constraint.friction_anisotropy = friction_anisotropy;
                 }
                 constraint.im2 = mprops2.effective_inv_mass;
                 constraint.cfm_factor = cfm_factor;
@@ -167,9 +199,19 @@ impl VelocityGroundConstraint {
                     let mut rhs_wo_bias = (1.0 + is_bouncy * manifold_point.restitution) * dvel;
                     rhs_wo_bias += manifold_point.dist.max(0.0) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
-                    let rhs_bias = /* is_resting
-                        * */ erp_inv_dt
-                        * (manifold_point.dist + params.allowed_linear_error).clamp(-params.max_penetration_correction, 0.0);
+                    let (contact_erp_inv_dt, contact_cfm_factor) = contact_softness(
+                        params.dt,
+                        erp_inv_dt,
+                        manifold_point.stiffness,
+                        manifold_point.damping,
+                        projected_mass,
+                    );
+                    let rhs_bias = /* is_resting * */ contact_penetration_bias(
+                        params,
+                        manifold_point.dist,
+                        contact_erp_inv_dt,
+                        manifold_point.stiffness,
+                    );
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -179,8 +221,12 @@ impl VelocityGroundConstraint {
                         gcross2,
                         rhs,
                         rhs_wo_bias,
-                        impulse: na::zero(),
+                        impulse: warmstart_coeff
+                            * manifold.points[manifold_point.contact_id as usize]
+                                .data
+                                .impulse,
                         r: projected_mass,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -217,6 +263,23 @@ impl VelocityGroundConstraint {
                 }
             }
 
+            // Angular friction part.
+            {
+                let part = &mut constraint.angular_friction_part;
+                part.coefficients = angular_friction_coefficients;
+
+                for (i, axis) in angular_friction_axes.iter().enumerate() {
+                    let gcross2 = mprops2
+                        .effective_world_inv_inertia_sqrt
+                        .transform_vector(-*axis);
+
+                    part.gcross2[i] = gcross2;
+                    part.rhs[i] = (vels1.angvel - vels2.angvel).gdot(*axis);
+                    part.impulse[i] = 0.0;
+                    part.r[i] = utils::inv(gcross2.gdot(gcross2));
+                }
+            }
+
             constraint.cfm_factor = if is_fast_contact { 1.0 } else { cfm_factor };
 
             #[cfg(not(target_arch = "wasm32"))]
@@ -242,8 +305,11 @@ impl VelocityGroundConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_anisotropy,
             &self.im2,
             self.limit,
+            &mut self.angular_friction_part,
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
@@ -252,6 +318,19 @@ impl VelocityGroundConstraint {
         mj_lambdas[self.mj_lambda2 as usize] = mj_lambda2;
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = mj_lambdas[self.mj_lambda2];
+
+        VelocityGroundConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            &self.im2,
+            &mut mj_lambda2,
+        );
+
+        mj_lambdas[self.mj_lambda2] = mj_lambda2;
+    }
+
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         let manifold = &mut manifolds_all[self.manifold_id];
@@ -276,6 +355,7 @@ impl VelocityGroundConstraint {
         self.cfm_factor = 1.0;
         for elt in &mut self.elements {
             elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            elt.normal_part.cfm_factor = na::zero();
         }
     }
 }
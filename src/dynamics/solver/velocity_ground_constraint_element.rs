@@ -1,5 +1,6 @@
+use super::velocity_constraint_element::solve_normal_pair;
 use super::DeltaVel;
-use crate::math::{AngVector, Vector, DIM};
+use crate::math::{AngVector, Vector, ANG_DIM, DIM};
 use crate::utils::{WBasis, WDot, WReal};
 
 #[derive(Copy, Clone, Debug)]
@@ -93,6 +94,8 @@ pub(crate) struct VelocityGroundConstraintNormalPart<N: WReal> {
     pub rhs_wo_bias: N,
     pub impulse: N,
     pub r: N,
+    // The CFM factor of this contact if it is soft, or zero if it is rigid.
+    pub cfm_factor: N,
 }
 
 impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
@@ -103,9 +106,17 @@ impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
             rhs_wo_bias: na::zero(),
             impulse: na::zero(),
             r: na::zero(),
+            cfm_factor: na::zero(),
         }
     }
 
+    /// The CFM factor of this contact, given the CFM factor of the rigid contacts.
+    #[inline]
+    pub fn effective_cfm_factor(&self, cfm_factor: N) -> N {
+        self.cfm_factor
+            .select(self.cfm_factor.simd_gt(N::zero()), cfm_factor)
+    }
+
     #[inline]
     pub fn solve(
         &mut self,
@@ -117,13 +128,86 @@ impl<N: WReal> VelocityGroundConstraintNormalPart<N> {
         AngVector<N>: WDot<AngVector<N>, Result = N>,
     {
         let dvel = -dir1.dot(&mj_lambda2.linear) + self.gcross2.gdot(mj_lambda2.angular) + self.rhs;
-        let new_impulse = cfm_factor * (self.impulse - self.r * dvel).simd_max(N::zero());
+        let new_impulse = self.effective_cfm_factor(cfm_factor)
+            * (self.impulse - self.r * dvel).simd_max(N::zero());
         let dlambda = new_impulse - self.impulse;
         self.impulse = new_impulse;
 
         mj_lambda2.linear += dir1.component_mul(im2) * -dlambda;
         mj_lambda2.angular += self.gcross2 * dlambda;
     }
+
+    /// Solves the non-penetration constraints of two contact points simultaneously.
+    #[inline]
+    pub fn solve_pair(
+        part1: &mut Self,
+        part2: &mut Self,
+        cfm_factor: N,
+        dir1: &Vector<N>,
+        im2: &Vector<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        let dvel = |part: &Self| {
+            -dir1.dot(&mj_lambda2.linear) + part.gcross2.gdot(mj_lambda2.angular) + part.rhs
+        };
+        let k12 = dir1.dot(&im2.component_mul(dir1)) + part1.gcross2.gdot(part2.gcross2);
+        let [new_impulse1, new_impulse2] = solve_normal_pair(
+            [
+                part1.effective_cfm_factor(cfm_factor),
+                part2.effective_cfm_factor(cfm_factor),
+            ],
+            [part1.r, part2.r],
+            k12,
+            [part1.impulse, part2.impulse],
+            [dvel(part1), dvel(part2)],
+        );
+        let dlambda1 = new_impulse1 - part1.impulse;
+        let dlambda2 = new_impulse2 - part2.impulse;
+        part1.impulse = new_impulse1;
+        part2.impulse = new_impulse2;
+
+        mj_lambda2.linear += dir1.component_mul(im2) * -(dlambda1 + dlambda2);
+        mj_lambda2.angular += part1.gcross2 * dlambda1 + part2.gcross2 * dlambda2;
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VelocityGroundConstraintAngularFrictionPart<N: WReal> {
+    pub gcross2: [AngVector<N>; ANG_DIM],
+    pub rhs: [N; ANG_DIM],
+    pub impulse: [N; ANG_DIM],
+    pub r: [N; ANG_DIM],
+    pub coefficients: [N; ANG_DIM],
+}
+
+impl<N: WReal> VelocityGroundConstraintAngularFrictionPart<N> {
+    pub fn zero() -> Self {
+        Self {
+            gcross2: [na::zero(); ANG_DIM],
+            rhs: [na::zero(); ANG_DIM],
+            impulse: [na::zero(); ANG_DIM],
+            r: [na::zero(); ANG_DIM],
+            coefficients: [na::zero(); ANG_DIM],
+        }
+    }
+
+    #[inline]
+    pub fn solve(&mut self, normal_impulse: N, mj_lambda2: &mut DeltaVel<N>)
+    where
+        AngVector<N>: WDot<AngVector<N>, Result = N>,
+    {
+        for i in 0..ANG_DIM {
+            let limit = self.coefficients[i] * normal_impulse;
+            let dvel = self.gcross2[i].gdot(mj_lambda2.angular) + self.rhs[i];
+            let new_impulse = (self.impulse[i] - self.r[i] * dvel).simd_clamp(-limit, limit);
+            let dlambda = new_impulse - self.impulse[i];
+            self.impulse[i] = new_impulse;
+
+            mj_lambda2.angular += self.gcross2[i] * dlambda;
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -140,14 +224,31 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
         }
     }
 
+    /// Applies the warm-start non-penetration impulses of these elements to the body.
+    #[inline]
+    pub fn warmstart_group(
+        elements: &[Self],
+        dir1: &Vector<N>,
+        im2: &Vector<N>,
+        mj_lambda2: &mut DeltaVel<N>,
+    ) {
+        for element in elements {
+            let part = &element.normal_part;
+            mj_lambda2.linear += dir1.component_mul(im2) * -part.impulse;
+            mj_lambda2.angular += part.gcross2 * part.impulse;
+        }
+    }
+
     #[inline]
     pub fn solve_group(
         cfm_factor: N,
         elements: &mut [Self],
         dir1: &Vector<N>,
         #[cfg(feature = "dim3")] tangent1: &Vector<N>,
+        #[cfg(feature = "dim3")] friction_anisotropy: N,
         im2: &Vector<N>,
         limit: N,
+        angular_friction_part: &mut VelocityGroundConstraintAngularFrictionPart<N>,
         mj_lambda2: &mut DeltaVel<N>,
         solve_normal: bool,
         solve_friction: bool,
@@ -155,27 +256,59 @@ impl<N: WReal> VelocityGroundConstraintElement<N> {
         Vector<N>: WBasis,
         AngVector<N>: WDot<AngVector<N>, Result = N>,
     {
-        // Solve penetration.
+        // Solve penetration. Manifolds with two contacts are solved with a block solver. With
+        // four contacts (typically the corners of a face), the opposite corners are paired.
         if solve_normal {
-            for element in elements.iter_mut() {
-                element
-                    .normal_part
-                    .solve(cfm_factor, &dir1, im2, mj_lambda2);
+            if let [element1, element2] = elements {
+                VelocityGroundConstraintNormalPart::solve_pair(
+                    &mut element1.normal_part,
+                    &mut element2.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im2,
+                    mj_lambda2,
+                );
+            } else if let [element1, element2, element3, element4] = elements {
+                VelocityGroundConstraintNormalPart::solve_pair(
+                    &mut element1.normal_part,
+                    &mut element3.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im2,
+                    mj_lambda2,
+                );
+                VelocityGroundConstraintNormalPart::solve_pair(
+                    &mut element2.normal_part,
+                    &mut element4.normal_part,
+                    cfm_factor,
+                    dir1,
+                    im2,
+                    mj_lambda2,
+                );
+            } else {
+                for element in elements.iter_mut() {
+                    element.normal_part.solve(cfm_factor, dir1, im2, mj_lambda2);
+                }
             }
         }
 
         // Solve friction.
         if solve_friction {
             #[cfg(feature = "dim3")]
-            let tangents1 = [tangent1, &dir1.cross(&tangent1)];
+            let tangents1 = [tangent1, &(dir1.cross(tangent1) * friction_anisotropy)];
             #[cfg(feature = "dim2")]
             let tangents1 = [&dir1.orthonormal_vector()];
 
+            let mut normal_impulse = N::zero();
+
             for element in elements.iter_mut() {
                 let limit = limit * element.normal_part.impulse;
+                normal_impulse += element.normal_part.impulse;
                 let part = &mut element.tangent_part;
                 part.solve(tangents1, im2, limit, mj_lambda2);
             }
+
+            angular_friction_part.solve(normal_impulse, mj_lambda2);
         }
     }
 }
@@ -1,19 +1,21 @@
 use super::{
-    AnyVelocityConstraint, DeltaVel, VelocityGroundConstraintElement,
+    contact_penetration_bias, contact_softness, AnyVelocityConstraint, DeltaVel,
+    VelocityGroundConstraintAngularFrictionPart, VelocityGroundConstraintElement,
     VelocityGroundConstraintNormalPart,
 };
 use crate::dynamics::{
     IntegrationParameters, RigidBodyIds, RigidBodyMassProps, RigidBodySet, RigidBodyVelocity,
 };
 use crate::geometry::{ContactManifold, ContactManifoldIndex};
+use crate::math::SimdBool;
 use crate::math::{
-    AngVector, AngularInertia, Point, Real, SimdReal, Vector, DIM, MAX_MANIFOLD_POINTS, SIMD_WIDTH,
+    AngVector, AngularInertia, Point, Real, SimdReal, Vector, ANG_DIM, DIM, MAX_MANIFOLD_POINTS,
+    SIMD_WIDTH,
 };
 #[cfg(feature = "dim2")]
 use crate::utils::WBasis;
 use crate::utils::{self, WAngularInertia, WCross, WDot};
 use num::Zero;
-use parry::math::SimdBool;
 use simba::simd::{SimdPartialOrd, SimdValue};
 
 #[derive(Copy, Clone, Debug)]
@@ -21,7 +23,10 @@ pub(crate) struct WVelocityGroundConstraint {
     pub dir1: Vector<SimdReal>, // Non-penetration force direction for the first body.
     #[cfg(feature = "dim3")]
     pub tangent1: Vector<SimdReal>, // One of the friction force directions.
+    #[cfg(feature = "dim3")]
+    pub friction_anisotropy: SimdReal, // The scale of the second friction force direction.
     pub elements: [VelocityGroundConstraintElement<SimdReal>; MAX_MANIFOLD_POINTS],
+    pub angular_friction_part: VelocityGroundConstraintAngularFrictionPart<SimdReal>,
     pub num_contacts: u8,
     pub im2: Vector<SimdReal>,
     pub cfm_factor: SimdReal,
@@ -43,9 +48,8 @@ impl WVelocityGroundConstraint {
         let cfm_factor = SimdReal::splat(params.cfm_factor());
         let dt = SimdReal::splat(params.dt);
         let inv_dt = SimdReal::splat(params.inv_dt());
-        let allowed_lin_err = SimdReal::splat(params.allowed_linear_error);
         let erp_inv_dt = SimdReal::splat(params.erp_inv_dt());
-        let max_penetration_correction = SimdReal::splat(params.max_penetration_correction);
+        let warmstart_coeff = SimdReal::splat(params.warmstart_coefficient);
 
         let mut handles1 = gather![|ii| manifolds[ii].data.rigid_body1];
         let mut handles2 = gather![|ii| manifolds[ii].data.rigid_body2];
@@ -80,7 +84,7 @@ impl WVelocityGroundConstraint {
 
         let im2 = Vector::from(gather![|ii| mprops2[ii].effective_inv_mass]);
         let ii2: AngularInertia<SimdReal> =
-            AngularInertia::from(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
+            utils::simd_angular_inertia(gather![|ii| mprops2[ii].effective_world_inv_inertia_sqrt]);
 
         let linvel1 = Vector::from(gather![|ii| vels1[ii].linvel]);
         let angvel1 = AngVector::<SimdReal>::from(gather![|ii| vels1[ii].angvel]);
@@ -100,7 +104,36 @@ impl WVelocityGroundConstraint {
         #[cfg(feature = "dim2")]
         let tangents1 = force_dir1.orthonormal_basis();
         #[cfg(feature = "dim3")]
-        let tangents1 = super::compute_tangent_contact_directions(&force_dir1, &linvel1, &linvel2);
+        let (tangents1, friction_anisotropy) = {
+            let friction_dir = Vector::from(gather![|ii| manifolds[ii]
+                .data
+                .friction_direction
+                .unwrap_or_else(Vector::zeros)]);
+            let friction_anisotropy =
+                SimdReal::from(gather![|ii| manifolds[ii].data.friction_anisotropy]);
+            super::compute_tangent_contact_directions(
+                &force_dir1,
+                &linvel1,
+                &linvel2,
+                &friction_dir,
+                friction_anisotropy,
+            )
+        };
+
+        let rolling_friction = SimdReal::from(gather![|ii| manifolds[ii].data.rolling_friction]);
+        #[cfg(feature = "dim3")]
+        let twist_friction = SimdReal::from(gather![|ii| manifolds[ii].data.twist_friction]);
+
+        // The rolling axes, followed by the twist axis in 3D.
+        #[cfg(feature = "dim2")]
+        let angular_friction_axes: [AngVector<SimdReal>; ANG_DIM] = [SimdReal::splat(1.0)];
+        #[cfg(feature = "dim3")]
+        let angular_friction_axes: [AngVector<SimdReal>; ANG_DIM] =
+            [tangents1[0], force_dir1.cross(&tangents1[0]), force_dir1];
+        #[cfg(feature = "dim2")]
+        let angular_friction_coefficients = [rolling_friction];
+        #[cfg(feature = "dim3")]
+        let angular_friction_coefficients = [rolling_friction, rolling_friction, twist_friction];
 
         for l in (0..num_active_contacts).step_by(MAX_MANIFOLD_POINTS) {
             let manifold_points = gather![|ii| &manifolds[ii].data.solver_contacts[l..]];
@@ -111,7 +144,10 @@ impl WVelocityGroundConstraint {
                 dir1: force_dir1,
                 #[cfg(feature = "dim3")]
                 tangent1: tangents1[0],
+                #[cfg(feature = "dim3")]
+                friction_anisotropy,
                 elements: [VelocityGroundConstraintElement::zero(); MAX_MANIFOLD_POINTS],
+                angular_friction_part: VelocityGroundConstraintAngularFrictionPart::zero(),
                 im2,
                 cfm_factor,
                 limit: SimdReal::splat(0.0),
@@ -145,6 +181,10 @@ impl WVelocityGroundConstraint {
                 // Normal part.
                 {
                     let gcross2 = ii2.transform_vector(dp2.gcross(-force_dir1));
+                    let impulse = SimdReal::from(gather![|ii| manifolds[ii].points
+                        [manifold_points[ii][k].contact_id as usize]
+                        .data
+                        .impulse]);
 
                     let projected_mass = utils::simd_inv(
                         force_dir1.dot(&im2.component_mul(&force_dir1)) + gcross2.gdot(gcross2),
@@ -155,9 +195,12 @@ impl WVelocityGroundConstraint {
                         (SimdReal::splat(1.0) + is_bouncy * restitution) * projected_velocity;
                     rhs_wo_bias += dist.simd_max(SimdReal::zero()) * inv_dt;
                     rhs_wo_bias *= is_bouncy + is_resting;
-                    let rhs_bias = (dist + allowed_lin_err)
-                        .simd_clamp(-max_penetration_correction, SimdReal::zero())
-                        * (erp_inv_dt/* * is_resting */);
+                    let stiffness = SimdReal::from(gather![|ii| manifold_points[ii][k].stiffness]);
+                    let damping = SimdReal::from(gather![|ii| manifold_points[ii][k].damping]);
+                    let (contact_erp_inv_dt, contact_cfm_factor) =
+                        contact_softness(dt, erp_inv_dt, stiffness, damping, projected_mass);
+                    let rhs_bias = /* is_resting * */
+                        contact_penetration_bias(params, dist, contact_erp_inv_dt, stiffness);
 
                     let rhs = rhs_wo_bias + rhs_bias;
                     is_fast_contact =
@@ -167,8 +210,9 @@ impl WVelocityGroundConstraint {
                         gcross2,
                         rhs,
                         rhs_wo_bias,
-                        impulse: na::zero(),
+                        impulse: warmstart_coeff * impulse,
                         r: projected_mass,
+                        cfm_factor: contact_cfm_factor,
                     };
                 }
 
@@ -198,6 +242,20 @@ impl WVelocityGroundConstraint {
                 }
             }
 
+            // Angular friction part.
+            {
+                let part = &mut constraint.angular_friction_part;
+                part.coefficients = angular_friction_coefficients;
+
+                for (i, axis) in angular_friction_axes.iter().enumerate() {
+                    let gcross2 = ii2.transform_vector(-*axis);
+
+                    part.gcross2[i] = gcross2;
+                    part.rhs[i] = (angvel1 - angvel2).gdot(*axis);
+                    part.r[i] = utils::simd_inv(gcross2.gdot(gcross2));
+                }
+            }
+
             constraint.cfm_factor = SimdReal::splat(1.0).select(is_fast_contact, cfm_factor);
 
             if let Some(at) = insert_at {
@@ -228,8 +286,11 @@ impl WVelocityGroundConstraint {
             &self.dir1,
             #[cfg(feature = "dim3")]
             &self.tangent1,
+            #[cfg(feature = "dim3")]
+            self.friction_anisotropy,
             &self.im2,
             self.limit,
+            &mut self.angular_friction_part,
             &mut mj_lambda2,
             solve_normal,
             solve_friction,
@@ -241,6 +302,27 @@ impl WVelocityGroundConstraint {
         }
     }
 
+    pub fn warmstart(&self, mj_lambdas: &mut [DeltaVel<Real>]) {
+        let mut mj_lambda2 = DeltaVel {
+            linear: Vector::from(gather![|ii| mj_lambdas[self.mj_lambda2[ii] as usize].linear]),
+            angular: AngVector::from(gather![
+                |ii| mj_lambdas[self.mj_lambda2[ii] as usize].angular
+            ]),
+        };
+
+        VelocityGroundConstraintElement::warmstart_group(
+            &self.elements[..self.num_contacts as usize],
+            &self.dir1,
+            &self.im2,
+            &mut mj_lambda2,
+        );
+
+        for ii in 0..SIMD_WIDTH {
+            mj_lambdas[self.mj_lambda2[ii] as usize].linear = mj_lambda2.linear.extract(ii);
+            mj_lambdas[self.mj_lambda2[ii] as usize].angular = mj_lambda2.angular.extract(ii);
+        }
+    }
+
     // FIXME: duplicated code. This is exactly the same as in the non-ground velocity constraint.
     pub fn writeback_impulses(&self, manifolds_all: &mut [&mut ContactManifold]) {
         for k in 0..self.num_contacts as usize {
@@ -272,6 +354,7 @@ impl WVelocityGroundConstraint {
         self.cfm_factor = SimdReal::splat(1.0);
         for elt in &mut self.elements {
             elt.normal_part.rhs = elt.normal_part.rhs_wo_bias;
+            elt.normal_part.cfm_factor = na::zero();
         }
     }
 }
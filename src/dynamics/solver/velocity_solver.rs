@@ -1,9 +1,10 @@
-use super::AnyJointVelocityConstraint;
+use super::{AnyJointVelocityConstraint, ShockPropagation};
+use crate::counters::Counters;
 use crate::dynamics::{
     solver::{AnyVelocityConstraint, DeltaVel},
     IntegrationParameters, IslandManager, JointGraphEdge, MultibodyJointSet, RigidBodySet,
 };
-use crate::geometry::ContactManifold;
+use crate::geometry::{ContactManifold, ContactManifoldIndex};
 use crate::math::Real;
 use crate::utils::WAngularInertia;
 use na::DVector;
@@ -11,6 +12,15 @@ use na::DVector;
 pub(crate) struct VelocitySolver {
     pub mj_lambdas: Vec<DeltaVel<Real>>,
     pub generic_mj_lambdas: DVector<Real>,
+    // The number of velocity iterations executed by the last call to `solve`.
+    pub num_velocity_iterations: usize,
+    // The change of the delta-velocities during the last velocity iteration of the last call
+    // to `solve`. Only measured if the convergence is checked or the counters are enabled.
+    pub velocity_error: Real,
+    // The delta-velocities at the end of the previous iteration, used for early-outs.
+    prev_mj_lambdas: Vec<DeltaVel<Real>>,
+    prev_generic_mj_lambdas: DVector<Real>,
+    shock_propagation: ShockPropagation,
 }
 
 impl VelocitySolver {
@@ -18,23 +28,57 @@ impl VelocitySolver {
         Self {
             mj_lambdas: Vec::new(),
             generic_mj_lambdas: DVector::zeros(0),
+            num_velocity_iterations: 0,
+            velocity_error: 0.0,
+            prev_mj_lambdas: Vec::new(),
+            prev_generic_mj_lambdas: DVector::zeros(0),
+            shock_propagation: ShockPropagation::new(),
         }
     }
 
+    // The largest change of the delta-velocities since the last call to this method.
+    fn delta_vel_change(&mut self) -> Real {
+        let mut max_change: Real = 0.0;
+
+        self.prev_mj_lambdas
+            .resize(self.mj_lambdas.len(), DeltaVel::zero());
+        for (curr, prev) in self.mj_lambdas.iter().zip(self.prev_mj_lambdas.iter_mut()) {
+            for change in (*curr - *prev).as_slice() {
+                max_change = max_change.max(change.abs());
+            }
+            *prev = *curr;
+        }
+
+        if self.prev_generic_mj_lambdas.len() == self.generic_mj_lambdas.len() {
+            max_change =
+                max_change.max((&self.generic_mj_lambdas - &self.prev_generic_mj_lambdas).amax());
+            self.prev_generic_mj_lambdas
+                .copy_from(&self.generic_mj_lambdas);
+        } else {
+            max_change = Real::MAX;
+            self.prev_generic_mj_lambdas = self.generic_mj_lambdas.clone();
+        }
+
+        max_change
+    }
+
     pub fn solve(
         &mut self,
         island_id: usize,
+        counters: &mut Counters,
         params: &IntegrationParameters,
         islands: &IslandManager,
         bodies: &mut RigidBodySet,
         multibodies: &mut MultibodyJointSet,
         manifolds_all: &mut [&mut ContactManifold],
+        manifold_indices: &[ContactManifoldIndex],
         joints_all: &mut [JointGraphEdge],
         contact_constraints: &mut [AnyVelocityConstraint],
         generic_contact_jacobians: &DVector<Real>,
         joint_constraints: &mut [AnyJointVelocityConstraint],
         generic_joint_jacobians: &DVector<Real>,
     ) {
+        counters.solver.velocity_resolution_time.resume();
         self.mj_lambdas.clear();
         self.mj_lambdas
             .resize(islands.active_island(island_id).len(), DeltaVel::zero());
@@ -68,10 +112,46 @@ impl VelocitySolver {
             }
         }
 
+        /*
+         * Warmstart constraints.
+         */
+        if params.joint_warmstart_coefficient != 0.0 {
+            for constraint in &*joint_constraints {
+                constraint.warmstart(
+                    generic_joint_jacobians,
+                    &mut self.mj_lambdas[..],
+                    &mut self.generic_mj_lambdas,
+                );
+            }
+        }
+
+        if params.warmstart_coefficient != 0.0 {
+            for constraint in &*contact_constraints {
+                constraint.warmstart(&mut self.mj_lambdas[..]);
+            }
+        }
+
         /*
          * Solve constraints.
          */
+        let check_velocity_convergence = params.velocity_iterations_tolerance > 0.0;
+        // If the convergence isn’t checked, the error is only measured for the last iteration.
+        let measure_last_velocity_error = counters.enabled() && !check_velocity_convergence;
+        let mut num_friction_iterations = 0;
+        self.num_velocity_iterations = 0;
+        self.velocity_error = 0.0;
+
+        if check_velocity_convergence {
+            let _ = self.delta_vel_change();
+        }
+
         for i in 0..params.max_velocity_iterations {
+            let last_iteration = i + 1 == params.max_velocity_iterations;
+
+            if measure_last_velocity_error && last_iteration {
+                let _ = self.delta_vel_change();
+            }
+
             let solve_friction = params.interleave_restitution_and_friction_resolution
                 && params.max_velocity_friction_iterations + i >= params.max_velocity_iterations;
 
@@ -103,17 +183,23 @@ impl VelocitySolver {
                         true,
                     );
                 }
+                num_friction_iterations += 1;
+            }
+
+            self.num_velocity_iterations += 1;
+
+            if check_velocity_convergence || measure_last_velocity_error && last_iteration {
+                self.velocity_error = self.delta_vel_change();
+
+                if self.velocity_error < params.velocity_iterations_tolerance {
+                    break;
+                }
             }
         }
 
-        let remaining_friction_iterations =
-            if !params.interleave_restitution_and_friction_resolution {
-                params.max_velocity_friction_iterations
-            } else if params.max_velocity_friction_iterations > params.max_velocity_iterations {
-                params.max_velocity_friction_iterations - params.max_velocity_iterations
-            } else {
-                0
-            };
+        let remaining_friction_iterations = params
+            .max_velocity_friction_iterations
+            .saturating_sub(num_friction_iterations);
 
         for _ in 0..remaining_friction_iterations {
             for constraint in &mut *contact_constraints {
@@ -125,6 +211,29 @@ impl VelocitySolver {
                     true,
                 );
             }
+
+            if check_velocity_convergence
+                && self.delta_vel_change() < params.velocity_iterations_tolerance
+            {
+                break;
+            }
+        }
+
+        counters.solver.velocity_resolution_time.pause();
+
+        counters.solver.position_resolution_time.resume();
+
+        if params.shock_propagation {
+            self.shock_propagation.solve(
+                island_id,
+                params,
+                islands,
+                bodies,
+                multibodies,
+                manifolds_all,
+                manifold_indices,
+                &mut self.mj_lambdas,
+            );
         }
 
         // Integrate positions.
@@ -176,6 +285,12 @@ impl VelocitySolver {
             constraint.remove_bias_from_rhs();
         }
 
+        let check_stabilization_convergence = params.stabilization_iterations_tolerance > 0.0;
+
+        if check_stabilization_convergence && params.max_stabilization_iterations > 0 {
+            let _ = self.delta_vel_change();
+        }
+
         for _ in 0..params.max_stabilization_iterations {
             for constraint in &mut *joint_constraints {
                 constraint.solve(
@@ -204,9 +319,18 @@ impl VelocitySolver {
                     true,
                 );
             }
+
+            if check_stabilization_convergence
+                && self.delta_vel_change() < params.stabilization_iterations_tolerance
+            {
+                break;
+            }
         }
 
+        counters.solver.position_resolution_time.pause();
+
         // Update velocities.
+        counters.solver.velocity_update_time.resume();
         for handle in islands.active_island(island_id) {
             if let Some(link) = multibodies.rigid_body_link(*handle).copied() {
                 let multibody = multibodies
@@ -241,5 +365,6 @@ impl VelocitySolver {
         for constraint in &*contact_constraints {
             constraint.writeback_impulses(manifolds_all);
         }
+        counters.solver.velocity_update_time.pause();
     }
 }
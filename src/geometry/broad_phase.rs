@@ -0,0 +1,48 @@
+use crate::geometry::{Aabb, BroadPhasePairEvent, ColliderHandle, ColliderSet};
+use crate::math::Real;
+
+/// A trait implemented by broad-phase algorithms supported by Rapier.
+///
+/// The task of a broad-phase algorithm is to detect all pairs of colliders with overlapping
+/// AABBs, and to report these pairs to the narrow-phase as [`BroadPhasePairEvent`]s. Rapier
+/// provides two broad-phases: [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap)
+/// (the [`DefaultBroadPhase`](crate::geometry::DefaultBroadPhase)), and
+/// [`BroadPhaseBvh`](crate::geometry::BroadPhaseBvh), better suited for worlds where the
+/// colliders are spread in clusters across a large space.
+pub trait BroadPhase: Send + Sync + 'static {
+    /// Updates the broad-phase, taking into account the new collider positions.
+    ///
+    /// The pairs of colliders that started or stopped overlapping since the last update must
+    /// be pushed to `events`. Reporting a pair that already overlapped at the last update is
+    /// allowed, but less efficient. The pairs involving a removed collider don’t have to be
+    /// reported.
+    ///
+    /// # Parameters
+    /// - `prediction_distance`: the AABBs of the colliders must be enlarged by half this
+    ///   distance (and by their contact skin) before checking their overlaps.
+    /// - `colliders`: the set of colliders. Only the `modified_colliders` have to be read.
+    /// - `modified_colliders`: the colliders inserted or modified since the last update,
+    ///   including the ones that moved.
+    /// - `removed_colliders`: the colliders removed or disabled since the last update.
+    /// - `events`: the buffer the broad-phase events must be pushed to.
+    fn update(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    );
+
+    /// Calls `callback` on the handle of each collider with an Aabb intersecting `aabb`.
+    ///
+    /// This only relies on the Aabbs stored by the broad-phase (enlarged by the prediction
+    /// distance and contact skins of the colliders), without any narrow-phase check, so it may
+    /// report colliders that don’t actually intersect `aabb`. This makes it a cheap way to find
+    /// the colliders located in a given region of space, e.g., for streaming or interest
+    /// management. The broad-phase reflects the collider positions at its last update.
+    ///
+    /// Each collider is reported at most once. The query stops as soon as `callback` returns
+    /// `false`.
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(&ColliderHandle) -> bool);
+}
@@ -3,8 +3,8 @@ use super::{
 };
 use crate::geometry::broad_phase_multi_sap::SAPProxyIndex;
 use crate::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition, ColliderSet,
-    ColliderShape,
+    Aabb, BroadPhase, ColliderBroadPhaseData, ColliderChanges, ColliderHandle, ColliderPosition,
+    ColliderSet, ColliderShape,
 };
 use crate::math::Real;
 use crate::utils::IndexMut2;
@@ -74,7 +74,7 @@ use parry::utils::hashmap::HashMap;
 ///   broad-phase, as well as the Aabbs of all the regions part of this broad-phase.
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct BroadPhase {
+pub struct BroadPhaseMultiSap {
     proxies: SAPProxies,
     layers: Vec<SAPLayer>,
     smallest_layer: u8,
@@ -114,16 +114,16 @@ pub struct BroadPhase {
     reporting: HashMap<(u32, u32), bool>, // Workspace
 }
 
-impl Default for BroadPhase {
+impl Default for BroadPhaseMultiSap {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BroadPhase {
+impl BroadPhaseMultiSap {
     /// Create a new empty broad-phase.
     pub fn new() -> Self {
-        BroadPhase {
+        BroadPhaseMultiSap {
             proxies: SAPProxies::new(),
             layers: Vec::new(),
             smallest_layer: 0,
@@ -138,7 +138,7 @@ impl BroadPhase {
     ///
     /// For each colliders marked as removed, we make their containing layer mark
     /// its proxy as pre-deleted. The actual proxy removal will happen at the end
-    /// of the `BroadPhase::update`.
+    /// of the `BroadPhaseMultiSap::update`.
     fn handle_removed_colliders(&mut self, removed_colliders: &[ColliderHandle]) {
         // For each removed collider, remove the corresponding proxy.
         for removed in removed_colliders {
@@ -353,13 +353,13 @@ impl BroadPhase {
         prediction_distance: Real,
         handle: ColliderHandle,
         proxy_index: &mut u32,
-        collider: (&ColliderPosition, &ColliderShape, &ColliderChanges),
+        collider: (&ColliderPosition, &ColliderShape, &ColliderChanges, Real),
     ) -> bool {
-        let (co_pos, co_shape, co_changes) = collider;
+        let (co_pos, co_shape, co_changes, co_contact_skin) = collider;
 
         let mut aabb = co_shape
             .compute_aabb(co_pos)
-            .loosened(prediction_distance / 2.0);
+            .loosened(prediction_distance / 2.0 + co_contact_skin);
 
         if aabb.mins.coords.iter().any(|e| !e.is_finite())
             || aabb.maxs.coords.iter().any(|e| !e.is_finite())
@@ -450,65 +450,6 @@ impl BroadPhase {
         need_region_propagation
     }
 
-    /// Updates the broad-phase, taking into account the new collider positions.
-    pub fn update(
-        &mut self,
-        prediction_distance: Real,
-        colliders: &mut ColliderSet,
-        modified_colliders: &[ColliderHandle],
-        removed_colliders: &[ColliderHandle],
-        events: &mut Vec<BroadPhasePairEvent>,
-    ) {
-        // Phase 1: pre-delete the collisions that have been deleted.
-        self.handle_removed_colliders(removed_colliders);
-
-        let mut need_region_propagation = false;
-
-        // Phase 2: pre-delete the collisions that have been deleted.
-        for handle in modified_colliders {
-            // NOTE: we use `get` because the collider may no longer
-            //       exist if it has been removed.
-            if let Some(co) = colliders.get_mut_internal(*handle) {
-                if !co.is_enabled() || !co.changes.needs_broad_phase_update() {
-                    continue;
-                }
-
-                let mut new_proxy_id = co.bf_data.proxy_index;
-
-                if self.handle_modified_collider(
-                    prediction_distance,
-                    *handle,
-                    &mut new_proxy_id,
-                    (&co.pos, &co.shape, &co.changes),
-                ) {
-                    need_region_propagation = true;
-                }
-
-                if co.bf_data.proxy_index != new_proxy_id {
-                    self.colliders_proxy_ids.insert(*handle, new_proxy_id);
-
-                    // Make sure we have the new proxy index in case
-                    // the collider was added for the first time.
-                    co.bf_data = ColliderBroadPhaseData {
-                        proxy_index: new_proxy_id,
-                    };
-                }
-            }
-        }
-
-        // Phase 3: bottom-up pass to propagate new regions from smaller layers to larger layers.
-        if need_region_propagation {
-            self.propagate_created_regions();
-        }
-
-        // Phase 4: top-down pass to propagate proxies from larger layers to smaller layers.
-        self.update_layers_and_find_pairs(events);
-
-        // Phase 5: bottom-up pass to remove proxies, and propagate region removed from smaller
-        // layers to possible remove regions from larger layers that would become empty that way.
-        self.complete_removals(colliders, removed_colliders);
-    }
-
     /// Propagate regions from the smallest layers up to the larger layers.
     ///
     /// Whenever a region is created on a layer `n`, then its Aabb must be
@@ -619,16 +560,87 @@ impl BroadPhase {
     }
 }
 
+impl BroadPhase for BroadPhaseMultiSap {
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(&ColliderHandle) -> bool) {
+        for layer in &self.layers {
+            if !layer.colliders_in_aabb(aabb, &self.proxies, callback) {
+                return;
+            }
+        }
+    }
+
+    /// Updates the broad-phase, taking into account the new collider positions.
+    fn update(
+        &mut self,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        // Phase 1: pre-delete the collisions that have been deleted.
+        self.handle_removed_colliders(removed_colliders);
+
+        let mut need_region_propagation = false;
+
+        // Phase 2: pre-delete the collisions that have been deleted.
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            if let Some(co) = colliders.get_mut_internal(*handle) {
+                if !co.is_enabled() || !co.changes.needs_broad_phase_update() {
+                    continue;
+                }
+
+                let mut new_proxy_id = co.bf_data.proxy_index;
+
+                if self.handle_modified_collider(
+                    prediction_distance,
+                    *handle,
+                    &mut new_proxy_id,
+                    (&co.pos, &co.shape, &co.changes, co.contact_skin()),
+                ) {
+                    need_region_propagation = true;
+                }
+
+                if co.bf_data.proxy_index != new_proxy_id {
+                    self.colliders_proxy_ids.insert(*handle, new_proxy_id);
+
+                    // Make sure we have the new proxy index in case
+                    // the collider was added for the first time.
+                    co.bf_data = ColliderBroadPhaseData {
+                        proxy_index: new_proxy_id,
+                    };
+                }
+            }
+        }
+
+        // Phase 3: bottom-up pass to propagate new regions from smaller layers to larger layers.
+        if need_region_propagation {
+            self.propagate_created_regions();
+        }
+
+        // Phase 4: top-down pass to propagate proxies from larger layers to smaller layers.
+        self.update_layers_and_find_pairs(events);
+
+        // Phase 5: bottom-up pass to remove proxies, and propagate region removed from smaller
+        // layers to possible remove regions from larger layers that would become empty that way.
+        self.complete_removals(colliders, removed_colliders);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::dynamics::{
         ImpulseJointSet, IslandManager, MultibodyJointSet, RigidBodyBuilder, RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet};
+    use crate::geometry::{
+        BroadPhase, BroadPhaseMultiSap, ColliderBuilder, ColliderPair, ColliderSet,
+    };
 
     #[test]
     fn test_add_update_remove() {
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = BroadPhaseMultiSap::new();
         let mut bodies = RigidBodySet::new();
         let mut colliders = ColliderSet::new();
         let mut impulse_joints = ImpulseJointSet::new();
@@ -662,4 +674,111 @@ mod test {
         // Make sure the proxy handles is recycled properly.
         broad_phase.update(0.0, &mut colliders, &[coh], &[], &mut events);
     }
+
+    #[test]
+    fn distant_colliders_are_partitioned_into_regions() {
+        use crate::geometry::BroadPhasePairEvent;
+        use crate::math::{Real, Vector};
+
+        let mut broad_phase = BroadPhaseMultiSap::new();
+        let mut colliders = ColliderSet::new();
+
+        // Static colliders spread across a 10 km wide world.
+        let mut handles = vec![];
+        for i in 0..40 {
+            for j in 0..25 {
+                let pos = Vector::x() * (i as Real * 250.0) + Vector::y() * (j as Real * 400.0);
+                handles.push(colliders.insert(ColliderBuilder::ball(0.5).translation(pos)));
+            }
+        }
+
+        let mut events = Vec::new();
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+        assert!(events.is_empty());
+
+        // Insert a collider touching the last one.
+        let pos = Vector::x() * 9_750.5 + Vector::y() * 9_600.0;
+        let new_handle = colliders.insert(ColliderBuilder::ball(0.5).translation(pos));
+        broad_phase.update(0.0, &mut colliders, &[new_handle], &[], &mut events);
+        assert_eq!(
+            events,
+            [BroadPhasePairEvent::AddPair(ColliderPair::new(
+                *handles.last().unwrap(),
+                new_handle
+            ))]
+        );
+
+        // Each region only keeps the endpoints of the few proxies it contains, so far-away
+        // colliders never affect the cost of updating a region.
+        let mut num_regions = 0;
+        for layer in &broad_phase.layers {
+            for region_id in layer.regions.values() {
+                let region = broad_phase.proxies[*region_id].data.as_region();
+                for axis in &region.axes {
+                    // Two sentinels, and two endpoints per proxy.
+                    assert!(axis.endpoints.len() <= 2 + 2 * 2);
+                }
+                num_regions += 1;
+            }
+        }
+        assert!(num_regions >= handles.len());
+    }
+
+    #[test]
+    fn colliders_in_aabb_match_their_aabbs() {
+        use crate::geometry::Aabb;
+        use crate::math::{Point, Real, Vector};
+        use parry::bounding_volume::BoundingVolume;
+
+        let mut broad_phase = BroadPhaseMultiSap::new();
+        let mut colliders = ColliderSet::new();
+
+        // Colliders of very different sizes, so they end up on several layers.
+        let mut handles = vec![];
+        for i in 0..30 {
+            let radius = [0.1, 1.0, 20.0][i % 3];
+            let pos = Vector::x() * (i as Real * 7.3) + Vector::y() * ((i * i) as Real % 13.0);
+            handles.push(colliders.insert(ColliderBuilder::ball(radius).translation(pos)));
+        }
+
+        let mut events = Vec::new();
+        broad_phase.update(0.0, &mut colliders, &handles, &[], &mut events);
+
+        let cube = |mins: Real, maxs: Real| {
+            Aabb::new(
+                Point::origin() + Vector::repeat(mins),
+                Point::origin() + Vector::repeat(maxs),
+            )
+        };
+
+        for aabb in [
+            cube(-1.0, 1.0),
+            cube(10.0, 30.0),
+            cube(-100.0, 300.0),
+            cube(500.0, 600.0),
+        ] {
+            let mut found = vec![];
+            broad_phase.colliders_in_aabb(&aabb, &mut |handle| {
+                found.push(*handle);
+                true
+            });
+
+            let mut expected: Vec<_> = handles
+                .iter()
+                .copied()
+                .filter(|h| colliders[*h].compute_aabb().intersects(&aabb))
+                .collect();
+            found.sort_by_key(|h| h.into_raw_parts());
+            expected.sort_by_key(|h| h.into_raw_parts());
+            assert_eq!(found, expected);
+        }
+
+        // The query stops as soon as the callback returns `false`.
+        let mut num_found = 0;
+        broad_phase.colliders_in_aabb(&cube(-1000.0, 1000.0), &mut |_| {
+            num_found += 1;
+            false
+        });
+        assert_eq!(num_found, 1);
+    }
 }
@@ -1,6 +1,6 @@
-use super::{SAPProxies, SAPProxy, SAPRegion, SAPRegionPool};
+use super::{SAPProxies, SAPProxy, SAPProxyData, SAPRegion, SAPRegionPool};
 use crate::geometry::broad_phase_multi_sap::DELETED_AABB_VALUE;
-use crate::geometry::{Aabb, SAPProxyIndex};
+use crate::geometry::{Aabb, ColliderHandle, SAPProxyIndex};
 use crate::math::{Point, Real};
 use parry::bounding_volume::BoundingVolume;
 use parry::utils::hashmap::{Entry, HashMap};
@@ -39,6 +39,94 @@ impl SAPLayer {
         }
     }
 
+    /// Calls `callback` on each collider proxy of this layer with an Aabb intersecting `aabb`.
+    ///
+    /// Each collider is reported at most once, even if it intersects several regions. Returns
+    /// `false` if `callback` returned `false`, interrupting the query.
+    pub fn colliders_in_aabb(
+        &self,
+        aabb: &Aabb,
+        proxies: &SAPProxies,
+        callback: &mut dyn FnMut(&ColliderHandle) -> bool,
+    ) -> bool {
+        let mins = super::point_key(aabb.mins, self.region_width);
+        let maxs = super::point_key(aabb.maxs, self.region_width);
+        let num_keys = (maxs - mins)
+            .iter()
+            .map(|e| *e as u64 + 1)
+            .fold(1u64, |a, b| a.saturating_mul(b));
+
+        let mut visit_region = |region_key: &Point<i32>, region_id: &SAPProxyIndex| {
+            let region = proxies[*region_id].data.as_region();
+
+            for endpoint in &region.axes[0].endpoints {
+                if endpoint.is_sentinel() || !endpoint.is_start() {
+                    continue;
+                }
+
+                let proxy = &proxies[endpoint.proxy()];
+
+                if let SAPProxyData::Collider(handle) = &proxy.data {
+                    // NOTE: a collider is reported only by the region containing the
+                    //       smallest corner of its intersection with `aabb`, so that it
+                    //       isn’t reported once per region.
+                    if proxy.layer_id == self.layer_id
+                        && proxy.aabb.intersects(aabb)
+                        && super::point_key(proxy.aabb.mins.sup(&aabb.mins), self.region_width)
+                            == *region_key
+                        && !callback(handle)
+                    {
+                        return false;
+                    }
+                }
+            }
+
+            true
+        };
+
+        if num_keys < self.regions.len() as u64 {
+            // Few regions intersect the Aabb, look them up directly.
+            #[cfg(feature = "dim2")]
+            for i in mins.x..=maxs.x {
+                for j in mins.y..=maxs.y {
+                    let key = Point::new(i, j);
+                    if let Some(region_id) = self.regions.get(&key) {
+                        if !visit_region(&key, region_id) {
+                            return false;
+                        }
+                    }
+                }
+            }
+
+            #[cfg(feature = "dim3")]
+            for i in mins.x..=maxs.x {
+                for j in mins.y..=maxs.y {
+                    for k in mins.z..=maxs.z {
+                        let key = Point::new(i, j, k);
+                        if let Some(region_id) = self.regions.get(&key) {
+                            if !visit_region(&key, region_id) {
+                                return false;
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            for (key, region_id) in &self.regions {
+                let in_range = key
+                    .iter()
+                    .zip(mins.iter().zip(maxs.iter()))
+                    .all(|(k, (min, max))| k >= min && k <= max);
+
+                if in_range && !visit_region(key, region_id) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
     /// Deletes from all the regions of this layer, all the endpoints corresponding
     /// to subregions. Clears the arrays of subregions indices from all the regions of
     /// this layer.
@@ -71,7 +159,7 @@ impl SAPLayer {
     ///
     /// This method must be called in a bottom-up loop, propagating new regions from the
     /// smallest layer, up to the largest layer. That loop is done by the Phase 3 of the
-    /// BroadPhase::update.
+    /// BroadPhaseMultiSap::update.
     pub fn propagate_created_regions(
         &mut self,
         larger_layer: &mut Self,
@@ -182,7 +270,7 @@ impl SAPLayer {
     /// If the region with the given region key does not exist yet, it is created.
     /// When a region is created, it creates a new proxy for that region, and its
     /// proxy ID is added to `self.created_region` so it can be propagated during
-    /// the Phase 3 of `BroadPhase::update`.
+    /// the Phase 3 of `BroadPhaseMultiSap::update`.
     ///
     /// This returns the proxy ID of the already existing region if it existed, or
     /// of the new region if it did not exist and has been created by this method.
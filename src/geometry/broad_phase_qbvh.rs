@@ -1,88 +1,357 @@
-use crate::geometry::{BroadPhasePairEvent, ColliderHandle, ColliderPair, ColliderSet};
+use crate::geometry::{
+    Aabb, BroadPhase, BroadPhasePairEvent, ColliderHandle, ColliderPair, ColliderSet,
+};
+use crate::utils;
 use parry::bounding_volume::BoundingVolume;
 use parry::math::Real;
 use parry::partitioning::Qbvh;
-use parry::partitioning::QbvhUpdateWorkspace;
-use parry::query::visitors::BoundingVolumeIntersectionsSimultaneousVisitor;
+use parry::partitioning::{QbvhDataGenerator, QbvhUpdateWorkspace};
+use parry::query::visitors::BoundingVolumeIntersectionsVisitor;
+use parry::utils::hashmap::HashMap;
 
+/// A broad-phase based on a dynamic AABB tree.
+///
+/// Each collider is stored in a bounding volume hierarchy that is refitted and rebalanced
+/// incrementally as the colliders move. Contrary to the
+/// [`BroadPhaseMultiSap`](crate::geometry::BroadPhaseMultiSap), its performances don’t depend
+/// on how the colliders are distributed in space. This makes it well suited for very large
+/// worlds where colliders are spread in clusters far away from each other.
+///
+/// Its tree can be reused by the [`QueryPipeline`](crate::pipeline::QueryPipeline) with
+/// [`QueryPipeline::update_from_broad_phase`](crate::pipeline::QueryPipeline::update_from_broad_phase).
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
 #[derive(Clone)]
-pub struct BroadPhase {
+pub struct BroadPhaseBvh {
     qbvh: Qbvh<ColliderHandle>,
-    stack: Vec<(u32, u32)>,
+    // The pairs with overlapping AABBs, and the timestamp of the last update they were found
+    // overlapping at.
+    #[cfg_attr(
+        feature = "serde-serialize",
+        serde(
+            serialize_with = "parry::utils::hashmap::serialize_hashmap_capacity",
+            deserialize_with = "parry::utils::hashmap::deserialize_hashmap_capacity"
+        )
+    )]
+    pairs: HashMap<ColliderPair, u32>,
+    timestamp: u32,
     #[cfg_attr(feature = "serde-serialize", serde(skip))]
     workspace: QbvhUpdateWorkspace,
+    // The colliders updated during the last update, associated to `true` if they were removed.
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    changed_colliders: HashMap<ColliderHandle, bool>,
+    #[cfg_attr(feature = "serde-serialize", serde(skip))]
+    intersections: Vec<ColliderHandle>,
 }
 
-impl Default for BroadPhase {
+impl Default for BroadPhaseBvh {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BroadPhase {
+impl BroadPhaseBvh {
+    /// The margin added to the AABBs stored in the tree, to avoid updating them when the
+    /// colliders only move slightly.
+    const MARGIN: Real = 0.01;
+
+    /// Create a new empty broad-phase.
     pub fn new() -> Self {
         Self {
             qbvh: Qbvh::new(),
-            stack: vec![],
+            pairs: HashMap::default(),
+            timestamp: 0,
             workspace: QbvhUpdateWorkspace::default(),
+            changed_colliders: HashMap::default(),
+            intersections: vec![],
         }
     }
 
-    #[allow(dead_code)] // This broad-phase is just experimental right now.
-    pub fn update(
+    /// The tree containing the AABBs of all the enabled colliders.
+    ///
+    /// These AABBs are enlarged by the prediction distance and the contact skin of the
+    /// colliders, as well as by a small margin.
+    pub fn qbvh(&self) -> &Qbvh<ColliderHandle> {
+        &self.qbvh
+    }
+}
+
+impl BroadPhase for BroadPhaseBvh {
+    fn update(
         &mut self,
         prediction_distance: Real,
-        colliders: &ColliderSet,
+        colliders: &mut ColliderSet,
         modified_colliders: &[ColliderHandle],
         removed_colliders: &[ColliderHandle],
         events: &mut Vec<BroadPhasePairEvent>,
     ) {
-        let margin = 0.01;
+        self.timestamp = self.timestamp.wrapping_add(1);
+        self.changed_colliders.clear();
+
+        // NOTE: we remove first to handle the case where a removed collider and a collider
+        //       inserted afterward share the same index.
+        for removed in removed_colliders {
+            let _ = self.qbvh.remove(*removed);
+            let _ = self.changed_colliders.insert(*removed, true);
+        }
 
-        if modified_colliders.is_empty() {
+        let mut needs_rebuild = false;
+
+        for handle in modified_colliders {
+            // NOTE: we use `get` because the collider may no longer
+            //       exist if it has been removed.
+            if let Some(co) = colliders.get(*handle) {
+                if !co.is_enabled() {
+                    let _ = self.qbvh.remove(*handle);
+                    let _ = self.changed_colliders.insert(*handle, true);
+                } else if co.changes.needs_broad_phase_update() {
+                    if !utils::qbvh_contains(&self.qbvh, *handle)
+                        && !utils::qbvh_can_insert(&self.qbvh)
+                    {
+                        needs_rebuild = true;
+                    } else if !needs_rebuild {
+                        self.qbvh.pre_update_or_insert(*handle);
+                    }
+                    let _ = self.changed_colliders.insert(*handle, false);
+                }
+            }
+        }
+
+        if self.changed_colliders.is_empty() {
             return;
         }
 
-        // Visitor to find collision pairs.
-        let mut visitor = BoundingVolumeIntersectionsSimultaneousVisitor::new(
-            |co1: &ColliderHandle, co2: &ColliderHandle| {
-                events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(*co1, *co2)));
-                true
-            },
-        );
+        let loosened_aabb = |handle: &ColliderHandle| {
+            let co = &colliders[*handle];
+            co.compute_aabb()
+                .loosened(prediction_distance / 2.0 + co.contact_skin())
+        };
+
+        if needs_rebuild {
+            struct DataGenerator<'a, F>(&'a ColliderSet, F);
+
+            impl<'a, F: Fn(&ColliderHandle) -> Aabb> QbvhDataGenerator<ColliderHandle>
+                for DataGenerator<'a, F>
+            {
+                fn size_hint(&self) -> usize {
+                    self.0.len()
+                }
+
+                fn for_each(&mut self, mut f: impl FnMut(ColliderHandle, Aabb)) {
+                    for (handle, _) in self.0.iter_enabled() {
+                        f(handle, (self.1)(&handle))
+                    }
+                }
+            }
 
-        let full_rebuild = self.qbvh.raw_nodes().is_empty();
-
-        if full_rebuild {
-            self.qbvh.clear_and_rebuild(
-                colliders.iter().map(|(handle, collider)| {
-                    (
-                        handle,
-                        collider.compute_aabb().loosened(prediction_distance / 2.0),
-                    )
-                }),
-                margin,
-            );
             self.qbvh
-                .traverse_bvtt_with_stack(&self.qbvh, &mut visitor, &mut self.stack);
+                .clear_and_rebuild(DataGenerator(colliders, loosened_aabb), 0.0);
         } else {
-            for modified in modified_colliders {
-                self.qbvh.pre_update_or_insert(*modified);
+            // NOTE: colliders moving within the margin of their AABB don’t change the tree, so
+            //       there is nothing to rebalance.
+            let num_changed = self
+                .qbvh
+                .refit(Self::MARGIN, &mut self.workspace, loosened_aabb);
+            if num_changed > 0 {
+                self.qbvh.rebalance(Self::MARGIN, &mut self.workspace);
             }
+        }
 
-            for removed in removed_colliders {
-                self.qbvh.remove(*removed);
+        // Find all the pairs involving a collider that moved.
+        for handle in modified_colliders {
+            if self.changed_colliders.get(handle) != Some(&false) {
+                continue;
             }
 
-            let _ = self.qbvh.refit(margin, &mut self.workspace, |handle| {
-                colliders[*handle]
-                    .compute_aabb()
-                    .loosened(prediction_distance / 2.0)
-            });
+            self.intersections.clear();
             self.qbvh
-                .traverse_modified_bvtt_with_stack(&self.qbvh, &mut visitor, &mut self.stack);
-            self.qbvh.rebalance(margin, &mut self.workspace);
+                .intersect_aabb(&loosened_aabb(handle), &mut self.intersections);
+
+            for other in &self.intersections {
+                if other == handle {
+                    continue;
+                }
+
+                let pair = if handle.into_raw_parts() < other.into_raw_parts() {
+                    ColliderPair::new(*handle, *other)
+                } else {
+                    ColliderPair::new(*other, *handle)
+                };
+
+                if self.pairs.insert(pair, self.timestamp).is_none() {
+                    events.push(BroadPhasePairEvent::AddPair(pair));
+                }
+            }
         }
+
+        // Remove the pairs that are no longer overlapping. The pairs involving a removed collider
+        // are handled by the narrow-phase directly.
+        let timestamp = self.timestamp;
+        let changed_colliders = &self.changed_colliders;
+        self.pairs.retain(|pair, last_seen| {
+            let removed1 = changed_colliders.get(&pair.collider1).copied();
+            let removed2 = changed_colliders.get(&pair.collider2).copied();
+
+            if removed1 == Some(true) || removed2 == Some(true) {
+                false
+            } else if *last_seen != timestamp && (removed1.is_some() || removed2.is_some()) {
+                events.push(BroadPhasePairEvent::DeletePair(*pair));
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn colliders_in_aabb(&self, aabb: &Aabb, callback: &mut dyn FnMut(&ColliderHandle) -> bool) {
+        let mut visitor = BoundingVolumeIntersectionsVisitor::new(aabb, callback);
+        self.qbvh.traverse_depth_first(&mut visitor);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+        RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::{
+        Aabb, BroadPhase, BroadPhaseBvh, BroadPhasePairEvent, ColliderBuilder, ColliderPair,
+        ColliderSet, DefaultBroadPhase, NarrowPhase,
+    };
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn pairs_are_added_and_deleted() {
+        let mut broad_phase = BroadPhaseBvh::new();
+        let mut colliders = ColliderSet::new();
+
+        let co1 = colliders.insert(ColliderBuilder::ball(0.5));
+        let co2 = colliders.insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 0.8));
+        let co3 = colliders.insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 100.0));
+
+        let mut events = Vec::new();
+        broad_phase.update(0.0, &mut colliders, &[co1, co2, co3], &[], &mut events);
+        assert_eq!(
+            events,
+            [BroadPhasePairEvent::AddPair(ColliderPair::new(co1, co2))]
+        );
+
+        // Moving the second collider next to the third one.
+        events.clear();
+        colliders[co2].set_translation(Vector::x() * 99.2);
+        broad_phase.update(0.0, &mut colliders, &[co2], &[], &mut events);
+        assert_eq!(
+            events,
+            [
+                BroadPhasePairEvent::AddPair(ColliderPair::new(co2, co3)),
+                BroadPhasePairEvent::DeletePair(ColliderPair::new(co1, co2)),
+            ]
+        );
+
+        // The pairs of removed colliders are silently dropped.
+        events.clear();
+        let _ = colliders.remove(co3, &mut Default::default(), &mut Default::default(), false);
+        broad_phase.update(0.0, &mut colliders, &[], &[co3], &mut events);
+        assert!(events.is_empty());
+
+        colliders[co2].set_translation(Vector::x() * 0.8);
+        broad_phase.update(0.0, &mut colliders, &[co2], &[], &mut events);
+        assert_eq!(
+            events,
+            [BroadPhasePairEvent::AddPair(ColliderPair::new(co1, co2))]
+        );
+
+        let mut found = vec![];
+        let aabb = Aabb::new(Point::origin(), Point::origin() + Vector::x() * 10.0);
+        broad_phase.colliders_in_aabb(&aabb, &mut |handle| {
+            found.push(*handle);
+            true
+        });
+        found.sort_by_key(|h| h.into_raw_parts());
+        assert_eq!(found, [co1, co2]);
+    }
+
+    #[test]
+    fn bvh_broad_phase_simulates_like_the_default_one() {
+        use crate::geometry::Ray;
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+
+        // Two far-away clusters, each made of a box falling on a ground.
+        fn simulate(broad_phase: &mut dyn BroadPhase) -> (Vec<Vector<Real>>, ColliderSet) {
+            let mut pipeline = PhysicsPipeline::new();
+            let integration_parameters = IntegrationParameters::default();
+            let mut narrow_phase = NarrowPhase::new();
+            let mut bodies = RigidBodySet::new();
+            let mut colliders = ColliderSet::new();
+            let mut ccd = CCDSolver::new();
+            let mut impulse_joints = ImpulseJointSet::new();
+            let mut multibody_joints = MultibodyJointSet::new();
+            let mut islands = IslandManager::new();
+
+            let mut handles = vec![];
+            for offset in [0.0, 10_000.0] {
+                let (ground, cube) = (cuboid(5.0, 0.5), cuboid(0.5, 0.5));
+                colliders.insert(ground.translation(Vector::x() * offset));
+
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * offset + Vector::y() * 2.0)
+                    .build();
+                let body = bodies.insert(body);
+                colliders.insert_with_parent(cube, body, &mut bodies);
+                handles.push(body);
+            }
+
+            for _ in 0..120 {
+                pipeline.step(
+                    &(Vector::y() * -9.81),
+                    &integration_parameters,
+                    &mut islands,
+                    broad_phase,
+                    &mut narrow_phase,
+                    &mut bodies,
+                    &mut colliders,
+                    &mut impulse_joints,
+                    &mut multibody_joints,
+                    &mut ccd,
+                    None,
+                    &(),
+                    &(),
+                );
+            }
+
+            let positions = handles.iter().map(|h| *bodies[*h].translation()).collect();
+            (positions, colliders)
+        }
+
+        let (expected, _) = simulate(&mut DefaultBroadPhase::new());
+        let mut broad_phase = BroadPhaseBvh::new();
+        let (positions, colliders) = simulate(&mut broad_phase);
+
+        for (pos, expected) in positions.iter().zip(expected.iter()) {
+            assert!((pos - expected).norm() < 1.0e-3, "{} != {}", pos, expected);
+            assert!((pos.y - 1.0).abs() < 1.0e-2, "{}", pos);
+        }
+
+        // The query pipeline can reuse the tree of the broad-phase.
+        let bodies = RigidBodySet::new();
+        let mut queries = QueryPipeline::new();
+        queries.update_from_broad_phase(&broad_phase);
+        let ray = Ray::new(
+            Point::origin() + Vector::x() * 10_000.0 + Vector::y() * 10.0,
+            -Vector::y(),
+        );
+        let (_, toi) = queries
+            .cast_ray(
+                &bodies,
+                &colliders,
+                &ray,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert!((toi - 8.5).abs() < 1.0e-2, "{}", toi);
     }
 }
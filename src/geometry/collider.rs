@@ -2,14 +2,17 @@ use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle};
 use crate::geometry::{
     ActiveCollisionTypes, ColliderBroadPhaseData, ColliderChanges, ColliderFlags,
     ColliderMassProps, ColliderMaterial, ColliderParent, ColliderPosition, ColliderShape,
-    ColliderType, InteractionGroups, SharedShape,
+    ColliderType, FluidVolume, InteractionGroups, MaterialHandle, SharedShape, TypedShape, Voxels,
 };
-use crate::math::{AngVector, Isometry, Point, Real, Rotation, Vector, DIM};
+use crate::math::{AngVector, Isometry, Point, Real, Rotation, UnitVector, Vector, DIM};
 use crate::parry::transformation::vhacd::VHACDParameters;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
 use crate::prelude::ColliderEnabled;
+use crate::utils::WCross;
 use na::Unit;
-use parry::bounding_volume::Aabb;
+use num::Zero;
+use parry::bounding_volume::{Aabb, BoundingSphere, BoundingVolume};
+use parry::either::Either;
 use parry::shape::{Shape, TriMeshFlags};
 
 #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
@@ -25,9 +28,16 @@ pub struct Collider {
     pub(crate) parent: Option<ColliderParent>,
     pub(crate) pos: ColliderPosition,
     pub(crate) material: ColliderMaterial,
+    pub(crate) shared_material: Option<MaterialHandle>,
     pub(crate) flags: ColliderFlags,
     pub(crate) bf_data: ColliderBroadPhaseData,
     contact_force_event_threshold: Real,
+    contact_skin: Real,
+    #[cfg(feature = "dim2")]
+    one_sided: bool,
+    fluid: Option<FluidVolume>,
+    surface_linvel: Vector<Real>,
+    surface_angvel: AngVector<Real>,
     /// User-defined data associated to this collider.
     pub user_data: u128,
 }
@@ -100,6 +110,75 @@ impl Collider {
         self.material.friction = coefficient
     }
 
+    /// The local-space direction of the anisotropic friction of this collider, if any.
+    ///
+    /// The friction coefficient of this collider is [`Self::friction`] along this direction, and
+    /// [`Self::cross_friction`] across it.
+    pub fn friction_direction(&self) -> Option<UnitVector<Real>> {
+        self.material.friction_direction
+    }
+
+    /// Sets the local-space direction of the anisotropic friction of this collider.
+    ///
+    /// Set it to `None` to make the friction of this collider isotropic.
+    pub fn set_friction_direction(&mut self, direction: Option<UnitVector<Real>>) {
+        self.material.friction_direction = direction;
+    }
+
+    /// The friction coefficient of this collider across its friction direction.
+    pub fn cross_friction(&self) -> Real {
+        self.material.cross_friction
+    }
+
+    /// Sets the friction coefficient of this collider across its friction direction.
+    pub fn set_cross_friction(&mut self, coefficient: Real) {
+        self.material.cross_friction = coefficient
+    }
+
+    /// The rolling friction coefficient of this collider.
+    pub fn rolling_friction(&self) -> Real {
+        self.material.rolling_friction
+    }
+
+    /// Sets the rolling friction coefficient of this collider.
+    pub fn set_rolling_friction(&mut self, coefficient: Real) {
+        self.material.rolling_friction = coefficient
+    }
+
+    /// The twist friction coefficient of this collider.
+    #[cfg(feature = "dim3")]
+    pub fn twist_friction(&self) -> Real {
+        self.material.twist_friction
+    }
+
+    /// Sets the twist friction coefficient of this collider.
+    #[cfg(feature = "dim3")]
+    pub fn set_twist_friction(&mut self, coefficient: Real) {
+        self.material.twist_friction = coefficient
+    }
+
+    /// The stiffness of the contacts with this collider, or zero if they are rigid.
+    pub fn contact_stiffness(&self) -> Real {
+        self.material.contact_stiffness
+    }
+
+    /// Sets the stiffness of the contacts with this collider.
+    ///
+    /// Set it to zero to make the contacts rigid.
+    pub fn set_contact_stiffness(&mut self, stiffness: Real) {
+        self.material.contact_stiffness = stiffness
+    }
+
+    /// The damping of the contacts with this collider.
+    pub fn contact_damping(&self) -> Real {
+        self.material.contact_damping
+    }
+
+    /// Sets the damping of the contacts with this collider.
+    pub fn set_contact_damping(&mut self, damping: Real) {
+        self.material.contact_damping = damping
+    }
+
     /// The combine rule used by this collider to combine its friction
     /// coefficient with the friction coefficient of the other collider it
     /// is in contact with.
@@ -143,6 +222,102 @@ impl Collider {
         self.contact_force_event_threshold = threshold;
     }
 
+    /// The contact skin of this collider.
+    ///
+    /// See [`ColliderBuilder::contact_skin`] for details.
+    pub fn contact_skin(&self) -> Real {
+        self.contact_skin
+    }
+
+    /// Sets the contact skin of this collider.
+    ///
+    /// See [`ColliderBuilder::contact_skin`] for details.
+    pub fn set_contact_skin(&mut self, skin: Real) {
+        if skin != self.contact_skin {
+            self.changes.insert(ColliderChanges::SHAPE);
+            self.contact_skin = skin;
+        }
+    }
+
+    /// The linear velocity of the surface of this collider, in its local-space.
+    ///
+    /// See [`ColliderBuilder::surface_linvel`] for details.
+    pub fn surface_linvel(&self) -> Vector<Real> {
+        self.surface_linvel
+    }
+
+    /// Sets the linear velocity of the surface of this collider, in its local-space.
+    ///
+    /// See [`ColliderBuilder::surface_linvel`] for details.
+    pub fn set_surface_linvel(&mut self, linvel: Vector<Real>) {
+        self.surface_linvel = linvel;
+    }
+
+    /// The angular velocity of the surface of this collider around its origin, in its local-space.
+    ///
+    /// See [`ColliderBuilder::surface_angvel`] for details.
+    pub fn surface_angvel(&self) -> AngVector<Real> {
+        self.surface_angvel
+    }
+
+    /// Sets the angular velocity of the surface of this collider around its origin, in its
+    /// local-space.
+    ///
+    /// See [`ColliderBuilder::surface_angvel`] for details.
+    pub fn set_surface_angvel(&mut self, angvel: AngVector<Real>) {
+        self.surface_angvel = angvel;
+    }
+
+    /// Does the surface of this collider have a non-zero velocity?
+    pub(crate) fn has_surface_velocity(&self) -> bool {
+        !self.surface_linvel.is_zero() || !self.surface_angvel.is_zero()
+    }
+
+    /// The world-space velocity of the surface of this collider at the given world-space point,
+    /// relative to the rigid-body it is attached to.
+    pub(crate) fn surface_velocity_at_point(&self, point: &Point<Real>) -> Vector<Real> {
+        #[cfg(feature = "dim2")]
+        let angvel = self.surface_angvel;
+        #[cfg(feature = "dim3")]
+        let angvel = self.pos.rotation * self.surface_angvel;
+        self.pos.rotation * self.surface_linvel
+            + angvel.gcross(point - Point::from(self.pos.translation.vector))
+    }
+
+    /// The fluid filling the shape of this collider, if any.
+    ///
+    /// See [`ColliderBuilder::fluid`] for details.
+    pub fn fluid(&self) -> Option<&FluidVolume> {
+        self.fluid.as_ref()
+    }
+
+    /// Sets the fluid filling the shape of this collider.
+    ///
+    /// Note that, contrary to [`ColliderBuilder::fluid`], this doesn’t change whether this
+    /// collider is a sensor.
+    pub fn set_fluid(&mut self, fluid: Option<FluidVolume>) {
+        self.fluid = fluid;
+    }
+
+    /// Is the polyline shape of this collider one-sided?
+    ///
+    /// See [`ColliderBuilder::one_sided`] for details.
+    #[cfg(feature = "dim2")]
+    pub fn is_one_sided(&self) -> bool {
+        self.one_sided
+    }
+
+    /// Sets whether the polyline shape of this collider is one-sided.
+    ///
+    /// See [`ColliderBuilder::one_sided`] for details.
+    #[cfg(feature = "dim2")]
+    pub fn set_one_sided(&mut self, one_sided: bool) {
+        if one_sided != self.one_sided {
+            self.changes.insert(ColliderChanges::SHAPE);
+            self.one_sided = one_sided;
+        }
+    }
+
     /// Sets whether or not this is a sensor collider.
     pub fn set_sensor(&mut self, is_sensor: bool) {
         if is_sensor != self.is_sensor() {
@@ -272,10 +447,28 @@ impl Collider {
     }
 
     /// The material (friction and restitution properties) of this collider.
+    ///
+    /// This material, as well as the material-related getters and setters of this collider
+    /// (friction, restitution, etc.), are ignored while this collider references a shared material
+    /// (see [`Self::shared_material`]).
     pub fn material(&self) -> &ColliderMaterial {
         &self.material
     }
 
+    /// The handle of the shared material used by this collider instead of its own material, if any.
+    ///
+    /// The shared material is read from [`ColliderSet::materials`](crate::geometry::ColliderSet::materials).
+    pub fn shared_material(&self) -> Option<MaterialHandle> {
+        self.shared_material
+    }
+
+    /// Sets the shared material used by this collider instead of its own material.
+    ///
+    /// Set it to `None` for this collider to use its own material again.
+    pub fn set_shared_material(&mut self, material: Option<MaterialHandle>) {
+        self.shared_material = material;
+    }
+
     /// The volume (or surface in 2D) of this collider.
     pub fn volume(&self) -> Real {
         self.shape.mass_properties(1.0).mass()
@@ -369,6 +562,26 @@ impl Collider {
         self.shape = shape;
     }
 
+    /// Scales the shape of this collider by the given (possibly non-uniform) scaling factor.
+    ///
+    /// Shapes that can’t be represented exactly after a non-uniform scaling (balls, capsules,
+    /// cylinders, cones) are replaced by a convex approximation with `num_subdivisions`
+    /// subdivisions. The mass properties of the collider are updated automatically unless they
+    /// were set explicitly.
+    ///
+    /// Returns `false` and leaves the shape unchanged if one of the scaling factors isn’t strictly
+    /// positive, if the shape doesn’t support scaling (round shapes, compound shapes with rotated
+    /// sub-shapes scaled non-uniformly), or if the scaled shape would be degenerate.
+    pub fn scale_shape(&mut self, scale: &Vector<Real>, num_subdivisions: u32) -> bool {
+        match scaled_shape(&self.shape, scale, num_subdivisions) {
+            Some(shape) => {
+                self.set_shape(shape);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Retrieve the SharedShape. Also see the `shape()` function
     pub fn shared_shape(&self) -> &SharedShape {
         &self.shape
@@ -381,10 +594,56 @@ impl Collider {
 
     /// Compute the axis-aligned bounding box of this collider moving from its current position
     /// to the given `next_position`
+    ///
+    /// For a collider attached to a rigid-body, its predicted position at the end of the timestep
+    /// is `body.next_position() * collider.position_wrt_parent()`.
     pub fn compute_swept_aabb(&self, next_position: &Isometry<Real>) -> Aabb {
         self.shape.compute_swept_aabb(&self.pos, next_position)
     }
 
+    /// Compute the axis-aligned bounding box of this collider moving from its current position
+    /// to the given `next_position`, while rotating by `angle` around the point `center`.
+    ///
+    /// Contrary to [`Collider::compute_swept_aabb`], this also encloses the intermediate
+    /// orientations of the collider, which matters for fast rotations. The `center` must move
+    /// linearly, which is the case of the center of mass of a rigid-body.
+    pub(crate) fn compute_rotating_swept_aabb(
+        &self,
+        next_position: &Isometry<Real>,
+        center: &Point<Real>,
+        angle: Real,
+    ) -> Aabb {
+        let aabb = self.compute_swept_aabb(next_position);
+
+        if angle <= 0.0 {
+            return aabb;
+        }
+
+        let sphere = self.shape.compute_local_bounding_sphere();
+        let radius = (self.pos.0 * sphere.center - center).norm() + sphere.radius;
+        // A point rotating by `angle` stays within `radius * (1 - cos(angle / 2))` of the
+        // linear interpolation between its initial and final positions.
+        let angle = angle.min(na::RealField::two_pi());
+        aabb.loosened(radius * (1.0 - (angle / 2.0).cos()))
+    }
+
+    /// Compute the bounding sphere of this collider.
+    pub fn compute_bounding_sphere(&self) -> BoundingSphere {
+        self.shape.compute_bounding_sphere(&self.pos)
+    }
+
+    /// Compute the bounding sphere of this collider moving from its current position
+    /// to the given `next_position`.
+    ///
+    /// See [`Collider::compute_swept_aabb`] for the predicted position of a collider attached to
+    /// a rigid-body.
+    pub fn compute_swept_bounding_sphere(&self, next_position: &Isometry<Real>) -> BoundingSphere {
+        let local_sphere = self.shape.compute_local_bounding_sphere();
+        local_sphere
+            .transform_by(&self.pos)
+            .merged(&local_sphere.transform_by(next_position))
+    }
+
     /// Compute the local-space mass properties of this collider.
     pub fn mass_properties(&self) -> MassProperties {
         self.mprops.mass_properties(&*self.shape)
@@ -407,6 +666,19 @@ pub struct ColliderBuilder {
     pub mass_properties: ColliderMassProps,
     /// The friction coefficient of the collider to be built.
     pub friction: Real,
+    /// The local-space direction of the anisotropic friction of the collider to be built, if any.
+    pub friction_direction: Option<UnitVector<Real>>,
+    /// The friction coefficient across the friction direction of the collider to be built.
+    pub cross_friction: Real,
+    /// The rolling friction coefficient of the collider to be built.
+    pub rolling_friction: Real,
+    /// The twist friction coefficient of the collider to be built.
+    #[cfg(feature = "dim3")]
+    pub twist_friction: Real,
+    /// The stiffness of the contacts with the collider to be built, or zero if they are rigid.
+    pub contact_stiffness: Real,
+    /// The damping of the contacts with the collider to be built.
+    pub contact_damping: Real,
     /// The rule used to combine two friction coefficients.
     pub friction_combine_rule: CoefficientCombineRule,
     /// The restitution coefficient of the collider to be built.
@@ -433,15 +705,40 @@ pub struct ColliderBuilder {
     pub enabled: bool,
     /// The total force magnitude beyond which a contact force event can be emitted.
     pub contact_force_event_threshold: Real,
+    /// The contact skin of the collider being built.
+    pub contact_skin: Real,
+    /// Will the polyline shape of the collider being built be one-sided?
+    #[cfg(feature = "dim2")]
+    pub one_sided: bool,
+    /// The shared material used by the collider being built instead of its own material, if any.
+    pub shared_material: Option<MaterialHandle>,
+    /// The fluid filling the shape of the collider being built, if any.
+    pub fluid: Option<FluidVolume>,
+    /// The linear velocity of the surface of the collider being built, in its local-space.
+    pub surface_linvel: Vector<Real>,
+    /// The angular velocity of the surface of the collider being built, in its local-space.
+    pub surface_angvel: AngVector<Real>,
 }
 
 impl ColliderBuilder {
     /// Initialize a new collider builder with the given shape.
+    ///
+    /// The shape is reference-counted: all the colliders built from clones of the same
+    /// `SharedShape` (e.g. thousands of identical crates, or a trimesh instanced many times)
+    /// share the same shape data instead of duplicating it. The shape of one of these colliders
+    /// is only copied if it is modified with [`Collider::shape_mut`].
     pub fn new(shape: SharedShape) -> Self {
         Self {
             shape,
             mass_properties: ColliderMassProps::default(),
             friction: Self::default_friction(),
+            friction_direction: None,
+            cross_friction: Self::default_friction(),
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            twist_friction: 0.0,
+            contact_stiffness: 0.0,
+            contact_damping: 0.0,
             restitution: 0.0,
             position: Isometry::identity(),
             is_sensor: false,
@@ -455,14 +752,36 @@ impl ColliderBuilder {
             active_events: ActiveEvents::empty(),
             enabled: true,
             contact_force_event_threshold: 0.0,
+            contact_skin: 0.0,
+            fluid: None,
+            surface_linvel: Vector::zeros(),
+            surface_angvel: na::zero(),
+            shared_material: None,
+            #[cfg(feature = "dim2")]
+            one_sided: false,
         }
     }
 
+    /// Initialize a new collider builder with the given owned shape.
+    ///
+    /// This is the same as `ColliderBuilder::new(SharedShape::new(shape))`. Use
+    /// [`ColliderBuilder::new`] with clones of the same `SharedShape` instead to share the shape
+    /// between several colliders.
+    pub fn from_shape(shape: impl Shape) -> Self {
+        Self::new(SharedShape::new(shape))
+    }
+
     /// Initialize a new collider builder with a compound shape.
     pub fn compound(shapes: Vec<(Isometry<Real>, SharedShape)>) -> Self {
         Self::new(SharedShape::compound(shapes))
     }
 
+    /// Initialize a new collider builder with a voxel shape defined by the size of each voxel
+    /// and the grid coordinates of the filled voxels.
+    pub fn voxels(voxel_size: Vector<Real>, voxels: &[Point<i32>]) -> Self {
+        Self::new(SharedShape::new(Voxels::new(voxel_size, voxels)))
+    }
+
     /// Initialize a new collider builder with a ball shape defined by its radius.
     pub fn ball(radius: Real) -> Self {
         Self::new(SharedShape::ball(radius))
@@ -571,6 +890,11 @@ impl ColliderBuilder {
     }
 
     /// Initializes a collider builder with a polyline shape defined by its vertex and index buffers.
+    ///
+    /// If `indices` is `None`, consecutive vertices are linked by a segment. In 2D, contacts at
+    /// the junction of two consecutive segments (i.e. segments `[a, b]` and `[b, c]`) are corrected
+    /// so shapes moving along the polyline don’t snag on that junction. In 2D, see
+    /// `ColliderBuilder::one_sided` to make the polyline one-sided.
     pub fn polyline(vertices: Vec<Point<Real>>, indices: Option<Vec<[u32; 2]>>) -> Self {
         Self::new(SharedShape::polyline(vertices, indices))
     }
@@ -582,6 +906,10 @@ impl ColliderBuilder {
 
     /// Initializes a collider builder with a triangle mesh shape defined by its vertex and index buffers and
     /// flags controlling its pre-processing.
+    ///
+    /// In 3D, if the flags contain `TriMeshFlags::ORIENTED`, the triangles are assumed to have
+    /// outward normals and contacts against the mesh’s internal edges and vertices are corrected,
+    /// so bodies sliding or rolling across a flat triangulated surface don’t snag on its seams.
     pub fn trimesh_with_flags(
         vertices: Vec<Point<Real>>,
         indices: Vec<[u32; 3]>,
@@ -640,31 +968,53 @@ impl ColliderBuilder {
 
     /// Initializes a new collider builder with a 2D convex polygon or 3D convex polyhedron
     /// obtained after computing the convex-hull of the given points.
+    ///
+    /// Returns `None` if the convex-hull is degenerate, e.g., if all the points are collinear (in
+    /// 2D) or coplanar (in 3D).
     pub fn convex_hull(points: &[Point<Real>]) -> Option<Self> {
-        SharedShape::convex_hull(points).map(Self::new)
+        if points.len() <= DIM {
+            return None;
+        }
+
+        // NOTE: reject flat hulls (e.g. from collinear points in 2D or coplanar points in 3D)
+        //       since they have no mass.
+        SharedShape::convex_hull(points)
+            .filter(|shape| shape.mass_properties(1.0).mass() > 0.0)
+            .map(Self::new)
     }
 
     /// Initializes a new collider builder with a round 2D convex polygon or 3D convex polyhedron
     /// obtained after computing the convex-hull of the given points. The shape is dilated
     /// by a sphere of radius `border_radius`.
     pub fn round_convex_hull(points: &[Point<Real>], border_radius: Real) -> Option<Self> {
+        if points.len() <= DIM {
+            return None;
+        }
+
         SharedShape::round_convex_hull(points, border_radius).map(Self::new)
     }
 
     /// Creates a new collider builder that is a convex polygon formed by the
-    /// given polyline assumed to be convex (no convex-hull will be automatically
-    /// computed).
+    /// given closed polyline (no convex-hull will be automatically computed).
+    ///
+    /// The vertices may be given in clockwise or counterclockwise order, and collinear vertices
+    /// are removed. Returns `None` if the polyline isn’t convex, or if it is degenerate (e.g. if
+    /// all its vertices are collinear). Use [`ColliderBuilder::convex_hull`] to build a polygon
+    /// from any set of points.
     #[cfg(feature = "dim2")]
     pub fn convex_polyline(points: Vec<Point<Real>>) -> Option<Self> {
-        SharedShape::convex_polyline(points).map(Self::new)
+        SharedShape::convex_polyline(ccw_convex_polyline(points)?).map(Self::new)
     }
 
     /// Creates a new collider builder that is a round convex polygon formed by the
-    /// given polyline assumed to be convex (no convex-hull will be automatically
-    /// computed). The polygon shape is dilated by a sphere of radius `border_radius`.
+    /// given closed polyline (no convex-hull will be automatically computed).
+    /// The polygon shape is dilated by a sphere of radius `border_radius`.
+    ///
+    /// See [`ColliderBuilder::convex_polyline`] for the requirements on the polyline.
     #[cfg(feature = "dim2")]
     pub fn round_convex_polyline(points: Vec<Point<Real>>, border_radius: Real) -> Option<Self> {
-        SharedShape::round_convex_polyline(points, border_radius).map(Self::new)
+        SharedShape::round_convex_polyline(ccw_convex_polyline(points)?, border_radius)
+            .map(Self::new)
     }
 
     /// Creates a new collider builder that is a convex polyhedron formed by the
@@ -765,6 +1115,49 @@ impl ColliderBuilder {
         self
     }
 
+    /// Makes the friction of the collider this builder will build anisotropic.
+    ///
+    /// The friction coefficient is the one set with [`Self::friction`] along the given
+    /// local-space `direction`, and `cross_friction` across it. This can simulate, e.g., skis
+    /// or tank treads sliding easily forward but not sideways.
+    pub fn anisotropic_friction(
+        mut self,
+        direction: UnitVector<Real>,
+        cross_friction: Real,
+    ) -> Self {
+        self.friction_direction = Some(direction);
+        self.cross_friction = cross_friction;
+        self
+    }
+
+    /// Sets the rolling friction coefficient of the collider this builder will build.
+    ///
+    /// This resists the rolling of the bodies in contact, making, e.g., balls eventually stop
+    /// rolling on flat ground.
+    pub fn rolling_friction(mut self, rolling_friction: Real) -> Self {
+        self.rolling_friction = rolling_friction;
+        self
+    }
+
+    /// Sets the twist friction coefficient of the collider this builder will build.
+    ///
+    /// This resists the relative spinning of the bodies in contact around the contact normal.
+    #[cfg(feature = "dim3")]
+    pub fn twist_friction(mut self, twist_friction: Real) -> Self {
+        self.twist_friction = twist_friction;
+        self
+    }
+
+    /// Makes the contacts with the collider this builder will build soft.
+    ///
+    /// Instead of being rigid, the contacts behave like a spring-damper with the given stiffness
+    /// and damping. This can simulate, e.g., tires, rubber balls, or soft ground.
+    pub fn soft_contacts(mut self, stiffness: Real, damping: Real) -> Self {
+        self.contact_stiffness = stiffness;
+        self.contact_damping = damping;
+        self
+    }
+
     /// Sets the rule to be used to combine two friction coefficients in a contact.
     pub fn friction_combine_rule(mut self, rule: CoefficientCombineRule) -> Self {
         self.friction_combine_rule = rule;
@@ -822,6 +1215,78 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets the contact skin of the collider to be built.
+    ///
+    /// The contact skin inflates the shape of the collider by the given thickness for contact
+    /// generation only: contacts are computed as if the shape was larger, but the solver
+    /// considers the colliders as touching when their skins touch. Colliders resting on each
+    /// other will thus be separated by a small gap equal to the sum of their skins, but contacts
+    /// are kept stable instead of appearing and disappearing at each timestep, reducing the
+    /// jitter of stacked objects.
+    ///
+    /// The skin doesn’t affect sensors, scene queries, or the mass properties of the collider.
+    pub fn contact_skin(mut self, skin: Real) -> Self {
+        self.contact_skin = skin;
+        self
+    }
+
+    /// Sets the linear velocity of the surface of the collider to be built, in its local-space.
+    ///
+    /// The contacts with this collider behave as if its surface was moving at this velocity
+    /// relative to the rigid-body it is attached to, without the collider actually moving. The
+    /// friction then drags the objects touching it along this velocity, which makes it easy to
+    /// simulate conveyor belts or treadmills.
+    pub fn surface_linvel(mut self, linvel: Vector<Real>) -> Self {
+        self.surface_linvel = linvel;
+        self
+    }
+
+    /// Sets the angular velocity of the surface of the collider to be built around its origin, in
+    /// its local-space.
+    ///
+    /// Similarly to [`ColliderBuilder::surface_linvel`], the friction then drags the objects
+    /// touching this collider as if its surface was rotating, e.g., to simulate turntables.
+    pub fn surface_angvel(mut self, angvel: AngVector<Real>) -> Self {
+        self.surface_angvel = angvel;
+        self
+    }
+
+    /// Makes the collider to be built use a shared material instead of its own material.
+    ///
+    /// The friction, restitution, and other material properties set with this builder are then
+    /// ignored in favor of the material with the given handle in
+    /// [`ColliderSet::materials`](crate::geometry::ColliderSet::materials), so they can be tuned
+    /// at once for all the colliders sharing it.
+    pub fn shared_material(mut self, material: MaterialHandle) -> Self {
+        self.shared_material = Some(material);
+        self
+    }
+
+    /// Fills the shape of the collider to be built with a fluid, and makes it a sensor.
+    ///
+    /// The dynamic rigid-bodies intersecting this collider are subject to the buoyancy and the
+    /// drag of the fluid. See [`FluidVolume`] for details.
+    pub fn fluid(mut self, fluid: FluidVolume) -> Self {
+        self.fluid = Some(fluid);
+        self.is_sensor = true;
+        self
+    }
+
+    /// Sets whether the polyline shape of the collider to be built is one-sided.
+    ///
+    /// The outward normal of each segment `[a, b]` of a polyline points to the right of `b - a`
+    /// (e.g. downward for a segment going along the positive X axis), so solid regions should be
+    /// outlined counterclockwise. A one-sided polyline only generates contacts with shapes on the
+    /// outward side of its segments: shapes on the other side go through it freely. This is
+    /// typically used for platformer terrains and one-way platforms.
+    ///
+    /// This only affects polyline shapes.
+    #[cfg(feature = "dim2")]
+    pub fn one_sided(mut self, one_sided: bool) -> Self {
+        self.one_sided = one_sided;
+        self
+    }
+
     /// Sets the initial translation of the collider to be created.
     ///
     /// If the collider will be attached to a rigid-body, this sets the translation relative to the
@@ -864,6 +1329,17 @@ impl ColliderBuilder {
         self
     }
 
+    /// Scales the shape of the collider to be built by the given (possibly non-uniform) scaling
+    /// factor.
+    ///
+    /// See [`Collider::scale_shape`] for details about the supported shapes. Returns `None` if
+    /// one of the scaling factors isn’t strictly positive, if the shape doesn’t support scaling,
+    /// or if the scaled shape would be degenerate.
+    pub fn scale(mut self, scale: Vector<Real>, num_subdivisions: u32) -> Option<Self> {
+        self.shape = scaled_shape(&self.shape, &scale, num_subdivisions)?;
+        Some(self)
+    }
+
     /// Enable or disable the collider after its creation.
     pub fn enabled(mut self, enabled: bool) -> Self {
         self.enabled = enabled;
@@ -875,7 +1351,14 @@ impl ColliderBuilder {
         let shape = self.shape.clone();
         let material = ColliderMaterial {
             friction: self.friction,
+            friction_direction: self.friction_direction,
+            cross_friction: self.cross_friction,
             restitution: self.restitution,
+            rolling_friction: self.rolling_friction,
+            #[cfg(feature = "dim3")]
+            twist_friction: self.twist_friction,
+            contact_stiffness: self.contact_stiffness,
+            contact_damping: self.contact_damping,
             friction_combine_rule: self.friction_combine_rule,
             restitution_combine_rule: self.restitution_combine_rule,
         };
@@ -904,6 +1387,7 @@ impl ColliderBuilder {
             shape,
             mprops: self.mass_properties.clone(),
             material,
+            shared_material: self.shared_material,
             parent: None,
             changes,
             pos,
@@ -911,6 +1395,12 @@ impl ColliderBuilder {
             flags,
             coll_type,
             contact_force_event_threshold: self.contact_force_event_threshold,
+            contact_skin: self.contact_skin,
+            #[cfg(feature = "dim2")]
+            one_sided: self.one_sided,
+            fluid: self.fluid,
+            surface_linvel: self.surface_linvel,
+            surface_angvel: self.surface_angvel,
             user_data: self.user_data,
         }
     }
@@ -921,3 +1411,1083 @@ impl Into<Collider> for ColliderBuilder {
         self.build()
     }
 }
+
+/// Orients the given closed polyline counterclockwise, as expected by the convex polygon shape.
+///
+/// Returns `None` if the polyline isn’t convex or has a zero area.
+#[cfg(feature = "dim2")]
+fn ccw_convex_polyline(mut points: Vec<Point<Real>>) -> Option<Vec<Point<Real>>> {
+    use na::RealField;
+
+    if points.len() < 3 {
+        return None;
+    }
+
+    let edge = |i: usize| points[(i + 1) % points.len()] - points[i];
+    let double_area: Real = (0..points.len())
+        .map(|i| points[i].coords.perp(&edge(i)))
+        .sum();
+
+    if double_area.abs() <= Real::EPSILON {
+        return None;
+    }
+
+    // NOTE: the polyline is convex if it always turns in the same direction, and makes exactly
+    //       one turn (self-intersecting polylines, like a star, make several turns).
+    let orientation = double_area.signum();
+    let mut total_turn = 0.0;
+    for i in 0..points.len() {
+        let (e1, e2) = (edge(i), edge((i + 1) % points.len()));
+        let turn = e1.perp(&e2).atan2(e1.dot(&e2)) * orientation;
+
+        if turn < -crate::math::DEFAULT_EPSILON.sqrt() {
+            return None;
+        }
+
+        total_turn += turn;
+    }
+
+    if (total_turn - Real::two_pi()).abs() > 1.0e-3 {
+        return None;
+    }
+
+    if orientation < 0.0 {
+        points.reverse();
+    }
+
+    Some(points)
+}
+
+fn scaled_shape(
+    shape: &SharedShape,
+    scale: &Vector<Real>,
+    num_subdivisions: u32,
+) -> Option<SharedShape> {
+    fn either<A: Shape, B: Shape>(shape: Either<A, B>) -> SharedShape {
+        match shape {
+            Either::Left(shape) => SharedShape::new(shape),
+            Either::Right(shape) => SharedShape::new(shape),
+        }
+    }
+
+    // NOTE: a zero scale would collapse the shape, and a negative one would mirror it, flipping
+    //       the orientation of its faces.
+    if scale.iter().any(|s| !(*s > 0.0 && s.is_finite())) {
+        return None;
+    }
+
+    let is_uniform = scale.iter().all(|s| *s == scale.x);
+
+    let result = match shape.as_typed_shape() {
+        TypedShape::Ball(s) => either(s.scaled(scale, num_subdivisions)?),
+        TypedShape::Cuboid(s) => SharedShape::new(s.scaled(scale)),
+        TypedShape::Capsule(s) => either(s.scaled(scale, num_subdivisions)?),
+        TypedShape::Segment(s) => SharedShape::new(s.scaled(scale)),
+        TypedShape::Triangle(s) => SharedShape::new(s.scaled(scale)),
+        TypedShape::TriMesh(s) => SharedShape::new(s.clone().scaled(scale)),
+        TypedShape::Polyline(s) => SharedShape::new(s.clone().scaled(scale)),
+        TypedShape::HalfSpace(s) => SharedShape::new(s.scaled(scale)?),
+        TypedShape::HeightField(s) => SharedShape::new(s.clone().scaled(scale)),
+        #[cfg(feature = "dim2")]
+        TypedShape::ConvexPolygon(s) => SharedShape::new(s.clone().scaled(scale)?),
+        #[cfg(feature = "dim3")]
+        TypedShape::ConvexPolyhedron(s) => SharedShape::new(s.clone().scaled(scale)?),
+        #[cfg(feature = "dim3")]
+        TypedShape::Cylinder(s) => either(s.scaled(scale, num_subdivisions)?),
+        #[cfg(feature = "dim3")]
+        TypedShape::Cone(s) => either(s.scaled(scale, num_subdivisions)?),
+        TypedShape::Compound(s) => {
+            let mut shapes = Vec::with_capacity(s.shapes().len());
+
+            for (pos, part) in s.shapes() {
+                // NOTE: a rotated sub-shape scaled non-uniformly along the compound’s axes
+                //       would be sheared, which can’t be represented.
+                if !is_uniform && pos.rotation != Rotation::identity() {
+                    return None;
+                }
+
+                let mut pos = *pos;
+                pos.translation.vector.component_mul_assign(scale);
+                shapes.push((pos, scaled_shape(part, scale, num_subdivisions)?));
+            }
+
+            SharedShape::compound(shapes)
+        }
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn sensors_detect_bodies_without_pushing_them() {
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let sensor = cuboid(10.0, 0.5);
+        let sensor = state.colliders.insert(
+            sensor
+                .sensor(true)
+                .active_events(ActiveEvents::COLLISION_EVENTS),
+        );
+
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let ball =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _contact_force_recv) = crossbeam::channel::unbounded();
+        let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let mut intersected = false;
+        for _ in 0..60 {
+            state.step(&mut pipeline, &(), &event_handler);
+
+            intersected |= state.narrow_phase.intersection_pair(sensor, ball) == Some(true);
+            assert!(state.narrow_phase.contact_pair(sensor, ball).is_none());
+        }
+
+        // The ball went through the sensor in free fall.
+        assert!(intersected);
+        assert!(state.bodies[body].translation().y < -1.0);
+        assert!((state.bodies[body].linvel().y + 9.81).abs() < 1.0e-3);
+
+        let events: Vec<_> = collision_recv.try_iter().collect();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].started() && events[1].stopped());
+        assert!(events.iter().all(|e| e.sensor()));
+    }
+
+    #[test]
+    fn compound_colliders_behave_like_their_sub_shapes() {
+        use crate::geometry::SharedShape;
+        use crate::math::Translation;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = state.colliders.insert(ground);
+
+        // A dumbbell made of two balls.
+        let shapes = vec![
+            (
+                Translation::from(-Vector::x()).into(),
+                SharedShape::ball(0.5),
+            ),
+            (
+                Translation::from(Vector::x()).into(),
+                SharedShape::ball(0.5),
+            ),
+        ];
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let dumbbell = state.colliders.insert_with_parent(
+            ColliderBuilder::compound(shapes).density(1.0),
+            body,
+            &mut state.bodies,
+        );
+
+        let ball_mass = ColliderBuilder::ball(0.5).density(1.0).build().mass();
+        assert!((state.bodies[body].mass() - ball_mass * 2.0).abs() < 1.0e-4);
+        assert!(state.bodies[body].center_of_mass().x.abs() < 1.0e-4);
+        let aabb = state.colliders[dumbbell].compute_aabb();
+        assert!((aabb.maxs.x - aabb.mins.x - 3.0).abs() < 1.0e-4);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        // Both balls rest on the ground, each with its own contact manifold.
+        assert!((state.bodies[body].translation().y - 1.0).abs() < 1.0e-2);
+        let pair = state.narrow_phase.contact_pair(ground, dumbbell).unwrap();
+        let touching = pair
+            .manifolds
+            .iter()
+            .filter(|m| !m.data.solver_contacts.is_empty())
+            .count();
+        assert_eq!(touching, 2);
+    }
+
+    #[test]
+    fn convex_hulls_ignore_interior_points() {
+        // The corners of a box, shuffled with points inside of it.
+        let mut points = vec![];
+        for i in 0..8 {
+            let corner = Vector::from_fn(|k, _| if i & (1 << k) != 0 { 1.0 } else { -1.0 });
+            points.push(Point::from(corner * 0.5));
+            points.push(Point::from(corner * 0.25));
+        }
+        points.push(Point::origin());
+
+        let hull = ColliderBuilder::convex_hull(&points)
+            .unwrap()
+            .density(2.0)
+            .build();
+        let cuboid = cuboid(0.5, 0.5);
+        let cuboid = cuboid.density(2.0).build();
+
+        let hull_mprops = hull.mass_properties();
+        let cuboid_mprops = cuboid.mass_properties();
+        assert!((hull_mprops.mass() - cuboid_mprops.mass()).abs() < 1.0e-4);
+        assert!(hull_mprops.local_com.coords.norm() < 1.0e-4);
+        #[cfg(feature = "dim2")]
+        assert!(
+            (hull_mprops.principal_inertia() - cuboid_mprops.principal_inertia()).abs() < 1.0e-4
+        );
+        #[cfg(feature = "dim3")]
+        assert!(
+            (hull_mprops.principal_inertia() - cuboid_mprops.principal_inertia()).norm() < 1.0e-4
+        );
+
+        // Degenerate point clouds don’t have a convex hull.
+        assert!(ColliderBuilder::convex_hull(&[]).is_none());
+        assert!(ColliderBuilder::convex_hull(&[Point::origin()]).is_none());
+        let collinear: Vec<_> = (0..4)
+            .map(|i| Point::from(Vector::x() * i as Real))
+            .collect();
+        assert!(ColliderBuilder::convex_hull(&collinear).is_none());
+        #[cfg(feature = "dim3")]
+        {
+            let coplanar: Vec<_> = points
+                .iter()
+                .map(|pt| Point::new(pt.x, pt.y, 0.0))
+                .collect();
+            assert!(ColliderBuilder::convex_hull(&coplanar).is_none());
+        }
+    }
+
+    #[test]
+    fn convex_decomposition_of_concave_shapes() {
+        // An L-shaped polygon, extruded along the Z axis in 3D.
+        let profile = [
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 2.0],
+            [0.0, 2.0],
+        ];
+        #[cfg(feature = "dim2")]
+        let (vertices, indices): (Vec<_>, Vec<_>) = (
+            profile.iter().map(|p| Point::new(p[0], p[1])).collect(),
+            (0..6).map(|i| [i, (i + 1) % 6]).collect(),
+        );
+        #[cfg(feature = "dim3")]
+        let (vertices, indices): (Vec<_>, Vec<_>) = {
+            let mut vertices: Vec<_> = profile
+                .iter()
+                .map(|p| Point::new(p[0], p[1], 0.0))
+                .collect();
+            vertices.extend(profile.iter().map(|p| Point::new(p[0], p[1], 1.0)));
+            let mut indices = vec![];
+            for i in 0..6 {
+                let j = (i + 1) % 6;
+                indices.push([i, j, j + 6]);
+                indices.push([i, j + 6, i + 6]);
+            }
+            for [a, b, c] in [[3, 4, 5], [3, 5, 0], [3, 0, 1], [3, 1, 2]] {
+                indices.push([a + 6, b + 6, c + 6]);
+                indices.push([a, c, b]);
+            }
+            (vertices, indices)
+        };
+
+        let collider = ColliderBuilder::convex_decomposition(&vertices, &indices)
+            .density(1.0)
+            .build();
+        let compound = collider.shape().as_compound().unwrap();
+        assert!(compound.shapes().len() >= 2);
+        assert!(compound.shapes().iter().all(|(_, part)| part.is_convex()));
+        // The L-shape has an area (in 2D) or a volume (in 3D) of 3, and its convex hull of 3.5.
+        // The convex parts may slightly overlap, so their total mass is only bounded by these.
+        assert!(
+            collider.mass() > 3.0 - 1.0e-2 && collider.mass() < 3.5,
+            "{}",
+            collider.mass()
+        );
+    }
+
+    #[test]
+    fn heightfields_support_holes() {
+        use crate::geometry::{HeightField, SharedShape};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A flat terrain with a hole under the second ball.
+        let hole = Point::from(Vector::repeat(2.5));
+        #[cfg(feature = "dim2")]
+        let heightfield = {
+            let mut heightfield = HeightField::new(na::DVector::zeros(21), Vector::new(20.0, 1.0));
+            let cell = heightfield.cell_at_point(&hole).unwrap();
+            heightfield.set_segment_removed(cell, true);
+            heightfield
+        };
+        #[cfg(feature = "dim3")]
+        let heightfield = {
+            use crate::geometry::HeightFieldCellStatus;
+            let mut heightfield =
+                HeightField::new(na::DMatrix::zeros(21, 21), Vector::new(20.0, 1.0, 20.0));
+            let (i, j) = heightfield.cell_at_point(&hole).unwrap();
+            heightfield.set_cell_status(i, j, HeightFieldCellStatus::CELL_REMOVED);
+            heightfield
+        };
+        state
+            .colliders
+            .insert(ColliderBuilder::new(SharedShape::new(heightfield)));
+
+        let mut balls = vec![];
+        for x in [-2.5, 2.5] {
+            let mut translation = Vector::repeat(x);
+            translation.y = 1.0;
+            let body = state
+                .bodies
+                .insert(RigidBodyBuilder::dynamic().translation(translation));
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.2), body, &mut state.bodies);
+            balls.push(body);
+        }
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        assert!((state.bodies[balls[0]].translation().y - 0.2).abs() < 1.0e-2);
+        assert!(state.bodies[balls[1]].translation().y < -5.0);
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn trimesh_internal_edges_do_not_bounce_sliding_bodies() {
+        use crate::geometry::TriMeshFlags;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A flat floor made of 2x2 squares, each split into two triangles.
+        let n = 40;
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for i in 0..=n {
+            for j in 0..=n {
+                vertices.push(Point::new(
+                    i as Real * 2.0 - 40.0,
+                    0.0,
+                    j as Real * 2.0 - 40.0,
+                ));
+            }
+        }
+        for i in 0..n {
+            for j in 0..n {
+                let a = i * (n + 1) + j;
+                let b = a + n + 1;
+                indices.push([a, a + 1, b + 1]);
+                indices.push([a, b + 1, b]);
+            }
+        }
+        let floor = ColliderBuilder::trimesh_with_flags(vertices, indices, TriMeshFlags::ORIENTED);
+        state.colliders.insert(floor.friction(0.0));
+
+        let body = RigidBodyBuilder::dynamic()
+            .translation(Vector::new(-15.0, 0.25, 0.3))
+            .linvel(Vector::new(15.0, -3.0, 5.0));
+        let body = state.bodies.insert(body);
+        let collider = ColliderBuilder::capsule_x(0.5, 0.25).friction(0.0);
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        for i in 0..150 {
+            state.step(&mut pipeline, &(), &());
+
+            // Once landed, the capsule slides across the triangle edges without bouncing.
+            if i >= 30 {
+                let rb = &state.bodies[body];
+                assert!(rb.linvel().y.abs() < 0.15, "{}: {}", i, rb.linvel());
+                assert!((rb.translation().y - 0.25).abs() < 0.05);
+            }
+        }
+    }
+
+    #[test]
+    fn round_shapes_rest_on_their_border() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        #[cfg(feature = "dim2")]
+        let (ground, round_cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::round_cuboid(0.5, 0.5, 0.1),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, round_cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::round_cuboid(0.5, 0.5, 0.5, 0.1),
+        );
+        state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        let hull_points: Vec<_> = (0..8)
+            .map(|i| Point::from(Vector::from_fn(|k, _| ((i >> k) & 1) as Real - 0.5)))
+            .collect();
+        let shapes = [
+            round_cuboid,
+            ColliderBuilder::round_triangle(
+                Point::from(Vector::x() * -0.5),
+                Point::from(Vector::x() * 0.5),
+                Point::from(Vector::y() * 0.5),
+                0.1,
+            )
+            // NOTE: the triangle has no volume in 3D.
+            .mass(1.0),
+            ColliderBuilder::round_convex_hull(&hull_points, 0.1).unwrap(),
+        ];
+
+        // The AABB of a round shape includes its border.
+        let aabb = shapes[0].build().compute_aabb();
+        assert!((aabb.maxs.x - 0.6).abs() < 1.0e-4);
+
+        let mut handles = vec![];
+        for (i, shape) in shapes.into_iter().enumerate() {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * i as Real * 3.0 + Vector::y())
+                .lock_rotations();
+            let body = state.bodies.insert(body);
+            handles.push(
+                state
+                    .colliders
+                    .insert_with_parent(shape, body, &mut state.bodies),
+            );
+        }
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        // Each shape rests on the ground with its rounded border touching it.
+        for handle in handles {
+            let aabb = state.colliders[handle].compute_aabb();
+            assert!(aabb.mins.y.abs() < 1.0e-2, "{}", aabb.mins.y);
+        }
+    }
+
+    #[cfg(feature = "dim3")]
+    #[test]
+    fn cylinders_and_cones_rest_on_boxes_and_trimeshes() {
+        use std::f32::consts::PI;
+
+        let (half_height, radius) = (0.5, 0.4);
+        let cylinder = ColliderBuilder::cylinder(half_height, radius).density(2.0);
+        let cone = ColliderBuilder::cone(half_height, radius).density(2.0);
+        let cylinder_volume = PI as Real * radius * radius * half_height * 2.0;
+        assert!((cylinder.build().mass() - cylinder_volume * 2.0).abs() < 1.0e-4);
+        assert!((cone.build().mass() - cylinder_volume * 2.0 / 3.0).abs() < 1.0e-4);
+
+        let ground_shapes = [
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0).translation(Vector::y() * -0.5),
+            ColliderBuilder::trimesh(
+                vec![
+                    Point::new(-10.0, 0.0, -10.0),
+                    Point::new(10.0, 0.0, -10.0),
+                    Point::new(10.0, 0.0, 10.0),
+                    Point::new(-10.0, 0.0, 10.0),
+                ],
+                vec![[0, 2, 1], [0, 3, 2]],
+            ),
+        ];
+
+        for ground in ground_shapes {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+
+            state.colliders.insert(ground);
+
+            let mut handles = vec![];
+            for (i, shape) in [cylinder.clone(), cone.clone()].into_iter().enumerate() {
+                let body =
+                    RigidBodyBuilder::dynamic().translation(Vector::new(i as Real * 3.0, 1.0, 0.0));
+                let body = state.bodies.insert(body);
+                state
+                    .colliders
+                    .insert_with_parent(shape, body, &mut state.bodies);
+                handles.push(body);
+            }
+
+            step_n(&mut state, &mut pipeline, 120);
+
+            // Both shapes stand still on their flat base.
+            for handle in handles {
+                let rb = &state.bodies[handle];
+                assert!((rb.translation().y - half_height).abs() < 1.0e-2);
+                assert!((rb.rotation() * Vector::y()).y > 0.999);
+                assert!(rb.linvel().norm() < 5.0e-2, "{}", rb.linvel());
+            }
+        }
+    }
+
+    #[test]
+    fn collider_shapes_can_be_replaced() {
+        use crate::geometry::SharedShape;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 0.5));
+        let ball =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        let small_mass = state.bodies[body].mass();
+
+        for i in 0..120 {
+            if i == 60 {
+                // Grow the ball once it rests on the ground.
+                assert!((state.bodies[body].translation().y - 0.5).abs() < 1.0e-2);
+                state.colliders[ball].set_shape(SharedShape::ball(1.0));
+            }
+
+            state.step(&mut pipeline, &(), &());
+
+            if i == 60 {
+                // The AABB used by the broad-phase, and the mass, were updated right away.
+                assert!(state.bodies[body].mass() > small_mass * 3.0);
+                let aabb = state.colliders[ball].compute_aabb();
+                assert!((aabb.maxs.x - aabb.mins.x - 2.0).abs() < 1.0e-4);
+            }
+        }
+
+        // The bigger ball was pushed out of the ground while staying in contact with it.
+        assert!((state.bodies[body].translation().y - 1.0).abs() < 1.0e-2);
+        let pair = state.narrow_phase.contact_pair(ground, ball).unwrap();
+        assert!(pair.has_any_active_contact);
+    }
+
+    #[test]
+    fn collider_position_wrt_parent_can_be_animated() {
+        use crate::math::Isometry;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // A character with a sensor hitbox, and a target next to it.
+        let character = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let body_collider = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            character,
+            &mut state.bodies,
+        );
+        let hitbox = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.2).sensor(true).density(0.0),
+            character,
+            &mut state.bodies,
+        );
+        let target = state
+            .colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 2.0 + Vector::y() * 2.0));
+
+        for i in 0..=10 {
+            // Swing the hitbox toward the target.
+            let offset = Vector::x() * 0.15 * i as Real;
+            state.colliders[hitbox].set_position_wrt_parent(Isometry::from(offset));
+
+            state.step(&mut pipeline, &(), &());
+
+            let expected_pos = state.bodies[character].position() * Isometry::from(offset);
+            assert_eq!(*state.colliders[hitbox].position(), expected_pos);
+        }
+
+        // The hitbox reached the target, without contributing to the mass.
+        assert_eq!(
+            state.narrow_phase.intersection_pair(hitbox, target),
+            Some(true)
+        );
+        assert!(state.bodies[character].center_of_mass().x.abs() < 1.0e-6);
+
+        // Moving a solid collider moves the center-of-mass of its parent.
+        state.colliders[body_collider].set_position_wrt_parent(Isometry::from(-Vector::x()));
+        state.step(&mut pipeline, &(), &());
+        assert!((state.bodies[character].center_of_mass().x + 1.0).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn collider_shapes_can_be_scaled() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let mut squash = Vector::repeat(2.0);
+        squash.y = 0.5;
+
+        // A unit cube exported by a content pipeline, scaled into a large flat ground.
+        let unit_cube = cuboid(0.5, 0.5);
+        let ground = unit_cube.clone().scale(Vector::repeat(20.0), 8).unwrap();
+        let ground_half_extents = ground.shape.as_cuboid().unwrap().half_extents;
+        assert_eq!(ground_half_extents, Vector::repeat(10.0));
+        state
+            .colliders
+            .insert(ground.translation(Vector::y() * -10.0));
+
+        // A unit cube squashed into a slab at spawn time.
+        let slab = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 5.0 + Vector::y() * 0.5));
+        state.colliders.insert_with_parent(
+            unit_cube.clone().scale(squash, 8).unwrap(),
+            slab,
+            &mut state.bodies,
+        );
+
+        // A ball squashed at runtime is turned into a convex approximation of an ellipsoid.
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let ball =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        let ball_mass = state.colliders[ball].mass();
+        assert!(state.colliders[ball].scale_shape(&squash, 16));
+        assert!(state.colliders[ball].shape().as_ball().is_none());
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        let expected_mass = ball_mass * squash.iter().product::<Real>();
+        assert!((state.bodies[body].mass() - expected_mass).abs() < expected_mass * 0.05);
+        assert!(state.bodies[body].translation().y > 0.2);
+        assert!((state.bodies[slab].mass() - squash.iter().product::<Real>()).abs() < 1.0e-4);
+        assert!((state.bodies[slab].translation().y - 0.25).abs() < 1.0e-2);
+
+        // Round shapes can’t be scaled.
+        #[cfg(feature = "dim2")]
+        let round = ColliderBuilder::round_cuboid(0.5, 0.5, 0.1);
+        #[cfg(feature = "dim3")]
+        let round = ColliderBuilder::round_cuboid(0.5, 0.5, 0.5, 0.1);
+        let round = state.colliders.insert(round);
+        assert!(!state.colliders[round].scale_shape(&squash, 32));
+        assert!(state.colliders[round].shape().as_round_cuboid().is_some());
+
+        // Zero and negative scales are rejected.
+        assert!(unit_cube.clone().scale(Vector::zeros(), 8).is_none());
+        assert!(unit_cube.scale(-squash, 8).is_none());
+    }
+
+    #[test]
+    fn contact_skins_keep_resting_contacts_separated() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        let ground = state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        let skin = 0.05;
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let co =
+            state
+                .colliders
+                .insert_with_parent(cuboid.contact_skin(skin), body, &mut state.bodies);
+        assert_eq!(state.colliders[co].contact_skin(), skin);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        // The box rests on its skin.
+        assert!((state.bodies[body].translation().y - 0.5 - skin).abs() < 5.0e-3);
+        {
+            let pair = state.narrow_phase.contact_pair(ground, co).unwrap();
+            assert!(pair.has_any_active_contact);
+            let manifold = &pair.manifolds[0];
+            assert!(manifold
+                .points
+                .iter()
+                .all(|pt| (pt.dist - skin).abs() < 5.0e-3));
+            assert!(manifold
+                .data
+                .solver_contacts
+                .iter()
+                .all(|contact| contact.dist.abs() < 5.0e-3));
+        }
+
+        // Thickening the skin at runtime lifts the box.
+        state.colliders[co].set_contact_skin(skin * 2.0);
+        state.bodies[body].wake_up(true);
+        step_n(&mut state, &mut pipeline, 60);
+        assert!((state.bodies[body].translation().y - 0.5 - skin * 2.0).abs() < 5.0e-3);
+    }
+
+    #[test]
+    fn disabled_colliders_are_ignored_by_contacts_and_queries() {
+        use crate::pipeline::QueryFilter;
+        use parry::query::Ray;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A hitbox toggled on and off by an animation.
+        let body = state.bodies.insert(RigidBodyBuilder::fixed());
+        let hitbox =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        let ball = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), ball, &mut state.bodies);
+
+        let ray = Ray::new(Point::from(Vector::x() * -5.0), Vector::x());
+
+        step_n(&mut state, &mut pipeline, 60);
+        assert!((state.bodies[ball].translation().y - 1.0).abs() < 1.0e-2);
+        let hit = state.query_pipeline.cast_ray(
+            &state.bodies,
+            &state.colliders,
+            &ray,
+            Real::MAX,
+            true,
+            QueryFilter::default(),
+        );
+        assert_eq!(hit.map(|h| h.0), Some(hitbox));
+
+        // Disable the hitbox: the ball falls through it and rays miss it.
+        state.colliders[hitbox].set_enabled(false);
+        assert_eq!(state.colliders.len(), 2);
+        step_n(&mut state, &mut pipeline, 60);
+        assert!(state.bodies[ball].translation().y < -1.0);
+        let hit = state.query_pipeline.cast_ray(
+            &state.bodies,
+            &state.colliders,
+            &ray,
+            Real::MAX,
+            true,
+            QueryFilter::default(),
+        );
+        assert!(hit.is_none());
+
+        // Enable it again.
+        state.colliders[hitbox].set_enabled(true);
+        step_n(&mut state, &mut pipeline, 1);
+        let hit = state.query_pipeline.cast_ray(
+            &state.bodies,
+            &state.colliders,
+            &ray,
+            Real::MAX,
+            true,
+            QueryFilter::default(),
+        );
+        assert_eq!(hit.map(|h| h.0), Some(hitbox));
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn one_sided_polylines_without_ghost_collisions() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A flat terrain made of many small segments, listed from right to left so its
+        // normals point upward.
+        let vertices = (0..=80)
+            .map(|i| Point::new(20.0 - i as Real * 0.5, 0.0))
+            .collect();
+        state.colliders.insert(
+            ColliderBuilder::polyline(vertices, None)
+                .friction(0.0)
+                .one_sided(true),
+        );
+
+        // A ball landing and sliding across the segment junctions.
+        let sliding = state.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::new(-15.0, 2.0))
+                .linvel(Vector::x() * 5.0)
+                .lock_rotations(),
+        );
+        state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).friction(0.0),
+            sliding,
+            &mut state.bodies,
+        );
+
+        // Another ball shot upward through the terrain.
+        let ball = state.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::new(15.0, -1.0))
+                .linvel(Vector::y() * 10.0),
+        );
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), ball, &mut state.bodies);
+
+        for _ in 0..120 {
+            state.step(&mut pipeline, &(), &());
+            assert!((state.bodies[sliding].linvel().x - 5.0).abs() < 1.0e-3);
+        }
+
+        assert!((state.bodies[sliding].translation().y - 0.5).abs() < 1.0e-2);
+        // The ball went through the terrain from below, then landed on it.
+        assert!((state.bodies[ball].translation().y - 0.5).abs() < 1.0e-2);
+    }
+
+    #[test]
+    #[cfg(feature = "dim2")]
+    fn convex_polygons_from_ordered_vertices() {
+        let hexagon: Vec<_> = (0..6)
+            .map(|i| {
+                let angle = i as Real * std::f32::consts::FRAC_PI_3 as Real;
+                Point::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        let mut clockwise = hexagon.clone();
+        clockwise.reverse();
+
+        let ccw_mprops = ColliderBuilder::convex_polyline(hexagon.clone())
+            .unwrap()
+            .shape
+            .mass_properties(1.0);
+        let cw_mprops = ColliderBuilder::convex_polyline(clockwise)
+            .unwrap()
+            .shape
+            .mass_properties(1.0);
+        let area = 3.0 * (3.0 as Real).sqrt() / 2.0;
+        assert!((ccw_mprops.mass() - area).abs() < 1.0e-4);
+        assert!((cw_mprops.mass() - area).abs() < 1.0e-4);
+        assert!(cw_mprops.local_com.coords.norm() < 1.0e-4);
+
+        // Concave, self-intersecting, and degenerate polylines are rejected.
+        let concave = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.0),
+            Point::new(1.0, 1.0),
+            Point::new(1.0, 2.0),
+            Point::new(0.0, 2.0),
+        ];
+        let star = (0..5)
+            .map(|i| {
+                let angle = i as Real * 4.0 * std::f32::consts::PI as Real / 5.0;
+                Point::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        let collinear = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(2.0, 0.0),
+        ];
+        assert!(ColliderBuilder::convex_polyline(concave.clone()).is_none());
+        assert!(ColliderBuilder::convex_polyline(star).is_none());
+        assert!(ColliderBuilder::convex_polyline(collinear).is_none());
+        assert!(ColliderBuilder::convex_hull(&concave).is_some());
+
+        // A hexagon resting on its flat side has a two-point contact manifold.
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = state
+            .colliders
+            .insert(ColliderBuilder::cuboid(10.0, 0.5).translation(Vector::y() * -0.5));
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let polygon = state.colliders.insert_with_parent(
+            ColliderBuilder::convex_polyline(hexagon).unwrap(),
+            body,
+            &mut state.bodies,
+        );
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        let half_height = (3.0 as Real).sqrt() / 2.0;
+        assert!((state.bodies[body].translation().y - half_height).abs() < 1.0e-2);
+        let pair = state.narrow_phase.contact_pair(ground, polygon).unwrap();
+        assert_eq!(pair.manifolds.len(), 1);
+        assert_eq!(pair.manifolds[0].points.len(), 2);
+    }
+
+    #[test]
+    fn collider_bounding_volumes() {
+        use crate::math::Isometry;
+        use parry::bounding_volume::BoundingVolume;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        let body = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 2.0));
+        let handle = colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).translation(Vector::y()),
+            body,
+            &mut bodies,
+        );
+        let collider = &colliders[handle];
+        let center = Point::from(Vector::x() * 2.0 + Vector::y());
+
+        let aabb = collider.compute_aabb();
+        assert_eq!(aabb.center(), center);
+        assert_eq!(aabb.half_extents(), Vector::repeat(0.5));
+
+        let sphere = collider.compute_bounding_sphere();
+        assert_eq!(*sphere.center(), center);
+        assert_eq!(sphere.radius(), 0.5);
+
+        // Sweep the collider toward its position after its rigid-body moved along X.
+        let next_position = Isometry::from(Vector::x() * 2.0) * collider.position();
+        let swept_aabb = collider.compute_swept_aabb(&next_position);
+        let swept_sphere = collider.compute_swept_bounding_sphere(&next_position);
+        let next_sphere = collider.shape().compute_bounding_sphere(&next_position);
+        assert!(swept_aabb.contains(&aabb));
+        assert!(swept_aabb.contains(&collider.shape().compute_aabb(&next_position)));
+        assert!(swept_sphere.contains(&sphere));
+        assert!(swept_sphere.contains(&next_sphere));
+        assert_eq!(swept_sphere.radius(), 1.5);
+    }
+
+    #[test]
+    fn colliders_can_share_shapes() {
+        use crate::geometry::{Ball, SharedShape};
+        use std::sync::Arc;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+
+        let shape = SharedShape::ball(0.5);
+        let handles: Vec<_> = (0..10)
+            .map(|i| {
+                let body =
+                    bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::x() * i as Real));
+                colliders.insert_with_parent(ColliderBuilder::new(shape.clone()), body, &mut bodies)
+            })
+            .collect();
+        let owned = colliders.insert(ColliderBuilder::from_shape(Ball::new(0.5)));
+
+        assert_eq!(Arc::strong_count(&shape.0), 11);
+        for handle in &handles {
+            assert!(Arc::ptr_eq(&colliders[*handle].shared_shape().0, &shape.0));
+        }
+        assert!(!Arc::ptr_eq(&colliders[owned].shared_shape().0, &shape.0));
+        assert_eq!(colliders[owned].shape().as_ball().unwrap().radius, 0.5);
+
+        // Modifying the shape of one collider doesn’t affect the others.
+        colliders[handles[0]]
+            .shape_mut()
+            .as_ball_mut()
+            .unwrap()
+            .radius = 1.0;
+        assert_eq!(Arc::strong_count(&shape.0), 10);
+        assert_eq!(colliders[handles[0]].shape().as_ball().unwrap().radius, 1.0);
+        assert_eq!(colliders[handles[1]].shape().as_ball().unwrap().radius, 0.5);
+    }
+
+    #[test]
+    fn colliders_with_a_surface_velocity_act_as_conveyor_belts() {
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // The surface velocity is in the local-space of the collider, so this belt, which is
+        // upside down, moves its objects toward `-X`.
+        #[cfg(feature = "dim2")]
+        let belt = ColliderBuilder::cuboid(100.0, 0.5).rotation(std::f32::consts::PI as Real);
+        #[cfg(feature = "dim3")]
+        let belt = ColliderBuilder::cuboid(100.0, 0.5, 100.0)
+            .rotation(Vector::z() * std::f32::consts::PI as Real);
+        let belt = state
+            .colliders
+            .insert(belt.surface_linvel(Vector::x() * 2.0));
+        assert_eq!(state.colliders[belt].surface_linvel(), Vector::x() * 2.0);
+
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y()));
+        let collider = cuboid(0.5, 0.5);
+        state
+            .colliders
+            .insert_with_parent(collider, body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        let linvel = *state.bodies[body].linvel();
+        assert!((linvel + Vector::x() * 2.0).norm() < 1.0e-2, "{}", linvel);
+    }
+
+    #[test]
+    fn colliders_with_an_anisotropic_friction_only_resist_sliding_across_their_direction() {
+        use crate::dynamics::CoefficientCombineRule;
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // Two frictionless boxes sliding on two grounds with a friction only across `direction`.
+        let mut boxes = vec![];
+        for (i, direction) in [Vector::x_axis(), Vector::y_axis()].into_iter().enumerate() {
+            let shift = Vector::x() * 100.0 * i as Real;
+            let ground = cuboid(20.0, 0.5);
+            let ground = ground
+                .translation(shift)
+                .friction(0.0)
+                .anisotropic_friction(direction, 1.0)
+                .friction_combine_rule(CoefficientCombineRule::Max);
+            state.colliders.insert(ground);
+
+            #[cfg(feature = "dim2")]
+            let linvel = Vector::new(1.0, 0.0);
+            #[cfg(feature = "dim3")]
+            let linvel = Vector::new(1.0, 0.0, 1.0);
+            let body = state.bodies.insert(
+                RigidBodyBuilder::dynamic()
+                    .translation(shift + Vector::y())
+                    .linvel(linvel),
+            );
+            let collider = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(collider.friction(0.0), body, &mut state.bodies);
+            boxes.push(body);
+        }
+
+        step_n(&mut state, &mut pipeline, 60);
+
+        // The first box keeps sliding along the friction direction, but not across it.
+        let linvel = *state.bodies[boxes[0]].linvel();
+        assert!((linvel.x - 1.0).abs() < 1.0e-2, "{}", linvel);
+        #[cfg(feature = "dim3")]
+        assert!(linvel.z.abs() < 1.0e-2, "{}", linvel);
+        // The friction direction of the second ground is orthogonal to its surface, so its
+        // friction is `cross_friction` in every tangent direction.
+        let linvel = *state.bodies[boxes[1]].linvel();
+        assert!(linvel.norm() < 1.0e-2, "{}", linvel);
+    }
+}
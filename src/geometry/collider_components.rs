@@ -1,6 +1,6 @@
 use crate::dynamics::{CoefficientCombineRule, MassProperties, RigidBodyHandle, RigidBodyType};
 use crate::geometry::{InteractionGroups, SAPProxyIndex, Shape, SharedShape};
-use crate::math::{Isometry, Real};
+use crate::math::{Isometry, Real, UnitVector, Vector};
 use crate::parry::partitioning::IndexedData;
 use crate::pipeline::{ActiveEvents, ActiveHooks};
 use std::ops::{Deref, DerefMut};
@@ -262,13 +262,49 @@ pub struct ColliderMaterial {
     /// The greater the value, the stronger the friction forces will be.
     /// Should be `>= 0`.
     pub friction: Real,
+    /// The local-space direction of the anisotropic friction of this collider.
+    ///
+    /// If set, the friction coefficient of this collider is `friction` along this direction, and
+    /// `cross_friction` across it. This can simulate, e.g., skis, tank treads, or brushed metal.
+    pub friction_direction: Option<UnitVector<Real>>,
+    /// The friction coefficient of this collider across its `friction_direction`.
+    ///
+    /// This is ignored if `friction_direction` is `None`. Should be `>= 0`.
+    pub cross_friction: Real,
     /// The restitution coefficient of this collider.
     ///
     /// Increase this value to make contacts with this collider more "bouncy".
     /// Should be `>= 0` and should generally not be greater than `1` (perfectly elastic
     /// collision).
     pub restitution: Real,
+    /// The rolling friction coefficient of this collider.
+    ///
+    /// The torque resisting the rolling of the colliders in contact is at most this
+    /// coefficient multiplied by the magnitude of the contact force, so this coefficient is
+    /// homogeneous to a length. Should be `>= 0`.
+    pub rolling_friction: Real,
+    /// The twist friction coefficient of this collider.
+    ///
+    /// The torque resisting the relative spinning of the colliders in contact around the contact
+    /// normal is at most this coefficient multiplied by the magnitude of the contact force, so
+    /// this coefficient is homogeneous to a length. Should be `>= 0`.
+    #[cfg(feature = "dim3")]
+    pub twist_friction: Real,
+    /// The stiffness of the contacts with this collider.
+    ///
+    /// If non-zero, the contacts with this collider behave like a spring-damper with this
+    /// stiffness, instead of being rigid. This can simulate, e.g., tires, rubber balls, or soft
+    /// ground. The contacts between two soft colliders combine their stiffnesses as springs in
+    /// series. Should be `>= 0`.
+    pub contact_stiffness: Real,
+    /// The damping of the contacts with this collider.
+    ///
+    /// This is ignored if both colliders in contact have a zero `contact_stiffness`.
+    /// Should be `>= 0`.
+    pub contact_damping: Real,
     /// The rule applied to combine the friction coefficients of two colliders in contact.
+    ///
+    /// This is also applied to combine their rolling and twist friction coefficients.
     pub friction_combine_rule: CoefficientCombineRule,
     /// The rule applied to combine the restitution coefficients of two colliders.
     pub restitution_combine_rule: CoefficientCombineRule,
@@ -283,13 +319,35 @@ impl ColliderMaterial {
             ..Default::default()
         }
     }
+
+    /// The friction coefficient of this material along the given local-space unit direction.
+    ///
+    /// With an anisotropic friction, the coefficients along the directions between the
+    /// `friction_direction` and the directions orthogonal to it are interpolated elliptically.
+    pub(crate) fn friction_along(&self, local_dir: &Vector<Real>) -> Real {
+        match self.friction_direction {
+            Some(friction_dir) => {
+                let cos = local_dir.dot(&friction_dir);
+                let sin_sq = (1.0 - cos * cos).max(0.0);
+                ((self.friction * cos).powi(2) + self.cross_friction.powi(2) * sin_sq).sqrt()
+            }
+            None => self.friction,
+        }
+    }
 }
 
 impl Default for ColliderMaterial {
     fn default() -> Self {
         Self {
             friction: 1.0,
+            friction_direction: None,
+            cross_friction: 1.0,
             restitution: 0.0,
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            twist_friction: 0.0,
+            contact_stiffness: 0.0,
+            contact_damping: 0.0,
             friction_combine_rule: CoefficientCombineRule::default(),
             restitution_combine_rule: CoefficientCombineRule::default(),
         }
@@ -1,6 +1,8 @@
 use crate::data::arena::Arena;
 use crate::dynamics::{IslandManager, RigidBodyHandle, RigidBodySet};
-use crate::geometry::{Collider, ColliderChanges, ColliderHandle, ColliderParent};
+use crate::geometry::{
+    Collider, ColliderChanges, ColliderHandle, ColliderMaterial, ColliderParent, MaterialSet,
+};
 use crate::math::Isometry;
 use std::ops::{Index, IndexMut};
 
@@ -11,6 +13,7 @@ pub struct ColliderSet {
     pub(crate) colliders: Arena<Collider>,
     pub(crate) modified_colliders: Vec<ColliderHandle>,
     pub(crate) removed_colliders: Vec<ColliderHandle>,
+    pub(crate) materials: MaterialSet,
 }
 
 impl ColliderSet {
@@ -20,9 +23,34 @@ impl ColliderSet {
             colliders: Arena::new(),
             modified_colliders: Vec::new(),
             removed_colliders: Vec::new(),
+            materials: MaterialSet::new(),
         }
     }
 
+    /// The materials that can be shared by the colliders of this set.
+    pub fn materials(&self) -> &MaterialSet {
+        &self.materials
+    }
+
+    /// A mutable reference to the materials that can be shared by the colliders of this set.
+    ///
+    /// The modifications of these materials affect the colliders sharing them starting from the
+    /// next simulation step.
+    pub fn materials_mut(&mut self) -> &mut MaterialSet {
+        &mut self.materials
+    }
+
+    /// The material actually used by the given collider for computing contacts.
+    ///
+    /// This is the shared material referenced by the collider if it exists, and the collider’s
+    /// own material otherwise.
+    pub fn effective_material<'a>(&'a self, collider: &'a Collider) -> &'a ColliderMaterial {
+        collider
+            .shared_material
+            .and_then(|handle| self.materials.get(handle))
+            .unwrap_or(&collider.material)
+    }
+
     pub(crate) fn take_modified(&mut self) -> Vec<ColliderHandle> {
         std::mem::replace(&mut self.modified_colliders, vec![])
     }
@@ -60,6 +88,24 @@ impl ColliderSet {
         })
     }
 
+    /// Iterates mutably and in parallel through all the colliders on this set.
+    ///
+    /// Just like [`Self::iter_mut`], all the colliders are marked as modified, even if the
+    /// iterator isn’t fully consumed.
+    #[cfg(all(feature = "parallel", not(feature = "dev-remove-slow-accessors")))]
+    pub fn par_iter_mut(
+        &mut self,
+    ) -> impl rayon::iter::ParallelIterator<Item = (ColliderHandle, &mut Collider)> {
+        use rayon::prelude::*;
+
+        self.modified_colliders.clear();
+        self.modified_colliders
+            .extend(self.colliders.iter().map(|(h, _)| ColliderHandle(h)));
+        self.colliders
+            .par_iter_mut()
+            .map(|(h, b)| (ColliderHandle(h), b))
+    }
+
     /// Iterates mutably through all the enabled colliders on this set.
     #[cfg(not(feature = "dev-remove-slow-accessors"))]
     pub fn iter_enabled_mut(&mut self) -> impl Iterator<Item = (ColliderHandle, &mut Collider)> {
@@ -134,6 +180,11 @@ impl ColliderSet {
     }
 
     /// Sets the parent of the given collider.
+    ///
+    /// The collider keeps its position relative to its parent, so it will be moved to
+    /// the new parent’s frame. If `new_parent_handle` is `None`, the collider is detached and
+    /// behaves as static geometry from its current position. The mass properties of both the
+    /// old and the new parent are updated at the next timestep.
     // TODO: find a way to define this as a method of Collider.
     pub fn set_parent(
         &mut self,
@@ -320,3 +371,131 @@ impl IndexMut<ColliderHandle> for ColliderSet {
         collider
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn standalone_colliders_act_as_static_geometry() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A level made of many chunks, none of them attached to a rigid-body.
+        let mut chunks = vec![];
+        for i in 0..100 {
+            let chunk = cuboid(0.5, 0.5);
+            let chunk = chunk.translation(Vector::x() * (i as Real - 50.0) - Vector::y() * 0.5);
+            chunks.push(state.colliders.insert(chunk));
+        }
+
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 2.0));
+        let ball =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 120);
+
+        assert_eq!(state.bodies.len(), 1);
+        assert!(state.islands.active_dynamic_bodies().len() <= 1);
+        assert!((state.bodies[body].translation().y - 0.5).abs() < 1.0e-2);
+        for chunk in &chunks {
+            assert!(state.colliders[*chunk].parent().is_none());
+        }
+
+        let pair = state.narrow_phase.contact_pair(chunks[50], ball).unwrap();
+        assert!(pair.has_any_active_contact);
+        let manifold = &pair.manifolds[0];
+        assert!(manifold.data.rigid_body1.is_none() || manifold.data.rigid_body2.is_none());
+    }
+
+    #[test]
+    fn colliders_can_be_reparented() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        #[cfg(feature = "dim2")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5),
+            ColliderBuilder::cuboid(0.5, 0.5),
+        );
+        #[cfg(feature = "dim3")]
+        let (ground, cuboid) = (
+            ColliderBuilder::cuboid(10.0, 0.5, 10.0),
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5),
+        );
+        state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        let body_a = state.bodies.insert(
+            RigidBodyBuilder::dynamic().translation(Vector::x() * -3.0 + Vector::y() * 0.5),
+        );
+        let body_b = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 3.0 + Vector::y() * 0.5));
+        state
+            .colliders
+            .insert_with_parent(cuboid.clone(), body_a, &mut state.bodies);
+        state
+            .colliders
+            .insert_with_parent(cuboid.clone(), body_b, &mut state.bodies);
+        let item_builder = ColliderBuilder::ball(0.25).translation(Vector::y() * 0.75);
+        let item_mass = item_builder.build().mass();
+        let item = state
+            .colliders
+            .insert_with_parent(item_builder, body_a, &mut state.bodies);
+        let cuboid_mass = cuboid.build().mass();
+
+        step_n(&mut state, &mut pipeline, 10);
+        assert!((state.bodies[body_a].mass() - cuboid_mass - item_mass).abs() < 1.0e-4);
+        assert!((state.bodies[body_b].mass() - cuboid_mass).abs() < 1.0e-4);
+
+        // Pick the item up with the second body.
+        state
+            .colliders
+            .set_parent(item, Some(body_b), &mut state.bodies);
+        assert!(state.bodies[body_a].colliders().iter().all(|h| *h != item));
+        assert!(state.bodies[body_b].colliders().contains(&item));
+        step_n(&mut state, &mut pipeline, 1);
+
+        assert_eq!(state.colliders[item].parent(), Some(body_b));
+        assert!((state.bodies[body_a].mass() - cuboid_mass).abs() < 1.0e-4);
+        assert!((state.bodies[body_b].mass() - cuboid_mass - item_mass).abs() < 1.0e-4);
+        assert!((state.colliders[item].translation().x - 3.0).abs() < 1.0e-3);
+
+        // Drop it: it becomes static geometry staying where it was.
+        let dropped_at = *state.colliders[item].translation();
+        state.colliders.set_parent(item, None, &mut state.bodies);
+        step_n(&mut state, &mut pipeline, 1);
+
+        assert!(state.colliders[item].parent().is_none());
+        assert!(state.bodies[body_b].colliders().iter().all(|h| *h != item));
+        assert!((state.bodies[body_b].mass() - cuboid_mass).abs() < 1.0e-4);
+        assert!((state.colliders[item].translation() - dropped_at).norm() < 1.0e-5);
+
+        // Other bodies now collide with it as they would with any static collider.
+        let falling = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(dropped_at + Vector::y() * 1.0));
+        let falling_co = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.25),
+            falling,
+            &mut state.bodies,
+        );
+        step_n(&mut state, &mut pipeline, 60);
+
+        assert!((state.bodies[falling].translation().y - dropped_at.y - 0.5).abs() < 1.0e-2);
+        let pair = state.narrow_phase.contact_pair(item, falling_co).unwrap();
+        assert!(pair.has_any_active_contact);
+        let manifold = &pair.manifolds[0];
+        assert!(manifold.data.rigid_body1.is_none() || manifold.data.rigid_body2.is_none());
+    }
+}
@@ -14,6 +14,13 @@ bitflags::bitflags! {
         /// The constraint solver will take this contact manifold into
         /// account for force computation.
         const COMPUTE_IMPULSES = 0b001;
+        /// The friction coefficient of all the contacts of this manifold is set to zero,
+        /// as well as its rolling and twist friction coefficients, regardless of the materials
+        /// of the colliders.
+        const DISABLE_FRICTION = 0b010;
+        /// The restitution coefficient of all the contacts of this manifold is set to zero,
+        /// regardless of the materials of the colliders.
+        const DISABLE_RESTITUTION = 0b100;
     }
 }
 
@@ -271,6 +278,22 @@ pub struct ContactManifoldData {
     // So right now it is best to just serialize this field and keep it that way until it
     // is proven to be actually problematic in real applications (in terms of snapshot size for example).
     pub solver_contacts: Vec<SolverContact>,
+    /// The effective rolling friction coefficient of this contact manifold.
+    pub rolling_friction: Real,
+    /// The effective twist friction coefficient of this contact manifold.
+    #[cfg(feature = "dim3")]
+    pub twist_friction: Real,
+    /// The world-space tangent direction of the strongest friction, if the friction of this
+    /// contact manifold is anisotropic.
+    ///
+    /// The friction coefficients of the solver contacts apply along this direction, and are
+    /// multiplied by `friction_anisotropy` across it.
+    #[cfg(feature = "dim3")]
+    pub friction_direction: Option<Vector<Real>>,
+    /// The ratio, in `[0, 1]`, between the friction coefficients across and along the
+    /// `friction_direction`.
+    #[cfg(feature = "dim3")]
+    pub friction_anisotropy: Real,
     /// The relative dominance of the bodies involved in this contact manifold.
     pub relative_dominance: i16,
     /// A user-defined piece of data.
@@ -292,6 +315,15 @@ pub struct SolverContact {
     pub friction: Real,
     /// The effective restitution coefficient at this contact point.
     pub restitution: Real,
+    /// The stiffness of this contact, if it is soft.
+    ///
+    /// A soft contact behaves like a spring-damper pushing the colliders apart, instead of
+    /// preventing any penetration. This is zero for rigid contacts.
+    pub stiffness: Real,
+    /// The damping of this contact, if it is soft.
+    ///
+    /// This is ignored if [`Self::stiffness`] is zero.
+    pub damping: Real,
     /// The desired tangent relative velocity at the contact point.
     ///
     /// This is set to zero by default. Set to a non-zero value to
@@ -334,6 +366,13 @@ impl ContactManifoldData {
             solver_flags,
             normal: Vector::zeros(),
             solver_contacts: Vec::new(),
+            rolling_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            twist_friction: 0.0,
+            #[cfg(feature = "dim3")]
+            friction_direction: None,
+            #[cfg(feature = "dim3")]
+            friction_anisotropy: 1.0,
             relative_dominance: 0,
             user_data: 0,
         }
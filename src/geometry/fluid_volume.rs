@@ -0,0 +1,187 @@
+use crate::geometry::Shape;
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+#[cfg(feature = "dim3")]
+use na::RealField;
+
+/// The properties of a fluid filling the shape of a collider.
+///
+/// The dynamic rigid-bodies intersecting a collider with a fluid volume (see
+/// [`ColliderBuilder::fluid`](crate::geometry::ColliderBuilder::fluid)) are subject to:
+/// - a buoyancy force, opposite to the gravity and proportional to the density of the fluid and
+///   the submerged volume of their colliders, applied at the center of the submerged volume.
+/// - a linear drag, proportional to the submerged volume and the velocity of the submerged
+///   volume relative to the flow of the fluid.
+/// - an angular drag, proportional to the submerged volume and the angular velocity of the
+///   rigid-body.
+///
+/// The surface of the fluid is the plane perpendicular to the gravity that passes through the
+/// highest point of the AABB of the fluid collider. The submerged volumes are computed exactly for
+/// balls, and approximated from a subdivision of the local AABB of the other shapes. These
+/// approximations ignore the lateral bounds of the fluid collider, so they are better suited to
+/// fluid colliders that are larger than the rigid-bodies in them.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FluidVolume {
+    /// The density of the fluid.
+    pub density: Real,
+    /// The linear drag coefficient, i.e., the force per unit of submerged volume and per unit of
+    /// relative velocity resisting the motion of the rigid-bodies in the fluid.
+    pub linear_drag: Real,
+    /// The angular drag coefficient, i.e., the torque per unit of submerged volume and per unit
+    /// of angular velocity resisting the rotation of the rigid-bodies in the fluid.
+    pub angular_drag: Real,
+    /// The world-space velocity of the fluid, dragging the rigid-bodies along its flow.
+    pub flow_velocity: Vector<Real>,
+}
+
+impl FluidVolume {
+    /// A still fluid with the given density, and without any drag.
+    pub fn new(density: Real) -> Self {
+        Self {
+            density,
+            linear_drag: 0.0,
+            angular_drag: 0.0,
+            flow_velocity: Vector::zeros(),
+        }
+    }
+}
+
+/// The volume of the given shape below the plane with normal `up` at the distance `surface` from
+/// the origin, and the world-space center of this submerged volume.
+pub(crate) fn submerged_volume(
+    shape: &dyn Shape,
+    pos: &Isometry<Real>,
+    up: &Vector<Real>,
+    surface: Real,
+) -> Option<(Real, Point<Real>)> {
+    if let Some(ball) = shape.as_ball() {
+        let r = ball.radius;
+        let center = pos.translation.vector;
+        let h = (surface - center.dot(up) + r).clamp(0.0, 2.0 * r);
+
+        // The volume of the spherical cap (or the area of the circular segment) of height `h`,
+        // and the distance between its centroid and the center of the ball.
+        #[cfg(feature = "dim2")]
+        let (volume, dist) = {
+            let theta = 2.0 * ((r - h) / r).clamp(-1.0, 1.0).acos();
+            let volume = r * r * (theta - theta.sin()) / 2.0;
+            let dist = 4.0 * r * (theta / 2.0).sin().powi(3) / (3.0 * (theta - theta.sin()));
+            (volume, dist)
+        };
+        #[cfg(feature = "dim3")]
+        let (volume, dist) = {
+            let volume = Real::pi() * h * h * (3.0 * r - h) / 3.0;
+            let dist = 3.0 * (2.0 * r - h).powi(2) / (4.0 * (3.0 * r - h));
+            (volume, dist)
+        };
+
+        return (volume > 0.0).then(|| (volume, Point::from(center - up * dist)));
+    }
+
+    const SUBDIVISIONS: usize = 4;
+
+    let volume = shape.mass_properties(1.0).mass();
+    let aabb = shape.compute_local_aabb();
+    if !(volume > 0.0 && volume.is_finite() && aabb.half_extents().iter().all(|e| e.is_finite())) {
+        return None;
+    }
+
+    let cell_half_extents = aabb.half_extents() / SUBDIVISIONS as Real;
+    // The half-height of the cells along `up`.
+    let cell_half_height: Real = (0..DIM)
+        .map(|i| {
+            (pos.rotation * Vector::ith(i, cell_half_extents[i]))
+                .dot(up)
+                .abs()
+        })
+        .sum();
+    let num_cells = SUBDIVISIONS.pow(DIM as u32);
+    let cell_centers = (0..num_cells).map(|id| {
+        let mut coords = aabb.mins + cell_half_extents;
+        for i in 0..DIM {
+            let k = id / SUBDIVISIONS.pow(i as u32) % SUBDIVISIONS;
+            coords[i] += cell_half_extents[i] * 2.0 * k as Real;
+        }
+        coords
+    });
+
+    // NOTE: we only keep the cells with their center inside of the shape, unless the shape is too
+    //       thin to contain any.
+    let any_cell_inside = cell_centers
+        .clone()
+        .any(|pt| shape.contains_local_point(&pt));
+    let mut num_kept = 0;
+    let mut submerged = 0.0;
+    let mut centroid = Vector::zeros();
+
+    for pt in cell_centers {
+        if any_cell_inside && !shape.contains_local_point(&pt) {
+            continue;
+        }
+
+        num_kept += 1;
+        let pt = pos * pt;
+        let ratio =
+            ((surface - pt.coords.dot(up)) / (2.0 * cell_half_height) + 0.5).clamp(0.0, 1.0);
+        submerged += ratio;
+        centroid += pt.coords * ratio;
+    }
+
+    (submerged > 0.0).then(|| {
+        (
+            volume * submerged / num_kept as Real,
+            Point::from(centroid / submerged),
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn bodies_float_in_fluid_volumes() {
+        use crate::geometry::FluidVolume;
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // A fluid twice as dense as the bodies, with its surface at `y = 0`.
+        let mut water = FluidVolume::new(2.0);
+        water.linear_drag = 20.0;
+        water.angular_drag = 1.0;
+        let pool = cuboid(20.0, 5.0);
+        let pool = state
+            .colliders
+            .insert(pool.translation(Vector::y() * -5.0).fluid(water));
+        assert!(state.colliders[pool].is_sensor());
+        assert_eq!(state.colliders[pool].fluid(), Some(&water));
+
+        let cuboid = cuboid(0.5, 0.5);
+        let mut floaters = vec![];
+        for (x, collider) in [(-2.0, ColliderBuilder::ball(0.5)), (2.0, cuboid)] {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x + Vector::y() * 2.0)
+                .build();
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            floaters.push(body);
+        }
+
+        step_n(&mut state, &mut pipeline, 600);
+
+        // Half of each body is submerged at equilibrium.
+        for body in floaters {
+            let body = &state.bodies[body];
+            assert!(body.translation().y.abs() < 0.02, "{}", body.translation());
+            assert!(body.linvel().norm() < 0.01);
+        }
+    }
+}
@@ -164,3 +164,69 @@ impl From<Group> for u32 {
         val.bits()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn interaction_groups_filter_contacts_and_forces() {
+        use crate::geometry::{Group, InteractionGroups};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground_groups = InteractionGroups::new(Group::GROUP_1, Group::ALL);
+        let ground = cuboid(10.0, 0.5);
+        let ground = state.colliders.insert(
+            ground
+                .collision_groups(ground_groups)
+                .solver_groups(ground_groups),
+        );
+
+        // The first ball interacts with the ground, the second ignores it entirely, and the
+        // third generates contacts with it but no contact forces.
+        let ignore_ground = InteractionGroups::new(Group::GROUP_2, Group::GROUP_2);
+        let groups = [
+            (InteractionGroups::all(), InteractionGroups::all()),
+            (ignore_ground, InteractionGroups::all()),
+            (InteractionGroups::all(), ignore_ground),
+        ];
+        let mut balls = vec![];
+        for (i, (collision_groups, solver_groups)) in groups.into_iter().enumerate() {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.5 + Vector::x() * 3.0 * i as Real);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5)
+                .collision_groups(collision_groups)
+                .solver_groups(solver_groups);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            balls.push(body);
+        }
+
+        let mut touched = [false; 3];
+        for _ in 0..60 {
+            state.step(&mut pipeline, &(), &());
+
+            for (touched, ball) in touched.iter_mut().zip(balls.iter()) {
+                let collider = state.bodies[*ball].colliders()[0];
+                *touched |= state
+                    .narrow_phase
+                    .contact_pair(ground, collider)
+                    .map(|pair| pair.has_any_active_contact)
+                    == Some(true);
+            }
+        }
+
+        assert_eq!(touched, [true, false, true]);
+        assert!(state.bodies[balls[0]].translation().y > 0.9);
+        assert!(state.bodies[balls[1]].translation().y < 0.0);
+        assert!(state.bodies[balls[2]].translation().y < 0.0);
+    }
+}
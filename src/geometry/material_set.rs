@@ -0,0 +1,158 @@
+use crate::data::arena::Arena;
+use crate::geometry::ColliderMaterial;
+use std::ops::{Index, IndexMut};
+
+/// The unique identifier of a material added to a material set.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[repr(transparent)]
+pub struct MaterialHandle(pub crate::data::arena::Index);
+
+impl MaterialHandle {
+    /// Converts this handle into its (index, generation) components.
+    pub fn into_raw_parts(self) -> (u32, u32) {
+        self.0.into_raw_parts()
+    }
+
+    /// Reconstructs an handle from its (index, generation) components.
+    pub fn from_raw_parts(id: u32, generation: u32) -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(id, generation))
+    }
+
+    /// An always-invalid material handle.
+    pub fn invalid() -> Self {
+        Self(crate::data::arena::Index::from_raw_parts(
+            crate::INVALID_U32,
+            crate::INVALID_U32,
+        ))
+    }
+}
+
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone, Default)]
+/// A set of materials shared by colliders.
+///
+/// A collider referencing one of these materials (see
+/// [`ColliderBuilder::shared_material`](crate::geometry::ColliderBuilder::shared_material))
+/// uses it instead of its own material. Modifying a shared material thus affects all the
+/// colliders referencing it, starting from the next simulation step.
+///
+/// The material set of a [`ColliderSet`](crate::geometry::ColliderSet) is accessed with
+/// [`ColliderSet::materials`](crate::geometry::ColliderSet::materials).
+pub struct MaterialSet {
+    materials: Arena<ColliderMaterial>,
+}
+
+impl MaterialSet {
+    /// Create a new empty set of materials.
+    pub fn new() -> Self {
+        Self {
+            materials: Arena::new(),
+        }
+    }
+
+    /// The number of materials on this set.
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    /// `true` if there are no materials on this set.
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Is this material handle valid?
+    pub fn contains(&self, handle: MaterialHandle) -> bool {
+        self.materials.contains(handle.0)
+    }
+
+    /// Inserts a new material to this set and retrieve its handle.
+    pub fn insert(&mut self, material: ColliderMaterial) -> MaterialHandle {
+        MaterialHandle(self.materials.insert(material))
+    }
+
+    /// Removes a material from this set.
+    ///
+    /// The colliders still referencing this material fall back to their own material.
+    pub fn remove(&mut self, handle: MaterialHandle) -> Option<ColliderMaterial> {
+        self.materials.remove(handle.0)
+    }
+
+    /// Get the material with the given handle.
+    pub fn get(&self, handle: MaterialHandle) -> Option<&ColliderMaterial> {
+        self.materials.get(handle.0)
+    }
+
+    /// Gets a mutable reference to the material with the given handle.
+    pub fn get_mut(&mut self, handle: MaterialHandle) -> Option<&mut ColliderMaterial> {
+        self.materials.get_mut(handle.0)
+    }
+
+    /// Iterate through all the materials on this set.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (MaterialHandle, &ColliderMaterial)> {
+        self.materials.iter().map(|(h, m)| (MaterialHandle(h), m))
+    }
+}
+
+impl Index<MaterialHandle> for MaterialSet {
+    type Output = ColliderMaterial;
+
+    fn index(&self, handle: MaterialHandle) -> &ColliderMaterial {
+        &self.materials[handle.0]
+    }
+}
+
+impl IndexMut<MaterialHandle> for MaterialSet {
+    fn index_mut(&mut self, handle: MaterialHandle) -> &mut ColliderMaterial {
+        &mut self.materials[handle.0]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn colliders_use_their_shared_material() {
+        use crate::dynamics::CoefficientCombineRule;
+        use crate::geometry::ColliderMaterial;
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let mut ice = ColliderMaterial::new(0.0, 0.0);
+        ice.friction_combine_rule = CoefficientCombineRule::Min;
+        let ice = state.colliders.materials_mut().insert(ice);
+
+        // The own friction of the ground is ignored in favor of its shared material.
+        let ground = cuboid(20.0, 0.5);
+        let ground = state
+            .colliders
+            .insert(ground.friction(1.0).shared_material(ice));
+        assert_eq!(state.colliders[ground].shared_material(), Some(ice));
+
+        let body = state.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::y())
+                .linvel(Vector::x()),
+        );
+        let collider = cuboid(0.5, 0.5);
+        state
+            .colliders
+            .insert_with_parent(collider.friction(1.0), body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 30);
+        let linvel = *state.bodies[body].linvel();
+        assert!((linvel.x - 1.0).abs() < 1.0e-2, "{}", linvel);
+
+        // Tuning the shared material affects the colliders referencing it.
+        state.colliders.materials_mut()[ice].friction = 1.0;
+        step_n(&mut state, &mut pipeline, 30);
+        let linvel = *state.bodies[body].linvel();
+        assert!(linvel.norm() < 1.0e-2, "{}", linvel);
+    }
+}
@@ -2,8 +2,9 @@
 
 pub use self::broad_phase_multi_sap::{BroadPhasePairEvent, ColliderPair};
 
-pub use self::broad_phase_multi_sap::BroadPhase;
-// pub use self::broad_phase_qbvh::BroadPhase;
+pub use self::broad_phase::BroadPhase;
+pub use self::broad_phase_multi_sap::BroadPhaseMultiSap;
+pub use self::broad_phase_qbvh::BroadPhaseBvh;
 pub use self::collider_components::*;
 pub use self::contact_pair::{
     ContactData, ContactManifoldData, ContactPair, IntersectionPair, SolverContact, SolverFlags,
@@ -16,11 +17,17 @@ pub use self::narrow_phase::NarrowPhase;
 
 pub use self::collider::{Collider, ColliderBuilder};
 pub use self::collider_set::ColliderSet;
+pub use self::fluid_volume::FluidVolume;
+pub use self::material_set::{MaterialHandle, MaterialSet};
+pub use self::shape_queries::{contact, distance, ShapeContact};
+pub use self::voxels::Voxels;
 
 pub use parry::query::TrackedContact;
 
 use crate::math::{Real, Vector};
 
+/// The broad-phase used by default by Rapier.
+pub type DefaultBroadPhase = BroadPhaseMultiSap;
 /// A contact between two colliders.
 pub type Contact = parry::query::TrackedContact<ContactData>;
 /// A contact manifold between two colliders.
@@ -195,6 +202,7 @@ pub(crate) fn default_query_dispatcher() -> std::sync::Arc<dyn parry::query::Que
     std::sync::Arc::new(parry::query::DefaultQueryDispatcher)
 }
 
+mod broad_phase;
 mod broad_phase_multi_sap;
 mod collider_components;
 mod contact_pair;
@@ -205,3 +213,8 @@ mod narrow_phase;
 mod broad_phase_qbvh;
 mod collider;
 mod collider_set;
+mod fluid_volume;
+mod material_set;
+mod shape_queries;
+mod voxels;
+pub(crate) use self::fluid_volume::submerged_volume;
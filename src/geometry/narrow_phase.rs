@@ -7,17 +7,24 @@ use crate::dynamics::{
     CoefficientCombineRule, ImpulseJointSet, IslandManager, RigidBodyDominance, RigidBodySet,
     RigidBodyType,
 };
+#[cfg(feature = "dim2")]
+use crate::geometry::Polyline;
+#[cfg(feature = "dim3")]
+use crate::geometry::TriMesh;
 use crate::geometry::{
     BroadPhasePairEvent, ColliderChanges, ColliderGraphIndex, ColliderHandle, ColliderPair,
     ColliderSet, CollisionEvent, ContactData, ContactManifold, ContactManifoldData, ContactPair,
     InteractionGraph, IntersectionPair, SolverContact, SolverFlags, TemporaryInteractionIndex,
 };
+use crate::math::Isometry;
 use crate::math::{Real, Vector};
 use crate::pipeline::{
     ActiveEvents, ActiveHooks, ContactModificationContext, EventHandler, PairFilterContext,
     PhysicsHooks,
 };
 use crate::prelude::{CollisionEventFlags, MultibodyJointSet};
+use crate::utils::WBasis;
+use parry::query::PointQueryWithLocation;
 use parry::query::{DefaultQueryDispatcher, PersistentQueryDispatcher};
 use parry::utils::IsometryOpt;
 use std::collections::HashMap;
@@ -178,7 +185,7 @@ impl NarrowPhase {
     ///
     /// If this returns `None`, there is no contact between the two colliders.
     /// If this returns `Some`, then there may be a contact between the two colliders. Check the
-    /// result [`ContactPair::has_any_active_collider`] method to see if there is an actual contact.
+    /// result [`ContactPair::has_any_active_contact`] field to see if there is an actual contact.
     pub fn contact_pair_unknown_gen(&self, collider1: u32, collider2: u32) -> Option<&ContactPair> {
         let id1 = self.graph_indices.get_unknown_gen(collider1)?;
         let id2 = self.graph_indices.get_unknown_gen(collider2)?;
@@ -191,7 +198,7 @@ impl NarrowPhase {
     ///
     /// If this returns `None`, there is no contact between the two colliders.
     /// If this returns `Some`, then there may be a contact between the two colliders. Check the
-    /// result [`ContactPair::has_any_active_collider`] method to see if there is an actual contact.
+    /// result [`ContactPair::has_any_active_contact`] field to see if there is an actual contact.
     pub fn contact_pair(
         &self,
         collider1: ColliderHandle,
@@ -204,6 +211,19 @@ impl NarrowPhase {
             .map(|c| c.2)
     }
 
+    /// The contact pair involving two specific colliders, for modification.
+    pub(crate) fn contact_pair_mut(
+        &mut self,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Option<&mut ContactPair> {
+        let id1 = self.graph_indices.get(collider1.0)?;
+        let id2 = self.graph_indices.get(collider2.0)?;
+        self.contact_graph
+            .interaction_pair_mut(id1.contact_graph_index, id2.contact_graph_index)
+            .map(|c| c.2)
+    }
+
     /// The intersection pair involving two specific colliders.
     ///
     /// It is strongly recommended to use the [`NarrowPhase::intersection_pair`] method instead. This
@@ -240,6 +260,15 @@ impl NarrowPhase {
         self.contact_graph.interactions()
     }
 
+    /// All the contact pairs maintained by this narrow-phase, for modification.
+    pub(crate) fn contact_pairs_mut(&mut self) -> impl Iterator<Item = &mut ContactPair> {
+        self.contact_graph
+            .graph
+            .edges
+            .iter_mut()
+            .map(|edge| &mut edge.weight)
+    }
+
     /// All the intersection pairs maintained by this narrow-phase.
     pub fn intersection_pairs(
         &self,
@@ -890,27 +919,78 @@ impl NarrowPhase {
                     pair.workspace = None;
                 }
 
+                // NOTE: contacts are computed as if both shapes were inflated by their contact
+                //       skin, but the solver sees distances relative to the skins.
+                let contact_skin_sum = co1.contact_skin() + co2.contact_skin();
                 let pos12 = co1.pos.inv_mul(&co2.pos);
                 let _ = query_dispatcher.contact_manifolds(
                     &pos12,
                     &*co1.shape,
                     &*co2.shape,
-                    prediction_distance,
+                    prediction_distance + contact_skin_sum,
                     &mut pair.manifolds,
                     &mut pair.workspace,
                 );
 
+                #[cfg(feature = "dim3")]
+                if let Some(trimesh) = co1.shape.as_trimesh() {
+                    for manifold in &mut pair.manifolds {
+                        fix_internal_edge_contacts(manifold, trimesh, true, &pos12);
+                    }
+                } else if let Some(trimesh) = co2.shape.as_trimesh() {
+                    for manifold in &mut pair.manifolds {
+                        fix_internal_edge_contacts(manifold, trimesh, false, &pos12);
+                    }
+                }
+
+                #[cfg(feature = "dim2")]
+                if let Some(polyline) = co1.shape.as_polyline() {
+                    for manifold in &mut pair.manifolds {
+                        fix_polyline_contacts(manifold, polyline, true, co1.is_one_sided(), &pos12);
+                    }
+                } else if let Some(polyline) = co2.shape.as_polyline() {
+                    for manifold in &mut pair.manifolds {
+                        fix_polyline_contacts(
+                            manifold,
+                            polyline,
+                            false,
+                            co2.is_one_sided(),
+                            &pos12,
+                        );
+                    }
+                }
+
+                let material1 = colliders.effective_material(co1);
+                let material2 = colliders.effective_material(co2);
                 let friction = CoefficientCombineRule::combine(
-                    co1.material.friction,
-                    co2.material.friction,
-                    co1.material.friction_combine_rule as u8,
-                    co2.material.friction_combine_rule as u8,
+                    material1.friction,
+                    material2.friction,
+                    material1.friction_combine_rule as u8,
+                    material2.friction_combine_rule as u8,
+                );
+                let rolling_friction = CoefficientCombineRule::combine(
+                    material1.rolling_friction,
+                    material2.rolling_friction,
+                    material1.friction_combine_rule as u8,
+                    material2.friction_combine_rule as u8,
+                );
+                #[cfg(feature = "dim3")]
+                let twist_friction = CoefficientCombineRule::combine(
+                    material1.twist_friction,
+                    material2.twist_friction,
+                    material1.friction_combine_rule as u8,
+                    material2.friction_combine_rule as u8,
                 );
                 let restitution = CoefficientCombineRule::combine(
-                    co1.material.restitution,
-                    co2.material.restitution,
-                    co1.material.restitution_combine_rule as u8,
-                    co2.material.restitution_combine_rule as u8,
+                    material1.restitution,
+                    material2.restitution,
+                    material1.restitution_combine_rule as u8,
+                    material2.restitution_combine_rule as u8,
+                );
+
+                let (stiffness, damping) = combine_contact_softness(
+                    (material1.contact_stiffness, material1.contact_damping),
+                    (material2.contact_stiffness, material2.contact_damping),
                 );
 
                 let zero = RigidBodyDominance(0); // The value doesn't matter, it will be MAX because of the effective groups.
@@ -924,6 +1004,9 @@ impl NarrowPhase {
                     .unwrap_or(zero);
 
                 pair.has_any_active_contact = false;
+                let has_surface_velocity = co1.has_surface_velocity() || co2.has_surface_velocity();
+                let has_anisotropic_friction = material1.friction_direction.is_some()
+                    || material2.friction_direction.is_some();
 
                 for manifold in &mut pair.manifolds {
                     let world_pos1 = manifold.subshape_pos1.prepend_to(&co1.pos);
@@ -931,10 +1014,75 @@ impl NarrowPhase {
                     manifold.data.rigid_body1 = co1.parent.map(|p| p.handle);
                     manifold.data.rigid_body2 = co2.parent.map(|p| p.handle);
                     manifold.data.solver_flags = solver_flags;
+                    manifold.data.rolling_friction = rolling_friction;
+                    #[cfg(feature = "dim3")]
+                    {
+                        manifold.data.twist_friction = twist_friction;
+                    }
                     manifold.data.relative_dominance = dominance1.effective_group(&rb_type1)
                         - dominance2.effective_group(&rb_type2);
                     manifold.data.normal = world_pos1 * manifold.local_n1;
 
+                    #[cfg(feature = "dim3")]
+                    {
+                        manifold.data.friction_direction = None;
+                        manifold.data.friction_anisotropy = 1.0;
+                    }
+
+                    let friction = if has_anisotropic_friction {
+                        let normal = manifold.data.normal;
+                        let friction_along = |dir: &Vector<Real>| {
+                            CoefficientCombineRule::combine(
+                                material1.friction_along(&co1.pos.inverse_transform_vector(dir)),
+                                material2.friction_along(&co2.pos.inverse_transform_vector(dir)),
+                                material1.friction_combine_rule as u8,
+                                material2.friction_combine_rule as u8,
+                            )
+                        };
+
+                        // In 2D, the friction only applies along the unique tangent direction.
+                        #[cfg(feature = "dim2")]
+                        let friction = friction_along(&normal.orthonormal_vector());
+                        // In 3D, the friction is aligned with the friction direction of the
+                        // first anisotropic collider, projected on the contact plane.
+                        #[cfg(feature = "dim3")]
+                        let friction = {
+                            let friction_dir = co1
+                                .material
+                                .friction_direction
+                                .map(|dir| co1.pos.0 * dir)
+                                .or_else(|| material2.friction_direction.map(|dir| co2.pos.0 * dir))
+                                .unwrap();
+                            let tangent1 = (friction_dir.into_inner()
+                                - normal * normal.dot(&friction_dir))
+                            .try_normalize(1.0e-4);
+
+                            if let Some(tangent1) = tangent1 {
+                                let tangent2 = normal.cross(&tangent1);
+                                let friction1 = friction_along(&tangent1);
+                                let friction2 = friction_along(&tangent2);
+                                let (dir, strong, weak) = if friction1 >= friction2 {
+                                    (tangent1, friction1, friction2)
+                                } else {
+                                    (tangent2, friction2, friction1)
+                                };
+
+                                manifold.data.friction_direction = Some(dir);
+                                if strong > 0.0 {
+                                    manifold.data.friction_anisotropy = weak / strong;
+                                }
+                                strong
+                            } else {
+                                // The friction direction is orthogonal to the contact plane.
+                                friction_along(&normal.orthonormal_vector())
+                            }
+                        };
+
+                        friction
+                    } else {
+                        friction
+                    };
+
                     // Generate solver contacts.
                     for (contact_id, contact) in manifold.points.iter().enumerate() {
                         assert!(
@@ -942,16 +1090,32 @@ impl NarrowPhase {
                             "A contact manifold cannot contain more than 255 contacts currently."
                         );
 
-                        if contact.dist < prediction_distance {
+                        let effective_dist = contact.dist - contact_skin_sum;
+
+                        if effective_dist < prediction_distance {
+                            let point = world_pos1 * contact.local_p1
+                                + manifold.data.normal * contact.dist / 2.0;
+                            // The velocity of the second surface relative to the first one that
+                            // the friction should achieve.
+                            let tangent_velocity = if has_surface_velocity {
+                                let vel = co1.surface_velocity_at_point(&point)
+                                    - co2.surface_velocity_at_point(&point);
+                                let normal = manifold.data.normal;
+                                vel - normal * vel.dot(&normal)
+                            } else {
+                                Vector::zeros()
+                            };
+
                             // Generate the solver contact.
                             let solver_contact = SolverContact {
                                 contact_id: contact_id as u8,
-                                point: world_pos1 * contact.local_p1
-                                    + manifold.data.normal * contact.dist / 2.0,
-                                dist: contact.dist,
+                                point,
+                                dist: effective_dist,
                                 friction,
                                 restitution,
-                                tangent_velocity: Vector::zeros(),
+                                stiffness,
+                                damping,
+                                tangent_velocity,
                                 is_new: contact.data.impulse == 0.0,
                             };
 
@@ -966,6 +1130,7 @@ impl NarrowPhase {
                             std::mem::replace(&mut manifold.data.solver_contacts, Vec::new());
                         let mut modifiable_user_data = manifold.data.user_data;
                         let mut modifiable_normal = manifold.data.normal;
+                        let mut modifiable_solver_flags = manifold.data.solver_flags;
 
                         let mut context = ContactModificationContext {
                             bodies,
@@ -977,6 +1142,7 @@ impl NarrowPhase {
                             manifold,
                             solver_contacts: &mut modifiable_solver_contacts,
                             normal: &mut modifiable_normal,
+                            solver_flags: &mut modifiable_solver_flags,
                             user_data: &mut modifiable_user_data,
                         };
 
@@ -985,6 +1151,30 @@ impl NarrowPhase {
                         manifold.data.solver_contacts = modifiable_solver_contacts;
                         manifold.data.normal = modifiable_normal;
                         manifold.data.user_data = modifiable_user_data;
+                        manifold.data.solver_flags = modifiable_solver_flags;
+                    }
+
+                    // Apply the solver flags overriding the materials.
+                    let solver_flags = manifold.data.solver_flags;
+                    if solver_flags.intersects(
+                        SolverFlags::DISABLE_FRICTION | SolverFlags::DISABLE_RESTITUTION,
+                    ) {
+                        if solver_flags.contains(SolverFlags::DISABLE_FRICTION) {
+                            manifold.data.rolling_friction = 0.0;
+                            #[cfg(feature = "dim3")]
+                            {
+                                manifold.data.twist_friction = 0.0;
+                            }
+                        }
+
+                        for solver_contact in &mut manifold.data.solver_contacts {
+                            if solver_flags.contains(SolverFlags::DISABLE_FRICTION) {
+                                solver_contact.friction = 0.0;
+                            }
+                            if solver_flags.contains(SolverFlags::DISABLE_RESTITUTION) {
+                                solver_contact.restitution = 0.0;
+                            }
+                        }
                     }
                 }
 
@@ -1077,3 +1267,345 @@ impl NarrowPhase {
         }
     }
 }
+
+/// Combines the `(stiffness, damping)` of the contacts of two colliders.
+///
+/// A zero stiffness stands for a rigid contact, so if only one collider is soft, its softness
+/// is used as-is. If both are soft, their springs and dampers are combined in series.
+fn combine_contact_softness(softness1: (Real, Real), softness2: (Real, Real)) -> (Real, Real) {
+    let series = |a: Real, b: Real| {
+        if a + b > 0.0 {
+            a * b / (a + b)
+        } else {
+            0.0
+        }
+    };
+
+    match (softness1.0 > 0.0, softness2.0 > 0.0) {
+        (false, false) => (0.0, 0.0),
+        (true, false) => softness1,
+        (false, true) => softness2,
+        (true, true) => (
+            series(softness1.0, softness2.0),
+            series(softness1.1, softness2.1),
+        ),
+    }
+}
+
+/// Corrects the normal of a contact manifold involving one triangle of a triangle mesh with
+/// pseudo-normals (i.e. built with `TriMeshFlags::ORIENTED`).
+///
+/// Contacts against an edge or a vertex shared by several triangles may have a normal that
+/// isn’t part of the normal cone of this feature, e.g., the contacts with the internal edges of a
+/// flat triangulated floor. In that case, the normal is replaced by the triangle’s normal so that
+/// shapes sliding or rolling across the mesh don’t snag on these edges.
+#[cfg(feature = "dim3")]
+fn fix_internal_edge_contacts(
+    manifold: &mut ContactManifold,
+    trimesh: &TriMesh,
+    trimesh_is_first: bool,
+    pos12: &Isometry<Real>,
+) {
+    const EPS: Real = 1.0e-4;
+
+    let pseudo_normals = match trimesh.pseudo_normals() {
+        Some(pseudo_normals) => pseudo_normals,
+        None => return,
+    };
+    let (triangle_id, local_n) = if trimesh_is_first {
+        (manifold.subshape1, manifold.local_n1)
+    } else {
+        (manifold.subshape2, manifold.local_n2)
+    };
+    let triangle = trimesh.triangle(triangle_id);
+    let face_normal = match triangle.normal() {
+        Some(normal) => normal.into_inner(),
+        None => return,
+    };
+
+    // NOTE: contacts on the back side of the triangle are left untouched.
+    if manifold.points.is_empty() || local_n.dot(&face_normal) <= 0.0 {
+        return;
+    }
+
+    let indices = trimesh.indices()[triangle_id as usize];
+    let needs_fix = manifold.points.iter().any(|contact| {
+        let local_pt = if trimesh_is_first {
+            contact.local_p1
+        } else {
+            contact.local_p2
+        };
+        let (_, location) = triangle.project_local_point_and_get_location(&local_pt, false);
+        let bcoords = location.barycentric_coordinates().unwrap_or([1.0 / 3.0; 3]);
+        let edge_normals = &pseudo_normals.edges_pseudo_normal[triangle_id as usize];
+        let feature_normal = match bcoords.map(|bcoord| bcoord > EPS) {
+            [true, false, false] => pseudo_normals.vertices_pseudo_normal[indices[0] as usize],
+            [false, true, false] => pseudo_normals.vertices_pseudo_normal[indices[1] as usize],
+            [false, false, true] => pseudo_normals.vertices_pseudo_normal[indices[2] as usize],
+            // NOTE: the edges pseudo-normals are ordered as AB, BC, CA.
+            [true, true, false] => edge_normals[0],
+            [false, true, true] => edge_normals[1],
+            [true, false, true] => edge_normals[2],
+            _ => face_normal,
+        };
+        let feature_normal = feature_normal.try_normalize(EPS).unwrap_or(face_normal);
+
+        local_n.dot(&feature_normal) < face_normal.dot(&feature_normal) - EPS
+    });
+
+    if needs_fix {
+        let subshape_pos12 = manifold
+            .subshape_pos2
+            .prepend_to(&manifold.subshape_pos1.inv_mul(pos12));
+
+        if trimesh_is_first {
+            manifold.local_n1 = face_normal;
+            manifold.local_n2 = -subshape_pos12.inverse_transform_vector(&face_normal);
+        } else {
+            manifold.local_n1 = -(subshape_pos12 * face_normal);
+            manifold.local_n2 = face_normal;
+        }
+
+        for contact in &mut manifold.points {
+            contact.dist =
+                (subshape_pos12 * contact.local_p2 - contact.local_p1).dot(&manifold.local_n1);
+        }
+    }
+}
+
+/// Corrects the contacts of a contact manifold involving one segment of a polyline.
+///
+/// Contacts with the vertex shared by two consecutive segments may have a normal that isn’t
+/// between the normals of these segments, e.g., the contacts at the junctions of a flat polyline
+/// made of several segments. In that case, the normal is replaced by the segment’s normal so that
+/// shapes sliding or rolling along the polyline don’t snag on these junctions (aka. "ghost"
+/// collisions).
+///
+/// If the polyline is one-sided, contacts with shapes behind the segment are removed.
+#[cfg(feature = "dim2")]
+fn fix_polyline_contacts(
+    manifold: &mut ContactManifold,
+    polyline: &Polyline,
+    polyline_is_first: bool,
+    one_sided: bool,
+    pos12: &Isometry<Real>,
+) {
+    use parry::shape::SegmentPointLocation;
+
+    const EPS: Real = 1.0e-4;
+
+    let (segment_id, local_n) = if polyline_is_first {
+        (manifold.subshape1, manifold.local_n1)
+    } else {
+        (manifold.subshape2, manifold.local_n2)
+    };
+    let segment = polyline.segment(segment_id);
+    let face_normal = match segment.normal() {
+        Some(normal) => normal.into_inner(),
+        None => return,
+    };
+
+    if manifold.points.is_empty() {
+        return;
+    }
+
+    if local_n.dot(&face_normal) <= 0.0 {
+        if one_sided {
+            manifold.points.clear();
+        }
+
+        // NOTE: contacts on the back side of a two-sided polyline are left untouched.
+        return;
+    }
+
+    let indices = polyline.indices();
+    let segment_normal = |id: usize| {
+        polyline
+            .segment(id as u32)
+            .normal()
+            .map(|normal| normal.into_inner())
+    };
+    // Finds the normal of the segment sharing the `vid`-th vertex of this segment, if any.
+    let neighbor_normal = |vid: u32| {
+        let curr = indices[segment_id as usize];
+        let num_segments = indices.len();
+        if vid == 0 {
+            let prev = (segment_id as usize + num_segments - 1) % num_segments;
+            (indices[prev][1] == curr[0])
+                .then(|| segment_normal(prev))
+                .flatten()
+        } else {
+            let next = (segment_id as usize + 1) % num_segments;
+            (indices[next][0] == curr[1])
+                .then(|| segment_normal(next))
+                .flatten()
+        }
+    };
+
+    let needs_fix = manifold.points.iter().any(|contact| {
+        let local_pt = if polyline_is_first {
+            contact.local_p1
+        } else {
+            contact.local_p2
+        };
+        let (_, location) = segment.project_local_point_and_get_location(&local_pt, false);
+        let vid = match location {
+            SegmentPointLocation::OnVertex(vid) => vid,
+            SegmentPointLocation::OnEdge(_) => return false,
+        };
+
+        // NOTE: contacts with the free end of a polyline are left untouched.
+        neighbor_normal(vid).map_or(false, |neighbor_normal| {
+            let feature_normal = (neighbor_normal + face_normal)
+                .try_normalize(EPS)
+                .unwrap_or(face_normal);
+            local_n.dot(&feature_normal) < face_normal.dot(&feature_normal) - EPS
+        })
+    });
+
+    if needs_fix {
+        let subshape_pos12 = manifold
+            .subshape_pos2
+            .prepend_to(&manifold.subshape_pos1.inv_mul(pos12));
+
+        if polyline_is_first {
+            manifold.local_n1 = face_normal;
+            manifold.local_n2 = -subshape_pos12.inverse_transform_vector(&face_normal);
+        } else {
+            manifold.local_n1 = -(subshape_pos12 * face_normal);
+            manifold.local_n2 = face_normal;
+        }
+
+        for contact in &mut manifold.points {
+            contact.dist =
+                (subshape_pos12 * contact.local_p2 - contact.local_p1).dot(&manifold.local_n1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn user_data_round_trips_through_contact_pairs() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // Two overlapping balls, each tagged with an entity id.
+        for (i, x) in [0.0, 0.5].into_iter().enumerate() {
+            let entity = 42 + i as u128;
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * x)
+                .user_data(entity)
+                .build();
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).user_data(entity << 64);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+        }
+
+        state.step(&mut pipeline, &(), &());
+
+        let pair = state.narrow_phase.contact_pairs().next().unwrap();
+        assert!(pair.has_any_active_contact);
+
+        let mut entities = vec![];
+        for handle in [pair.collider1, pair.collider2] {
+            let collider = &state.colliders[handle];
+            let body = &state.bodies[collider.parent().unwrap()];
+            assert_eq!(collider.user_data, body.user_data << 64);
+            entities.push(body.user_data);
+        }
+
+        entities.sort();
+        assert_eq!(entities, [42, 43]);
+    }
+
+    #[test]
+    fn narrow_phase_contact_queries() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+        let sensor = state.colliders.insert(
+            ColliderBuilder::ball(1.0)
+                .translation(Vector::y() * 0.5)
+                .sensor(true),
+        );
+
+        // One ball resting on the ground inside of the sensor, and one ball in the air.
+        let grounded_body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y()));
+        let grounded = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            grounded_body,
+            &mut state.bodies,
+        );
+        let flying_body = state.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 5.0 + Vector::y() * 5.0)
+                .gravity_scale(0.0),
+        );
+        let flying = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5),
+            flying_body,
+            &mut state.bodies,
+        );
+
+        step_n(&mut state, &mut pipeline, 30);
+
+        let is_grounded = |handle| {
+            state
+                .narrow_phase
+                .contacts_with(handle)
+                .any(|pair| pair.has_any_active_contact)
+        };
+        assert!(is_grounded(grounded));
+        assert!(!is_grounded(flying));
+
+        // The contact pair can be retrieved in any order.
+        let pair = state.narrow_phase.contact_pair(ground, grounded).unwrap();
+        let pair_rev = state.narrow_phase.contact_pair(grounded, ground).unwrap();
+        assert_eq!(
+            (pair.collider1, pair.collider2),
+            (pair_rev.collider1, pair_rev.collider2)
+        );
+        assert!(state.narrow_phase.contact_pair(ground, flying).is_none());
+        assert_eq!(state.narrow_phase.contact_pairs().count(), 1);
+
+        // Where the ball touches the ground.
+        let (manifold, _) = pair.find_deepest_contact().unwrap();
+        assert!(manifold
+            .data
+            .solver_contacts
+            .iter()
+            .all(|contact| contact.point.coords.norm() < 1.0e-2));
+
+        // Sensors are only reported by the intersection queries.
+        assert_eq!(
+            state.narrow_phase.intersection_pair(sensor, grounded),
+            Some(true)
+        );
+        // NOTE: the intersection between two fixed colliders (the sensor and the ground) isn’t
+        //       computed.
+        let intersections: Vec<_> = state
+            .narrow_phase
+            .intersections_with(sensor)
+            .filter(|(_, _, intersecting)| *intersecting)
+            .collect();
+        assert_eq!(intersections.len(), 1);
+        assert!([intersections[0].0, intersections[0].1].contains(&grounded));
+        assert!(state.narrow_phase.contacts_with(sensor).next().is_none());
+    }
+}
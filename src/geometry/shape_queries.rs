@@ -0,0 +1,87 @@
+use crate::geometry::Shape;
+use crate::math::{Isometry, Real};
+use parry::query::{DefaultQueryDispatcher, QueryDispatcher, Unsupported};
+
+/// The contact between two shapes, as computed by [`contact`].
+///
+/// Contrary to a [`Contact`](crate::geometry::Contact) of a contact manifold, its points and
+/// normals are expressed in world-space.
+pub type ShapeContact = parry::query::Contact;
+
+/// Computes the deepest contact between two shapes, without any pipeline.
+///
+/// This relies on the same collision-detection algorithms as the default
+/// [`NarrowPhase`](crate::geometry::NarrowPhase), and can be used by tools that need to test two
+/// shapes directly.
+///
+/// # Parameters
+/// * `shape1`, `pos1`: the first shape and its world-space position.
+/// * `shape2`, `pos2`: the second shape and its world-space position.
+/// * `prediction`: the contact is reported if the distance between the shapes is smaller than
+///   this value. Must be positive or zero.
+///
+/// Returns `Ok(None)` if the shapes are further apart than `prediction`, and
+/// `Err(Unsupported)` if this pair of shapes isn’t supported.
+pub fn contact(
+    shape1: &dyn Shape,
+    pos1: &Isometry<Real>,
+    shape2: &dyn Shape,
+    pos2: &Isometry<Real>,
+    prediction: Real,
+) -> Result<Option<ShapeContact>, Unsupported> {
+    let pos12 = pos1.inv_mul(pos2);
+    let contact = DefaultQueryDispatcher.contact(&pos12, shape1, shape2, prediction)?;
+    Ok(contact.map(|mut c| {
+        c.transform_by_mut(pos1, pos2);
+        c
+    }))
+}
+
+/// Computes the minimal distance between two shapes, without any pipeline.
+///
+/// Returns `0.0` if the shapes are intersecting, and `Err(Unsupported)` if this pair of shapes
+/// isn’t supported.
+pub fn distance(
+    shape1: &dyn Shape,
+    pos1: &Isometry<Real>,
+    shape2: &dyn Shape,
+    pos2: &Isometry<Real>,
+) -> Result<Real, Unsupported> {
+    let pos12 = pos1.inv_mul(pos2);
+    DefaultQueryDispatcher.distance(&pos12, shape1, shape2)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::geometry::{contact, distance, Ball, Cuboid};
+    use crate::math::{Isometry, Vector};
+
+    #[test]
+    fn contact_and_distance_between_two_shapes() {
+        let ball = Ball::new(0.5);
+        let cuboid = Cuboid::new(Vector::repeat(1.0));
+        let pos1 = Isometry::translation(
+            10.0,
+            0.0,
+            #[cfg(feature = "dim3")]
+            0.0,
+        );
+        let pos2 = Isometry::translation(
+            10.0,
+            2.0,
+            #[cfg(feature = "dim3")]
+            0.0,
+        );
+
+        let dist = distance(&ball, &pos2, &cuboid, &pos1).unwrap();
+        assert!((dist - 0.5).abs() < 1.0e-5);
+
+        assert_eq!(contact(&ball, &pos2, &cuboid, &pos1, 0.4).unwrap(), None);
+        let c = contact(&ball, &pos2, &cuboid, &pos1, 0.6).unwrap().unwrap();
+        assert!((c.dist - 0.5).abs() < 1.0e-5);
+        // The contact is expressed in world-space.
+        assert!((c.point1.y - 1.5).abs() < 1.0e-5);
+        assert!((c.point2.y - 1.0).abs() < 1.0e-5);
+        assert!((c.normal1.y + 1.0).abs() < 1.0e-5);
+    }
+}
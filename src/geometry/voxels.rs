@@ -0,0 +1,568 @@
+use crate::math::{Isometry, Point, Real, Vector, DIM};
+use parry::bounding_volume::{Aabb, BoundingSphere};
+use parry::mass_properties::MassProperties;
+use parry::partitioning::{Qbvh, QbvhUpdateWorkspace};
+use parry::query::details::PointCompositeShapeProjBestFirstVisitor;
+use parry::query::{PointProjection, PointQuery, Ray, RayCast, RayIntersection};
+use parry::shape::{
+    Cuboid, FeatureId, Shape, ShapeType, SimdCompositeShape, TypedShape, TypedSimdCompositeShape,
+};
+use parry::utils::hashmap::HashMap;
+use parry::utils::DefaultStorage;
+
+/// The number of voxels along each side of a chunk.
+const CHUNK_SIDE: i32 = 8;
+/// The number of voxels in a chunk.
+const CHUNK_LEN: usize = (CHUNK_SIDE as usize).pow(DIM as u32);
+/// The number of 64-bits words needed to store the state of each voxel of a chunk.
+const CHUNK_WORDS: usize = CHUNK_LEN / 64;
+
+#[derive(Clone, Debug)]
+struct VoxelChunk {
+    key: Point<i32>,
+    filled: [u64; CHUNK_WORDS],
+    len: usize,
+}
+
+impl VoxelChunk {
+    fn new(key: Point<i32>) -> Self {
+        Self {
+            key,
+            filled: [0; CHUNK_WORDS],
+            len: 0,
+        }
+    }
+
+    fn is_filled(&self, local_id: usize) -> bool {
+        self.filled[local_id / 64] & (1 << (local_id % 64)) != 0
+    }
+
+    fn set_filled(&mut self, local_id: usize, filled: bool) {
+        if filled {
+            self.filled[local_id / 64] |= 1 << (local_id % 64);
+        } else {
+            self.filled[local_id / 64] &= !(1 << (local_id % 64));
+        }
+    }
+}
+
+#[derive(Clone)]
+/// A shape made of a sparse set of axis-aligned boxes (voxels) placed on a regular grid.
+///
+/// The voxel identified by the grid coordinates `key` occupies the box ranging from
+/// `key * voxel_size` to `(key + 1) * voxel_size` in the local-space of the shape. The grid is
+/// stored as a sparse set of chunks, so voxels can be added or removed individually, very
+/// efficiently, with [`Voxels::set_voxel`]. This is typically useful for destructible terrains.
+///
+/// Voxels are attached to a collider with [`ColliderBuilder::voxels`](crate::geometry::ColliderBuilder::voxels)
+/// and can be modified afterward through [`Collider::shape_mut`](crate::geometry::Collider::shape_mut):
+/// `collider.shape_mut().as_shape_mut::<Voxels>()`.
+///
+/// Colliders with a voxel shape can’t be serialized.
+pub struct Voxels {
+    voxel_size: Vector<Real>,
+    chunks: Vec<VoxelChunk>,
+    chunk_ids: HashMap<Point<i32>, u32>,
+    free_chunks: Vec<u32>,
+    len: usize,
+    qbvh: Qbvh<u32>,
+    qbvh_workspace: QbvhUpdateWorkspace,
+}
+
+impl Voxels {
+    /// Creates a new voxel shape with the given voxel size and set of filled voxels, identified by
+    /// their grid coordinates.
+    pub fn new(voxel_size: Vector<Real>, voxels: &[Point<i32>]) -> Self {
+        let mut result = Self {
+            voxel_size,
+            chunks: vec![],
+            chunk_ids: HashMap::default(),
+            free_chunks: vec![],
+            len: 0,
+            qbvh: Qbvh::new(),
+            qbvh_workspace: QbvhUpdateWorkspace::default(),
+        };
+
+        for key in voxels {
+            let (chunk_id, local_id) = result.chunk_and_local_ids_or_insert(*key);
+            let chunk = &mut result.chunks[chunk_id as usize];
+
+            if !chunk.is_filled(local_id) {
+                chunk.set_filled(local_id, true);
+                chunk.len += 1;
+                result.len += 1;
+            }
+        }
+
+        let leaves: Vec<_> = result
+            .part_ids()
+            .map(|id| (id, result.part_aabb(id)))
+            .collect();
+        result.qbvh.clear_and_rebuild(leaves.into_iter(), 0.0);
+        result
+    }
+
+    /// The size of each voxel.
+    pub fn voxel_size(&self) -> Vector<Real> {
+        self.voxel_size
+    }
+
+    /// The number of filled voxels.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is this shape empty, i.e., without any filled voxel?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The grid coordinates of the voxel containing the given local-space point.
+    pub fn voxel_at_point(&self, point: &Point<Real>) -> Point<i32> {
+        let mut key = Point::origin();
+        for i in 0..DIM {
+            key[i] = (point[i] / self.voxel_size[i]).floor() as i32;
+        }
+        key
+    }
+
+    /// The local-space center of the voxel with the given grid coordinates.
+    pub fn voxel_center(&self, key: Point<i32>) -> Point<Real> {
+        let mut center = Point::origin();
+        for i in 0..DIM {
+            center[i] = (key[i] as Real + 0.5) * self.voxel_size[i];
+        }
+        center
+    }
+
+    /// The local-space Aabb of the voxel with the given grid coordinates.
+    pub fn voxel_aabb(&self, key: Point<i32>) -> Aabb {
+        Aabb::from_half_extents(self.voxel_center(key), self.voxel_size / 2.0)
+    }
+
+    /// Is the voxel with the given grid coordinates filled?
+    pub fn is_voxel_filled(&self, key: Point<i32>) -> bool {
+        let (chunk_key, local_id) = Self::chunk_key_and_local_id(key);
+        self.chunk_ids
+            .get(&chunk_key)
+            .map(|chunk_id| self.chunks[*chunk_id as usize].is_filled(local_id))
+            .unwrap_or(false)
+    }
+
+    /// Fills or empties the voxel with the given grid coordinates.
+    ///
+    /// Returns whether the voxel was filled before this call.
+    pub fn set_voxel(&mut self, key: Point<i32>, filled: bool) -> bool {
+        if filled {
+            let (chunk_id, local_id) = self.chunk_and_local_ids_or_insert(key);
+            let chunk = &mut self.chunks[chunk_id as usize];
+
+            if chunk.is_filled(local_id) {
+                return true;
+            }
+
+            chunk.set_filled(local_id, true);
+            chunk.len += 1;
+            self.len += 1;
+            self.qbvh
+                .pre_update_or_insert(Self::part_id(chunk_id, local_id));
+        } else {
+            let (chunk_key, local_id) = Self::chunk_key_and_local_id(key);
+            let chunk_id = match self.chunk_ids.get(&chunk_key) {
+                Some(chunk_id) => *chunk_id,
+                None => return false,
+            };
+            let chunk = &mut self.chunks[chunk_id as usize];
+
+            if !chunk.is_filled(local_id) {
+                return false;
+            }
+
+            chunk.set_filled(local_id, false);
+            chunk.len -= 1;
+            self.len -= 1;
+            let _ = self.qbvh.remove(Self::part_id(chunk_id, local_id));
+
+            if chunk.len == 0 {
+                // Recycle the empty chunk.
+                let _ = self.chunk_ids.remove(&chunk_key);
+                self.free_chunks.push(chunk_id);
+            }
+        }
+
+        self.update_qbvh();
+        !filled
+    }
+
+    /// Iterates through the grid coordinates of all the filled voxels.
+    pub fn voxels(&self) -> impl Iterator<Item = Point<i32>> + '_ {
+        self.part_ids().map(move |id| self.part_key(id))
+    }
+
+    fn update_qbvh(&mut self) {
+        let Self {
+            voxel_size,
+            chunks,
+            qbvh,
+            qbvh_workspace,
+            ..
+        } = self;
+
+        let _ = qbvh.refit(0.0, qbvh_workspace, |id| {
+            let key = Self::key_from_part(chunks, *id);
+            let mut center = Point::origin();
+            for i in 0..DIM {
+                center[i] = (key[i] as Real + 0.5) * voxel_size[i];
+            }
+            Aabb::from_half_extents(center, *voxel_size / 2.0)
+        });
+        qbvh.rebalance(0.0, qbvh_workspace);
+    }
+
+    fn chunk_key_and_local_id(key: Point<i32>) -> (Point<i32>, usize) {
+        let mut chunk_key = Point::origin();
+        let mut local_id = 0;
+        let mut stride = 1;
+
+        for i in 0..DIM {
+            chunk_key[i] = key[i].div_euclid(CHUNK_SIDE);
+            local_id += key[i].rem_euclid(CHUNK_SIDE) as usize * stride;
+            stride *= CHUNK_SIDE as usize;
+        }
+
+        (chunk_key, local_id)
+    }
+
+    fn chunk_and_local_ids_or_insert(&mut self, key: Point<i32>) -> (u32, usize) {
+        let (chunk_key, local_id) = Self::chunk_key_and_local_id(key);
+
+        if let Some(chunk_id) = self.chunk_ids.get(&chunk_key) {
+            return (*chunk_id, local_id);
+        }
+
+        let chunk_id = match self.free_chunks.pop() {
+            Some(chunk_id) => {
+                self.chunks[chunk_id as usize] = VoxelChunk::new(chunk_key);
+                chunk_id
+            }
+            None => {
+                self.chunks.push(VoxelChunk::new(chunk_key));
+                self.chunks.len() as u32 - 1
+            }
+        };
+
+        let _ = self.chunk_ids.insert(chunk_key, chunk_id);
+        (chunk_id, local_id)
+    }
+
+    fn part_id(chunk_id: u32, local_id: usize) -> u32 {
+        chunk_id * CHUNK_LEN as u32 + local_id as u32
+    }
+
+    fn key_from_part(chunks: &[VoxelChunk], part_id: u32) -> Point<i32> {
+        let chunk = &chunks[part_id as usize / CHUNK_LEN];
+        let mut local_id = part_id as usize % CHUNK_LEN;
+        let mut key = Point::origin();
+
+        for i in 0..DIM {
+            key[i] = chunk.key[i] * CHUNK_SIDE + (local_id % CHUNK_SIDE as usize) as i32;
+            local_id /= CHUNK_SIDE as usize;
+        }
+
+        key
+    }
+
+    fn part_key(&self, part_id: u32) -> Point<i32> {
+        Self::key_from_part(&self.chunks, part_id)
+    }
+
+    fn part_aabb(&self, part_id: u32) -> Aabb {
+        self.voxel_aabb(self.part_key(part_id))
+    }
+
+    fn part_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.chunk_ids.values().flat_map(move |chunk_id| {
+            let chunk = &self.chunks[*chunk_id as usize];
+            (0..CHUNK_LEN)
+                .filter(move |local_id| chunk.is_filled(*local_id))
+                .map(move |local_id| Self::part_id(*chunk_id, local_id))
+        })
+    }
+
+    fn voxel_shape(&self) -> Cuboid {
+        Cuboid::new(self.voxel_size / 2.0)
+    }
+}
+
+impl RayCast for Voxels {
+    fn cast_local_ray_and_get_normal(
+        &self,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+    ) -> Option<RayIntersection> {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Traverse the grid cells crossed by the ray, in order, starting from the point where
+        // it enters the shape’s Aabb.
+        let aabb = self.compute_local_aabb();
+        let entry_toi = aabb.cast_local_ray(ray, max_toi, true)?;
+        let min_key = self.voxel_at_point(&aabb.mins);
+        let max_key = self.voxel_at_point(&aabb.maxs);
+        let mut key = self.voxel_at_point(&ray.point_at(entry_toi));
+        let mut step = [0; DIM];
+        let mut next_toi = [Real::MAX; DIM];
+        let mut delta_toi = [Real::MAX; DIM];
+
+        for i in 0..DIM {
+            key[i] = key[i].max(min_key[i]).min(max_key[i]);
+
+            if ray.dir[i] > 0.0 {
+                step[i] = 1;
+                next_toi[i] =
+                    ((key[i] + 1) as Real * self.voxel_size[i] - ray.origin[i]) / ray.dir[i];
+                delta_toi[i] = self.voxel_size[i] / ray.dir[i];
+            } else if ray.dir[i] < 0.0 {
+                step[i] = -1;
+                next_toi[i] = (key[i] as Real * self.voxel_size[i] - ray.origin[i]) / ray.dir[i];
+                delta_toi[i] = -self.voxel_size[i] / ray.dir[i];
+            }
+        }
+
+        let voxel = self.voxel_shape();
+
+        loop {
+            if self.is_voxel_filled(key) {
+                let pos = Isometry::from(self.voxel_center(key).coords);
+                if let Some(hit) = voxel.cast_ray_and_get_normal(&pos, ray, max_toi, solid) {
+                    return Some(hit);
+                }
+            }
+
+            let mut axis = 0;
+            for i in 1..DIM {
+                if next_toi[i] < next_toi[axis] {
+                    axis = i;
+                }
+            }
+
+            if next_toi[axis] > max_toi {
+                return None;
+            }
+
+            key[axis] += step[axis];
+            next_toi[axis] += delta_toi[axis];
+
+            if key[axis] < min_key[axis] || key[axis] > max_key[axis] {
+                return None;
+            }
+        }
+    }
+}
+
+impl PointQuery for Voxels {
+    fn project_local_point(&self, point: &Point<Real>, solid: bool) -> PointProjection {
+        if solid && self.contains_local_point(point) {
+            return PointProjection::new(true, *point);
+        }
+
+        let mut visitor = PointCompositeShapeProjBestFirstVisitor::new(self, point, solid);
+        self.qbvh
+            .traverse_best_first(&mut visitor)
+            .map(|result| result.1 .0)
+            .unwrap_or_else(|| PointProjection::new(false, *point))
+    }
+
+    fn project_local_point_and_get_feature(
+        &self,
+        point: &Point<Real>,
+    ) -> (PointProjection, FeatureId) {
+        (self.project_local_point(point, false), FeatureId::Unknown)
+    }
+
+    fn contains_local_point(&self, point: &Point<Real>) -> bool {
+        self.is_voxel_filled(self.voxel_at_point(point))
+    }
+}
+
+impl Shape for Voxels {
+    fn compute_local_aabb(&self) -> Aabb {
+        if self.is_empty() {
+            Aabb::new(Point::origin(), Point::origin())
+        } else {
+            // NOTE: the root Aabb of the Qbvh may be slightly larger than the actual voxels
+            //       after some of them were removed, which is fine.
+            self.qbvh.raw_nodes()[0].simd_aabb.to_merged_aabb()
+        }
+    }
+
+    fn compute_local_bounding_sphere(&self) -> BoundingSphere {
+        self.compute_local_aabb().bounding_sphere()
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    fn mass_properties(&self, density: Real) -> MassProperties {
+        let voxel = MassProperties::from_cuboid(density, self.voxel_size / 2.0);
+        self.voxels()
+            .map(|key| voxel.transform_by(&Isometry::from(self.voxel_center(key).coords)))
+            .sum()
+    }
+
+    fn shape_type(&self) -> ShapeType {
+        ShapeType::Custom
+    }
+
+    fn as_typed_shape(&self) -> TypedShape<'_> {
+        TypedShape::Custom(0)
+    }
+
+    fn ccd_thickness(&self) -> Real {
+        self.voxel_size.min() / 2.0
+    }
+
+    fn ccd_angular_thickness(&self) -> Real {
+        std::f32::consts::FRAC_PI_2 as Real
+    }
+
+    fn as_composite_shape(&self) -> Option<&dyn SimdCompositeShape> {
+        Some(self as &dyn SimdCompositeShape)
+    }
+}
+
+impl SimdCompositeShape for Voxels {
+    fn map_part_at(&self, part_id: u32, f: &mut dyn FnMut(Option<&Isometry<Real>>, &dyn Shape)) {
+        let pos = Isometry::from(self.voxel_center(self.part_key(part_id)).coords);
+        f(Some(&pos), &self.voxel_shape())
+    }
+
+    fn qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+impl TypedSimdCompositeShape for Voxels {
+    type PartShape = Cuboid;
+    type PartId = u32;
+    type QbvhStorage = DefaultStorage;
+
+    fn map_typed_part_at(
+        &self,
+        part_id: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &Self::PartShape),
+    ) {
+        let pos = Isometry::from(self.voxel_center(self.part_key(part_id)).coords);
+        f(Some(&pos), &self.voxel_shape())
+    }
+
+    fn map_untyped_part_at(
+        &self,
+        part_id: u32,
+        mut f: impl FnMut(Option<&Isometry<Real>>, &dyn Shape),
+    ) {
+        let pos = Isometry::from(self.voxel_center(self.part_key(part_id)).coords);
+        f(Some(&pos), &self.voxel_shape())
+    }
+
+    fn typed_qbvh(&self) -> &Qbvh<u32> {
+        &self.qbvh
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::step_n;
+
+    #[test]
+    fn voxels_can_be_edited_and_ray_cast() {
+        use crate::geometry::{Shape, Voxels};
+        use crate::math::Isometry;
+        use parry::query::{Ray, RayCast};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        // Two layers of terrain: the top of the first is at y = 0, the top of the second at y = -2.
+        let mut keys = vec![];
+        for i in -10..10 {
+            for layer in [-1, -3] {
+                #[cfg(feature = "dim2")]
+                keys.push(Point::new(i, layer));
+                #[cfg(feature = "dim3")]
+                for k in -10..10 {
+                    keys.push(Point::new(i, layer, k));
+                }
+            }
+        }
+
+        let terrain = state
+            .colliders
+            .insert(ColliderBuilder::voxels(Vector::repeat(1.0), &keys));
+        let voxels = state.colliders[terrain]
+            .shape()
+            .as_shape::<Voxels>()
+            .unwrap();
+        assert_eq!(voxels.len(), keys.len());
+        let voxel_mass = voxels.mass_properties(1.0).mass();
+        assert!((voxel_mass - keys.len() as Real).abs() < 1.0e-3);
+
+        // Ray-casts traverse the grid.
+        let hole = voxels.voxel_at_point(&Point::from(Vector::repeat(0.5) - Vector::y()));
+        assert_eq!(hole, Point::from(-Vector::y()));
+        let ray = Ray::new(
+            Point::from(Vector::repeat(0.5) + Vector::y() * 5.0),
+            -Vector::y(),
+        );
+        let hit = voxels
+            .cast_ray_and_get_normal(&Isometry::identity(), &ray, Real::MAX, true)
+            .unwrap();
+        assert!((hit.toi - 5.5).abs() < 1.0e-5);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-5);
+        let diagonal = Ray::new(ray.origin, (Vector::x() - Vector::y()).normalize());
+        let hit = voxels.cast_ray(&Isometry::identity(), &diagonal, Real::MAX, true);
+        assert!((hit.unwrap() - 5.5 * Real::sqrt(2.0)).abs() < 1.0e-4);
+        assert!(voxels
+            .cast_ray(&Isometry::identity(), &ray, 5.0, true)
+            .is_none());
+
+        let body = state.bodies.insert(
+            RigidBodyBuilder::dynamic().translation(Vector::repeat(0.5) + Vector::y() * 1.0),
+        );
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.4), body, &mut state.bodies);
+
+        step_n(&mut state, &mut pipeline, 60);
+        assert!((state.bodies[body].translation().y - 0.4).abs() < 1.0e-2);
+
+        // Dig a hole below the ball: it falls onto the second layer.
+        let voxels = state.colliders[terrain]
+            .shape_mut()
+            .as_shape_mut::<Voxels>()
+            .unwrap();
+        assert!(voxels.set_voxel(hole, false));
+        assert!(!voxels.set_voxel(hole, false));
+        assert!(!voxels.is_voxel_filled(hole));
+        assert_eq!(voxels.len(), keys.len() - 1);
+        state.bodies[body].wake_up(true);
+        step_n(&mut state, &mut pipeline, 120);
+        assert!((state.bodies[body].translation().y + 1.6).abs() < 1.0e-2);
+
+        // Fill it again above the ball.
+        let voxels = state.colliders[terrain]
+            .shape_mut()
+            .as_shape_mut::<Voxels>()
+            .unwrap();
+        assert!(!voxels.set_voxel(hole, true));
+        let hit = voxels.cast_ray(&Isometry::identity(), &ray, Real::MAX, true);
+        assert!((hit.unwrap() - 5.5).abs() < 1.0e-5);
+    }
+}
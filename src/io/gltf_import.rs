@@ -0,0 +1,271 @@
+use super::{ImportError, ImportedBody, MeshShape};
+use crate::dynamics::RigidBodyBuilder;
+use crate::geometry::ColliderBuilder;
+use crate::math::{Isometry, Point, Real, Translation, Vector};
+use gltf::buffer::Source;
+use gltf::mesh::Mode;
+use na::{Matrix4, Quaternion, UnitQuaternion};
+use std::path::Path;
+
+/// Loads the colliders of the default scene of a glTF file, or of its first scene if it doesn’t
+/// specify a default one.
+///
+/// Each root node of the scene becomes a fixed rigid-body positioned at the translation and
+/// rotation of this node. The meshes of this node and of its descendants become colliders of
+/// this rigid-body, with their vertices transformed by the node hierarchy, so the scales
+/// (including non-uniform ones) are baked into the collider shapes. The root nodes without any
+/// mesh in their subtree are ignored.
+///
+/// The external buffers of the file are loaded relative to its directory. The buffers embedded
+/// as data URIs aren’t supported.
+pub fn load_gltf(
+    path: impl AsRef<Path>,
+    shape: &MeshShape,
+) -> Result<Vec<ImportedBody>, ImportError> {
+    let path = path.as_ref();
+    let gltf = gltf::Gltf::from_slice(&std::fs::read(path)?)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let buffers = gltf
+        .document
+        .buffers()
+        .map(|buffer| match buffer.source() {
+            Source::Bin => gltf
+                .blob
+                .clone()
+                .ok_or(ImportError::UnsupportedBuffer(buffer.index())),
+            Source::Uri(uri) if !uri.starts_with("data:") => Ok(std::fs::read(base_dir.join(uri))?),
+            Source::Uri(_) => Err(ImportError::UnsupportedBuffer(buffer.index())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    import_document(&gltf.document, &buffers, shape)
+}
+
+/// Loads the colliders of a binary glTF (`.glb`) file from memory.
+///
+/// Only the buffer stored in the binary chunk of the file is supported. See [`load_gltf`] for
+/// the way the nodes are mapped to rigid-bodies and colliders.
+pub fn load_gltf_from_slice(
+    bytes: &[u8],
+    shape: &MeshShape,
+) -> Result<Vec<ImportedBody>, ImportError> {
+    let gltf = gltf::Gltf::from_slice(bytes)?;
+    let buffers = gltf
+        .document
+        .buffers()
+        .map(|buffer| match buffer.source() {
+            Source::Bin => gltf
+                .blob
+                .clone()
+                .ok_or(ImportError::UnsupportedBuffer(buffer.index())),
+            Source::Uri(_) => Err(ImportError::UnsupportedBuffer(buffer.index())),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    import_document(&gltf.document, &buffers, shape)
+}
+
+fn import_document(
+    document: &gltf::Document,
+    buffers: &[Vec<u8>],
+    shape: &MeshShape,
+) -> Result<Vec<ImportedBody>, ImportError> {
+    let scene = match document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        Some(scene) => scene,
+        None => return Ok(vec![]),
+    };
+    let mut result = vec![];
+
+    for node in scene.nodes() {
+        let (translation, rotation, scale) = node.transform().decomposed();
+        let translation = Translation::new(
+            translation[0] as Real,
+            translation[1] as Real,
+            translation[2] as Real,
+        );
+        let rotation = UnitQuaternion::new_normalize(
+            Quaternion::new(rotation[3], rotation[0], rotation[1], rotation[2]).cast::<Real>(),
+        );
+        // The scale of the root node isn’t part of the rigid-body position, so it is applied to
+        // the vertices of the colliders instead.
+        let node_to_body = Matrix4::new_nonuniform_scaling(&Vector::new(
+            scale[0] as Real,
+            scale[1] as Real,
+            scale[2] as Real,
+        ));
+
+        let mut colliders = vec![];
+        collect_colliders(&node, &node_to_body, buffers, shape, &mut colliders)?;
+
+        if !colliders.is_empty() {
+            result.push(ImportedBody {
+                name: node.name().map(str::to_string),
+                body: RigidBodyBuilder::fixed()
+                    .position(Isometry::from_parts(translation, rotation)),
+                colliders,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+fn collect_colliders(
+    node: &gltf::Node,
+    node_to_body: &Matrix4<Real>,
+    buffers: &[Vec<u8>],
+    shape: &MeshShape,
+    out: &mut Vec<ColliderBuilder>,
+) -> Result<(), ImportError> {
+    if let Some(mesh) = node.mesh() {
+        // NOTE: all the triangle primitives of a mesh are merged into a single collider.
+        let mut vertices = vec![];
+        let mut indices = vec![];
+
+        for primitive in mesh.primitives() {
+            if primitive.mode() != Mode::Triangles {
+                continue;
+            }
+
+            let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|b| &b[..]));
+            let positions = match reader.read_positions() {
+                Some(positions) => positions,
+                None => continue,
+            };
+
+            let base_id = vertices.len() as u32;
+            vertices.extend(positions.map(|p| {
+                let p = Point::new(p[0] as Real, p[1] as Real, p[2] as Real);
+                node_to_body.transform_point(&p)
+            }));
+            let num_vertices = vertices.len() as u32 - base_id;
+            let primitive_indices: Vec<u32> = match reader.read_indices() {
+                Some(ids) => ids.into_u32().collect(),
+                None => (0..num_vertices).collect(),
+            };
+            if primitive_indices.iter().any(|id| *id >= num_vertices) {
+                return Err(ImportError::InvalidIndex);
+            }
+            indices.extend(
+                primitive_indices
+                    .chunks_exact(3)
+                    .map(|tri| [base_id + tri[0], base_id + tri[1], base_id + tri[2]]),
+            );
+        }
+
+        if !indices.is_empty() {
+            out.push(shape.build(vertices, indices)?);
+        }
+    }
+
+    for child in node.children() {
+        let child_to_node = Matrix4::from(child.transform().matrix()).cast::<Real>();
+        collect_colliders(&child, &(node_to_body * child_to_node), buffers, shape, out)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::load_gltf_from_slice;
+    use crate::io::{ImportError, MeshShape};
+    use crate::math::{Point, Vector};
+
+    fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+        let mut json = json.as_bytes().to_vec();
+        while json.len() % 4 != 0 {
+            json.push(b' ');
+        }
+
+        let mut bytes = vec![];
+        bytes.extend_from_slice(b"glTF");
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+        bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"JSON");
+        bytes.extend_from_slice(&json);
+        bytes.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"BIN\0");
+        bytes.extend_from_slice(bin);
+        bytes
+    }
+
+    #[test]
+    fn gltf_nodes_are_mapped_to_bodies_with_their_child_meshes() {
+        let json = r#"{
+            "asset": { "version": "2.0" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0, 2] }],
+            "nodes": [
+                { "name": "level", "translation": [1, 2, 3], "scale": [2, 2, 2], "children": [1] },
+                { "mesh": 0, "translation": [0, 0, 1] },
+                { "name": "empty" }
+            ],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "accessors": [{
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0, 0, 0], "max": [1, 1, 0]
+            }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+            "buffers": [{ "byteLength": 36 }]
+        }"#;
+        let bin: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+            .iter()
+            .flat_map(|e| e.to_le_bytes())
+            .collect();
+
+        let bodies = load_gltf_from_slice(&glb(json, &bin), &MeshShape::TriMesh).unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0].name.as_deref(), Some("level"));
+        assert_eq!(
+            bodies[0].body.position.translation.vector,
+            Vector::new(1.0, 2.0, 3.0)
+        );
+        assert_eq!(bodies[0].colliders.len(), 1);
+
+        // The child translation and the root scale are baked into the vertices.
+        let trimesh = bodies[0].colliders[0].shape.as_trimesh().unwrap();
+        assert_eq!(
+            trimesh.vertices(),
+            &[
+                Point::new(0.0, 0.0, 2.0),
+                Point::new(2.0, 0.0, 2.0),
+                Point::new(0.0, 2.0, 2.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn gltf_indices_out_of_bounds_are_rejected() {
+        let json = r#"{
+            "asset": { "version": "2.0" },
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+            "accessors": [
+                {
+                    "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                    "min": [0, 0, 0], "max": [1, 1, 0]
+                },
+                { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+            ],
+            "bufferViews": [
+                { "buffer": 0, "byteLength": 36 },
+                { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+            ],
+            "buffers": [{ "byteLength": 44 }]
+        }"#;
+        let mut bin: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+            .iter()
+            .flat_map(|e| e.to_le_bytes())
+            .collect();
+        bin.extend([0u16, 1, 5, 0].iter().flat_map(|e| e.to_le_bytes()));
+
+        let result = load_gltf_from_slice(&glb(json, &bin), &MeshShape::TriMesh);
+        assert!(matches!(result, Err(ImportError::InvalidIndex)));
+    }
+}
@@ -0,0 +1,143 @@
+//! Loading of colliders from mesh files authored in external tools.
+//!
+//! This module is only available in 3D (i.e. with `rapier3d` and `rapier3d-f64`), with the `io`
+//! feature. It builds trimesh, convex-hull, or convex-decomposition colliders from the meshes of
+//! glTF (see [`load_gltf`]) and OBJ (see [`load_obj`]) files, so the collision geometry of a level
+//! can be authored directly in a modeling software.
+//!
+//! Each root node of a glTF scene becomes a fixed rigid-body, with the meshes of its subtree as
+//! colliders, and an OBJ file becomes a single fixed rigid-body. Their builders can be modified
+//! before insertion, e.g., to make an imported prop dynamic.
+
+pub use self::gltf_import::{load_gltf, load_gltf_from_slice};
+pub use self::obj_import::{load_obj, load_obj_from_reader};
+
+use crate::dynamics::{RigidBodyBuilder, RigidBodyHandle, RigidBodySet};
+use crate::geometry::{ColliderBuilder, ColliderSet};
+use crate::math::{Point, Real};
+use crate::parry::transformation::vhacd::VHACDParameters;
+use std::fmt;
+
+mod gltf_import;
+mod obj_import;
+
+/// The kind of shape built from each imported mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MeshShape {
+    /// A triangle mesh, best suited to the static geometry of a level.
+    TriMesh,
+    /// The convex hull of the mesh vertices.
+    ConvexHull,
+    /// A set of convex parts approximating the mesh, computed with the given parameters.
+    ConvexDecomposition(VHACDParameters),
+}
+
+impl MeshShape {
+    fn build(
+        &self,
+        vertices: Vec<Point<Real>>,
+        indices: Vec<[u32; 3]>,
+    ) -> Result<ColliderBuilder, ImportError> {
+        match self {
+            MeshShape::TriMesh => Ok(ColliderBuilder::trimesh(vertices, indices)),
+            MeshShape::ConvexHull => {
+                ColliderBuilder::convex_hull(&vertices).ok_or(ImportError::DegenerateMesh)
+            }
+            MeshShape::ConvexDecomposition(params) => Ok(
+                ColliderBuilder::convex_decomposition_with_params(&vertices, &indices, params),
+            ),
+        }
+    }
+}
+
+/// A rigid-body imported from a mesh file, with its colliders.
+///
+/// The `body` builder is fixed by default, and can be modified before insertion, e.g., to make an
+/// imported prop dynamic.
+#[derive(Clone)]
+pub struct ImportedBody {
+    /// The name of the node this rigid-body was imported from, if any.
+    pub name: Option<String>,
+    /// The builder of this rigid-body, positioned at the world-space transform of its node.
+    pub body: RigidBodyBuilder,
+    /// The builders of the colliders of this rigid-body, positioned relative to it.
+    pub colliders: Vec<ColliderBuilder>,
+}
+
+impl ImportedBody {
+    /// Inserts this rigid-body and its colliders into the given sets.
+    pub fn insert(self, bodies: &mut RigidBodySet, colliders: &mut ColliderSet) -> RigidBodyHandle {
+        let handle = bodies.insert(self.body);
+        for collider in self.colliders {
+            colliders.insert_with_parent(collider, handle, bodies);
+        }
+        handle
+    }
+}
+
+/// An error that occurred while importing colliders from a mesh file.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The file, or one of the buffers it references, couldn’t be read.
+    Io(std::io::Error),
+    /// The glTF file is invalid.
+    Gltf(gltf::Error),
+    /// The OBJ file is invalid.
+    Obj(obj::ObjError),
+    /// A glTF buffer is embedded as a data URI, or isn’t available when loading from a slice.
+    ///
+    /// Only the binary chunk of `.glb` files and the external buffer files are supported.
+    UnsupportedBuffer(usize),
+    /// A triangle of a glTF mesh references a vertex that doesn’t exist.
+    InvalidIndex,
+    /// The convex hull of a mesh couldn’t be computed, e.g., because its vertices are coplanar.
+    DegenerateMesh,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportError::Io(err) => write!(f, "failed to read the mesh file: {}", err),
+            ImportError::Gltf(err) => write!(f, "invalid glTF file: {}", err),
+            ImportError::Obj(err) => write!(f, "invalid OBJ file: {}", err),
+            ImportError::UnsupportedBuffer(id) => {
+                write!(f, "the glTF buffer {} has an unsupported source", id)
+            }
+            ImportError::InvalidIndex => {
+                write!(f, "a triangle references a vertex that doesn’t exist")
+            }
+            ImportError::DegenerateMesh => {
+                write!(f, "the convex hull of the mesh couldn’t be computed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Io(err) => Some(err),
+            ImportError::Gltf(err) => Some(err),
+            ImportError::Obj(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(err: std::io::Error) -> Self {
+        ImportError::Io(err)
+    }
+}
+
+impl From<gltf::Error> for ImportError {
+    fn from(err: gltf::Error) -> Self {
+        ImportError::Gltf(err)
+    }
+}
+
+impl From<obj::ObjError> for ImportError {
+    fn from(err: obj::ObjError) -> Self {
+        ImportError::Obj(err)
+    }
+}
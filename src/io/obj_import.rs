@@ -0,0 +1,112 @@
+use super::{ImportError, ImportedBody, MeshShape};
+use crate::dynamics::RigidBodyBuilder;
+use crate::math::{Point, Real};
+use obj::raw::object::Polygon;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+/// Loads the colliders of an OBJ file.
+///
+/// OBJ files don’t have any node hierarchy, so this returns a single fixed rigid-body at the
+/// origin. Each group of polygons of the file (declared with the `g` statement) becomes one of its
+/// colliders, sorted by group name, and the polygons are triangulated as fans.
+pub fn load_obj(path: impl AsRef<Path>, shape: &MeshShape) -> Result<ImportedBody, ImportError> {
+    let file = std::fs::File::open(path)?;
+    load_obj_from_reader(std::io::BufReader::new(file), shape)
+}
+
+/// Loads the colliders of an OBJ file from a reader. See [`load_obj`].
+pub fn load_obj_from_reader(
+    reader: impl BufRead,
+    shape: &MeshShape,
+) -> Result<ImportedBody, ImportError> {
+    let obj = obj::raw::parse_obj(reader)?;
+
+    // NOTE: sort the groups so the order of the colliders is deterministic.
+    let mut groups: Vec<_> = obj
+        .groups
+        .iter()
+        .filter(|(_, group)| !group.polygons.is_empty())
+        .collect();
+    groups.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut colliders = vec![];
+
+    for (_, group) in groups {
+        // Only keep the vertices used by this group, so the convex hulls are computed correctly.
+        let mut vertex_ids = HashMap::new();
+        let mut vertices = vec![];
+        let mut indices = vec![];
+
+        for range in &group.polygons {
+            for polygon in &obj.polygons[range.start..range.end] {
+                let polygon: Vec<usize> = match polygon {
+                    Polygon::P(ids) => ids.clone(),
+                    Polygon::PT(ids) | Polygon::PN(ids) => ids.iter().map(|id| id.0).collect(),
+                    Polygon::PTN(ids) => ids.iter().map(|id| id.0).collect(),
+                };
+                let polygon: Vec<u32> = polygon
+                    .into_iter()
+                    .map(|id| {
+                        *vertex_ids.entry(id).or_insert_with(|| {
+                            let p = obj.positions[id];
+                            vertices.push(Point::new(p.0 as Real, p.1 as Real, p.2 as Real));
+                            vertices.len() as u32 - 1
+                        })
+                    })
+                    .collect();
+
+                for i in 2..polygon.len() {
+                    indices.push([polygon[0], polygon[i - 1], polygon[i]]);
+                }
+            }
+        }
+
+        if !indices.is_empty() {
+            colliders.push(shape.build(vertices, indices)?);
+        }
+    }
+
+    Ok(ImportedBody {
+        name: obj.name,
+        body: RigidBodyBuilder::fixed(),
+        colliders,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::load_obj_from_reader;
+    use crate::io::MeshShape;
+
+    #[test]
+    fn obj_groups_become_colliders_of_a_single_body() {
+        let obj = "\
+o level
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 0 1 1
+vt 0 0
+g floor
+f 1 2 3 4
+g ramp
+f 5/1 6/1 7/1
+";
+        let body = load_obj_from_reader(obj.as_bytes(), &MeshShape::TriMesh).unwrap();
+        assert_eq!(body.name.as_deref(), Some("level"));
+        assert_eq!(body.colliders.len(), 2);
+
+        // The quad is triangulated, and each collider only keeps the vertices it uses.
+        let floor = body.colliders[0].shape.as_trimesh().unwrap();
+        assert_eq!(floor.vertices().len(), 4);
+        assert_eq!(floor.indices(), &[[0, 1, 2], [0, 2, 3]]);
+        let ramp = body.colliders[1].shape.as_trimesh().unwrap();
+        assert_eq!(ramp.vertices().len(), 3);
+        assert_eq!(ramp.indices().len(), 1);
+    }
+}
@@ -9,6 +9,25 @@
 //! are compliant with the IEEE 754-2008 floating point standard.
 //!
 //! User documentation for Rapier is on [the official Rapier site](https://rapier.rs/docs/).
+//!
+//! # Determinism
+//!
+//! Rapier is deterministic: running the same simulation twice, i.e., starting from the same
+//! state and applying the same modifications in the same order, gives bit-identical results.
+//! None of the internal data structures depend on random hashing, so the order in which the
+//! broad-phase pairs, the contact manifolds, the islands, and the constraints are processed
+//! only depends on the order of the user’s insertions, removals, and modifications. This is also
+//! true when the `parallel` feature is enabled, whatever the number of threads: the islands are
+//! then solved concurrently and the large islands are split across threads, without affecting the
+//! order of the floating-point operations. Restoring a
+//! snapshot of the whole physics state (including the broad-phase, narrow-phase, and island
+//! manager) gives the same results as continuing the original simulation.
+//!
+//! By default, this is only guaranteed on a given platform with a given build. Enable the
+//! `enhanced-determinism` feature to get bit-identical results across all the platforms compliant
+//! with the IEEE 754-2008 floating point standard (including WASM), e.g., for lockstep networking.
+//! This replaces the platform-dependent math functions by portable implementations, and can’t be
+//! combined with the `simd-stable` or `simd-nightly` features.
 
 #![deny(bare_trait_objects)]
 #![warn(missing_docs)] // FIXME: deny that
@@ -40,6 +59,8 @@ pub use rayon;
     not(feature = "simd-nightly")
 ))]
 std::compile_error!("The `simd-is-enabled` feature should not be enabled explicitly. Please enable the `simd-stable` or the `simd-nightly` feature instead.");
+#[cfg(all(feature = "simd-wide8", feature = "simd-nightly"))]
+std::compile_error!("The `simd-wide8` feature is only supported by the `simd-stable` backend.");
 #[cfg(all(feature = "simd-is-enabled", feature = "enhanced-determinism"))]
 std::compile_error!(
     "SIMD cannot be enabled when the `enhanced-determinism` feature is also enabled."
@@ -57,8 +78,8 @@ macro_rules! gather(
         {
             #[inline(always)]
             #[allow(dead_code)]
-            fn create_arr<T>(mut callback: impl FnMut(usize) -> T) -> [T; SIMD_WIDTH] {
-                [callback(0usize), callback(1usize), callback(2usize), callback(3usize)]
+            fn create_arr<T>(callback: impl FnMut(usize) -> T) -> [T; SIMD_WIDTH] {
+                std::array::from_fn(callback)
             }
 
             create_arr($callback)
@@ -135,13 +156,37 @@ pub mod counters;
 pub mod data;
 pub mod dynamics;
 pub mod geometry;
+#[cfg(all(feature = "dim3", feature = "io"))]
+pub mod io;
 pub mod pipeline;
 pub mod utils;
 
+#[cfg(test)]
+mod test_utils;
+
 /// Elementary mathematical entities (vectors, matrices, isometries, etc).
 pub mod math {
     pub use parry::math::*;
 
+    /*
+     * SIMD
+     */
+    /// The number of lanes of the SIMD types used by the constraints solver.
+    #[cfg(feature = "simd-wide8")]
+    pub const SIMD_WIDTH: usize = 8;
+
+    /// `SIMD_WIDTH - 1`
+    #[cfg(feature = "simd-wide8")]
+    pub const SIMD_LAST_INDEX: usize = 7;
+
+    /// A SIMD float with `SIMD_WIDTH` lanes.
+    #[cfg(feature = "simd-wide8")]
+    pub use simba::simd::WideF32x8 as SimdReal;
+
+    /// A SIMD boolean with `SIMD_WIDTH` lanes.
+    #[cfg(feature = "simd-wide8")]
+    pub use simba::simd::WideBoolF32x8 as SimdBool;
+
     /*
      * 2D
      */
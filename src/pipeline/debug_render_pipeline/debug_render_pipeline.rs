@@ -2,7 +2,7 @@ use super::{outlines, DebugRenderBackend};
 use crate::dynamics::{
     GenericJoint, ImpulseJointSet, MultibodyJointSet, RigidBodySet, RigidBodyType,
 };
-use crate::geometry::{Ball, ColliderSet, Cuboid, NarrowPhase, Shape, TypedShape};
+use crate::geometry::{Ball, ColliderSet, Cuboid, NarrowPhase, Shape, TypedShape, Voxels};
 #[cfg(feature = "dim3")]
 use crate::geometry::{Cone, Cylinder};
 use crate::math::{Isometry, Point, Real, Vector, DIM};
@@ -402,7 +402,15 @@ impl DebugRenderPipeline {
                 let vtx = s.to_polyline(self.style.border_subdivisions);
                 backend.draw_line_strip(object, &vtx, pos, &Vector::repeat(1.0), color, true)
             }
-            TypedShape::Custom(_) => {}
+            TypedShape::Custom(_) => {
+                if let Some(voxels) = shape.as_shape::<Voxels>() {
+                    let voxel = Cuboid::new(voxels.voxel_size() / 2.0);
+                    for key in voxels.voxels() {
+                        let voxel_pos = pos * Isometry::from(voxels.voxel_center(key).coords);
+                        self.render_shape(object, backend, &voxel, &voxel_pos, color)
+                    }
+                }
+            }
         }
     }
 
@@ -555,7 +563,15 @@ impl DebugRenderPipeline {
                 let (vtx, idx) = s.to_outline(self.style.border_subdivisions);
                 backend.draw_polyline(object, &vtx, &idx, pos, &Vector::repeat(1.0), color)
             }
-            TypedShape::Custom(_) => {}
+            TypedShape::Custom(_) => {
+                if let Some(voxels) = shape.as_shape::<Voxels>() {
+                    let voxel = Cuboid::new(voxels.voxel_size() / 2.0);
+                    for key in voxels.voxels() {
+                        let voxel_pos = pos * Isometry::from(voxels.voxel_center(key).coords);
+                        self.render_shape(object, backend, &voxel, &voxel_pos, color)
+                    }
+                }
+            }
         }
     }
 }
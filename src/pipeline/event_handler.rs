@@ -36,7 +36,7 @@ pub trait EventHandler: Send + Sync {
     /// * `event` - The collision event.
     /// * `bodies` - The set of rigid-bodies.
     /// * `colliders` - The set of colliders.
-    /// * `contact_pair` - The current state of contacts between the two colliders. This is set ot `None`
+    /// * `contact_pair` - The current state of contacts between the two colliders. This is set to `None`
     ///                    if at least one of the collider is a sensor (in which case no contact information
     ///                    is ever computed).
     fn handle_collision_event(
@@ -131,3 +131,222 @@ impl EventHandler for ChannelEventCollector {
         let _ = self.contact_force_event_sender.send(result);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+        RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::{ColliderBuilder, ColliderSet, DefaultBroadPhase, NarrowPhase};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn collision_events_are_opt_in() {
+        use crate::geometry::ColliderHandle;
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        state.colliders.insert(ground);
+
+        // Only the first ball opts in to collision events.
+        let mut balls: Vec<ColliderHandle> = vec![];
+        for (i, events) in [ActiveEvents::COLLISION_EVENTS, ActiveEvents::empty()]
+            .into_iter()
+            .enumerate()
+        {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 1.5 + Vector::x() * 3.0 * i as Real);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5).active_events(events);
+            balls.push(
+                state
+                    .colliders
+                    .insert_with_parent(collider, body, &mut state.bodies),
+            );
+        }
+
+        let (collision_send, collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, _contact_force_recv) = crossbeam::channel::unbounded();
+        let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        for _ in 0..60 {
+            state.step(&mut pipeline, &(), &event_handler);
+        }
+
+        let events: Vec<_> = collision_recv.try_iter().collect();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].started());
+        assert!(events[0].collider1() == balls[0] || events[0].collider2() == balls[0]);
+        // Both balls are still touching the ground.
+        assert!(state
+            .narrow_phase
+            .contacts_with(balls[1])
+            .any(|pair| pair.has_any_active_contact));
+    }
+
+    #[test]
+    fn collision_events_track_contact_transitions() {
+        use crate::geometry::{CollisionEvent, ContactPair};
+        use crate::pipeline::{ActiveEvents, EventHandler};
+        use std::sync::Mutex;
+
+        // A custom event handler recording whether contact information came with each event.
+        #[derive(Default)]
+        struct EventRecorder(Mutex<Vec<(CollisionEvent, bool)>>);
+
+        impl EventHandler for EventRecorder {
+            fn handle_collision_event(
+                &self,
+                _: &RigidBodySet,
+                _: &ColliderSet,
+                event: CollisionEvent,
+                contact_pair: Option<&ContactPair>,
+            ) {
+                self.0.lock().unwrap().push((event, contact_pair.is_some()));
+            }
+
+            fn handle_contact_force_event(
+                &self,
+                _: Real,
+                _: &RigidBodySet,
+                _: &ColliderSet,
+                _: &ContactPair,
+                _: Real,
+            ) {
+            }
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let integration_parameters = IntegrationParameters::default();
+        let mut broad_phase = DefaultBroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut ccd = CCDSolver::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = colliders.insert(ground.translation(Vector::y() * -0.5));
+        let body = bodies.insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 1.0));
+        let ball = colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).active_events(ActiveEvents::COLLISION_EVENTS),
+            body,
+            &mut bodies,
+        );
+
+        let recorder = EventRecorder::default();
+        let mut step = |bodies: &mut RigidBodySet,
+                        colliders: &mut ColliderSet,
+                        islands: &mut IslandManager,
+                        n: usize| {
+            for _ in 0..n {
+                pipeline.step(
+                    &(Vector::y() * -9.81),
+                    &integration_parameters,
+                    islands,
+                    &mut broad_phase,
+                    &mut narrow_phase,
+                    bodies,
+                    colliders,
+                    &mut impulse_joints,
+                    &mut multibody_joints,
+                    &mut ccd,
+                    None,
+                    &(),
+                    &recorder,
+                );
+            }
+        };
+        let take_events = || std::mem::take(&mut *recorder.0.lock().unwrap());
+
+        // The ball lands on the ground: a single event is emitted, even if it keeps resting.
+        step(&mut bodies, &mut colliders, &mut islands, 60);
+        let events = take_events();
+        assert_eq!(events.len(), 1);
+        let (event, has_contact_pair) = events[0];
+        assert!(event.started() && !event.sensor() && has_contact_pair);
+        let pair = [event.collider1(), event.collider2()];
+        assert!(pair == [ground, ball] || pair == [ball, ground]);
+
+        // Throw the ball upward.
+        bodies[body].set_linvel(Vector::y() * 10.0, true);
+        step(&mut bodies, &mut colliders, &mut islands, 10);
+        let events = take_events();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].0.stopped() && !events[0].0.removed() && events[0].1);
+
+        // Remove the ball once it landed again.
+        step(&mut bodies, &mut colliders, &mut islands, 180);
+        assert!(take_events()[0].0.started());
+        colliders.remove(ball, &mut islands, &mut bodies, true);
+        step(&mut bodies, &mut colliders, &mut islands, 1);
+        let events = take_events();
+        assert_eq!(events.len(), 1);
+        assert!(events[0].0.stopped() && events[0].0.removed());
+    }
+
+    #[test]
+    fn contact_force_events_above_threshold() {
+        use crate::pipeline::{ActiveEvents, ChannelEventCollector};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        state
+            .colliders
+            .insert(ground.translation(Vector::y() * -0.5));
+
+        // A ball with a weight of 9.81N, only reporting forces greater than 20N.
+        let body = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::y() * 5.0));
+        let ball = state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5)
+                .mass(1.0)
+                .active_events(ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(20.0),
+            body,
+            &mut state.bodies,
+        );
+
+        let (collision_send, _collision_recv) = crossbeam::channel::unbounded();
+        let (contact_force_send, contact_force_recv) = crossbeam::channel::unbounded();
+        let event_handler = ChannelEventCollector::new(collision_send, contact_force_send);
+
+        let mut step = |state: &mut PhysicsState, n: usize| {
+            for _ in 0..n {
+                state.step(&mut pipeline, &(), &event_handler);
+            }
+        };
+
+        // Only the landing impact is reported.
+        step(&mut state, 120);
+        let events: Vec<_> = contact_force_recv.try_iter().collect();
+        assert!(!events.is_empty() && events.len() < 5);
+        for event in &events {
+            assert!(event.collider1 == ball || event.collider2 == ball);
+            assert!(event.total_force_magnitude > 20.0);
+            assert!(event.max_force_magnitude > 0.0);
+            assert!((event.max_force_direction.y.abs() - 1.0).abs() < 1.0e-3);
+        }
+
+        // Once the threshold is lowered, the weight of the resting ball is reported each step.
+        state.colliders[ball].set_contact_force_event_threshold(5.0);
+        step(&mut state, 10);
+        let events: Vec<_> = contact_force_recv.try_iter().collect();
+        assert_eq!(events.len(), 10);
+        for event in &events {
+            assert!((event.total_force.norm() - 9.81).abs() < 1.0e-2);
+            assert!((event.total_force_magnitude - 9.81).abs() < 1.0e-2);
+        }
+    }
+}
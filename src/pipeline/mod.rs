@@ -2,9 +2,16 @@
 
 pub use collision_pipeline::CollisionPipeline;
 pub use event_handler::{ActiveEvents, ChannelEventCollector, EventHandler};
-pub use physics_hooks::{ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks};
+pub use physics_hooks::{
+    ActiveHooks, ContactModificationContext, ForceModificationContext, PairFilterContext,
+    PhysicsHooks, PipelineStage, PipelineStageContext,
+};
 pub use physics_pipeline::PhysicsPipeline;
+pub use physics_recorder::{PhysicsCommand, PhysicsRecorder, PhysicsRecording, PhysicsReplayer};
+pub use physics_state::PhysicsState;
 pub use query_pipeline::{QueryFilter, QueryFilterFlags, QueryPipeline, QueryPipelineMode};
+pub use snapshot::DynamicsSnapshot;
+pub use validation::{validate_physics_state, InvalidState};
 
 #[cfg(feature = "debug-render")]
 pub use self::debug_render_pipeline::{
@@ -16,8 +23,12 @@ mod collision_pipeline;
 mod event_handler;
 mod physics_hooks;
 mod physics_pipeline;
+mod physics_recorder;
+mod physics_state;
 mod query_pipeline;
+mod snapshot;
 mod user_changes;
+mod validation;
 
 #[cfg(feature = "debug-render")]
 mod debug_render_pipeline;
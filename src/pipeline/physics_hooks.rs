@@ -1,6 +1,12 @@
-use crate::dynamics::{RigidBodyHandle, RigidBodySet};
-use crate::geometry::{ColliderHandle, ColliderSet, ContactManifold, SolverContact, SolverFlags};
-use crate::math::{Real, Vector};
+use crate::dynamics::{
+    IntegrationParameters, IslandManager, RigidBodyHandle, RigidBodySet, RigidBodyVelocity,
+};
+use crate::geometry::{
+    ColliderHandle, ColliderSet, ContactManifold, NarrowPhase, SolverContact, SolverFlags,
+};
+use crate::math::{AngVector, Isometry, Point, Real, Vector};
+use crate::pipeline::QueryPipeline;
+use crate::utils::WCross;
 use na::ComplexField;
 
 /// Context given to custom collision filters to filter-out collisions.
@@ -39,6 +45,9 @@ pub struct ContactModificationContext<'a> {
     pub solver_contacts: &'a mut Vec<SolverContact>,
     /// The contact normal that can be modified.
     pub normal: &'a mut Vector<Real>,
+    /// The solver flags of the manifold that can be modified, e.g., to disable friction or
+    /// restitution for this manifold only.
+    pub solver_flags: &'a mut SolverFlags,
     /// User-defined data attached to the manifold.
     // NOTE: we keep this a &'a mut u32 to emphasize the
     // fact that this can be modified.
@@ -115,6 +124,92 @@ impl<'a> ContactModificationContext<'a> {
     }
 }
 
+/// Context given to custom force generators to modify the forces applied to a rigid-body.
+pub struct ForceModificationContext<'a> {
+    /// The handle of the rigid-body the forces are applied to.
+    pub rigid_body: RigidBodyHandle,
+    /// The user-defined data associated to the rigid-body.
+    pub user_data: u128,
+    /// The world-space position of the rigid-body.
+    pub position: &'a Isometry<Real>,
+    /// The world-space center-of-mass of the rigid-body.
+    pub center_of_mass: &'a Point<Real>,
+    /// The mass of the rigid-body.
+    pub mass: Real,
+    /// The velocities of the rigid-body.
+    pub velocity: &'a RigidBodyVelocity,
+    /// The force applied to the rigid-body during this timestep, that can be modified.
+    ///
+    /// This is initialized with the gravity, the forces added by the user, and the buoyancy and
+    /// drag of the fluid volumes the rigid-body is in.
+    pub force: &'a mut Vector<Real>,
+    /// The torque applied to the rigid-body during this timestep, that can be modified.
+    ///
+    /// This is initialized with the torques added by the user, and the torques of the buoyancy
+    /// and drag of the fluid volumes the rigid-body is in.
+    pub torque: &'a mut AngVector<Real>,
+}
+
+impl<'a> ForceModificationContext<'a> {
+    /// Adds a force applied at the center-of-mass of the rigid-body during this timestep.
+    pub fn add_force(&mut self, force: Vector<Real>) {
+        *self.force += force;
+    }
+
+    /// Adds a torque applied to the rigid-body during this timestep.
+    pub fn add_torque(&mut self, torque: AngVector<Real>) {
+        *self.torque += torque;
+    }
+
+    /// Adds a force applied at the given world-space point of the rigid-body during this timestep.
+    pub fn add_force_at_point(&mut self, force: Vector<Real>, point: Point<Real>) {
+        *self.force += force;
+        *self.torque += (point - self.center_of_mass).gcross(force);
+    }
+}
+
+/// A stage of `PhysicsPipeline::step` after which `PhysicsHooks::on_stage_completed` is called.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PipelineStage {
+    /// The collision detection executed at the beginning of the timestep.
+    ///
+    /// The broad-phase, the narrow-phase, and the query pipeline (if any) are up-to-date with
+    /// the current positions of the colliders, and the constraints solver didn’t run yet. The
+    /// forces added to the rigid-bodies at this stage are taken into account by the solver.
+    CollisionDetection,
+    /// The constraints solver of one substep.
+    ///
+    /// The velocities of the rigid-bodies have been updated by the solver, but their positions
+    /// have not been advanced yet.
+    Solver,
+    /// The integration of the positions of one substep.
+    ///
+    /// The rigid-bodies and colliders are at their new positions, and the broad-phase, the
+    /// narrow-phase, and the query pipeline (if any) have been updated accordingly.
+    Integration,
+}
+
+/// Context given to `PhysicsHooks::on_stage_completed` after each stage of a timestep.
+pub struct PipelineStageContext<'a> {
+    /// The stage that just completed.
+    pub stage: PipelineStage,
+    /// The integration parameters of the current substep.
+    pub integration_parameters: &'a IntegrationParameters,
+    /// The island manager.
+    pub islands: &'a IslandManager,
+    /// The set of rigid-bodies.
+    ///
+    /// Rigid-bodies must not be inserted or removed here. Any modification other than
+    /// velocities and forces are only fully taken into account at the next timestep.
+    pub bodies: &'a mut RigidBodySet,
+    /// The set of colliders.
+    pub colliders: &'a ColliderSet,
+    /// The narrow-phase.
+    pub narrow_phase: &'a NarrowPhase,
+    /// The query pipeline, if one was given to `PhysicsPipeline::step`.
+    pub query_pipeline: Option<&'a QueryPipeline>,
+}
+
 bitflags::bitflags! {
     #[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
     /// Flags affecting the behavior of the constraints solver for a given contact manifold.
@@ -152,6 +247,12 @@ pub trait PhysicsHooks {
 
     /// Modifies the set of contacts seen by the constraints solver.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Modifies the forces applied to an awake dynamic rigid-body.
+    fn modify_forces(&self, _context: &mut ForceModificationContext) {}
+
+    /// Called after each stage of a timestep.
+    fn on_stage_completed(&self, _context: &mut PipelineStageContext) {}
 }
 
 /// User-defined functions called by the physics engines during one timestep in order to customize its behavior.
@@ -224,7 +325,8 @@ pub trait PhysicsHooks: Send + Sync {
     /// do `context.solver_contacts.clear()`.
     ///
     /// Modifying the solver contacts allow you to achieve various effects, including:
-    /// - Simulating conveyor belts by setting the `surface_velocity` of a solver contact.
+    /// - Simulating conveyor belts by setting the `tangent_velocity` of a solver contact (the
+    ///   simplest cases are also covered by `ColliderBuilder::surface_linvel`).
     /// - Simulating shapes with multiply materials by modifying the friction and restitution
     ///   coefficient depending of the features in contacts.
     /// - Simulating one-way platforms depending on the contact normal.
@@ -235,6 +337,28 @@ pub trait PhysicsHooks: Send + Sync {
     ///
     /// The world-space contact normal can be modified in `context.normal`.
     fn modify_solver_contacts(&self, _context: &mut ContactModificationContext) {}
+
+    /// Modifies the forces applied to an awake dynamic rigid-body.
+    ///
+    /// This method is called at each timestep, before the velocity integration, on each
+    /// dynamic rigid-body that isn’t sleeping. Sleeping rigid-bodies are not woken up by the
+    /// forces applied here.
+    ///
+    /// This can be used to implement custom force generators, e.g., radial gravity fields,
+    /// wind, or magnets, by adding forces to `context.force` and `context.torque`. These forces
+    /// only affect the current timestep.
+    fn modify_forces(&self, _context: &mut ForceModificationContext) {}
+
+    /// Called after each stage of a timestep.
+    ///
+    /// This can be used to interleave custom logic with the physics pipeline, e.g., to run
+    /// scene queries against up-to-date collider positions before the constraints solver runs, or
+    /// to add forces to rigid-bodies after the collision detection. See [`PipelineStage`] for
+    /// the state of the simulation after each stage.
+    ///
+    /// The [`PipelineStage::Solver`] and [`PipelineStage::Integration`] stages are completed once
+    /// per CCD substep.
+    fn on_stage_completed(&self, _context: &mut PipelineStageContext) {}
 }
 
 impl PhysicsHooks for () {
@@ -248,3 +372,229 @@ impl PhysicsHooks for () {
 
     fn modify_solver_contacts(&self, _: &mut ContactModificationContext) {}
 }
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{
+        CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+        RigidBodyBuilder, RigidBodySet,
+    };
+    use crate::geometry::{ColliderBuilder, ColliderSet, DefaultBroadPhase, NarrowPhase};
+    use crate::math::{Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn physics_hooks_filter_and_modify_contacts() {
+        use crate::geometry::SolverFlags;
+        use crate::pipeline::{
+            ActiveHooks, ContactModificationContext, PairFilterContext, PhysicsHooks,
+        };
+
+        // A ground acting as a conveyor belt, ignoring bodies with a non-zero user-data.
+        struct ConveyorBelt;
+        impl PhysicsHooks for ConveyorBelt {
+            fn filter_contact_pair(&self, context: &PairFilterContext) -> Option<SolverFlags> {
+                let ignored = [context.rigid_body1, context.rigid_body2]
+                    .iter()
+                    .flatten()
+                    .any(|h| context.bodies[*h].user_data != 0);
+                if ignored {
+                    None
+                } else {
+                    Some(SolverFlags::COMPUTE_IMPULSES)
+                }
+            }
+
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                for contact in context.solver_contacts.iter_mut() {
+                    contact.tangent_velocity = Vector::x() * 2.0;
+                }
+            }
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let integration_parameters = IntegrationParameters::default();
+        let mut broad_phase = DefaultBroadPhase::new();
+        let mut narrow_phase = NarrowPhase::new();
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut ccd = CCDSolver::new();
+        let mut impulse_joints = ImpulseJointSet::new();
+        let mut multibody_joints = MultibodyJointSet::new();
+        let mut islands = IslandManager::new();
+
+        let ground = cuboid(100.0, 0.5);
+        colliders.insert(
+            ground.active_hooks(
+                ActiveHooks::FILTER_CONTACT_PAIRS | ActiveHooks::MODIFY_SOLVER_CONTACTS,
+            ),
+        );
+
+        let mut boxes = vec![];
+        for i in 0..2 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() + Vector::x() * 3.0 * i as Real)
+                .user_data(i);
+            let body = bodies.insert(body);
+            let collider = cuboid(0.5, 0.5);
+            colliders.insert_with_parent(collider, body, &mut bodies);
+            boxes.push(body);
+        }
+
+        for _ in 0..120 {
+            pipeline.step(
+                &(Vector::y() * -9.81),
+                &integration_parameters,
+                &mut islands,
+                &mut broad_phase,
+                &mut narrow_phase,
+                &mut bodies,
+                &mut colliders,
+                &mut impulse_joints,
+                &mut multibody_joints,
+                &mut ccd,
+                None,
+                &ConveyorBelt,
+                &(),
+            );
+        }
+
+        // The first box is carried by the conveyor belt, the second one fell through it.
+        let carried = &bodies[boxes[0]];
+        assert!(carried.translation().y > 0.9);
+        assert!(
+            (carried.linvel().x.abs() - 2.0).abs() < 1.0e-2,
+            "{}",
+            carried.linvel()
+        );
+        assert!(bodies[boxes[1]].translation().y < -5.0);
+    }
+
+    #[test]
+    fn solver_flags_disable_friction_and_restitution() {
+        use crate::dynamics::CoefficientCombineRule;
+        use crate::geometry::SolverFlags;
+        use crate::pipeline::{
+            ActiveHooks, ContactModificationContext, PhysicsHooks, PhysicsState,
+        };
+
+        // Makes the bodies with a non-zero user-data slide and stop bouncing.
+        struct Slippery;
+        impl PhysicsHooks for Slippery {
+            fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+                let slippery = [context.rigid_body1, context.rigid_body2]
+                    .iter()
+                    .flatten()
+                    .any(|h| context.bodies[*h].user_data != 0);
+                if slippery {
+                    context
+                        .solver_flags
+                        .insert(SolverFlags::DISABLE_FRICTION | SolverFlags::DISABLE_RESTITUTION);
+                }
+            }
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(100.0, 0.5);
+        state
+            .colliders
+            .insert(ground.active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS));
+
+        let mut sliders = vec![];
+        let mut balls = vec![];
+        for i in 0..2 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() - Vector::x() * 20.0 * i as Real)
+                .linvel(Vector::x() * 2.0)
+                .user_data(i);
+            let body = state.bodies.insert(body);
+            let collider = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(collider.friction(1.0), body, &mut state.bodies);
+            sliders.push(body);
+
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * 3.0 + Vector::x() * 10.0 * (i + 1) as Real)
+                .user_data(i);
+            let body = state.bodies.insert(body);
+            let collider = ColliderBuilder::ball(0.5)
+                .restitution(1.0)
+                .restitution_combine_rule(CoefficientCombineRule::Max);
+            state
+                .colliders
+                .insert_with_parent(collider, body, &mut state.bodies);
+            balls.push(body);
+        }
+
+        let mut max_heights = [0.0; 2];
+        for k in 0..120 {
+            state.step(&mut pipeline, &Slippery, &());
+
+            if k > 60 {
+                for i in 0..2 {
+                    max_heights[i] = state.bodies[balls[i]].translation().y.max(max_heights[i]);
+                }
+            }
+        }
+
+        // The box with friction stopped, the other one keeps sliding.
+        assert!(state.bodies[sliders[0]].linvel().x.abs() < 1.0e-3);
+        assert!(state.bodies[sliders[1]].linvel().x > 1.9);
+        // The ball with restitution keeps bouncing, the other one rests on the ground.
+        assert!(max_heights[0] > 2.0);
+        assert!(max_heights[1] < 1.1);
+    }
+
+    #[test]
+    fn force_generators_apply_radial_gravity() {
+        use crate::pipeline::{ForceModificationContext, PhysicsHooks};
+
+        // Attracts every body toward the origin.
+        struct Planet;
+        impl PhysicsHooks for Planet {
+            fn modify_forces(&self, context: &mut ForceModificationContext) {
+                let dir = -context.center_of_mass.coords.normalize();
+                context.add_force(dir * 9.81 * context.mass);
+            }
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let mut handles = vec![];
+        for (dir, sleeping) in [
+            (Vector::x(), false),
+            (-Vector::y(), false),
+            (Vector::y(), true),
+        ] {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(dir * 10.0)
+                .sleeping(sleeping)
+                .build();
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+            handles.push((body, dir));
+        }
+
+        for _ in 0..60 {
+            state.step(&mut pipeline, &Planet, &());
+        }
+
+        // After one second, the awake bodies fell by `g / 2` toward the origin. The sleeping
+        // body wasn’t affected.
+        for (body, dir) in handles {
+            let rb = &state.bodies[body];
+            let fallen = 10.0 - rb.translation().dot(&dir);
+            let expected = if rb.is_sleeping() { 0.0 } else { 9.81 / 2.0 };
+            assert!((fallen - expected).abs() < 0.1, "fallen: {}", fallen);
+            assert!((rb.translation() - dir * (10.0 - fallen)).norm() < 1.0e-3);
+        }
+    }
+}
@@ -1,22 +1,32 @@
 //! Physics pipeline structures.
 
 use crate::counters::Counters;
-#[cfg(not(feature = "parallel"))]
-use crate::dynamics::IslandSolver;
 use crate::dynamics::{
-    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
-    RigidBodyChanges, RigidBodyHandle, RigidBodyPosition, RigidBodyType,
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, IslandSolver,
+    MultibodyJointSet, RigidBody, RigidBodyChanges, RigidBodyHandle, RigidBodyPosition,
+    RigidBodyType,
 };
 #[cfg(feature = "parallel")]
-use crate::dynamics::{JointGraphEdge, ParallelIslandSolver as IslandSolver};
+use crate::dynamics::{JointGraphEdge, ParallelIslandSolver};
 use crate::geometry::{
-    BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
-    ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
+    self, BroadPhase, BroadPhasePairEvent, ColliderChanges, ColliderHandle, ColliderPair,
+    ContactManifold, ContactManifoldIndex, NarrowPhase, TemporaryInteractionIndex,
 };
-use crate::math::{Real, Vector};
-use crate::pipeline::{EventHandler, PhysicsHooks, QueryPipeline};
+use crate::math::{Isometry, Point, Real, Vector};
+use crate::pipeline::{
+    EventHandler, ForceModificationContext, PhysicsHooks, PipelineStage, PipelineStageContext,
+    QueryPipeline,
+};
+use crate::utils::WCross;
 use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 
+/// The minimum number of active dynamic bodies of an island for its resolution to be split
+/// across several threads when the `parallel` feature is enabled.
+///
+/// Smaller islands are solved by a single thread, concurrently with the other islands, since
+/// synchronizing several threads on them costs more than it saves.
+const MIN_PARALLEL_ISLAND_SIZE: usize = 128;
+
 /// The physics pipeline, responsible for stepping the whole physics simulation.
 ///
 /// This structure only contains temporary data buffers. It can be dropped and replaced by a fresh
@@ -29,7 +39,11 @@ use {crate::dynamics::RigidBodySet, crate::geometry::ColliderSet};
 /// - A position based solver based on non-linear PGS which performs constraint stabilization (i.e. correction of errors like penetrations).
 // NOTE: this contains only workspace data, so there is no point in making this serializable.
 pub struct PhysicsPipeline {
-    /// Counters used for benchmarking only.
+    /// The performance counters of the last timestep.
+    ///
+    /// They are reset at the beginning of each step, and can be read afterward, e.g., to display
+    /// a profiling overlay. The timings are only measured if the `profiler` feature is enabled,
+    /// and the counters can be disabled with `Counters::disable` to avoid their (small) overhead.
     pub counters: Counters,
     contact_pair_indices: Vec<TemporaryInteractionIndex>,
     manifold_indices: Vec<Vec<ContactManifoldIndex>>,
@@ -37,6 +51,9 @@ pub struct PhysicsPipeline {
     broadphase_collider_pairs: Vec<ColliderPair>,
     broad_phase_events: Vec<BroadPhasePairEvent>,
     solvers: Vec<IslandSolver>,
+    #[cfg(feature = "parallel")]
+    parallel_solvers: Vec<ParallelIslandSolver>,
+    substep_start_positions: Vec<Isometry<Real>>,
 }
 
 impl Default for PhysicsPipeline {
@@ -57,11 +74,29 @@ impl PhysicsPipeline {
         PhysicsPipeline {
             counters: Counters::new(true),
             solvers: vec![],
+            #[cfg(feature = "parallel")]
+            parallel_solvers: vec![],
             contact_pair_indices: vec![],
             manifold_indices: vec![],
             joint_constraint_indices: vec![],
             broadphase_collider_pairs: vec![],
             broad_phase_events: vec![],
+            substep_start_positions: vec![],
+        }
+    }
+
+    #[cfg(feature = "debug-validate-state")]
+    fn validate_state(
+        stage: PipelineStage,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        narrow_phase: &NarrowPhase,
+    ) {
+        if let Err(err) = crate::pipeline::validate_physics_state(bodies, colliders, narrow_phase) {
+            panic!(
+                "Invalid physics state after the {:?} stage: {:?}.",
+                stage, err
+            );
         }
     }
 
@@ -89,11 +124,27 @@ impl PhysicsPipeline {
         }
     }
 
+    /// Is the resolution of the given island split across several threads?
+    ///
+    /// The shock propagation and the early-outs of the solver iterations process the whole island
+    /// at once, so the islands relying on them are always solved by a single thread.
+    fn is_solved_in_parallel(
+        params: &IntegrationParameters,
+        islands: &IslandManager,
+        island_id: usize,
+    ) -> bool {
+        cfg!(feature = "parallel")
+            && !params.shock_propagation
+            && params.velocity_iterations_tolerance <= 0.0
+            && params.stabilization_iterations_tolerance <= 0.0
+            && islands.active_island(island_id).len() >= MIN_PARALLEL_ISLAND_SIZE
+    }
+
     fn detect_collisions(
         &mut self,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut dyn BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
         colliders: &mut ColliderSet,
@@ -152,10 +203,62 @@ impl PhysicsPipeline {
         );
         narrow_phase.compute_intersections(bodies, colliders, modified_colliders, hooks, events);
 
+        if self.counters.enabled() {
+            self.counters.set_ncontact_pairs(
+                narrow_phase
+                    .contact_pairs()
+                    .filter(|pair| pair.has_any_active_contact)
+                    .count(),
+            );
+        }
+
         self.counters.cd.narrow_phase_time.pause();
         self.counters.stages.collision_detection_time.pause();
     }
 
+    /// Adds the buoyancy and drag of the fluid volumes intersecting the colliders of `rb` to its
+    /// forces.
+    fn apply_fluid_forces(
+        gravity: &Vector<Real>,
+        narrow_phase: &NarrowPhase,
+        colliders: &ColliderSet,
+        rb: &mut RigidBody,
+    ) {
+        let up = -gravity.try_normalize(0.0).unwrap_or_else(|| -Vector::y());
+
+        for handle in &rb.colliders.0 {
+            let co = &colliders[*handle];
+            if co.is_sensor() {
+                continue;
+            }
+
+            for (handle1, handle2, intersecting) in narrow_phase.intersections_with(*handle) {
+                let fluid_handle = if handle1 == *handle { handle2 } else { handle1 };
+                let fluid_co = &colliders[fluid_handle];
+                let fluid = match fluid_co.fluid() {
+                    Some(fluid) if intersecting && fluid_co.is_enabled() => fluid,
+                    _ => continue,
+                };
+
+                let fluid_aabb = fluid_co.compute_aabb();
+                let surface =
+                    fluid_aabb.center().coords.dot(&up) + fluid_aabb.half_extents().dot(&up.abs());
+
+                if let Some((volume, centroid)) =
+                    geometry::submerged_volume(&*co.shape, &co.pos, &up, surface)
+                {
+                    let vel = rb.vels.velocity_at_point(&centroid, &rb.mprops.world_com)
+                        - fluid.flow_velocity;
+                    let force =
+                        -gravity * fluid.density * volume - vel * fluid.linear_drag * volume;
+                    rb.forces.force += force;
+                    rb.forces.torque += (centroid - rb.mprops.world_com).gcross(force)
+                        - rb.vels.angvel * fluid.angular_drag * volume;
+                }
+            }
+        }
+    }
+
     fn build_islands_and_solve_velocity_constraints(
         &mut self,
         gravity: &Vector<Real>,
@@ -166,6 +269,7 @@ impl PhysicsPipeline {
         colliders: &mut ColliderSet,
         impulse_joints: &mut ImpulseJointSet,
         multibody_joints: &mut MultibodyJointSet,
+        hooks: &dyn PhysicsHooks,
         events: &dyn EventHandler,
     ) {
         self.counters.stages.island_construction_time.resume();
@@ -177,6 +281,7 @@ impl PhysicsPipeline {
             impulse_joints,
             multibody_joints,
             integration_parameters.min_island_size,
+            integration_parameters.sleeping_enabled,
         );
         self.counters.stages.island_construction_time.pause();
 
@@ -190,20 +295,6 @@ impl PhysicsPipeline {
                 .resize(islands.num_islands(), Vec::new());
         }
 
-        let mut manifolds = Vec::new();
-        narrow_phase.select_active_contacts(
-            islands,
-            bodies,
-            &mut self.contact_pair_indices,
-            &mut manifolds,
-            &mut self.manifold_indices,
-        );
-        impulse_joints.select_active_interactions(
-            islands,
-            bodies,
-            &mut self.joint_constraint_indices,
-        );
-
         self.counters.stages.update_time.resume();
         for handle in islands.active_dynamic_bodies() {
             let rb = bodies.index_mut_internal(*handle);
@@ -211,6 +302,32 @@ impl PhysicsPipeline {
             let effective_mass = rb.mprops.effective_mass();
             rb.forces
                 .compute_effective_force_and_torque(&gravity, &effective_mass);
+
+            #[cfg(feature = "dim3")]
+            if rb.forces.gyroscopic_forces_enabled
+                && multibody_joints.rigid_body_link(*handle).is_none()
+            {
+                let impulse = rb.vels.gyroscopic_torque_impulse(
+                    integration_parameters.dt,
+                    &rb.pos.position.rotation,
+                    &rb.mprops.local_mprops,
+                );
+                rb.apply_torque_impulse(impulse, false);
+            }
+
+            Self::apply_fluid_forces(gravity, narrow_phase, colliders, rb);
+
+            let mut context = ForceModificationContext {
+                rigid_body: *handle,
+                user_data: rb.user_data,
+                position: &rb.pos.position,
+                center_of_mass: &rb.mprops.world_com,
+                mass: rb.mprops.local_mprops.mass(),
+                velocity: &rb.vels,
+                force: &mut rb.forces.force,
+                torque: &mut rb.forces.torque,
+            };
+            hooks.modify_forces(&mut context);
         }
 
         for multibody in &mut multibody_joints.multibodies {
@@ -221,83 +338,223 @@ impl PhysicsPipeline {
         }
         self.counters.stages.update_time.pause();
 
+        let mut manifolds = Vec::new();
+        narrow_phase.select_active_contacts(
+            islands,
+            bodies,
+            &mut self.contact_pair_indices,
+            &mut manifolds,
+            &mut self.manifold_indices,
+        );
+        impulse_joints.select_active_interactions(
+            islands,
+            bodies,
+            &mut self.joint_constraint_indices,
+        );
+
         self.counters.stages.solver_time.resume();
         if self.solvers.len() < islands.num_islands() {
             self.solvers
                 .resize_with(islands.num_islands(), IslandSolver::new);
         }
 
-        #[cfg(not(feature = "parallel"))]
-        {
-            enable_flush_to_zero!();
+        #[cfg(feature = "parallel")]
+        if self.parallel_solvers.len() < islands.num_islands() {
+            self.parallel_solvers
+                .resize_with(islands.num_islands(), ParallelIslandSolver::new);
+        }
 
-            for island_id in 0..islands.num_islands() {
-                self.solvers[island_id].init_and_solve(
-                    island_id,
-                    &mut self.counters,
-                    integration_parameters,
-                    islands,
-                    bodies,
-                    &mut manifolds[..],
-                    &self.manifold_indices[island_id],
-                    impulse_joints.joints_mut(),
-                    &self.joint_constraint_indices[island_id],
-                    multibody_joints,
-                )
-            }
+        let num_substeps = integration_parameters.num_solver_substeps.max(1);
+        let mut substep_parameters = *integration_parameters;
+        substep_parameters.dt /= num_substeps as Real;
+
+        if num_substeps > 1 {
+            self.substep_start_positions.clear();
+            self.substep_start_positions.extend(
+                islands
+                    .active_dynamic_bodies()
+                    .iter()
+                    .map(|handle| bodies[*handle].pos.position),
+            );
         }
 
-        #[cfg(feature = "parallel")]
-        {
-            use crate::geometry::ContactManifold;
-            use rayon::prelude::*;
-            use std::sync::atomic::Ordering;
-
-            let num_islands = islands.num_islands();
-            let solvers = &mut self.solvers[..num_islands];
-            let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
-            let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
-            let impulse_joints =
-                &std::sync::atomic::AtomicPtr::new(impulse_joints.joints_vec_mut() as *mut _);
-            let multibody_joints = &std::sync::atomic::AtomicPtr::new(multibody_joints as *mut _);
-            let manifold_indices = &self.manifold_indices[..];
-            let joint_constraint_indices = &self.joint_constraint_indices[..];
-
-            rayon::scope(|scope| {
+        if self.counters.enabled() {
+            let num_contacts = self.manifold_indices[..islands.num_islands()]
+                .iter()
+                .flatten()
+                .map(|id| manifolds[*id].data.num_active_contacts())
+                .sum();
+            self.counters.set_ncontacts(num_contacts);
+        }
+
+        // Init the solver id for multibody_joints.
+        // We need that for building the constraints.
+        let mut solver_id = 0;
+        for (_, multibody) in multibody_joints.multibodies.iter_mut() {
+            multibody.solver_id = solver_id;
+            solver_id += multibody.ndofs();
+        }
+
+        for substep in 0..num_substeps {
+            if substep > 0 {
+                Self::advance_solver_substep(&substep_parameters, islands, bodies, &mut manifolds);
+            }
+
+            #[cfg(not(feature = "parallel"))]
+            {
                 enable_flush_to_zero!();
 
-                solvers
-                    .par_iter_mut()
-                    .enumerate()
-                    .for_each(|(island_id, solver)| {
-                        let bodies: &mut RigidBodySet =
-                            unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
-                        let manifolds: &mut Vec<&mut ContactManifold> =
-                            unsafe { std::mem::transmute(manifolds.load(Ordering::Relaxed)) };
-                        let impulse_joints: &mut Vec<JointGraphEdge> =
-                            unsafe { std::mem::transmute(impulse_joints.load(Ordering::Relaxed)) };
-                        let multibody_joints: &mut MultibodyJointSet = unsafe {
-                            std::mem::transmute(multibody_joints.load(Ordering::Relaxed))
+                for island_id in 0..islands.num_islands() {
+                    self.solvers[island_id].init_and_solve(
+                        island_id,
+                        &mut self.counters,
+                        &substep_parameters,
+                        islands,
+                        bodies,
+                        &mut manifolds[..],
+                        &self.manifold_indices[island_id],
+                        impulse_joints.joints_mut(),
+                        &self.joint_constraint_indices[island_id],
+                        multibody_joints,
+                    )
+                }
+            }
+
+            // Each island only involves its own bodies, contacts, and joints, so they are solved
+            // concurrently. The resolution of the largest islands is also split across threads.
+            #[cfg(feature = "parallel")]
+            {
+                use rayon::prelude::*;
+                use std::sync::atomic::Ordering;
+
+                let num_islands = islands.num_islands();
+                let solvers = &mut self.solvers[..num_islands];
+                let parallel_solvers = &mut self.parallel_solvers[..num_islands];
+                let bodies = &std::sync::atomic::AtomicPtr::new(bodies as *mut _);
+                let manifolds = &std::sync::atomic::AtomicPtr::new(&mut manifolds as *mut _);
+                let impulse_joints =
+                    &std::sync::atomic::AtomicPtr::new(impulse_joints.joints_vec_mut() as *mut _);
+                let multibody_joints =
+                    &std::sync::atomic::AtomicPtr::new(multibody_joints as *mut _);
+                let manifold_indices = &self.manifold_indices[..];
+                let joint_constraint_indices = &self.joint_constraint_indices[..];
+                let counters_enabled = self.counters.enabled();
+
+                rayon::scope(|scope| {
+                    enable_flush_to_zero!();
+
+                    solvers
+                        .par_iter_mut()
+                        .zip(parallel_solvers.par_iter_mut())
+                        .enumerate()
+                        .for_each(|(island_id, (solver, parallel_solver))| {
+                            enable_flush_to_zero!();
+
+                            let bodies: &mut RigidBodySet =
+                                unsafe { std::mem::transmute(bodies.load(Ordering::Relaxed)) };
+                            let manifolds: &mut Vec<&mut ContactManifold> =
+                                unsafe { std::mem::transmute(manifolds.load(Ordering::Relaxed)) };
+                            let impulse_joints: &mut Vec<JointGraphEdge> = unsafe {
+                                std::mem::transmute(impulse_joints.load(Ordering::Relaxed))
+                            };
+                            let multibody_joints: &mut MultibodyJointSet = unsafe {
+                                std::mem::transmute(multibody_joints.load(Ordering::Relaxed))
+                            };
+
+                            if Self::is_solved_in_parallel(&substep_parameters, islands, island_id)
+                            {
+                                parallel_solver.init_and_solve(
+                                    scope,
+                                    island_id,
+                                    islands,
+                                    &substep_parameters,
+                                    bodies,
+                                    manifolds,
+                                    &manifold_indices[island_id],
+                                    impulse_joints,
+                                    &joint_constraint_indices[island_id],
+                                    multibody_joints,
+                                    counters_enabled,
+                                )
+                            } else {
+                                // NOTE: the counters can't be shared between threads, so the
+                                //       assembly and resolution times of each island aren't
+                                //       measured.
+                                solver.init_and_solve(
+                                    island_id,
+                                    &mut Counters::new(counters_enabled),
+                                    &substep_parameters,
+                                    islands,
+                                    bodies,
+                                    &mut manifolds[..],
+                                    &manifold_indices[island_id],
+                                    impulse_joints,
+                                    &joint_constraint_indices[island_id],
+                                    multibody_joints,
+                                )
+                            }
+                        });
+                });
+            }
+
+            // NOTE: the convergence statistics are read from the island solvers once all the
+            //       islands are solved, so they are available with the `parallel` feature too.
+            if self.counters.enabled() {
+                let solver_counters = &mut self.counters.solver;
+                for island_id in 0..islands.num_islands() {
+                    let (num_velocity_iterations, velocity_error) =
+                        if Self::is_solved_in_parallel(&substep_parameters, islands, island_id) {
+                            // The islands split across threads never stop their iterations early.
+                            #[cfg(feature = "parallel")]
+                            let velocity_error = self.parallel_solvers[island_id].velocity_error();
+                            #[cfg(not(feature = "parallel"))]
+                            let velocity_error = 0.0;
+                            (substep_parameters.max_velocity_iterations, velocity_error)
+                        } else {
+                            let solver = &self.solvers[island_id];
+                            (solver.num_velocity_iterations(), solver.velocity_error())
                         };
 
-                        solver.init_and_solve(
-                            scope,
-                            island_id,
-                            islands,
-                            integration_parameters,
-                            bodies,
-                            manifolds,
-                            &manifold_indices[island_id],
-                            impulse_joints,
-                            &joint_constraint_indices[island_id],
-                            multibody_joints,
-                        )
-                    });
-            });
+                    solver_counters.velocity_iterations = solver_counters
+                        .velocity_iterations
+                        .max(num_velocity_iterations);
+                    solver_counters.velocity_error =
+                        solver_counters.velocity_error.max(velocity_error);
+                }
+            }
+        }
+
+        if self.counters.enabled() {
+            let num_constraints = (0..islands.num_islands())
+                .map(|island_id| {
+                    #[cfg(feature = "parallel")]
+                    if Self::is_solved_in_parallel(&substep_parameters, islands, island_id) {
+                        return self.parallel_solvers[island_id].num_constraints();
+                    }
+
+                    self.solvers[island_id].num_constraints()
+                })
+                .sum();
+            self.counters.set_nconstraints(num_constraints);
+        }
+
+        if num_substeps > 1 {
+            // Move the bodies back to their position at the beginning of the timestep so the
+            // CCD and the final integration see the whole motion of this timestep.
+            for (handle, position) in islands
+                .active_dynamic_bodies()
+                .iter()
+                .zip(self.substep_start_positions.iter())
+            {
+                let rb = bodies.index_mut_internal(*handle);
+                rb.pos.position = *position;
+                rb.mprops.update_world_mass_properties(position);
+            }
         }
 
         // Generate contact force events if needed.
-        let inv_dt = crate::utils::inv(integration_parameters.dt);
+        // NOTE: the impulses are the ones of the last substep, so we use the substep length.
+        let inv_dt = crate::utils::inv(substep_parameters.dt);
         for pair_id in self.contact_pair_indices.drain(..) {
             let pair = narrow_phase.contact_pair_at_index(pair_id);
             let co1 = &colliders[pair.collider1];
@@ -326,6 +583,54 @@ impl PhysicsPipeline {
         self.counters.stages.solver_time.pause();
     }
 
+    // Moves the dynamic bodies to the positions computed by the last solver substep, and updates
+    // the contacts accordingly since the collision detection doesn’t run between substeps.
+    fn advance_solver_substep(
+        substep_parameters: &IntegrationParameters,
+        islands: &IslandManager,
+        bodies: &mut RigidBodySet,
+        manifolds: &mut [&mut ContactManifold],
+    ) {
+        // NOTE: the dynamic bodies moved with the velocities including the constraints
+        //       stabilization bias, so their displacement must be computed from their poses
+        //       rather than from their (unbiased) velocities.
+        let displacement = |handle: Option<RigidBodyHandle>, point: &Point<Real>| {
+            handle
+                .and_then(|handle| bodies.get(handle))
+                .map(|rb| {
+                    if rb.is_dynamic() {
+                        rb.pos.next_position * rb.pos.position.inverse_transform_point(point)
+                            - point
+                    } else {
+                        rb.vels.velocity_at_point(point, &rb.mprops.world_com)
+                            * substep_parameters.dt
+                    }
+                })
+                .unwrap_or_else(Vector::zeros)
+        };
+
+        for manifold in manifolds.iter_mut() {
+            let normal = manifold.data.normal;
+            let (handle1, handle2) = (manifold.data.rigid_body1, manifold.data.rigid_body2);
+
+            for contact in &mut manifold.data.solver_contacts {
+                let displacement1 = displacement(handle1, &contact.point);
+                let displacement2 = displacement(handle2, &contact.point);
+                contact.dist += (displacement2 - displacement1).dot(&normal);
+                contact.point += (displacement1 + displacement2) / 2.0;
+                // The impact was already handled by the first substep, so the restitution must
+                // not be applied again.
+                contact.is_new = false;
+            }
+        }
+
+        for handle in islands.active_dynamic_bodies() {
+            let rb = bodies.index_mut_internal(*handle);
+            rb.pos.position = rb.pos.next_position;
+            rb.mprops.update_world_mass_properties(&rb.pos.position);
+        }
+    }
+
     fn run_ccd_motion_clamping(
         &mut self,
         integration_parameters: &IntegrationParameters,
@@ -409,7 +714,7 @@ impl PhysicsPipeline {
         gravity: &Vector<Real>,
         integration_parameters: &IntegrationParameters,
         islands: &mut IslandManager,
-        broad_phase: &mut BroadPhase,
+        broad_phase: &mut dyn BroadPhase,
         narrow_phase: &mut NarrowPhase,
         bodies: &mut RigidBodySet,
         colliders: &mut ColliderSet,
@@ -488,10 +793,30 @@ impl PhysicsPipeline {
             true,
         );
 
+        // NOTE: refit right away so scene queries run after the collision detection stage see
+        //       the colliders modified by the user.
         if let Some(queries) = query_pipeline.as_deref_mut() {
-            queries.update_incremental(colliders, &modified_colliders, &removed_colliders, false);
+            queries.update_incremental(colliders, &modified_colliders, &removed_colliders, true);
         }
 
+        #[cfg(feature = "debug-validate-state")]
+        Self::validate_state(
+            PipelineStage::CollisionDetection,
+            bodies,
+            colliders,
+            narrow_phase,
+        );
+
+        hooks.on_stage_completed(&mut PipelineStageContext {
+            stage: PipelineStage::CollisionDetection,
+            integration_parameters,
+            islands,
+            bodies,
+            colliders,
+            narrow_phase,
+            query_pipeline: query_pipeline.as_deref(),
+        });
+
         self.clear_modified_colliders(colliders, &mut modified_colliders);
         self.clear_modified_bodies(bodies, &mut modified_bodies);
         removed_colliders.clear();
@@ -576,9 +901,23 @@ impl PhysicsPipeline {
                 colliders,
                 impulse_joints,
                 multibody_joints,
+                hooks,
                 events,
             );
 
+            #[cfg(feature = "debug-validate-state")]
+            Self::validate_state(PipelineStage::Solver, bodies, colliders, narrow_phase);
+
+            hooks.on_stage_completed(&mut PipelineStageContext {
+                stage: PipelineStage::Solver,
+                integration_parameters: &integration_parameters,
+                islands,
+                bodies,
+                colliders,
+                narrow_phase,
+                query_pipeline: query_pipeline.as_deref(),
+            });
+
             // If CCD is enabled, execute the CCD motion clamping.
             if ccd_is_enabled {
                 // NOTE: don't the forces into account when updating the CCD active flags because
@@ -630,6 +969,19 @@ impl PhysicsPipeline {
             }
 
             self.clear_modified_colliders(colliders, &mut modified_colliders);
+
+            #[cfg(feature = "debug-validate-state")]
+            Self::validate_state(PipelineStage::Integration, bodies, colliders, narrow_phase);
+
+            hooks.on_stage_completed(&mut PipelineStageContext {
+                stage: PipelineStage::Integration,
+                integration_parameters: &integration_parameters,
+                islands,
+                bodies,
+                colliders,
+                narrow_phase,
+                query_pipeline: query_pipeline.as_deref(),
+            });
         }
 
         // Finally, make sure we update the world mass-properties of the rigid-bodies
@@ -653,10 +1005,11 @@ mod test {
         CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, RigidBodyBuilder,
         RigidBodySet,
     };
-    use crate::geometry::{BroadPhase, ColliderBuilder, ColliderSet, NarrowPhase};
+    use crate::geometry::{ColliderBuilder, ColliderSet, DefaultBroadPhase, NarrowPhase};
     use crate::math::Vector;
-    use crate::pipeline::PhysicsPipeline;
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
     use crate::prelude::MultibodyJointSet;
+    use crate::test_utils::{cuboid, step_n};
 
     #[test]
     fn kinematic_and_fixed_contact_crash() {
@@ -664,7 +1017,7 @@ mod test {
         let mut impulse_joints = ImpulseJointSet::new();
         let mut multibody_joints = MultibodyJointSet::new();
         let mut pipeline = PhysicsPipeline::new();
-        let mut bf = BroadPhase::new();
+        let mut bf = DefaultBroadPhase::new();
         let mut nf = NarrowPhase::new();
         let mut bodies = RigidBodySet::new();
         let mut islands = IslandManager::new();
@@ -696,13 +1049,410 @@ mod test {
         );
     }
 
+    #[test]
+    fn custom_logic_runs_after_each_pipeline_stage() {
+        use crate::geometry::Ray;
+        use crate::math::{Point, Real};
+        use crate::pipeline::{PhysicsHooks, PipelineStage, PipelineStageContext, QueryFilter};
+        use std::sync::Mutex;
+
+        // Pushes the body hit by a ray cast along the x axis, before the solver runs.
+        struct Pusher {
+            stages: Mutex<Vec<(PipelineStage, Real, Real)>>,
+        }
+        impl PhysicsHooks for Pusher {
+            fn on_stage_completed(&self, context: &mut PipelineStageContext) {
+                let queries = context.query_pipeline.unwrap();
+                let ray = Ray::new(Point::origin() - Vector::x() * 10.0, Vector::x());
+                let (hit, _) = queries
+                    .cast_ray(
+                        context.bodies,
+                        context.colliders,
+                        &ray,
+                        Real::MAX,
+                        true,
+                        QueryFilter::default(),
+                    )
+                    .unwrap();
+                let body = context.colliders[hit].parent().unwrap();
+
+                if context.stage == PipelineStage::CollisionDetection {
+                    context.bodies[body].add_force(Vector::x() * 60.0, true);
+                }
+
+                let rb = &context.bodies[body];
+                self.stages.lock().unwrap().push((
+                    context.stage,
+                    rb.translation().x,
+                    rb.linvel().x,
+                ));
+            }
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic().build());
+        state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.5).mass(1.0),
+            body,
+            &mut state.bodies,
+        );
+
+        let hooks = Pusher {
+            stages: Mutex::new(vec![]),
+        };
+        state.step(&mut pipeline, &hooks, &());
+
+        // The ray already hits the collider inserted before this step, and the force added
+        // after the collision detection is taken into account by the solver.
+        let stages = hooks.stages.into_inner().unwrap();
+        let names: Vec<_> = stages.iter().map(|stage| stage.0).collect();
+        assert_eq!(
+            names,
+            [
+                PipelineStage::CollisionDetection,
+                PipelineStage::Solver,
+                PipelineStage::Integration
+            ]
+        );
+        assert_eq!((stages[0].1, stages[0].2), (0.0, 0.0));
+        assert_eq!(stages[1].1, 0.0);
+        assert!((stages[1].2 - 1.0).abs() < 1.0e-5);
+        assert!((stages[2].1 - 1.0 / 60.0).abs() < 1.0e-5);
+        assert_eq!(state.bodies[body].linvel().x, stages[2].2);
+    }
+
+    #[test]
+    fn simulation_is_deterministic() {
+        use crate::math::{Isometry, Real};
+
+        fn simulate() -> Vec<Isometry<Real>> {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+
+            let ground = cuboid(20.0, 0.5);
+            state.colliders.insert(ground);
+
+            // A loose pile of balls and cubes, in contact with each other.
+            let mut handles = vec![];
+            for i in 0..40 {
+                let offset = (i % 7) as Real * 0.3 - 1.0;
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(Vector::x() * offset + Vector::y() * (1.0 + i as Real * 0.6))
+                    .build();
+                let body = state.bodies.insert(body);
+                let cube = cuboid(0.25, 0.25);
+                let collider = if i % 2 == 0 {
+                    cube
+                } else {
+                    ColliderBuilder::ball(0.25)
+                };
+                state
+                    .colliders
+                    .insert_with_parent(collider, body, &mut state.bodies);
+                handles.push(body);
+            }
+
+            for k in 0..200 {
+                // Removals affect the ordering of the internal data structures.
+                if k == 50 {
+                    for handle in handles.drain(..5) {
+                        state.bodies.remove(
+                            handle,
+                            &mut state.islands,
+                            &mut state.colliders,
+                            &mut state.impulse_joints,
+                            &mut state.multibody_joints,
+                            true,
+                        );
+                    }
+                }
+
+                state.step(&mut pipeline, &(), &());
+            }
+
+            handles
+                .iter()
+                .map(|h| *state.bodies[*h].position())
+                .collect()
+        }
+
+        let expected = simulate();
+        assert_eq!(simulate(), expected);
+
+        // The results don’t depend on the number of threads.
+        #[cfg(feature = "parallel")]
+        for num_threads in [1, 4] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            assert_eq!(pool.install(simulate), expected);
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn islands_solved_in_parallel_match_the_sequential_resolution() {
+        use crate::dynamics::RevoluteJointBuilder;
+        use crate::math::{Isometry, Point, Real};
+
+        fn simulate() -> Vec<Isometry<Real>> {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.integration_parameters.min_island_size = 1;
+
+            // Many piles of cubes and pendulums far from each other, so they are split into
+            // distinct islands.
+            let mut handles = vec![];
+            for k in 0..16 {
+                let origin = Vector::x() * (k as Real * 10.0);
+                let ground = cuboid(2.0, 0.5);
+                state.colliders.insert(ground.translation(origin));
+
+                for i in 0..6 {
+                    let body = RigidBodyBuilder::dynamic()
+                        .translation(
+                            origin
+                                + Vector::x() * (i % 3) as Real * 0.2
+                                + Vector::y() * (1.0 + i as Real),
+                        )
+                        .build();
+                    let body = state.bodies.insert(body);
+                    let cube = cuboid(0.5, 0.5);
+                    state
+                        .colliders
+                        .insert_with_parent(cube, body, &mut state.bodies);
+                    handles.push(body);
+                }
+
+                let anchor = state.bodies.insert(
+                    RigidBodyBuilder::fixed()
+                        .translation(origin + Vector::y() * 10.0)
+                        .build(),
+                );
+                let pendulum = state.bodies.insert(
+                    RigidBodyBuilder::dynamic()
+                        .translation(origin + Vector::x() * 2.0 + Vector::y() * 10.0)
+                        .build(),
+                );
+                state.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.25),
+                    pendulum,
+                    &mut state.bodies,
+                );
+                #[cfg(feature = "dim2")]
+                let joint = RevoluteJointBuilder::new();
+                #[cfg(feature = "dim3")]
+                let joint = RevoluteJointBuilder::new(Vector::z_axis());
+                let joint = joint.local_anchor2(Point::origin() - Vector::x() * 2.0);
+                state.impulse_joints.insert(anchor, pendulum, joint, true);
+                handles.push(pendulum);
+            }
+
+            step_n(&mut state, &mut pipeline, 100);
+
+            assert!(state.islands.num_islands() > 1);
+            handles
+                .iter()
+                .map(|h| *state.bodies[*h].position())
+                .collect()
+        }
+
+        // With a single thread, the islands are solved one after the other.
+        let sequential = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(simulate);
+        let parallel = rayon::ThreadPoolBuilder::new()
+            .num_threads(8)
+            .build()
+            .unwrap()
+            .install(simulate);
+        assert_eq!(parallel, sequential);
+    }
+
+    /// Simulates, with the given number of threads, a single wall of cubes large enough to be
+    /// solved by the intra-island parallel solver.
+    #[cfg(feature = "parallel")]
+    fn simulate_cube_wall(
+        integration_parameters: IntegrationParameters,
+        num_threads: usize,
+    ) -> (
+        PhysicsPipeline,
+        PhysicsState,
+        Vec<crate::math::Isometry<crate::math::Real>>,
+    ) {
+        use crate::math::Real;
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            let mut pipeline = PhysicsPipeline::new();
+            let mut state = PhysicsState::new();
+            state.integration_parameters = integration_parameters;
+
+            let ground = cuboid(50.0, 0.5);
+            state.colliders.insert(ground);
+
+            let mut handles = vec![];
+            for i in 0..WALL_WIDTH {
+                for j in 0..WALL_HEIGHT {
+                    let body = RigidBodyBuilder::dynamic()
+                        .translation(Vector::x() * i as Real + Vector::y() * (1.0 + j as Real))
+                        .build();
+                    let body = state.bodies.insert(body);
+                    let cube = cuboid(0.5, 0.5);
+                    state
+                        .colliders
+                        .insert_with_parent(cube, body, &mut state.bodies);
+                    handles.push(body);
+                }
+            }
+
+            step_n(&mut state, &mut pipeline, 200);
+
+            let positions = handles
+                .iter()
+                .map(|h| *state.bodies[*h].position())
+                .collect();
+            (pipeline, state, positions)
+        })
+    }
+
+    #[cfg(feature = "parallel")]
+    const WALL_WIDTH: usize = 10;
+    #[cfg(feature = "parallel")]
+    const WALL_HEIGHT: usize = 16;
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn large_islands_are_split_across_threads_and_remain_stable() {
+        use crate::math::Real;
+
+        // Keep the wall awake so its island is still solved at the last timestep.
+        let params = IntegrationParameters {
+            sleeping_enabled: false,
+            ..IntegrationParameters::default()
+        };
+        let (sequential_pipeline, _, sequential) = simulate_cube_wall(params, 1);
+        let (parallel_pipeline, state, parallel) = simulate_cube_wall(params, 8);
+        assert!(PhysicsPipeline::is_solved_in_parallel(
+            &state.integration_parameters,
+            &state.islands,
+            0
+        ));
+        assert_eq!(parallel, sequential);
+
+        // The convergence of the split islands is measured too.
+        let counters = &parallel_pipeline.counters.solver;
+        assert_eq!(counters.velocity_iterations, params.max_velocity_iterations);
+        assert!(counters.velocity_error > 0.0);
+        assert_eq!(
+            counters.velocity_error,
+            sequential_pipeline.counters.solver.velocity_error
+        );
+
+        // The wall must not collapse. The top cubes sink a little, as with the sequential solver.
+        for (k, pos) in parallel.iter().enumerate() {
+            let expected_y = 1.0 + (k % WALL_HEIGHT) as Real;
+            assert!(
+                (pos.translation.vector.y - expected_y).abs() < 0.25,
+                "{:?} vs. {}",
+                pos.translation.vector,
+                expected_y
+            );
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn large_islands_with_early_outs_or_shock_propagation_match_the_sequential_resolution() {
+        let params = IntegrationParameters {
+            max_velocity_iterations: 20,
+            velocity_iterations_tolerance: 1.0e-3,
+            stabilization_iterations_tolerance: 1.0e-3,
+            shock_propagation: true,
+            sleeping_enabled: false,
+            ..IntegrationParameters::default()
+        };
+        let (_, _, sequential) = simulate_cube_wall(params, 1);
+        let (pipeline, state, parallel) = simulate_cube_wall(params, 8);
+
+        // These options need the whole island, so it isn’t split across threads, and they
+        // aren’t ignored.
+        assert!(state.islands.active_island(0).len() >= super::MIN_PARALLEL_ISLAND_SIZE);
+        assert!(!PhysicsPipeline::is_solved_in_parallel(
+            &state.integration_parameters,
+            &state.islands,
+            0
+        ));
+        assert!(pipeline.counters.solver.velocity_iterations < params.max_velocity_iterations);
+        assert_eq!(parallel, sequential);
+
+        let (_, _, without_shock_propagation) = simulate_cube_wall(
+            IntegrationParameters {
+                shock_propagation: false,
+                ..params
+            },
+            8,
+        );
+        assert_ne!(parallel, without_shock_propagation);
+    }
+
+    #[test]
+    fn counters_record_the_constraints_and_the_convergence_of_the_solver() {
+        use crate::math::Real;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(100.0, 0.5);
+        state.colliders.insert(ground);
+
+        for i in 0..3 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * (1.0 + i as Real))
+                .build();
+            let body = state.bodies.insert(body);
+            let cube = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(cube, body, &mut state.bodies);
+        }
+
+        step_n(&mut state, &mut pipeline, 10);
+
+        let counters = &pipeline.counters;
+        assert_eq!(counters.cd.ncontact_pairs, 3);
+        // Each face-to-face contact involves several contact points.
+        assert!(counters.solver.ncontacts > 3);
+        assert!(counters.solver.nconstraints > 0);
+        assert_eq!(
+            counters.solver.velocity_iterations,
+            state.integration_parameters.max_velocity_iterations
+        );
+        assert!(counters.solver.velocity_error > 0.0);
+        assert!(counters.solver.velocity_error.is_finite());
+
+        // The velocity iterations stop as soon as they converged.
+        state.integration_parameters.velocity_iterations_tolerance = Real::MAX;
+        state.step(&mut pipeline, &(), &());
+        assert_eq!(pipeline.counters.solver.velocity_iterations, 1);
+    }
+
     #[test]
     fn rigid_body_removal_before_step() {
         let mut colliders = ColliderSet::new();
         let mut impulse_joints = ImpulseJointSet::new();
         let mut multibody_joints = MultibodyJointSet::new();
         let mut pipeline = PhysicsPipeline::new();
-        let mut bf = BroadPhase::new();
+        let mut bf = DefaultBroadPhase::new();
         let mut nf = NarrowPhase::new();
         let mut islands = IslandManager::new();
 
@@ -812,7 +1562,7 @@ mod test {
         let mut pipeline = PhysicsPipeline::new();
         let gravity = Vector::y() * -9.81;
         let integration_parameters = IntegrationParameters::default();
-        let mut broad_phase = BroadPhase::new();
+        let mut broad_phase = DefaultBroadPhase::new();
         let mut narrow_phase = NarrowPhase::new();
         let mut bodies = RigidBodySet::new();
         let mut colliders = ColliderSet::new();
@@ -0,0 +1,567 @@
+use crate::dynamics::{
+    GenericJoint, ImpulseJointHandle, IntegrationParameters, RigidBody, RigidBodyHandle,
+};
+use crate::geometry::{Collider, ColliderHandle};
+use crate::math::{AngVector, Isometry, Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline, PhysicsState};
+
+/// A modification of a physics world, as recorded by the [`PhysicsRecorder`].
+///
+/// The commands modifying a rigid-body, collider, or joint that no longer exists are ignored.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub enum PhysicsCommand {
+    /// Inserts a rigid-body.
+    InsertRigidBody(RigidBody),
+    /// Removes a rigid-body, and optionally its attached colliders.
+    RemoveRigidBody {
+        /// The rigid-body to remove.
+        handle: RigidBodyHandle,
+        /// Should the colliders attached to this rigid-body be removed too?
+        remove_attached_colliders: bool,
+    },
+    /// Inserts a collider, optionally attached to a rigid-body.
+    InsertCollider {
+        /// The collider to insert.
+        collider: Collider,
+        /// The rigid-body the collider is attached to.
+        parent: Option<RigidBodyHandle>,
+    },
+    /// Removes a collider.
+    RemoveCollider {
+        /// The collider to remove.
+        handle: ColliderHandle,
+        /// Should the rigid-body it was attached to be woken up?
+        wake_up: bool,
+    },
+    /// Inserts an impulse joint between two rigid-bodies.
+    InsertImpulseJoint {
+        /// The first rigid-body attached to the joint.
+        body1: RigidBodyHandle,
+        /// The second rigid-body attached to the joint.
+        body2: RigidBodyHandle,
+        /// The joint to insert.
+        joint: GenericJoint,
+        /// Should the attached rigid-bodies be woken up?
+        wake_up: bool,
+    },
+    /// Removes an impulse joint.
+    RemoveImpulseJoint {
+        /// The joint to remove.
+        handle: ImpulseJointHandle,
+        /// Should the attached rigid-bodies be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::set_position`].
+    SetPosition {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The new position of the rigid-body.
+        position: Isometry<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::set_next_kinematic_position`].
+    SetNextKinematicPosition {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The next position of the rigid-body.
+        position: Isometry<Real>,
+    },
+    /// Calls [`RigidBody::set_linvel`].
+    SetLinvel {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The new linear velocity of the rigid-body.
+        linvel: Vector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::set_angvel`].
+    SetAngvel {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The new angular velocity of the rigid-body.
+        angvel: AngVector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::add_force`].
+    AddForce {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The force to add.
+        force: Vector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::add_torque`].
+    AddTorque {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The torque to add.
+        torque: AngVector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::reset_forces`] and [`RigidBody::reset_torques`].
+    ResetForcesAndTorques {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::apply_impulse`].
+    ApplyImpulse {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The impulse to apply.
+        impulse: Vector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Calls [`RigidBody::apply_torque_impulse`].
+    ApplyTorqueImpulse {
+        /// The modified rigid-body.
+        handle: RigidBodyHandle,
+        /// The torque impulse to apply.
+        torque_impulse: AngVector<Real>,
+        /// Should the rigid-body be woken up?
+        wake_up: bool,
+    },
+    /// Sets the gravity of the physics world.
+    SetGravity(Vector<Real>),
+    /// Sets the integration parameters of the physics world.
+    SetIntegrationParameters(IntegrationParameters),
+    /// Executes one timestep.
+    Step,
+}
+
+impl PhysicsCommand {
+    /// Applies this command to the given physics state.
+    ///
+    /// The [`PhysicsCommand::Step`] command is executed with the given pipeline, without any
+    /// physics hooks nor event handler.
+    pub fn apply(&self, pipeline: &mut PhysicsPipeline, state: &mut PhysicsState) {
+        match self {
+            Self::InsertRigidBody(rb) => {
+                let _ = state.bodies.insert(rb.clone());
+            }
+            Self::RemoveRigidBody {
+                handle,
+                remove_attached_colliders,
+            } => {
+                let _ = state.bodies.remove(
+                    *handle,
+                    &mut state.islands,
+                    &mut state.colliders,
+                    &mut state.impulse_joints,
+                    &mut state.multibody_joints,
+                    *remove_attached_colliders,
+                );
+            }
+            Self::InsertCollider { collider, parent } => {
+                let _ = insert_collider(state, collider.clone(), *parent);
+            }
+            Self::RemoveCollider { handle, wake_up } => {
+                let _ = state.colliders.remove(
+                    *handle,
+                    &mut state.islands,
+                    &mut state.bodies,
+                    *wake_up,
+                );
+            }
+            Self::InsertImpulseJoint {
+                body1,
+                body2,
+                joint,
+                wake_up,
+            } => {
+                let _ = state
+                    .impulse_joints
+                    .insert(*body1, *body2, *joint, *wake_up);
+            }
+            Self::RemoveImpulseJoint { handle, wake_up } => {
+                let _ = state.impulse_joints.remove(*handle, *wake_up);
+            }
+            Self::SetPosition {
+                handle,
+                position,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.set_position(*position, *wake_up);
+                }
+            }
+            Self::SetNextKinematicPosition { handle, position } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.set_next_kinematic_position(*position);
+                }
+            }
+            Self::SetLinvel {
+                handle,
+                linvel,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.set_linvel(*linvel, *wake_up);
+                }
+            }
+            Self::SetAngvel {
+                handle,
+                angvel,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.set_angvel(*angvel, *wake_up);
+                }
+            }
+            Self::AddForce {
+                handle,
+                force,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.add_force(*force, *wake_up);
+                }
+            }
+            Self::AddTorque {
+                handle,
+                torque,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.add_torque(*torque, *wake_up);
+                }
+            }
+            Self::ResetForcesAndTorques { handle, wake_up } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.reset_forces(*wake_up);
+                    rb.reset_torques(*wake_up);
+                }
+            }
+            Self::ApplyImpulse {
+                handle,
+                impulse,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.apply_impulse(*impulse, *wake_up);
+                }
+            }
+            Self::ApplyTorqueImpulse {
+                handle,
+                torque_impulse,
+                wake_up,
+            } => {
+                if let Some(rb) = state.bodies.get_mut(*handle) {
+                    rb.apply_torque_impulse(*torque_impulse, *wake_up);
+                }
+            }
+            Self::SetGravity(gravity) => state.gravity = *gravity,
+            Self::SetIntegrationParameters(params) => state.integration_parameters = *params,
+            Self::Step => state.step(pipeline, &(), &()),
+        }
+    }
+}
+
+fn insert_collider(
+    state: &mut PhysicsState,
+    collider: Collider,
+    parent: Option<RigidBodyHandle>,
+) -> ColliderHandle {
+    if let Some(parent) = parent {
+        state
+            .colliders
+            .insert_with_parent(collider, parent, &mut state.bodies)
+    } else {
+        state.colliders.insert(collider)
+    }
+}
+
+/// An initial physics state, and all the commands subsequently applied to it.
+///
+/// With the `serde-serialize` feature, a recording can be saved to a file to reproduce a
+/// simulation deterministically, e.g., to attach it to a bug report.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct PhysicsRecording {
+    /// The physics state the commands are applied to.
+    pub initial_state: PhysicsState,
+    /// All the commands applied to the physics state, in order.
+    pub commands: Vec<PhysicsCommand>,
+    /// The [`PhysicsState::checksum`] of the physics state after each recorded timestep.
+    pub checksums: Vec<u64>,
+}
+
+/// Records all the modifications applied to a physics world.
+///
+/// The physics world is modified through the methods of the recorder, which record a
+/// [`PhysicsCommand`] for each modification. Modifications applied directly to the
+/// [`PhysicsRecorder::state_mut`] aren’t recorded, and will make the replay diverge. Such a
+/// divergence is reported by [`PhysicsReplayer::divergence`].
+pub struct PhysicsRecorder {
+    pipeline: PhysicsPipeline,
+    state: PhysicsState,
+    recording: PhysicsRecording,
+}
+
+impl PhysicsRecorder {
+    /// Starts recording the modifications applied to the given physics state.
+    pub fn new(state: PhysicsState) -> Self {
+        Self {
+            pipeline: PhysicsPipeline::new(),
+            recording: PhysicsRecording {
+                initial_state: state.clone(),
+                commands: vec![],
+                checksums: vec![],
+            },
+            state,
+        }
+    }
+
+    /// The current physics state.
+    pub fn state(&self) -> &PhysicsState {
+        &self.state
+    }
+
+    /// A mutable reference to the current physics state.
+    ///
+    /// The modifications applied through this reference aren’t recorded.
+    pub fn state_mut(&mut self) -> &mut PhysicsState {
+        &mut self.state
+    }
+
+    /// The recording of all the modifications applied so far.
+    pub fn recording(&self) -> &PhysicsRecording {
+        &self.recording
+    }
+
+    /// Stops recording and returns the recording of all the modifications applied.
+    pub fn into_recording(self) -> PhysicsRecording {
+        self.recording
+    }
+
+    /// Applies and records the given command.
+    ///
+    /// The [`PhysicsRecorder::step`] method should be used instead of the [`PhysicsCommand::Step`]
+    /// command to execute a timestep with physics hooks or an event handler.
+    pub fn execute(&mut self, command: PhysicsCommand) {
+        command.apply(&mut self.pipeline, &mut self.state);
+        if matches!(command, PhysicsCommand::Step) {
+            self.recording.checksums.push(self.state.checksum());
+        }
+        self.recording.commands.push(command);
+    }
+
+    /// Inserts a rigid-body and records it.
+    pub fn insert_rigid_body(&mut self, rb: impl Into<RigidBody>) -> RigidBodyHandle {
+        let rb = rb.into();
+        self.recording
+            .commands
+            .push(PhysicsCommand::InsertRigidBody(rb.clone()));
+        self.state.bodies.insert(rb)
+    }
+
+    /// Inserts a collider, optionally attached to a rigid-body, and records it.
+    pub fn insert_collider(
+        &mut self,
+        collider: impl Into<Collider>,
+        parent: Option<RigidBodyHandle>,
+    ) -> ColliderHandle {
+        let collider = collider.into();
+        self.recording
+            .commands
+            .push(PhysicsCommand::InsertCollider {
+                collider: collider.clone(),
+                parent,
+            });
+        insert_collider(&mut self.state, collider, parent)
+    }
+
+    /// Inserts an impulse joint between two rigid-bodies and records it.
+    pub fn insert_impulse_joint(
+        &mut self,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+        wake_up: bool,
+    ) -> ImpulseJointHandle {
+        let joint = joint.into();
+        self.recording
+            .commands
+            .push(PhysicsCommand::InsertImpulseJoint {
+                body1,
+                body2,
+                joint,
+                wake_up,
+            });
+        self.state
+            .impulse_joints
+            .insert(body1, body2, joint, wake_up)
+    }
+
+    /// Executes one timestep and records it.
+    ///
+    /// The physics hooks and events aren’t recorded: the recording will be replayed without any
+    /// physics hooks, so it will diverge if the `hooks` modify the simulation.
+    pub fn step(&mut self, hooks: &dyn PhysicsHooks, events: &dyn EventHandler) {
+        self.state.step(&mut self.pipeline, hooks, events);
+        self.recording.commands.push(PhysicsCommand::Step);
+        self.recording.checksums.push(self.state.checksum());
+    }
+}
+
+/// Replays a [`PhysicsRecording`], one timestep at a time.
+pub struct PhysicsReplayer {
+    pipeline: PhysicsPipeline,
+    state: PhysicsState,
+    recording: PhysicsRecording,
+    next_command: usize,
+    num_steps: usize,
+    divergence: Option<usize>,
+}
+
+impl PhysicsReplayer {
+    /// Creates a replayer starting at the initial state of the given recording.
+    pub fn new(recording: PhysicsRecording) -> Self {
+        Self {
+            pipeline: PhysicsPipeline::new(),
+            state: recording.initial_state.clone(),
+            recording,
+            next_command: 0,
+            num_steps: 0,
+            divergence: None,
+        }
+    }
+
+    /// The current physics state.
+    pub fn state(&self) -> &PhysicsState {
+        &self.state
+    }
+
+    /// The index of the first replayed timestep that didn’t result in the same physics state as
+    /// during the recording, if any.
+    ///
+    /// The replay diverges if the recorded physics state was modified without being recorded
+    /// (e.g. through [`PhysicsRecorder::state_mut`] or by physics hooks), or if the simulation
+    /// isn’t deterministic on this platform.
+    pub fn divergence(&self) -> Option<usize> {
+        self.divergence
+    }
+
+    /// Has every command of the recording been replayed?
+    pub fn is_finished(&self) -> bool {
+        self.next_command == self.recording.commands.len()
+    }
+
+    /// Replays all the commands up to, and including, the next timestep.
+    ///
+    /// Returns `false` if there was no command left to replay.
+    pub fn step(&mut self) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        while let Some(command) = self.recording.commands.get(self.next_command) {
+            command.apply(&mut self.pipeline, &mut self.state);
+            self.next_command += 1;
+
+            if matches!(command, PhysicsCommand::Step) {
+                let checksum = self.recording.checksums.get(self.num_steps);
+                if self.divergence.is_none() && checksum != Some(&self.state.checksum()) {
+                    self.divergence = Some(self.num_steps);
+                }
+                self.num_steps += 1;
+                break;
+            }
+        }
+
+        true
+    }
+
+    /// Replays all the remaining commands.
+    pub fn finish(&mut self) {
+        while self.step() {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn recorded_simulation_replays_identically() {
+        use crate::math::Real;
+        use crate::pipeline::{PhysicsCommand, PhysicsRecorder, PhysicsReplayer, PhysicsState};
+
+        let mut recorder = PhysicsRecorder::new(PhysicsState::new());
+        let ground = cuboid(20.0, 0.5);
+        let _ = recorder.insert_collider(ground, None);
+
+        let mut handles = vec![];
+        let mut checksums = vec![];
+        for k in 0..60 {
+            if k % 10 == 0 {
+                let body = recorder.insert_rigid_body(
+                    RigidBodyBuilder::dynamic().translation(Vector::y() * (1.0 + k as Real * 0.1)),
+                );
+                let _ = recorder.insert_collider(ColliderBuilder::ball(0.5), Some(body));
+                handles.push(body);
+            }
+
+            recorder.execute(PhysicsCommand::ApplyImpulse {
+                handle: handles[k % handles.len()],
+                impulse: Vector::x() * 0.1,
+                wake_up: true,
+            });
+
+            if k == 35 {
+                recorder.execute(PhysicsCommand::RemoveRigidBody {
+                    handle: handles[1],
+                    remove_attached_colliders: true,
+                });
+                recorder.execute(PhysicsCommand::SetGravity(Vector::y() * -5.0));
+            }
+
+            recorder.step(&(), &());
+            checksums.push(recorder.state().checksum());
+        }
+
+        let mut replayer = PhysicsReplayer::new(recorder.into_recording());
+        for checksum in checksums {
+            assert!(replayer.step());
+            assert_eq!(replayer.state().checksum(), checksum);
+        }
+        assert!(replayer.is_finished());
+        assert!(!replayer.step());
+        assert_eq!(replayer.divergence(), None);
+    }
+
+    #[test]
+    fn unrecorded_modifications_are_reported_as_divergences() {
+        use crate::pipeline::{PhysicsRecorder, PhysicsReplayer, PhysicsState};
+
+        let mut recorder = PhysicsRecorder::new(PhysicsState::new());
+        let body = recorder.insert_rigid_body(RigidBodyBuilder::dynamic());
+        let _ = recorder.insert_collider(ColliderBuilder::ball(0.5), Some(body));
+
+        for k in 0..10 {
+            if k == 5 {
+                recorder.state_mut().bodies[body].set_linvel(Vector::x(), true);
+            }
+            recorder.step(&(), &());
+        }
+
+        let mut replayer = PhysicsReplayer::new(recorder.into_recording());
+        for _ in 0..5 {
+            assert!(replayer.step());
+            assert_eq!(replayer.divergence(), None);
+        }
+        replayer.finish();
+        assert_eq!(replayer.divergence(), Some(5));
+    }
+}
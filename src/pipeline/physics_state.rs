@@ -0,0 +1,271 @@
+use crate::dynamics::{
+    CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
+    RigidBodySet,
+};
+use crate::geometry::{ColliderSet, DefaultBroadPhase, NarrowPhase};
+use crate::math::{Real, Vector};
+use crate::pipeline::{EventHandler, PhysicsHooks, PhysicsPipeline, QueryPipeline};
+
+/// All the data-structures needed to step a physics simulation with the [`PhysicsPipeline`].
+///
+/// This bundles all the state of the physics world, including the state of the broad-phase,
+/// the narrow-phase (with the warm-start impulses of the contacts), and the island manager.
+/// With the `serde-serialize` feature, serializing this structure and deserializing it later
+/// allows the simulation to resume bit-identically, e.g., to save and restore a game, or to
+/// rollback a networked simulation.
+///
+/// The [`PhysicsPipeline`] only contains workspace data, so it isn’t part of this state.
+#[cfg_attr(feature = "serde-serialize", derive(Serialize, Deserialize))]
+#[derive(Clone)]
+pub struct PhysicsState {
+    /// The gravity applied to the dynamic rigid-bodies.
+    pub gravity: Vector<Real>,
+    /// The integration parameters of the simulation.
+    pub integration_parameters: IntegrationParameters,
+    /// The island manager, tracking which rigid-bodies are sleeping.
+    pub islands: IslandManager,
+    /// The broad-phase.
+    pub broad_phase: DefaultBroadPhase,
+    /// The narrow-phase, containing all the contacts and intersections between colliders.
+    pub narrow_phase: NarrowPhase,
+    /// The set of rigid-bodies.
+    pub bodies: RigidBodySet,
+    /// The set of colliders.
+    pub colliders: ColliderSet,
+    /// The set of impulse joints.
+    pub impulse_joints: ImpulseJointSet,
+    /// The set of multibody joints.
+    pub multibody_joints: MultibodyJointSet,
+    /// The solver responsible for continuous collision detection.
+    pub ccd_solver: CCDSolver,
+    /// The query pipeline, updated at each step.
+    pub query_pipeline: QueryPipeline,
+}
+
+impl Default for PhysicsState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicsState {
+    /// Creates an empty physics state, with a gravity of `-9.81` along the `y` axis, and the
+    /// default integration parameters.
+    pub fn new() -> Self {
+        Self {
+            gravity: Vector::y() * -9.81,
+            integration_parameters: IntegrationParameters::default(),
+            islands: IslandManager::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd_solver: CCDSolver::new(),
+            query_pipeline: QueryPipeline::new(),
+        }
+    }
+
+    /// Executes one timestep of the simulation of this physics state with the given pipeline.
+    pub fn step(
+        &mut self,
+        pipeline: &mut PhysicsPipeline,
+        hooks: &dyn PhysicsHooks,
+        events: &dyn EventHandler,
+    ) {
+        pipeline.step(
+            &self.gravity,
+            &self.integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd_solver,
+            Some(&mut self.query_pipeline),
+            hooks,
+            events,
+        );
+    }
+
+    /// A hash of the dynamic state of this physics world.
+    ///
+    /// This covers the handles, poses, and velocities of the rigid-bodies, as well as the impulses
+    /// of the contacts. Contrary to the standard library hashers, the result only depends on the
+    /// bits of these values, so it is stable across platforms, executions, and versions of the
+    /// compiler. Networked peers running a deterministic simulation (see the crate-level
+    /// documentation) can compare their checksums at each tick to detect a desynchronization as
+    /// soon as it occurs.
+    pub fn checksum(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+
+        for (handle, rb) in self.bodies.iter() {
+            let (id, generation) = handle.into_raw_parts();
+            hasher.write_u32(id);
+            hasher.write_u32(generation);
+            hasher.write_reals(rb.position().translation.vector.as_slice());
+            #[cfg(feature = "dim2")]
+            hasher.write_reals(&[rb.position().rotation.re, rb.position().rotation.im]);
+            #[cfg(feature = "dim3")]
+            hasher.write_reals(rb.position().rotation.coords.as_slice());
+            hasher.write_reals(rb.linvel().as_slice());
+            #[cfg(feature = "dim2")]
+            hasher.write_reals(&[rb.angvel()]);
+            #[cfg(feature = "dim3")]
+            hasher.write_reals(rb.angvel().as_slice());
+        }
+
+        for pair in self.narrow_phase.contact_pairs() {
+            for manifold in &pair.manifolds {
+                for pt in &manifold.points {
+                    hasher.write_reals(&[pt.data.impulse]);
+                    #[cfg(feature = "dim2")]
+                    hasher.write_reals(&[pt.data.tangent_impulse]);
+                    #[cfg(feature = "dim3")]
+                    hasher.write_reals(pt.data.tangent_impulse.as_slice());
+                }
+            }
+        }
+
+        hasher.0
+    }
+}
+
+// The 64-bit FNV-1a hash function.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_reals(&mut self, values: &[Real]) {
+        for value in values {
+            self.write(&value.to_le_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::Vector;
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::cuboid;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn restored_physics_state_resumes_bit_identically() {
+        use crate::dynamics::FixedJointBuilder;
+        use crate::math::{Point, Real};
+        use crate::pipeline::PhysicsState;
+        use crate::test_utils::step_n;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(10.0, 0.5);
+        state.colliders.insert(ground);
+
+        let mut handles = vec![];
+        for i in 0..10 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i % 3) as Real * 0.2 + Vector::y() * (1.0 + i as Real))
+                .build();
+            let body = state.bodies.insert(body);
+            let cube = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(cube, body, &mut state.bodies);
+            handles.push(body);
+        }
+        state.impulse_joints.insert(
+            handles[0],
+            handles[1],
+            FixedJointBuilder::new().local_anchor2(Point::origin() - Vector::y()),
+            true,
+        );
+
+        step_n(&mut state, &mut pipeline, 30);
+
+        // Restore the state with a fresh pipeline, while the bodies are still moving and the
+        // contacts have non-zero warm-start impulses.
+        let serialized = bincode::serialize(&state).unwrap();
+        let mut restored: PhysicsState = bincode::deserialize(&serialized).unwrap();
+        let mut restored_pipeline = PhysicsPipeline::new();
+
+        for _ in 0..60 {
+            state.step(&mut pipeline, &(), &());
+            restored.step(&mut restored_pipeline, &(), &());
+        }
+
+        for handle in handles {
+            assert_eq!(
+                restored.bodies[handle].position(),
+                state.bodies[handle].position()
+            );
+            assert_eq!(
+                restored.bodies[handle].linvel(),
+                state.bodies[handle].linvel()
+            );
+        }
+    }
+
+    #[test]
+    fn checksum_detects_diverging_simulations() {
+        use crate::math::Real;
+        use crate::pipeline::PhysicsState;
+
+        fn world() -> PhysicsState {
+            let mut state = PhysicsState::new();
+            let ground = cuboid(20.0, 0.5);
+            state.colliders.insert(ground);
+
+            for i in 0..10 {
+                let body = RigidBodyBuilder::dynamic()
+                    .translation(
+                        Vector::x() * (i % 3) as Real * 0.4 + Vector::y() * (1.0 + i as Real),
+                    )
+                    .build();
+                let body = state.bodies.insert(body);
+                state.colliders.insert_with_parent(
+                    ColliderBuilder::ball(0.5),
+                    body,
+                    &mut state.bodies,
+                );
+            }
+            state
+        }
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state1 = world();
+        let mut state2 = world();
+        assert_eq!(state1.checksum(), state2.checksum());
+
+        for _ in 0..60 {
+            state1.step(&mut pipeline, &(), &());
+            state2.step(&mut pipeline, &(), &());
+            assert_eq!(state1.checksum(), state2.checksum());
+        }
+
+        // A tiny divergence changes the checksum.
+        let (handle, _) = state2.bodies.iter().next().unwrap();
+        let linvel = *state2.bodies[handle].linvel();
+        state2.bodies[handle].set_linvel(linvel + Vector::x() * 1.0e-6, false);
+        assert_ne!(state1.checksum(), state2.checksum());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::dynamics::RigidBodyHandle;
 use crate::geometry::{
-    Aabb, Collider, ColliderHandle, InteractionGroups, PointProjection, Qbvh, Ray, RayIntersection,
+    Aabb, BroadPhaseBvh, Collider, ColliderHandle, InteractionGroups, PointProjection, Qbvh, Ray,
+    RayIntersection,
 };
 use crate::math::{Isometry, Point, Real, Vector};
+use crate::utils;
 use crate::{dynamics::RigidBodySet, geometry::ColliderSet};
 use parry::partitioning::{QbvhDataGenerator, QbvhUpdateWorkspace};
 use parry::query::details::{
@@ -48,9 +50,9 @@ bitflags::bitflags! {
     pub struct QueryFilterFlags: u32 {
         /// Exclude from the query any collider attached to a fixed rigid-body and colliders with no rigid-body attached.
         const EXCLUDE_FIXED = 1 << 1;
-        /// Exclude from the query any collider attached to a dynamic rigid-body.
-        const EXCLUDE_KINEMATIC = 1 << 2;
         /// Exclude from the query any collider attached to a kinematic rigid-body.
+        const EXCLUDE_KINEMATIC = 1 << 2;
+        /// Exclude from the query any collider attached to a dynamic rigid-body.
         const EXCLUDE_DYNAMIC = 1 << 3;
         /// Exclude from the query any collider that is a sensor.
         const EXCLUDE_SENSORS = 1 << 4;
@@ -102,7 +104,7 @@ impl QueryFilterFlags {
     }
 }
 
-/// A filter tha describes what collider should be included or excluded from a scene query.
+/// A filter that describes what collider should be included or excluded from a scene query.
 #[derive(Copy, Clone, Default)]
 pub struct QueryFilter<'a> {
     /// Flags indicating what particular type of colliders should be excluded from the scene query.
@@ -323,6 +325,18 @@ impl QueryPipeline {
 
     /// Update the query pipeline incrementally, avoiding a complete rebuild of its
     /// internal data-structure.
+    ///
+    /// Only the `modified_colliders` (e.g. colliders that moved, or were inserted) and the
+    /// `removed_colliders` are updated, so the cost of this update doesn’t depend on the number of
+    /// static colliders. If `refit_and_rebalance` is `false`, the changes are only recorded, and
+    /// will be applied by the next call with `refit_and_rebalance` set to `true`: scene queries
+    /// shouldn’t be run in-between. This is called automatically by
+    /// [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) if it is given a query
+    /// pipeline.
+    ///
+    /// The rebalancing only reorganizes the parts of the tree that changed. If the tree quality
+    /// degrades after a lot of colliders moved far away from their initial position, it can be
+    /// rebuilt from scratch with [`QueryPipeline::update`].
     pub fn update_incremental(
         &mut self,
         colliders: &ColliderSet,
@@ -340,8 +354,20 @@ impl QueryPipeline {
 
         for modified in modified_colliders {
             // Check that the collider still exists as it may have been removed.
-            if colliders.contains(*modified) {
-                self.qbvh.pre_update_or_insert(*modified);
+            if let Some(co) = colliders.get(*modified) {
+                // Disabled colliders must not be found by scene queries.
+                if co.is_enabled() {
+                    if !utils::qbvh_contains(&self.qbvh, *modified)
+                        && !utils::qbvh_can_insert(&self.qbvh)
+                    {
+                        self.rebuild_from_current_positions(colliders);
+                        return;
+                    }
+
+                    self.qbvh.pre_update_or_insert(*modified);
+                } else {
+                    self.qbvh.remove(*modified);
+                }
             }
         }
 
@@ -353,6 +379,26 @@ impl QueryPipeline {
         }
     }
 
+    fn rebuild_from_current_positions(&mut self, colliders: &ColliderSet) {
+        struct DataGenerator<'a>(&'a ColliderSet);
+
+        impl<'a> QbvhDataGenerator<ColliderHandle> for DataGenerator<'a> {
+            fn size_hint(&self) -> usize {
+                self.0.len()
+            }
+
+            #[inline(always)]
+            fn for_each(&mut self, mut f: impl FnMut(ColliderHandle, Aabb)) {
+                for (h, co) in self.0.iter_enabled() {
+                    f(h, co.compute_aabb())
+                }
+            }
+        }
+
+        self.qbvh
+            .clear_and_rebuild(DataGenerator(colliders), self.dilation_factor);
+    }
+
     /// Update the acceleration structure on the query pipeline.
     pub fn update(&mut self, bodies: &RigidBodySet, colliders: &ColliderSet) {
         self.update_with_mode(bodies, colliders, QueryPipelineMode::CurrentPosition)
@@ -387,9 +433,24 @@ impl QueryPipeline {
                     QueryPipelineMode::SweepTestWithNextPosition => {
                         for (h, co) in self.colliders.iter_enabled() {
                             if let Some(co_parent) = co.parent {
-                                let rb_next_pos = &self.bodies[co_parent.handle].pos.next_position;
-                                let next_position = rb_next_pos * co_parent.pos_wrt_parent;
-                                f(h, co.shape.compute_swept_aabb(&co.pos, &next_position))
+                                let rb = &self.bodies[co_parent.handle];
+                                let next_position = rb.pos.next_position * co_parent.pos_wrt_parent;
+                                // NOTE: without a timestep length, we can only use the
+                                //       rotation between both positions, so rotations
+                                //       larger than a half-turn aren’t accounted for.
+                                let angle = rb
+                                    .pos
+                                    .position
+                                    .rotation
+                                    .angle_to(&rb.pos.next_position.rotation);
+                                f(
+                                    h,
+                                    co.compute_rotating_swept_aabb(
+                                        &next_position,
+                                        &rb.mprops.world_com,
+                                        angle,
+                                    ),
+                                )
                             } else {
                                 f(h, co.shape.compute_aabb(&co.pos))
                             }
@@ -402,9 +463,20 @@ impl QueryPipeline {
                                 let predicted_pos = rb.pos.integrate_forces_and_velocities(
                                     dt, &rb.forces, &rb.vels, &rb.mprops,
                                 );
+                                let predicted_angle = rb
+                                    .forces
+                                    .integrate(dt, &rb.vels, &rb.mprops)
+                                    .rotation_angle(dt);
 
                                 let next_position = predicted_pos * co_parent.pos_wrt_parent;
-                                f(h, co.shape.compute_swept_aabb(&co.pos, &next_position))
+                                f(
+                                    h,
+                                    co.compute_rotating_swept_aabb(
+                                        &next_position,
+                                        &rb.mprops.world_com,
+                                        predicted_angle,
+                                    ),
+                                )
                             } else {
                                 f(h, co.shape.compute_aabb(&co.pos))
                             }
@@ -422,6 +494,19 @@ impl QueryPipeline {
         self.qbvh.clear_and_rebuild(generator, self.dilation_factor);
     }
 
+    /// Update the acceleration structure on the query pipeline by copying the tree of a
+    /// [`BroadPhaseBvh`].
+    ///
+    /// This avoids rebuilding or refitting a tree when the broad-phase already maintains one.
+    /// The broad-phase must be up-to-date, so this should be called after
+    /// [`PhysicsPipeline::step`](crate::pipeline::PhysicsPipeline::step) (which should then be
+    /// given no query pipeline). Note that the AABBs of the broad-phase are larger than the
+    /// colliders’ so [`QueryPipeline::colliders_with_aabb_intersecting_aabb`] may return more
+    /// colliders. The other scene queries aren’t affected.
+    pub fn update_from_broad_phase(&mut self, broad_phase: &BroadPhaseBvh) {
+        self.qbvh.clone_from(broad_phase.qbvh());
+    }
+
     /// Find the closest intersection between a ray and a set of collider.
     ///
     /// # Parameters
@@ -449,7 +534,74 @@ impl QueryPipeline {
         self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
     }
 
-    /// Find the closest intersection between a ray and a set of collider.
+    /// Find the closest intersection between each ray of a batch and a set of collider.
+    ///
+    /// This is equivalent to calling [`QueryPipeline::cast_ray`] for each ray, and returns the
+    /// results in the same order as `rays`. If the `parallel` feature is enabled, the rays are
+    /// cast in parallel, unless the `filter` has a predicate (which isn’t required to be `Sync`).
+    ///
+    /// Note that the rays aren’t grouped into SIMD packets: each ray traverses the acceleration
+    /// structure independently, so this only saves the per-call overhead of [`QueryPipeline::cast_ray`].
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `rays`: the rays to cast.
+    /// * `max_toi`: the maximum time-of-impact that can be reported by each cast. This effectively
+    ///   limits the length of each ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for unbounded rays.
+    /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn cast_rays(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        rays: &[Ray],
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Vec<Option<(ColliderHandle, Real)>> {
+        #[cfg(feature = "parallel")]
+        if filter.predicate.is_none() {
+            use rayon::prelude::*;
+
+            let QueryFilter {
+                flags,
+                groups,
+                exclude_collider,
+                exclude_rigid_body,
+                ..
+            } = filter;
+
+            return rays
+                .par_iter()
+                .map(|ray| {
+                    let filter = QueryFilter {
+                        flags,
+                        groups,
+                        exclude_collider,
+                        exclude_rigid_body,
+                        predicate: None,
+                    };
+                    self.cast_ray(bodies, colliders, ray, max_toi, solid, filter)
+                })
+                .collect();
+        }
+
+        let pipeline_shape = self.as_composite_shape(bodies, colliders, filter);
+        rays.iter()
+            .map(|ray| {
+                let mut visitor =
+                    RayCompositeShapeToiBestFirstVisitor::new(&pipeline_shape, ray, max_toi, solid);
+                self.qbvh.traverse_best_first(&mut visitor).map(|h| h.1)
+            })
+            .collect()
+    }
+
+    /// Find the closest intersection between a ray and a set of collider, and the normal of the
+    /// collider at the hit point.
+    ///
+    /// The world-space hit point is `ray.point_at(intersection.toi)`.
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
@@ -523,6 +675,51 @@ impl QueryPipeline {
         self.qbvh.traverse_depth_first(&mut visitor);
     }
 
+    /// Find all the intersections between a ray and a set of collider, sorted by increasing
+    /// time-of-impact.
+    ///
+    /// Use [`QueryPipeline::intersections_with_ray`] instead if the order of the intersections
+    /// doesn’t matter.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `ray`: the ray to cast.
+    /// * `max_toi`: the maximum time-of-impact that can be reported by this cast. This effectively
+    ///   limits the length of the ray to `ray.dir.norm() * max_toi`. Use `Real::MAX` for an unbounded ray.
+    /// * `solid`: if this is `true` an impact at time 0.0 (i.e. at the ray origin) is returned if
+    ///   it starts inside of a shape. If this `false` then the ray will hit the shape's boundary
+    ///   even if its starts inside of it.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn cast_ray_and_get_all_hits(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        ray: &Ray,
+        max_toi: Real,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Vec<(ColliderHandle, RayIntersection)> {
+        let mut hits = vec![];
+        self.intersections_with_ray(
+            bodies,
+            colliders,
+            ray,
+            max_toi,
+            solid,
+            filter,
+            |handle, hit| {
+                hits.push((handle, hit));
+                true
+            },
+        );
+        hits.sort_by(|a, b| {
+            a.1.toi
+                .partial_cmp(&b.1.toi)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits
+    }
+
     /// Gets the handle of up to one collider intersecting the given shape.
     ///
     /// # Parameters
@@ -579,6 +776,36 @@ impl QueryPipeline {
             .map(|h| (h.1 .1, h.1 .0))
     }
 
+    /// Find the closest collider to a point, and the distance between them.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `point` - The point to compute the distance from.
+    /// * `solid` - If this is set to `true` then the collider shapes are considered to
+    ///   be plain (if the point is located inside of a plain shape, the distance is zero).
+    ///   If it is set to `false` the collider shapes are considered to be hollow (if the point
+    ///   is located inside of an hollow shape, the distance is minus the distance to the
+    ///   shape's boundary).
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn distance_to_collider(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        point: &Point<Real>,
+        solid: bool,
+        filter: QueryFilter,
+    ) -> Option<(ColliderHandle, Real)> {
+        self.project_point(bodies, colliders, point, solid, filter)
+            .map(|(handle, proj)| {
+                let dist = na::distance(point, &proj.point);
+                if !solid && proj.is_inside {
+                    (handle, -dist)
+                } else {
+                    (handle, dist)
+                }
+            })
+    }
+
     /// Find all the colliders containing the given point.
     ///
     /// # Parameters
@@ -652,7 +879,8 @@ impl QueryPipeline {
     ///
     /// This is similar to ray-casting except that we are casting a whole shape instead of just a
     /// point (the ray origin). In the resulting `TOI`, witness and normal 1 refer to the world
-    /// collider, and are in world space.
+    /// collider, and are in world space. Witness and normal 2 refer to the cast shape, and are
+    /// in its local-space.
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
@@ -660,7 +888,7 @@ impl QueryPipeline {
     /// * `shape_vel` - The constant velocity of the shape to cast (i.e. the cast direction).
     /// * `shape` - The shape to cast.
     /// * `max_toi` - The maximum time-of-impact that can be reported by this cast. This effectively
-    ///   limits the distance traveled by the shape to `shapeVel.norm() * maxToi`.
+    ///   limits the distance traveled by the shape to `shape_vel.norm() * max_toi`.
     /// * `stop_at_penetration` - If set to `false`, the linear shape-cast won’t immediately stop if
     ///   the shape is penetrating another shape at its starting point **and** its trajectory is such
     ///   that it’s on a path to exist that penetration state.
@@ -738,11 +966,12 @@ impl QueryPipeline {
     ///
     /// # Parameters
     /// * `colliders` - The set of colliders taking part in this pipeline.
-    /// * `shapePos` - The position of the shape to test.
-    /// * `shapeRot` - The orientation of the shape to test.
+    /// * `shape_pos` - The position of the shape to test.
     /// * `shape` - The shape to test.
     /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
     /// * `callback` - A function called with the handles of each collider intersecting the `shape`.
+    ///   There is no guarantees on the order the results will be yielded. If this callback
+    ///   returns `false`, this method will exit early, ignoring any further intersection.
     pub fn intersections_with_shape<'a>(
         &self,
         bodies: &RigidBodySet,
@@ -774,4 +1003,637 @@ impl QueryPipeline {
 
         self.qbvh.traverse_depth_first(&mut visitor);
     }
+
+    /// Computes a translation moving the given shape out of all the colliders it penetrates.
+    ///
+    /// The penetrations are resolved one after the other along their contact normals, and this
+    /// is repeated a few times to handle the collisions created by these corrections. This is
+    /// useful, e.g., to spawn an object at a free location close to the desired one, or to push
+    /// a kinematic controller out of the geometry it ended up inside of.
+    ///
+    /// Returns a zero vector if the shape doesn’t penetrate any collider. If the shape can’t fit
+    /// in the free space (e.g. between two walls closer than its width), the returned translation
+    /// may only reduce the penetrations.
+    ///
+    /// # Parameters
+    /// * `colliders` - The set of colliders taking part in this pipeline.
+    /// * `shape_pos` - The position of the shape to move out of the colliders.
+    /// * `shape` - The shape to move out of the colliders.
+    /// * `filter`: set of rules used to determine which collider is taken into account by this scene query.
+    pub fn depenetrate(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        shape_pos: &Isometry<Real>,
+        shape: &dyn Shape,
+        filter: QueryFilter,
+    ) -> Vector<Real> {
+        const MAX_ITERATIONS: usize = 10;
+
+        let dispatcher = &*self.query_dispatcher;
+        let mut translation = Vector::zeros();
+        // The world-space direction and depth of each penetration.
+        let mut penetrations = vec![];
+
+        for _ in 0..MAX_ITERATIONS {
+            let pos = Isometry::from_parts(
+                (shape_pos.translation.vector + translation).into(),
+                shape_pos.rotation,
+            );
+            let inv_pos = pos.inverse();
+            penetrations.clear();
+
+            let mut leaf_callback = &mut |handle: &ColliderHandle| {
+                if let Some(co) = colliders.get(*handle) {
+                    if filter.test(bodies, *handle, co) {
+                        let pos12 = inv_pos * co.pos.as_ref();
+
+                        if let Ok(Some(contact)) =
+                            dispatcher.contact(&pos12, shape, &*co.shape, 0.0)
+                        {
+                            if contact.dist < 0.0 {
+                                penetrations.push((pos * -contact.normal1, -contact.dist));
+                            }
+                        }
+                    }
+                }
+
+                true
+            };
+
+            let shape_aabb = shape.compute_aabb(&pos);
+            let mut visitor =
+                BoundingVolumeIntersectionsVisitor::new(&shape_aabb, &mut leaf_callback);
+            self.qbvh.traverse_depth_first(&mut visitor);
+
+            if penetrations.is_empty() {
+                break;
+            }
+
+            // NOTE: the penetrations are expressed at the beginning of this iteration, so we
+            //       account for the corrections of the previous penetrations.
+            let mut correction = Vector::zeros();
+            for (dir, depth) in &penetrations {
+                let remaining = depth - correction.dot(dir);
+                if remaining > 0.0 {
+                    correction += **dir * remaining;
+                }
+            }
+            translation += correction;
+        }
+
+        translation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::{RigidBodyBuilder, RigidBodySet};
+    use crate::geometry::{ColliderBuilder, ColliderSet};
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::cuboid;
+
+    #[test]
+    fn rays_can_be_cast_against_the_world() {
+        use crate::geometry::{Group, InteractionGroups};
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use parry::query::Ray;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = colliders.insert(ground.translation(Vector::y() * -0.5));
+        let ball = colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::y() * 3.0)
+                .collision_groups(InteractionGroups::new(Group::GROUP_2, Group::ALL)),
+        );
+        query_pipeline.update(&bodies, &colliders);
+
+        let ray = Ray::new(Point::from(Vector::y() * 10.0), -Vector::y());
+        let (handle, hit) = query_pipeline
+            .cast_ray_and_get_normal(
+                &bodies,
+                &colliders,
+                &ray,
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert_eq!(handle, ball);
+        assert!((hit.toi - 6.5).abs() < 1.0e-5);
+        assert!((ray.point_at(hit.toi) - Point::from(Vector::y() * 3.5)).norm() < 1.0e-5);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-5);
+
+        // Ignore the ball with collision groups.
+        let filter = QueryFilter::default().groups(InteractionGroups::new(
+            Group::ALL,
+            Group::ALL ^ Group::GROUP_2,
+        ));
+        let (handle, hit) = query_pipeline
+            .cast_ray_and_get_normal(&bodies, &colliders, &ray, Real::MAX, true, filter)
+            .unwrap();
+        assert_eq!(handle, ground);
+        assert!((hit.toi - 10.0).abs() < 1.0e-5);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-5);
+
+        // The ray is too short to hit anything.
+        let hit =
+            query_pipeline.cast_ray(&bodies, &colliders, &ray, 5.0, true, QueryFilter::default());
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn shapes_can_be_cast_against_the_world() {
+        use crate::geometry::Ball;
+        use crate::math::Isometry;
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use parry::query::TOIStatus;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        #[cfg(feature = "dim2")]
+        let wall = ColliderBuilder::cuboid(0.5, 5.0);
+        #[cfg(feature = "dim3")]
+        let wall = ColliderBuilder::cuboid(0.5, 5.0, 5.0);
+        let wall = colliders.insert(wall.translation(Vector::x() * 3.0 + Vector::y()));
+        query_pipeline.update(&bodies, &colliders);
+
+        // Sweep a ball toward the wall.
+        let shape_pos = Isometry::from(Vector::x() * -5.0 + Vector::y());
+        let shape_vel = Vector::x() * 2.0;
+        let (handle, toi) = query_pipeline
+            .cast_shape(
+                &bodies,
+                &colliders,
+                &shape_pos,
+                &shape_vel,
+                &Ball::new(0.5),
+                Real::MAX,
+                true,
+                QueryFilter::default(),
+            )
+            .unwrap();
+        assert_eq!(handle, wall);
+        assert_eq!(toi.status, TOIStatus::Converged);
+        assert!((toi.toi - 3.5).abs() < 1.0e-3);
+        // The witness and normal on the wall are in world-space.
+        let expected_witness1 = Point::from(Vector::x() * 2.5 + Vector::y());
+        assert!((toi.witness1 - expected_witness1).norm() < 1.0e-2);
+        assert!((*toi.normal1 + Vector::x()).norm() < 1.0e-3);
+        assert!((toi.witness2 - Point::from(Vector::x() * 0.5)).norm() < 1.0e-2);
+
+        // The sweep is too short to hit the wall.
+        let hit = query_pipeline.cast_shape(
+            &bodies,
+            &colliders,
+            &shape_pos,
+            &shape_vel,
+            &Ball::new(0.5),
+            3.0,
+            true,
+            QueryFilter::default(),
+        );
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn points_can_be_projected_on_the_world() {
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        let ground = cuboid(10.0, 0.5);
+        let ground = colliders.insert(ground.translation(Vector::y() * -0.5));
+        let ball = colliders
+            .insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 5.0 + Vector::y() * 3.0));
+        query_pipeline.update(&bodies, &colliders);
+
+        let filter = QueryFilter::default();
+        let above = Point::from(Vector::y() * 2.0);
+        let (handle, proj) = query_pipeline
+            .project_point(&bodies, &colliders, &above, true, filter)
+            .unwrap();
+        assert_eq!(handle, ground);
+        assert!(!proj.is_inside);
+        assert!(proj.point.coords.norm() < 1.0e-5);
+        let (handle, dist) = query_pipeline
+            .distance_to_collider(&bodies, &colliders, &above, true, filter)
+            .unwrap();
+        assert_eq!(handle, ground);
+        assert!((dist - 2.0).abs() < 1.0e-5);
+
+        let near_ball = Point::from(Vector::x() * 5.0 + Vector::y() * 4.0);
+        let (handle, dist) = query_pipeline
+            .distance_to_collider(&bodies, &colliders, &near_ball, true, filter)
+            .unwrap();
+        assert_eq!(handle, ball);
+        assert!((dist - 0.5).abs() < 1.0e-5);
+
+        // A point inside of the ground.
+        let inside = Point::from(Vector::y() * -0.2);
+        let (_, proj) = query_pipeline
+            .project_point(&bodies, &colliders, &inside, true, filter)
+            .unwrap();
+        assert!(proj.is_inside);
+        assert!((proj.point - inside).norm() < 1.0e-5);
+        let (_, dist) = query_pipeline
+            .distance_to_collider(&bodies, &colliders, &inside, true, filter)
+            .unwrap();
+        assert!(dist.abs() < 1.0e-5);
+        let (_, proj) = query_pipeline
+            .project_point(&bodies, &colliders, &inside, false, filter)
+            .unwrap();
+        assert!(proj.is_inside);
+        assert!(proj.point.coords.norm() < 1.0e-5);
+        let (_, dist) = query_pipeline
+            .distance_to_collider(&bodies, &colliders, &inside, false, filter)
+            .unwrap();
+        assert!((dist + 0.2).abs() < 1.0e-5);
+    }
+
+    #[test]
+    fn colliders_intersecting_a_shape() {
+        use crate::geometry::Ball;
+        use crate::math::Isometry;
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use std::collections::HashSet;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        // Balls at distances 0, 1, …, 5 from the origin.
+        let handles: Vec<_> = (0..6)
+            .map(|i| {
+                colliders.insert(ColliderBuilder::ball(0.25).translation(Vector::x() * i as Real))
+            })
+            .collect();
+        query_pipeline.update(&bodies, &colliders);
+
+        // An area-of-effect sphere reaching the three closest balls, excluding one of them.
+        let area = Ball::new(2.0);
+        let area_pos = Isometry::identity();
+        let mut hits = HashSet::new();
+        query_pipeline.intersections_with_shape(
+            &bodies,
+            &colliders,
+            &area_pos,
+            &area,
+            QueryFilter::default().exclude_collider(handles[1]),
+            |handle| {
+                hits.insert(handle);
+                true
+            },
+        );
+        assert_eq!(hits, [handles[0], handles[2]].into_iter().collect());
+
+        // Exit after the first intersection.
+        let mut num_hits = 0;
+        query_pipeline.intersections_with_shape(
+            &bodies,
+            &colliders,
+            &area_pos,
+            &area,
+            QueryFilter::default(),
+            |_| {
+                num_hits += 1;
+                false
+            },
+        );
+        assert_eq!(num_hits, 1);
+    }
+
+    #[test]
+    fn query_filters_exclude_colliders() {
+        use crate::geometry::{Collider, Group, InteractionGroups};
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use parry::query::Ray;
+
+        let mut bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        // A character with a capsule, looking at a sensor and two obstacles.
+        let character = bodies.insert(RigidBodyBuilder::kinematic_position_based());
+        colliders.insert_with_parent(
+            ColliderBuilder::capsule_y(0.5, 0.25),
+            character,
+            &mut bodies,
+        );
+        let sensor = colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * 2.0)
+                .sensor(true),
+        );
+        let obstacle1 = colliders.insert(
+            ColliderBuilder::ball(0.5)
+                .translation(Vector::x() * 4.0)
+                .collision_groups(InteractionGroups::new(Group::GROUP_2, Group::ALL)),
+        );
+        let obstacle2 = colliders.insert(ColliderBuilder::ball(0.5).translation(Vector::x() * 6.0));
+        query_pipeline.update(&bodies, &colliders);
+
+        let ray = Ray::new(Point::origin(), Vector::x());
+        let cast = |filter: QueryFilter| {
+            query_pipeline
+                .cast_ray(&bodies, &colliders, &ray, Real::MAX, true, filter)
+                .map(|hit| hit.0)
+        };
+
+        let filter = QueryFilter::default().exclude_rigid_body(character);
+        assert_eq!(cast(filter), Some(sensor));
+        let filter = filter.exclude_sensors();
+        assert_eq!(cast(filter), Some(obstacle1));
+        assert_eq!(cast(filter.exclude_collider(obstacle1)), Some(obstacle2));
+        let groups = InteractionGroups::new(Group::ALL, Group::ALL ^ Group::GROUP_2);
+        assert_eq!(cast(filter.groups(groups)), Some(obstacle2));
+        let predicate = |handle, _: &Collider| handle != obstacle2;
+        assert_eq!(
+            cast(filter.exclude_collider(obstacle1).predicate(&predicate)),
+            None
+        );
+        // Without exclusions, the ray starts inside of the character’s capsule.
+        assert_ne!(cast(QueryFilter::default()), Some(sensor));
+        assert_eq!(cast(QueryFilter::only_fixed()), Some(sensor));
+        assert_eq!(cast(QueryFilter::only_dynamic()), None);
+    }
+
+    #[test]
+    fn rays_can_be_cast_in_batches() {
+        use crate::geometry::Collider;
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use parry::query::Ray;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        for i in 0..10 {
+            colliders.insert(
+                ColliderBuilder::ball(0.5).translation(Vector::x() * 5.0 + Vector::y() * i as Real),
+            );
+        }
+        query_pipeline.update(&bodies, &colliders);
+
+        // A fan of rays, some of them missing every collider.
+        let rays: Vec<_> = (0..100)
+            .map(|i| {
+                let angle = i as Real * 0.02;
+                Ray::new(
+                    Point::origin(),
+                    Vector::x() * angle.cos() + Vector::y() * angle.sin(),
+                )
+            })
+            .collect();
+        let num_hits = |filter: QueryFilter| {
+            let hits =
+                query_pipeline.cast_rays(&bodies, &colliders, &rays, Real::MAX, true, filter);
+            assert_eq!(hits.len(), rays.len());
+
+            for (ray, hit) in rays.iter().zip(hits.iter()) {
+                let expected =
+                    query_pipeline.cast_ray(&bodies, &colliders, ray, Real::MAX, true, filter);
+                assert_eq!(*hit, expected);
+            }
+
+            hits.iter().filter(|hit| hit.is_some()).count()
+        };
+
+        let all_hits = num_hits(QueryFilter::default());
+        assert!(all_hits > 0 && all_hits < rays.len());
+        let predicate = |_, co: &Collider| co.translation().y > 0.0;
+        assert!(num_hits(QueryFilter::default().predicate(&predicate)) < all_hits);
+    }
+
+    #[test]
+    fn all_ray_hits_are_sorted() {
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+        use parry::query::Ray;
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        let mut query_pipeline = QueryPipeline::new();
+
+        // Insert the targets in a shuffled order.
+        let handles: Vec<_> = [3, 0, 4, 1, 2]
+            .iter()
+            .map(|i| {
+                colliders.insert(
+                    ColliderBuilder::ball(0.5).translation(Vector::x() * (*i as Real * 2.0 + 2.0)),
+                )
+            })
+            .collect();
+        query_pipeline.update(&bodies, &colliders);
+
+        let ray = Ray::new(Point::origin(), Vector::x());
+        let hits = query_pipeline.cast_ray_and_get_all_hits(
+            &bodies,
+            &colliders,
+            &ray,
+            7.0,
+            true,
+            QueryFilter::default().exclude_collider(handles[3]),
+        );
+
+        // The targets at x = 2 and x = 6 are hit, but the one at x = 4 is excluded, and the
+        // others are too far.
+        let expected = [(handles[1], 1.5), (handles[4], 5.5)];
+        assert_eq!(hits.len(), expected.len());
+        for (hit, (handle, toi)) in hits.iter().zip(expected.iter()) {
+            assert_eq!(hit.0, *handle);
+            assert!((hit.1.toi - toi).abs() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn query_pipeline_is_updated_incrementally() {
+        use crate::pipeline::QueryFilter;
+        use parry::query::Ray;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.gravity = Vector::zeros();
+
+        // A lot of static colliders, and a single moving one.
+        let statics: Vec<_> = (0..1000)
+            .map(|i| {
+                state.colliders.insert(
+                    ColliderBuilder::ball(0.25).translation(
+                        Vector::x() * (i % 100) as Real + Vector::y() * (i / 100) as Real,
+                    ),
+                )
+            })
+            .collect();
+        let body = state.bodies.insert(
+            RigidBodyBuilder::kinematic_velocity_based()
+                .translation(Vector::y() * -5.0)
+                .linvel(Vector::x() * 10.0),
+        );
+        let moving =
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+
+        let cast = |state: &PhysicsState, x: Real| {
+            let ray = Ray::new(
+                Point::from(Vector::x() * x + Vector::y() * -10.0),
+                Vector::y(),
+            );
+            state
+                .query_pipeline
+                .cast_ray(
+                    &state.bodies,
+                    &state.colliders,
+                    &ray,
+                    Real::MAX,
+                    true,
+                    QueryFilter::default(),
+                )
+                .map(|hit| hit.0)
+        };
+
+        for i in 0..60 {
+            state.step(&mut pipeline, &(), &());
+
+            let x = state.bodies[body].translation().x;
+            assert!((x - (i + 1) as Real * state.integration_parameters.dt * 10.0).abs() < 1.0e-4);
+            assert_eq!(cast(&state, x), Some(moving));
+
+            // The positions the moving collider left are empty again.
+            if x >= 1.0 {
+                let prev_x = (x - 1.0).floor();
+                assert_eq!(cast(&state, prev_x), Some(statics[prev_x as usize]));
+            }
+        }
+    }
+
+    #[test]
+    fn colliders_inserted_after_rebalancing_remain_queryable() {
+        use crate::geometry::{Aabb, BroadPhase, BroadPhaseBvh};
+        use crate::math::Real;
+        use crate::pipeline::PhysicsState;
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        let mut broad_phase = BroadPhaseBvh::new();
+
+        let ground = cuboid(20.0, 0.5);
+        state.colliders.insert(ground);
+
+        // Insert the colliders one by one, and remove some of them, so they are inserted into
+        // trees that were already rebalanced.
+        let mut handles = vec![];
+        for i in 0..30 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * (i % 4) as Real + Vector::y() * (1.0 + i as Real))
+                .build();
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+            handles.push(body);
+
+            if i % 3 == 2 {
+                let removed = handles.remove(i % handles.len());
+                state.bodies.remove(
+                    removed,
+                    &mut state.islands,
+                    &mut state.colliders,
+                    &mut state.impulse_joints,
+                    &mut state.multibody_joints,
+                    true,
+                );
+            }
+
+            pipeline.step(
+                &state.gravity,
+                &state.integration_parameters,
+                &mut state.islands,
+                &mut broad_phase,
+                &mut state.narrow_phase,
+                &mut state.bodies,
+                &mut state.colliders,
+                &mut state.impulse_joints,
+                &mut state.multibody_joints,
+                &mut state.ccd_solver,
+                Some(&mut state.query_pipeline),
+                &(),
+                &(),
+            );
+        }
+
+        let aabb = Aabb::new(
+            Point::origin() - Vector::repeat(1000.0),
+            Point::origin() + Vector::repeat(1000.0),
+        );
+        let mut found = 0;
+        state
+            .query_pipeline
+            .colliders_with_aabb_intersecting_aabb(&aabb, |_| {
+                found += 1;
+                true
+            });
+        assert_eq!(found, handles.len() + 1);
+
+        let mut found = 0;
+        broad_phase.colliders_in_aabb(&aabb, &mut |_| {
+            found += 1;
+            true
+        });
+        assert_eq!(found, handles.len() + 1);
+    }
+
+    #[test]
+    fn shapes_are_depenetrated_from_all_the_colliders() {
+        use crate::geometry::Ball;
+        use crate::math::Isometry;
+        use crate::pipeline::{QueryFilter, QueryPipeline};
+
+        let bodies = RigidBodySet::new();
+        let mut colliders = ColliderSet::new();
+        // A floor with its top at `y = 0`, and a wall with its right side at `x = 0`.
+        #[cfg(feature = "dim2")]
+        let (floor, wall) = (
+            ColliderBuilder::cuboid(10.0, 1.0),
+            ColliderBuilder::cuboid(1.0, 10.0),
+        );
+        #[cfg(feature = "dim3")]
+        let (floor, wall) = (
+            ColliderBuilder::cuboid(10.0, 1.0, 10.0),
+            ColliderBuilder::cuboid(1.0, 10.0, 10.0),
+        );
+        colliders.insert(floor.translation(Vector::y() * -1.0));
+        colliders.insert(wall.translation(Vector::x() * -1.0));
+
+        let mut query_pipeline = QueryPipeline::new();
+        query_pipeline.update(&bodies, &colliders);
+
+        let ball = Ball::new(0.5);
+        let depenetrate = |x, y| {
+            let pos = Isometry::translation(
+                x,
+                y,
+                #[cfg(feature = "dim3")]
+                0.0,
+            );
+            query_pipeline.depenetrate(&bodies, &colliders, &pos, &ball, QueryFilter::default())
+        };
+
+        assert_eq!(depenetrate(2.0, 1.0), Vector::zeros());
+
+        let translation = depenetrate(2.0, 0.3);
+        assert!((translation - Vector::y() * 0.2).norm() < 1.0e-4);
+
+        let translation = depenetrate(0.2, 0.3);
+        assert!((translation - (Vector::x() * 0.3 + Vector::y() * 0.2)).norm() < 1.0e-4);
+    }
 }
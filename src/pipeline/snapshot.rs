@@ -0,0 +1,847 @@
+use crate::dynamics::{
+    ImpulseJointSet, IslandManager, RigidBodyHandle, RigidBodyIds, RigidBodySet, RigidBodyVelocity,
+};
+use crate::geometry::{ColliderHandle, ColliderSet, Contact, ContactData, NarrowPhase};
+use crate::math::{Isometry, Real, Rotation, Translation, Vector, ANG_DIM, DIM, SPATIAL_DIM};
+use std::collections::HashMap;
+
+#[cfg(feature = "dim2")]
+const ROT_DIM: usize = 2;
+#[cfg(feature = "dim3")]
+const ROT_DIM: usize = 4;
+#[cfg(feature = "dim2")]
+const TANGENT_DIM: usize = 1;
+#[cfg(feature = "dim3")]
+const TANGENT_DIM: usize = 2;
+
+const U32_SIZE: usize = std::mem::size_of::<u32>();
+const REAL_SIZE: usize = std::mem::size_of::<Real>();
+// Handle, position, velocities, activation, and ids.
+const BODY_SIZE: usize =
+    U32_SIZE * 2 + REAL_SIZE * (DIM + ROT_DIM + DIM + ANG_DIM + 1) + 1 + U32_SIZE * 4;
+// Handle, and impulses of the locked axes, limits, and motors.
+const JOINT_SIZE: usize = U32_SIZE * 2 + REAL_SIZE * SPATIAL_DIM * 3;
+// Feature ids, and normal and tangent impulses.
+const CONTACT_SIZE: usize = U32_SIZE * 2 + REAL_SIZE * (1 + TANGENT_DIM);
+
+/// A compact binary snapshot of the dynamic state of a physics world.
+///
+/// Contrary to serializing the whole world (see [`PhysicsState`](crate::pipeline::PhysicsState)),
+/// this only stores the state that changes at each timestep: the poses, velocities and sleep
+/// state of the rigid-bodies, the awake islands, and the impulses accumulated by the contacts and
+/// impulse joints. This makes saving and restoring it fast enough to be done at every frame, e.g.,
+/// for rollback networking or replays.
+///
+/// A snapshot can only be restored into the world it was saved from, as long as no rigid-body or
+/// impulse joint was added or removed since then. The following are not part of the snapshot:
+/// - Any other property of the rigid-bodies, colliders and joints (including user-applied forces,
+///   and the targets of kinematic bodies), which are expected to be set again by the user.
+/// - The state of the multibody joints.
+/// - The broad-phase and the contact geometry of the narrow-phase: these are recomputed from the
+///   restored poses at the next timestep. The contact impulses are restored for the contact
+///   pairs that still exist, and the contact points that match the ones saved.
+///
+/// Because of the last point, resuming the simulation after a restore is bit-identical to the
+/// original simulation only if the set of contacts didn't change since the snapshot was saved.
+/// Deserialize a [`PhysicsState`](crate::pipeline::PhysicsState) for a bit-identical restore in
+/// every case.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DynamicsSnapshot {
+    data: Vec<u8>,
+}
+
+impl DynamicsSnapshot {
+    /// Creates an empty snapshot.
+    pub fn new() -> Self {
+        Self { data: vec![] }
+    }
+
+    /// Creates a snapshot from bytes previously obtained with [`DynamicsSnapshot::as_bytes`].
+    pub fn from_bytes(data: Vec<u8>) -> Self {
+        Self { data }
+    }
+
+    /// The binary encoding of this snapshot.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Converts this snapshot into its binary encoding.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Saves the dynamic state of the given physics world into this snapshot.
+    ///
+    /// This overwrites the previous content of this snapshot while reusing its allocation.
+    pub fn save(
+        &mut self,
+        islands: &IslandManager,
+        bodies: &RigidBodySet,
+        impulse_joints: &ImpulseJointSet,
+        narrow_phase: &NarrowPhase,
+    ) {
+        self.data.clear();
+        let data = &mut self.data;
+
+        // Islands.
+        write_u32(data, islands.active_set_timestamp);
+        for set in [&islands.active_dynamic_set, &islands.active_kinematic_set] {
+            write_u32(data, set.len() as u32);
+            for handle in set.iter() {
+                write_u32(data, handle.into_raw_parts().0);
+            }
+        }
+        write_u32(data, islands.active_islands.len() as u32);
+        for island in &islands.active_islands {
+            write_u32(data, *island as u32);
+        }
+
+        // Rigid-bodies.
+        write_u32(data, bodies.len() as u32);
+        data.reserve(bodies.len() * BODY_SIZE);
+        for (handle, rb) in bodies.iter() {
+            let (id, generation) = handle.into_raw_parts();
+            write_u32(data, id);
+            write_u32(data, generation);
+            write_reals(data, rb.pos.position.translation.vector.as_slice());
+            #[cfg(feature = "dim2")]
+            write_reals(
+                data,
+                &[rb.pos.position.rotation.re, rb.pos.position.rotation.im],
+            );
+            #[cfg(feature = "dim3")]
+            write_reals(data, rb.pos.position.rotation.coords.as_slice());
+            write_reals(data, rb.vels.linvel.as_slice());
+            #[cfg(feature = "dim2")]
+            write_reals(data, &[rb.vels.angvel]);
+            #[cfg(feature = "dim3")]
+            write_reals(data, rb.vels.angvel.as_slice());
+            write_reals(data, &[rb.activation.time_since_can_sleep]);
+            data.push(rb.activation.sleeping as u8);
+            write_u32(data, rb.ids.active_island_id as u32);
+            write_u32(data, rb.ids.active_set_id as u32);
+            write_u32(data, rb.ids.active_set_offset as u32);
+            write_u32(data, rb.ids.active_set_timestamp);
+        }
+
+        // Impulse joints.
+        write_u32(data, impulse_joints.len() as u32);
+        for (handle, joint) in impulse_joints.iter() {
+            let (id, generation) = handle.into_raw_parts();
+            write_u32(data, id);
+            write_u32(data, generation);
+            write_reals(data, joint.impulses.as_slice());
+            for limit in &joint.data.limits {
+                write_reals(data, &[limit.impulse]);
+            }
+            for motor in &joint.data.motors {
+                write_reals(data, &[motor.impulse]);
+            }
+        }
+
+        // Contact impulses.
+        let num_pairs_offset = data.len();
+        let mut num_pairs = 0;
+        write_u32(data, 0);
+        for pair in narrow_phase.contact_pairs() {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+
+            num_pairs += 1;
+            write_handle(data, pair.collider1);
+            write_handle(data, pair.collider2);
+            write_u32(data, pair.manifolds.len() as u32);
+            for manifold in &pair.manifolds {
+                write_u32(data, manifold.points.len() as u32);
+                for pt in &manifold.points {
+                    write_u32(data, pt.fid1.0);
+                    write_u32(data, pt.fid2.0);
+                    write_reals(data, &[pt.data.impulse]);
+                    #[cfg(feature = "dim2")]
+                    write_reals(data, &[pt.data.tangent_impulse]);
+                    #[cfg(feature = "dim3")]
+                    write_reals(data, pt.data.tangent_impulse.as_slice());
+                }
+            }
+        }
+        data[num_pairs_offset..num_pairs_offset + U32_SIZE]
+            .copy_from_slice(&(num_pairs as u32).to_le_bytes());
+    }
+
+    /// Restores the dynamic state saved in this snapshot into the given physics world.
+    ///
+    /// Returns `false`, without modifying the world, if this snapshot is malformed (including
+    /// inconsistent island indices), or if the rigid-bodies or impulse joints of the world don’t
+    /// match the ones this snapshot was saved from.
+    pub fn restore(
+        &self,
+        islands: &mut IslandManager,
+        bodies: &mut RigidBodySet,
+        colliders: &mut ColliderSet,
+        impulse_joints: &mut ImpulseJointSet,
+        narrow_phase: &mut NarrowPhase,
+    ) -> bool {
+        if !self.is_compatible_with(bodies, impulse_joints) {
+            return false;
+        }
+
+        // NOTE: from this point, the validity of the data has been checked so the reads can’t
+        //       fail.
+        let mut reader = SnapshotReader { data: &self.data };
+
+        // Islands.
+        islands.active_set_timestamp = reader.read_u32().unwrap();
+        for set in [
+            &mut islands.active_dynamic_set,
+            &mut islands.active_kinematic_set,
+        ] {
+            set.clear();
+            let len = reader.read_u32().unwrap();
+            for _ in 0..len {
+                let id = reader.read_u32().unwrap();
+                set.push(bodies.get_unknown_gen(id).unwrap().1);
+            }
+        }
+        let len = reader.read_u32().unwrap();
+        islands.active_islands.clear();
+        for _ in 0..len {
+            islands
+                .active_islands
+                .push(reader.read_u32().unwrap() as usize);
+        }
+
+        // Rigid-bodies.
+        let num_bodies = reader.read_u32().unwrap();
+        let mut modified_colliders = std::mem::take(&mut colliders.modified_colliders);
+        for _ in 0..num_bodies {
+            let handle = RigidBodyHandle::from_raw_parts(
+                reader.read_u32().unwrap(),
+                reader.read_u32().unwrap(),
+            );
+            let translation = Translation::from(Vector::from_column_slice(
+                &reader.read_reals::<DIM>().unwrap(),
+            ));
+            #[cfg(feature = "dim2")]
+            let rotation = {
+                let [re, im] = reader.read_reals::<ROT_DIM>().unwrap();
+                Rotation::new_unchecked(na::Complex { re, im })
+            };
+            #[cfg(feature = "dim3")]
+            let rotation = Rotation::new_unchecked(na::Quaternion::from(na::Vector4::from(
+                reader.read_reals::<ROT_DIM>().unwrap(),
+            )));
+            let linvel = Vector::from_column_slice(&reader.read_reals::<DIM>().unwrap());
+            #[cfg(feature = "dim2")]
+            let angvel = reader.read_reals::<ANG_DIM>().unwrap()[0];
+            #[cfg(feature = "dim3")]
+            let angvel = Vector::from(reader.read_reals::<ANG_DIM>().unwrap());
+            let time_since_can_sleep = reader.read_reals::<1>().unwrap()[0];
+            let sleeping = reader.read_u8().unwrap() != 0;
+            let ids = RigidBodyIds {
+                active_island_id: reader.read_u32().unwrap() as usize,
+                active_set_id: reader.read_u32().unwrap() as usize,
+                active_set_offset: reader.read_u32().unwrap() as usize,
+                active_set_timestamp: reader.read_u32().unwrap(),
+            };
+
+            let rb = bodies.index_mut_internal(handle);
+            let position = Isometry::from_parts(translation, rotation);
+
+            if rb.pos.position != position || rb.pos.next_position != position {
+                rb.pos.position = position;
+                rb.pos.next_position = position;
+                rb.update_world_mass_properties();
+                rb.colliders
+                    .update_positions(colliders, &mut modified_colliders, &position);
+            }
+
+            rb.vels = RigidBodyVelocity { linvel, angvel };
+            rb.activation.time_since_can_sleep = time_since_can_sleep;
+            rb.activation.sleeping = sleeping;
+            rb.ids = ids;
+        }
+        colliders.modified_colliders = modified_colliders;
+
+        // Impulse joints.
+        let _ = reader.read_u32();
+        for (_, joint) in impulse_joints.iter_mut() {
+            let _ = reader.read_u32();
+            let _ = reader.read_u32();
+            joint.impulses = reader.read_reals::<SPATIAL_DIM>().unwrap().into();
+            let limit_impulses = reader.read_reals::<SPATIAL_DIM>().unwrap();
+            for (limit, impulse) in joint.data.limits.iter_mut().zip(limit_impulses) {
+                limit.impulse = impulse;
+            }
+            let motor_impulses = reader.read_reals::<SPATIAL_DIM>().unwrap();
+            for (motor, impulse) in joint.data.motors.iter_mut().zip(motor_impulses) {
+                motor.impulse = impulse;
+            }
+        }
+
+        // Contact impulses.
+        restore_contact_impulses(&mut reader, narrow_phase).unwrap();
+
+        true
+    }
+
+    /// Checks that all the sections of this snapshot are well-formed, and that its rigid-bodies
+    /// and impulse joints match the given ones.
+    ///
+    /// This also checks that the island indices stored in the rigid-bodies are consistent with the
+    /// saved islands, so that restoring a corrupted snapshot can’t result in out-of-bounds
+    /// accesses in the island manager or the constraints solver.
+    fn is_compatible_with(&self, bodies: &RigidBodySet, impulse_joints: &ImpulseJointSet) -> bool {
+        let mut reader = SnapshotReader { data: &self.data };
+
+        let mut check = || -> Option<bool> {
+            // Islands.
+            let active_set_timestamp = reader.read_u32()?;
+            // The index, in the active dynamic and kinematic sets, of the bodies they contain.
+            let mut active_set_ids = [HashMap::new(), HashMap::new()];
+            for set_ids in &mut active_set_ids {
+                let len = reader.read_u32()?;
+                for i in 0..len as usize {
+                    let id = reader.read_u32()?;
+                    if bodies.get_unknown_gen(id).is_none() || set_ids.insert(id, i).is_some() {
+                        return Some(false);
+                    }
+                }
+            }
+            let len = reader.read_u32()?;
+            let mut active_islands = vec![];
+            for _ in 0..len {
+                active_islands.push(reader.read_u32()? as usize);
+            }
+            if active_islands.first().is_some_and(|start| *start != 0)
+                || active_islands.windows(2).any(|range| range[0] > range[1])
+            {
+                return Some(false);
+            }
+            // The islands must cover a prefix of the active dynamic set. They are empty only if
+            // they were never computed, i.e., before the first timestep.
+            let num_active_dynamic = active_set_ids[0].len();
+            let are_islands_in_bounds = match active_islands.last() {
+                Some(end) => active_set_timestamp != 0 && *end <= num_active_dynamic,
+                None => active_set_timestamp == 0 || num_active_dynamic == 0,
+            };
+            if !are_islands_in_bounds {
+                return Some(false);
+            }
+
+            // Rigid-bodies.
+            if reader.read_u32()? as usize != bodies.len() {
+                return Some(false);
+            }
+            for (handle, _) in bodies.iter() {
+                let saved = (reader.read_u32()?, reader.read_u32()?);
+                if saved != handle.into_raw_parts() {
+                    return Some(false);
+                }
+                reader.skip(BODY_SIZE - U32_SIZE * 6)?;
+                let active_island_id = reader.read_u32()? as usize;
+                let active_set_id = reader.read_u32()? as usize;
+                let active_set_offset = reader.read_u32()? as usize;
+                let timestamp = reader.read_u32()?;
+
+                // The bodies of the active sets must know their index in these sets.
+                if active_set_ids
+                    .iter()
+                    .any(|set_ids| set_ids.get(&saved.0).is_some_and(|i| *i != active_set_id))
+                {
+                    return Some(false);
+                }
+
+                // The bodies visited during the last islands computation must belong to one of
+                // the saved islands.
+                if active_set_timestamp != 0 && timestamp == active_set_timestamp {
+                    let is_in_island = active_island_id + 1 < active_islands.len()
+                        && active_set_offset
+                            < active_islands[active_island_id + 1]
+                                - active_islands[active_island_id];
+                    if !is_in_island {
+                        return Some(false);
+                    }
+                }
+            }
+
+            // Impulse joints.
+            if reader.read_u32()? as usize != impulse_joints.len() {
+                return Some(false);
+            }
+            for (handle, _) in impulse_joints.iter() {
+                let saved = (reader.read_u32()?, reader.read_u32()?);
+                if saved != handle.into_raw_parts() {
+                    return Some(false);
+                }
+                reader.skip(JOINT_SIZE - U32_SIZE * 2)?;
+            }
+
+            // Contact impulses. The contact pairs don’t need to exist anymore.
+            let num_pairs = reader.read_u32()?;
+            for _ in 0..num_pairs {
+                reader.skip(U32_SIZE * 4)?;
+                let num_manifolds = reader.read_u32()?;
+                for _ in 0..num_manifolds {
+                    let num_points = reader.read_u32()? as usize;
+                    reader.skip(num_points.checked_mul(CONTACT_SIZE)?)?;
+                }
+            }
+
+            Some(reader.data.is_empty())
+        };
+
+        check() == Some(true)
+    }
+}
+
+fn restore_contact_impulses(
+    reader: &mut SnapshotReader,
+    narrow_phase: &mut NarrowPhase,
+) -> Option<()> {
+    // The impulses of the contact points that weren’t saved must not be used for warm-starting.
+    for pair in narrow_phase.contact_pairs_mut() {
+        for manifold in &mut pair.manifolds {
+            for pt in &mut manifold.points {
+                pt.data = ContactData::default();
+            }
+        }
+    }
+
+    let num_pairs = reader.read_u32()?;
+    for _ in 0..num_pairs {
+        let collider1 = read_handle(reader)?;
+        let collider2 = read_handle(reader)?;
+        let mut pair = narrow_phase
+            .contact_pair_mut(collider1, collider2)
+            .filter(|pair| pair.collider1 == collider1);
+        let num_manifolds = reader.read_u32()? as usize;
+
+        for i in 0..num_manifolds {
+            let mut manifold = pair.as_mut().and_then(|pair| pair.manifolds.get_mut(i));
+            let num_points = reader.read_u32()?;
+
+            for k in 0..num_points as usize {
+                let fid1 = reader.read_u32()?;
+                let fid2 = reader.read_u32()?;
+                let impulse = reader.read_reals::<1>()?[0];
+                let tangent_impulse = reader.read_reals::<TANGENT_DIM>()?;
+
+                // Contact points are matched by feature ids, like the narrow-phase does when
+                // updating contact manifolds. Several points can share the same feature ids
+                // (e.g. the two points of a 2D face-face contact), so the point at the same
+                // index is preferred.
+                if let Some(pt) = manifold.as_mut().and_then(|manifold| {
+                    let matches = |pt: &Contact| pt.fid1.0 == fid1 && pt.fid2.0 == fid2;
+                    if manifold.points.get(k).map(matches) == Some(true) {
+                        manifold.points.get_mut(k)
+                    } else {
+                        manifold.points.iter_mut().find(|pt| matches(pt))
+                    }
+                }) {
+                    pt.data.impulse = impulse;
+                    #[cfg(feature = "dim2")]
+                    {
+                        pt.data.tangent_impulse = tangent_impulse[0];
+                    }
+                    #[cfg(feature = "dim3")]
+                    {
+                        pt.data.tangent_impulse = tangent_impulse.into();
+                    }
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn write_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_reals(data: &mut Vec<u8>, values: &[Real]) {
+    for value in values {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn write_handle(data: &mut Vec<u8>, handle: ColliderHandle) {
+    let (id, generation) = handle.into_raw_parts();
+    write_u32(data, id);
+    write_u32(data, generation);
+}
+
+fn read_handle(reader: &mut SnapshotReader) -> Option<ColliderHandle> {
+    Some(ColliderHandle::from_raw_parts(
+        reader.read_u32()?,
+        reader.read_u32()?,
+    ))
+}
+
+struct SnapshotReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SnapshotReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    fn read_u8(&mut self) -> Option<u8> {
+        Some(self.read_bytes(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(
+            self.read_bytes(U32_SIZE)?.try_into().ok()?,
+        ))
+    }
+
+    fn read_reals<const N: usize>(&mut self) -> Option<[Real; N]> {
+        let mut result = [0.0; N];
+        for value in &mut result {
+            *value = Real::from_le_bytes(self.read_bytes(REAL_SIZE)?.try_into().ok()?);
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DynamicsSnapshot, BODY_SIZE, CONTACT_SIZE, U32_SIZE};
+    use crate::dynamics::{RevoluteJointBuilder, RigidBodyBuilder};
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Point, Real, Vector};
+    use crate::pipeline::{PhysicsPipeline, PhysicsState};
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn snapshots_with_inconsistent_island_indices_are_rejected() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        for i in 0..3 {
+            let body = RigidBodyBuilder::dynamic().translation(Vector::x() * 2.0 * i as Real);
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        }
+
+        for _ in 0..10 {
+            state.step(&mut pipeline, &(), &());
+        }
+
+        let mut snapshot = DynamicsSnapshot::new();
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+        let mut restore = |bytes: Vec<u8>| {
+            DynamicsSnapshot::from_bytes(bytes).restore(
+                &mut state.islands,
+                &mut state.bodies,
+                &mut state.colliders,
+                &mut state.impulse_joints,
+                &mut state.narrow_phase,
+            )
+        };
+        assert!(restore(snapshot.as_bytes().to_vec()));
+
+        let islands_start =
+            U32_SIZE * 3 + U32_SIZE * (active_set_len(&snapshot, 1) + active_set_len(&snapshot, 2));
+        let num_islands = read_u32(snapshot.as_bytes(), islands_start) as usize;
+        let bodies_start = islands_start + U32_SIZE * (num_islands + 2);
+
+        // Islands that don’t start at the beginning of the active set.
+        let mut bytes = snapshot.as_bytes().to_vec();
+        write_u32(&mut bytes, islands_start + U32_SIZE, 1);
+        assert!(!restore(bytes));
+
+        // A body with an offset outside of its island.
+        let mut bytes = snapshot.as_bytes().to_vec();
+        write_u32(&mut bytes, bodies_start + BODY_SIZE - U32_SIZE * 2, 1000);
+        assert!(!restore(bytes));
+
+        // A body that doesn’t know its index in the active set.
+        let mut bytes = snapshot.as_bytes().to_vec();
+        write_u32(&mut bytes, bodies_start + BODY_SIZE - U32_SIZE * 3, 1000);
+        assert!(!restore(bytes));
+    }
+
+    #[test]
+    fn snapshots_restore_the_impulses_of_joint_limits_and_motors() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        let anchor = state.bodies.insert(RigidBodyBuilder::fixed());
+        let pendulum = state
+            .bodies
+            .insert(RigidBodyBuilder::dynamic().translation(Vector::x() * 2.0));
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), pendulum, &mut state.bodies);
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint
+            .local_anchor2(Point::origin() - Vector::x() * 2.0)
+            .motor_velocity(-1.0, 0.5)
+            .limits([-0.5, 0.5]);
+        let joint = state.impulse_joints.insert(anchor, pendulum, joint, true);
+
+        let impulses = |state: &PhysicsState| {
+            let data = &state.impulse_joints.get(joint).unwrap().data;
+            (
+                data.limits.map(|limit| limit.impulse),
+                data.motors.map(|motor| motor.impulse),
+            )
+        };
+
+        for _ in 0..20 {
+            state.step(&mut pipeline, &(), &());
+        }
+
+        let mut snapshot = DynamicsSnapshot::new();
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+        let saved = impulses(&state);
+
+        for _ in 0..20 {
+            state.step(&mut pipeline, &(), &());
+        }
+        assert_ne!(impulses(&state), saved);
+
+        assert!(snapshot.restore(
+            &mut state.islands,
+            &mut state.bodies,
+            &mut state.colliders,
+            &mut state.impulse_joints,
+            &mut state.narrow_phase,
+        ));
+        assert_eq!(impulses(&state), saved);
+    }
+
+    #[test]
+    fn snapshots_with_out_of_bounds_islands_are_rejected() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        for i in 0..3 {
+            let body = RigidBodyBuilder::dynamic().translation(Vector::x() * 2.0 * i as Real);
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        }
+
+        // Snapshots saved before the first timestep have no islands.
+        let mut snapshot = DynamicsSnapshot::new();
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+        let restore = |bytes: Vec<u8>, state: &mut PhysicsState| {
+            DynamicsSnapshot::from_bytes(bytes).restore(
+                &mut state.islands,
+                &mut state.bodies,
+                &mut state.colliders,
+                &mut state.impulse_joints,
+                &mut state.narrow_phase,
+            )
+        };
+        assert!(restore(snapshot.as_bytes().to_vec(), &mut state));
+
+        state.step(&mut pipeline, &(), &());
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+        assert!(restore(snapshot.as_bytes().to_vec(), &mut state));
+
+        let islands_start =
+            U32_SIZE * 3 + U32_SIZE * (active_set_len(&snapshot, 1) + active_set_len(&snapshot, 2));
+        let num_islands = read_u32(snapshot.as_bytes(), islands_start) as usize;
+        let last_island_end = islands_start + U32_SIZE * num_islands;
+
+        // Islands ending after the active set.
+        let mut bytes = snapshot.as_bytes().to_vec();
+        write_u32(&mut bytes, last_island_end, 1000);
+        assert!(!restore(bytes, &mut state));
+
+        // The same, with a timestamp of zero for which the islands membership isn’t checked.
+        let mut bytes = snapshot.as_bytes().to_vec();
+        write_u32(&mut bytes, 0, 0);
+        write_u32(&mut bytes, last_island_end, 1000);
+        assert!(!restore(bytes, &mut state));
+
+        // The snapshot is still valid and the islands can be iterated.
+        assert!(restore(snapshot.as_bytes().to_vec(), &mut state));
+        assert_eq!(state.islands.active_islands().flatten().count(), 3);
+    }
+
+    #[test]
+    fn truncated_snapshots_are_rejected_without_modifying_the_world() {
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+        state.colliders.insert(cuboid(20.0, 0.5));
+        for i in 0..3 {
+            let body = RigidBodyBuilder::dynamic().translation(Vector::y() * (1.0 + i as Real));
+            let body = state.bodies.insert(body);
+            state
+                .colliders
+                .insert_with_parent(cuboid(0.5, 0.5), body, &mut state.bodies);
+        }
+
+        step_n(&mut state, &mut pipeline, 30);
+        let mut snapshot = DynamicsSnapshot::new();
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+
+        step_n(&mut state, &mut pipeline, 30);
+        let checksum = state.checksum();
+
+        // Truncate the snapshot in the middle of its last contact point.
+        let mut bytes = snapshot.into_bytes();
+        bytes.truncate(bytes.len() - CONTACT_SIZE / 2);
+        assert!(!DynamicsSnapshot::from_bytes(bytes).restore(
+            &mut state.islands,
+            &mut state.bodies,
+            &mut state.colliders,
+            &mut state.impulse_joints,
+            &mut state.narrow_phase,
+        ));
+        assert_eq!(state.checksum(), checksum);
+    }
+
+    // The length of the `i`-th active set (1 for the dynamic set, 2 for the kinematic set).
+    fn active_set_len(snapshot: &DynamicsSnapshot, i: usize) -> usize {
+        let bytes = snapshot.as_bytes();
+        let dynamic_len = read_u32(bytes, U32_SIZE) as usize;
+        match i {
+            1 => dynamic_len,
+            _ => read_u32(bytes, U32_SIZE * (2 + dynamic_len)) as usize,
+        }
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + U32_SIZE].try_into().unwrap())
+    }
+
+    fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+        bytes[offset..offset + U32_SIZE].copy_from_slice(&value.to_le_bytes());
+    }
+
+    #[test]
+    fn restored_snapshot_resumes_the_simulation() {
+        use crate::dynamics::RevoluteJointBuilder;
+        use crate::math::{Isometry, Point, Real};
+        use crate::pipeline::{DynamicsSnapshot, PhysicsState};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let ground = cuboid(20.0, 0.5);
+        state.colliders.insert(ground);
+
+        // A stack of cubes resting on the ground.
+        let mut handles = vec![];
+        for i in 0..4 {
+            let body = RigidBodyBuilder::dynamic()
+                .translation(Vector::y() * (1.0 + i as Real))
+                .build();
+            let body = state.bodies.insert(body);
+            let cube = cuboid(0.5, 0.5);
+            state
+                .colliders
+                .insert_with_parent(cube, body, &mut state.bodies);
+            handles.push(body);
+        }
+
+        // A pendulum.
+        let anchor = state.bodies.insert(
+            RigidBodyBuilder::fixed()
+                .translation(Vector::x() * 5.0 + Vector::y() * 5.0)
+                .build(),
+        );
+        let pendulum = state.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .translation(Vector::x() * 7.0 + Vector::y() * 5.0)
+                .build(),
+        );
+        state.colliders.insert_with_parent(
+            ColliderBuilder::ball(0.25),
+            pendulum,
+            &mut state.bodies,
+        );
+        #[cfg(feature = "dim2")]
+        let joint = RevoluteJointBuilder::new();
+        #[cfg(feature = "dim3")]
+        let joint = RevoluteJointBuilder::new(Vector::z_axis());
+        let joint = joint.local_anchor2(Point::origin() - Vector::x() * 2.0);
+        state.impulse_joints.insert(anchor, pendulum, joint, true);
+        handles.push(pendulum);
+
+        step_n(&mut state, &mut pipeline, 60);
+
+        let mut snapshot = DynamicsSnapshot::new();
+        snapshot.save(
+            &state.islands,
+            &state.bodies,
+            &state.impulse_joints,
+            &state.narrow_phase,
+        );
+
+        let mut simulate = |state: &mut PhysicsState| -> Vec<Isometry<Real>> {
+            step_n(state, &mut pipeline, 30);
+            handles
+                .iter()
+                .map(|h| *state.bodies[*h].position())
+                .collect()
+        };
+
+        let expected = simulate(&mut state);
+        assert!(snapshot.restore(
+            &mut state.islands,
+            &mut state.bodies,
+            &mut state.colliders,
+            &mut state.impulse_joints,
+            &mut state.narrow_phase,
+        ));
+        assert_eq!(simulate(&mut state), expected);
+
+        // The snapshot no longer matches the world once a body is added.
+        let _ = state.bodies.insert(RigidBodyBuilder::dynamic());
+        assert!(!snapshot.restore(
+            &mut state.islands,
+            &mut state.bodies,
+            &mut state.colliders,
+            &mut state.impulse_joints,
+            &mut state.narrow_phase,
+        ));
+    }
+}
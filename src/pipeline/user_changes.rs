@@ -86,6 +86,16 @@ pub(crate) fn handle_user_changes_to_rigid_bodies(
                                         ids.active_set_id,
                                     ));
                                 }
+
+                                // Add to the active dynamic set, unless it is sleeping. This is
+                                // needed for bodies that were fixed, and hence never awake.
+                                if !rb.activation.sleeping
+                                    && islands.active_dynamic_set.get(ids.active_set_id)
+                                        != Some(handle)
+                                {
+                                    ids.active_set_id = islands.active_dynamic_set.len();
+                                    islands.active_dynamic_set.push(*handle);
+                                }
                             }
                             RigidBodyType::KinematicVelocityBased
                             | RigidBodyType::KinematicPositionBased => {
@@ -161,16 +171,6 @@ pub(crate) fn handle_user_changes_to_rigid_bodies(
                 }
             }
 
-            if changes
-                .intersects(RigidBodyChanges::LOCAL_MASS_PROPERTIES | RigidBodyChanges::COLLIDERS)
-            {
-                rb.mprops.recompute_mass_properties_from_colliders(
-                    colliders,
-                    &rb.colliders,
-                    &rb.pos.position,
-                );
-            }
-
             if changes.contains(RigidBodyChanges::ENABLED_OR_DISABLED) {
                 // Propagate the rigid-body’s enabled/disable status to its colliders.
                 for handle in rb.colliders.0.iter() {
@@ -208,6 +208,20 @@ pub(crate) fn handle_user_changes_to_rigid_bodies(
                 }
             }
 
+            // NOTE: this must happen after the enabled status was propagated to the colliders since
+            //       disabled colliders don’t contribute to the mass properties.
+            if changes.intersects(
+                RigidBodyChanges::LOCAL_MASS_PROPERTIES
+                    | RigidBodyChanges::COLLIDERS
+                    | RigidBodyChanges::ENABLED_OR_DISABLED,
+            ) {
+                rb.mprops.recompute_mass_properties_from_colliders(
+                    colliders,
+                    &rb.colliders,
+                    &rb.pos.position,
+                );
+            }
+
             rb.ids = ids;
             rb.activation = activation;
         }
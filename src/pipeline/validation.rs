@@ -0,0 +1,160 @@
+use crate::dynamics::{RigidBodyHandle, RigidBodySet};
+use crate::geometry::{ColliderHandle, ColliderSet, NarrowPhase};
+use crate::math::{Isometry, Real};
+
+/// The first invalid piece of the physics state found by [`validate_physics_state`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InvalidState {
+    /// The position or the center of mass of this rigid-body isn’t finite.
+    RigidBodyPosition(RigidBodyHandle),
+    /// The linear or angular velocity of this rigid-body isn’t finite.
+    RigidBodyVelocity(RigidBodyHandle),
+    /// The inverse mass or the inverse angular inertia of this rigid-body isn’t finite, or is
+    /// negative.
+    RigidBodyMassProperties(RigidBodyHandle),
+    /// The position of this collider isn’t finite.
+    ColliderPosition(ColliderHandle),
+    /// The shape of this collider is degenerate, e.g., a ball with a zero radius, or a shape with
+    /// a non-finite bounding box.
+    ColliderShape(ColliderHandle),
+    /// This collider is attached to a rigid-body that doesn’t exist.
+    ColliderParent(ColliderHandle),
+    /// This contact or intersection pair involves a collider, or a rigid-body, that doesn’t
+    /// exist.
+    InteractionPair(ColliderHandle, ColliderHandle),
+}
+
+/// Checks that the physics state is valid, and returns the first invalid piece found.
+///
+/// The rigid-bodies are checked before the colliders, and the colliders before the contact and
+/// intersection pairs, so an invalid value propagating from a rigid-body to its colliders is
+/// reported on the rigid-body. With the `debug-validate-state` feature, this is checked after
+/// each [`PipelineStage`](crate::pipeline::PipelineStage) of `PhysicsPipeline::step`, which
+/// panics with the first stage that left the physics state invalid.
+pub fn validate_physics_state(
+    bodies: &RigidBodySet,
+    colliders: &ColliderSet,
+    narrow_phase: &NarrowPhase,
+) -> Result<(), InvalidState> {
+    for (handle, rb) in bodies.iter() {
+        if !is_finite_isometry(&rb.pos.position) || !is_finite(rb.mprops.world_com.iter()) {
+            return Err(InvalidState::RigidBodyPosition(handle));
+        }
+
+        #[cfg(feature = "dim2")]
+        let angvel = [rb.vels.angvel];
+        #[cfg(feature = "dim3")]
+        let angvel = rb.vels.angvel;
+        if !is_finite(rb.vels.linvel.iter().chain(angvel.iter())) {
+            return Err(InvalidState::RigidBodyVelocity(handle));
+        }
+
+        #[cfg(feature = "dim2")]
+        let inv_inertia_sqrt = [rb.mprops.local_mprops.inv_principal_inertia_sqrt];
+        #[cfg(feature = "dim3")]
+        let inv_inertia_sqrt = rb.mprops.local_mprops.inv_principal_inertia_sqrt;
+        let is_valid_mass_props = std::iter::once(&rb.mprops.local_mprops.inv_mass)
+            .chain(rb.mprops.effective_inv_mass.iter())
+            .chain(inv_inertia_sqrt.iter())
+            .all(|e| e.is_finite() && *e >= 0.0);
+        if !is_valid_mass_props {
+            return Err(InvalidState::RigidBodyMassProperties(handle));
+        }
+    }
+
+    for (handle, co) in colliders.iter() {
+        if !is_finite_isometry(&co.pos) {
+            return Err(InvalidState::ColliderPosition(handle));
+        }
+
+        let aabb = co.shape.compute_local_aabb();
+        let is_degenerate = !is_finite(aabb.mins.iter().chain(aabb.maxs.iter()))
+            || aabb.mins.iter().zip(aabb.maxs.iter()).any(|(a, b)| a > b)
+            || co.shape.as_ball().is_some_and(|ball| ball.radius <= 0.0)
+            || co
+                .shape
+                .as_cuboid()
+                .is_some_and(|cuboid| cuboid.half_extents.iter().any(|e| *e <= 0.0));
+        if is_degenerate {
+            return Err(InvalidState::ColliderShape(handle));
+        }
+
+        if co.parent().is_some_and(|parent| !bodies.contains(parent)) {
+            return Err(InvalidState::ColliderParent(handle));
+        }
+    }
+
+    for pair in narrow_phase.contact_pairs() {
+        let body_exists = |body: Option<RigidBodyHandle>| body.is_none_or(|h| bodies.contains(h));
+        let is_valid = colliders.contains(pair.collider1)
+            && colliders.contains(pair.collider2)
+            && pair.manifolds.iter().all(|manifold| {
+                body_exists(manifold.data.rigid_body1) && body_exists(manifold.data.rigid_body2)
+            });
+        if !is_valid {
+            return Err(InvalidState::InteractionPair(
+                pair.collider1,
+                pair.collider2,
+            ));
+        }
+    }
+
+    for (collider1, collider2, _) in narrow_phase.intersection_pairs() {
+        if !colliders.contains(collider1) || !colliders.contains(collider2) {
+            return Err(InvalidState::InteractionPair(collider1, collider2));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_finite<'a>(mut values: impl Iterator<Item = &'a Real>) -> bool {
+    values.all(|e| e.is_finite())
+}
+
+fn is_finite_isometry(pos: &Isometry<Real>) -> bool {
+    is_finite(pos.to_homogeneous().iter())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::RigidBodyBuilder;
+    use crate::geometry::ColliderBuilder;
+    use crate::math::{Real, Vector};
+    use crate::pipeline::PhysicsPipeline;
+    use crate::test_utils::{cuboid, step_n};
+
+    #[test]
+    fn physics_state_validation_reports_the_first_invalid_object() {
+        use crate::pipeline::{validate_physics_state, InvalidState, PhysicsState};
+
+        let mut pipeline = PhysicsPipeline::new();
+        let mut state = PhysicsState::new();
+
+        let body = state.bodies.insert(RigidBodyBuilder::dynamic());
+        state
+            .colliders
+            .insert_with_parent(ColliderBuilder::ball(0.5), body, &mut state.bodies);
+        let ground = cuboid(10.0, 0.5);
+        state
+            .colliders
+            .insert(ground.translation(-Vector::y() * 0.9));
+
+        step_n(&mut state, &mut pipeline, 10);
+
+        let validate = |state: &PhysicsState| {
+            validate_physics_state(&state.bodies, &state.colliders, &state.narrow_phase)
+        };
+        assert_eq!(validate(&state), Ok(()));
+
+        let degenerate = state.colliders.insert(ColliderBuilder::ball(0.0));
+        assert_eq!(
+            validate(&state),
+            Err(InvalidState::ColliderShape(degenerate))
+        );
+
+        // The rigid-bodies are checked first.
+        state.bodies[body].set_linvel(Vector::repeat(Real::NAN), true);
+        assert_eq!(validate(&state), Err(InvalidState::RigidBodyVelocity(body)));
+    }
+}
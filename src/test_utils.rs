@@ -0,0 +1,21 @@
+//! Fixtures shared by the unit tests of the crate.
+
+use crate::geometry::ColliderBuilder;
+use crate::math::Real;
+use crate::pipeline::{PhysicsPipeline, PhysicsState};
+
+/// A cuboid collider with the given half-extent along `y`, and the given half-extent along all
+/// the other axes.
+pub(crate) fn cuboid(half_width: Real, half_height: Real) -> ColliderBuilder {
+    #[cfg(feature = "dim2")]
+    return ColliderBuilder::cuboid(half_width, half_height);
+    #[cfg(feature = "dim3")]
+    return ColliderBuilder::cuboid(half_width, half_height, half_width);
+}
+
+/// Runs `num_steps` timesteps of the simulation, without any hook or event handler.
+pub(crate) fn step_n(state: &mut PhysicsState, pipeline: &mut PhysicsPipeline, num_steps: usize) {
+    for _ in 0..num_steps {
+        state.step(pipeline, &(), &());
+    }
+}
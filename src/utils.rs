@@ -8,6 +8,7 @@ use num::Zero;
 use simba::simd::SimdValue;
 use std::ops::IndexMut;
 
+use parry::partitioning::{IndexedData, Qbvh};
 use parry::utils::SdpMatrix3;
 use {
     crate::math::{Real, SimdReal},
@@ -37,6 +38,13 @@ pub(crate) fn simd_inv<N: WReal>(val: N) -> N {
     N::zero().select(val.simd_gt(-eps) & val.simd_lt(eps), N::one() / val)
 }
 
+/// Computes the signed difference `a - b` between two angles, wrapped into `[-π, π]`.
+pub(crate) fn smallest_abs_diff_between_angles<N: WReal>(a: N, b: N) -> N {
+    let two_pi = N::simd_two_pi();
+    let diff = a - b;
+    diff - (diff / two_pi).simd_round() * two_pi
+}
+
 /// Trait to copy the sign of each component of one scalar/vector/matrix to another.
 pub trait WSign<Rhs>: Sized {
     // See SIMD implementations of copy_sign there: https://stackoverflow.com/a/57872652
@@ -752,6 +760,26 @@ impl Drop for DisableFloatingPointExceptionsFlags {
     }
 }
 
+/// Packs the angular inertias of `SIMD_WIDTH` rigid-bodies into a single SIMD angular inertia.
+///
+/// Contrary to the conversions provided by parry, this doesn’t assume 4 SIMD lanes.
+#[cfg(feature = "simd-is-enabled")]
+pub(crate) fn simd_angular_inertia(
+    inertias: [crate::math::AngularInertia<Real>; crate::math::SIMD_WIDTH],
+) -> crate::math::AngularInertia<SimdReal> {
+    #[cfg(feature = "dim2")]
+    return SimdReal::from(inertias);
+    #[cfg(feature = "dim3")]
+    return SdpMatrix3 {
+        m11: SimdReal::from(inertias.map(|m| m.m11)),
+        m12: SimdReal::from(inertias.map(|m| m.m12)),
+        m13: SimdReal::from(inertias.map(|m| m.m13)),
+        m22: SimdReal::from(inertias.map(|m| m.m22)),
+        m23: SimdReal::from(inertias.map(|m| m.m23)),
+        m33: SimdReal::from(inertias.map(|m| m.m33)),
+    };
+}
+
 pub(crate) fn select_other<T: PartialEq>(pair: (T, T), elt: T) -> T {
     if pair.0 == elt {
         pair.1
@@ -804,3 +832,35 @@ impl<T> IndexMut2<usize> for [T] {
         }
     }
 }
+
+/// Can a leaf that isn’t in the `qbvh` yet be inserted with `Qbvh::pre_update_or_insert`?
+///
+/// The new leaf is attached to the first child of the root with a free slot, assuming it is a
+/// leaf node. After the tree has been rebalanced, this child may be an internal node, and
+/// inserting into it would corrupt the tree. In that case, the tree has to be rebuilt instead.
+pub(crate) fn qbvh_can_insert<LeafData: IndexedData>(qbvh: &Qbvh<LeafData>) -> bool {
+    let nodes = qbvh.raw_nodes();
+
+    if nodes.is_empty() {
+        return true;
+    }
+
+    for child in nodes[0].children {
+        match nodes.get(child as usize) {
+            Some(node) if node.children.contains(&u32::MAX) => return node.is_leaf(),
+            Some(_) => {}
+            None => return true,
+        }
+    }
+
+    // The root is full: a new root will be created to make room.
+    true
+}
+
+/// Is the leaf with the given data already in the `qbvh`?
+pub(crate) fn qbvh_contains<LeafData: IndexedData>(qbvh: &Qbvh<LeafData>, data: LeafData) -> bool {
+    qbvh.raw_proxies()
+        .get(data.index())
+        .map(|proxy| proxy.node.index != u32::MAX)
+        .unwrap_or(false)
+}
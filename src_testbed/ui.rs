@@ -143,6 +143,14 @@ pub fn update_ui(ui_context: &mut EguiContext, state: &mut TestbedState, harness
         ui.add(
             Slider::new(&mut integration_parameters.max_ccd_substeps, 0..=10).text("CCD substeps"),
         );
+        ui.add(
+            Slider::new(&mut integration_parameters.num_solver_substeps, 1..=16)
+                .text("solver substeps"),
+        );
+        ui.checkbox(
+            &mut integration_parameters.shock_propagation,
+            "shock propagation",
+        );
         ui.add(
             Slider::new(&mut integration_parameters.min_island_size, 1..=10_000)
                 .text("min island size"),
@@ -214,6 +222,9 @@ Solver: {:.2}ms
    Velocity integration: {:.2}ms
    Position assembly: {:.2}ms
    Position resolution: {:.2}ms
+   # of constraints: {}
+   # of velocity iterations: {}
+   Velocity error: {:.2e}
 CCD: {:.2}ms
 |_ # of substeps: {}
    TOI computation: {:.2}ms
@@ -231,6 +242,9 @@ CCD: {:.2}ms
         counters.solver.velocity_update_time.time(),
         counters.solver.position_assembly_time.time(),
         counters.position_resolution_time(),
+        counters.solver.nconstraints,
+        counters.solver.velocity_iterations,
+        counters.solver.velocity_error,
         counters.ccd_time(),
         counters.ccd.num_substeps,
         counters.ccd.toi_computation_time.time(),